@@ -1,9 +1,27 @@
+use crate::config;
+use crate::policy::{self, Policy};
+use crate::snapshot;
 use crate::state;
+use crate::state::badges::{Activity, TabBadge};
+use crate::state::colors::RowColors;
+use crate::state::notifications::Severity;
 use crate::sys;
+use crate::tabs::{TabCommand, TabView};
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// How long a start/stop is followed before it is reported as stuck
+const SERVICE_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
+// How long killed lockers get to let go of a file before deleting or renaming it
+const UNLOCK_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub use crate::sys::handle::LockingProcess;
 
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 #[allow(dead_code)]
 pub enum AppEvent {
     Tick,
@@ -13,9 +31,187 @@ pub enum AppEvent {
     ServiceUpdate(Vec<sys::service::ServiceInfo>),
     ProcessUpdate(Vec<sys::process::ProcessInfo>),
     NetworkUpdate(Vec<sys::network::ConnectionInfo>),
+    /// A background kill or service operation finished.
+    ActionDone(PendingAction, crate::error::Result<()>),
+    /// A whois lookup for the given IP finished.
+    WhoisDone(String, crate::error::Result<String>),
+    /// A reverse DNS lookup for the given IP finished.
+    HostResolved(String, Option<String>),
+    /// Command lines read for the listed PIDs; None where a process couldn't be read.
+    CommandLines(Vec<(u32, Option<String>)>),
+    /// A scan of job objects and their processes finished.
+    Jobs(crate::error::Result<Vec<sys::job::JobInfo>>),
+    /// A firewall check of the listening sockets finished.
+    Firewall(crate::error::Result<Vec<(sys::firewall::Listener, sys::firewall::FirewallCheck)>>),
+    /// A background collector finished a run that took this long.
+    Timing(&'static str, Duration),
+    /// The named script action finished, with what it returned.
+    ScriptDone(String, crate::error::Result<String>),
+    /// A chunk of the numbered file lock search's directory scan was checked.
+    DirectoryScan(u64, crate::error::Result<sys::handle::ScanProgress>),
+    /// A check of the watched paths finished, with how many processes lock them.
+    LockWatch(crate::error::Result<usize>),
+    /// The script columns of a tab were computed for its rows.
+    ScriptColumns(Tab, crate::error::Result<ScriptValues>),
+}
+
+/// Script column values by row id, for the tab they were computed for.
+#[derive(Debug)]
+pub enum ScriptValues {
+    Locker(HashMap<u32, Vec<String>>),
+    Controller(HashMap<String, Vec<String>>),
+    Nexus(HashMap<state::nexus::ConnectionKey, Vec<String>>),
+}
+
+/// Where a process dump is written and how much it captures.
+#[derive(Debug, Clone)]
+pub struct DumpTarget {
+    pub path: PathBuf,
+    pub dump_type: sys::dump::DumpType,
+}
+
+/// Something only an elevated Aperture can do, with why.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminTask {
+    KillProcess,
+    DumpProcess,
+    ControlService,
+    ChangeServices,
+    CloseConnection,
+    Capture,
+    EndSession,
+}
+
+impl AdminTask {
+    pub fn action(&self) -> &'static str {
+        match self {
+            AdminTask::KillProcess => "Killing processes",
+            AdminTask::DumpProcess => "Writing process dumps",
+            AdminTask::ControlService => "Starting and stopping services",
+            AdminTask::ChangeServices => "Creating, deleting and reconfiguring services",
+            AdminTask::CloseConnection => "Closing TCP connections",
+            AdminTask::Capture => "Capturing packets",
+            AdminTask::EndSession => "Disconnecting and logging off other users' sessions",
+        }
+    }
+
+    /// The privilege or access right that is missing.
+    pub fn reason(&self) -> &'static str {
+        match self {
+            AdminTask::KillProcess => {
+                "Processes of services and other users only grant PROCESS_TERMINATE to an \
+                 elevated token with SeDebugPrivilege enabled."
+            }
+            AdminTask::DumpProcess => {
+                "MiniDumpWriteDump reads the whole address space, and processes of services \
+                 and other users only grant PROCESS_VM_READ to an elevated token with \
+                 SeDebugPrivilege enabled."
+            }
+            AdminTask::ControlService => {
+                "The Service Control Manager grants SERVICE_START and SERVICE_STOP on most \
+                 services to administrators only."
+            }
+            AdminTask::ChangeServices => {
+                "Creating and deleting services and changing their configuration needs \
+                 SC_MANAGER_CREATE_SERVICE, DELETE and SERVICE_CHANGE_CONFIG, which only \
+                 administrators get."
+            }
+            AdminTask::CloseConnection => {
+                "SetTcpEntry only resets connections for an elevated caller."
+            }
+            AdminTask::Capture => {
+                "pktmon and netsh trace start kernel capture sessions, which need \
+                 administrator rights."
+            }
+            AdminTask::EndSession => {
+                "Remote Desktop Services only grants WTS_SECURITY_DISCONNECT and \
+                 WTS_SECURITY_LOGOFF on other users' sessions to administrators."
+            }
+        }
+    }
+}
+
+/// A potentially slow Win32 operation run off the UI thread.
+#[derive(Debug, Clone)]
+pub enum PendingAction {
+    /// Kills `pid`, writing `dump` first when set. A failed dump leaves the process running.
+    Kill {
+        pid: u32,
+        /// Creation time of the process as confirmed; see `KillConfirmation::created`.
+        created: Option<u64>,
+        name: String,
+        options: sys::process::KillOptions,
+        dump: Option<DumpTarget>,
+        /// The descendants or job processes killed with it, as found when the kill was
+        /// confirmed. They go through the policy and guardrails too, and a tree kill
+        /// kills exactly these.
+        members: Vec<sys::process::KillMember>,
+    },
+    Dump {
+        pid: u32,
+        name: String,
+        target: DumpTarget,
+    },
+    StartService(String),
+    StopService(String),
+    /// Stops `dependents` in order, then `name`.
+    StopServiceTree { name: String, dependents: Vec<String> },
+    /// Applies imported settings to each service, carrying on past failures.
+    ApplyServiceConfigs(Vec<sys::service::ServiceConfig>),
+    StartCapture(sys::capture::Capture),
+    StopCapture(sys::capture::Capture),
+    /// Closes locking processes through Restart Manager instead of killing them.
+    ShutdownLockers(Arc<sys::handle::RestartSession>),
+    RestartLockers(Arc<sys::handle::RestartSession>),
+    /// Closes `handles` inside process `pid`, leaving it running.
+    CloseHandles {
+        pid: u32,
+        name: String,
+        /// Creation time of the process the handles were found in.
+        started: Option<u64>,
+        handles: Vec<sys::handle::HeldHandle>,
+    },
+    /// Kills `lockers` (PID, name, creation time), then deletes `paths`, or renames the
+    /// single path to `rename`.
+    Unlock {
+        paths: Vec<String>,
+        lockers: Vec<(u32, String, Option<u64>)>,
+        rename: Option<String>,
+    },
+    /// Disconnects or logs off logon session `id`, whose user is `user`.
+    EndSession {
+        id: u32,
+        user: String,
+        command: SessionCommand,
+    },
+}
+
+/// What `D` and `L` do to a logon session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// Ends the connection; the user stays logged on and their programs keep running.
+    Disconnect,
+    /// Ends the session and its programs.
+    LogOff,
+}
+
+impl SessionCommand {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionCommand::Disconnect => "Disconnect",
+            SessionCommand::LogOff => "Log off",
+        }
+    }
 }
 
+/// A mark key waiting for its letter: `m` sets a mark, `'` jumps to one.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkPrefix {
+    Set,
+    Jump,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum Tab {
     Locker,
     Controller,
@@ -42,6 +238,30 @@ impl std::fmt::Display for Tab {
     }
 }
 
+/// How fresh a tab's data is: when its last poll succeeded and why the latest failed.
+#[derive(Debug, Clone, Default)]
+pub struct RefreshStatus {
+    pub succeeded_at: Option<Instant>,
+    /// Wall-clock time of the last successful poll, HH:MM:SS.
+    pub succeeded_clock: Option<String>,
+    /// Error of the latest poll, None if it succeeded.
+    pub error: Option<String>,
+}
+
+impl RefreshStatus {
+    /// Polls run every 2 seconds; data older than a few missed polls counts as stalled.
+    pub const STALE_AFTER: Duration = Duration::from_secs(6);
+
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Stale once `after` has passed without a successful poll; see `App::stale_after`.
+    pub fn is_stale(&self, after: Duration) -> bool {
+        self.failed() || self.succeeded_at.is_none_or(|at| at.elapsed() >= after)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct ProcessDetails {
     pub pid: u32,
@@ -53,32 +273,321 @@ pub struct ProcessDetails {
     pub parent_pid: u32,
     pub cpu_usage: f32,
     pub memory_mb: f64,
+    /// Working set, private bytes, commit and peaks, if they could be read.
+    pub memory: Option<sys::process::MemoryCounters>,
+    /// When the process started, in Unix seconds.
+    pub start_time: Option<u64>,
+    pub error: Option<String>,
+    /// Visible top-level windows of the process; `selected_window` indexes into it.
+    pub windows: Vec<sys::window::WindowInfo>,
+    pub selected_window: usize,
+}
+
+/// Lookup targets for a remote IP: the configured URLs, then a local whois as the last entry.
+#[derive(Debug, Clone)]
+pub struct RemoteLookup {
+    pub ip: String,
+    pub selected: usize,
+    pub whois: Option<Vec<String>>,
+    pub running: bool,
+    pub scroll: usize,
+    pub error: Option<String>,
+}
+
+/// Threads of one process. Re-sampled with the metrics while open, so the CPU column
+/// shows usage since the previous sample.
+#[derive(Debug, Clone)]
+pub struct ThreadView {
+    pub pid: u32,
+    pub name: String,
+    pub threads: Vec<sys::thread::ThreadInfo>,
+    pub selected: usize,
+    pub sampled_at: Instant,
     pub error: Option<String>,
 }
 
+/// Kill confirmation with the options picked in the modal. The exit code is kept as typed
+/// so it can be edited in place; empty means the default of 1.
+#[derive(Debug, Clone)]
+pub struct KillConfirmation {
+    pub pid: u32,
+    /// When the process was created, read as the confirmation opens, so a PID reused
+    /// while it is up isn't killed in its place.
+    pub created: Option<u64>,
+    pub name: String,
+    pub force: bool,
+    pub tree: bool,
+    /// Terminate the job object the process belongs to, with every process in it.
+    pub job: bool,
+    /// Write a dump of the process before killing it.
+    pub dump: bool,
+    pub exit_code: String,
+}
+
+impl KillConfirmation {
+    pub fn new(pid: u32, name: String) -> Self {
+        Self {
+            pid,
+            created: sys::process::creation_time(pid),
+            name,
+            force: false,
+            tree: false,
+            job: false,
+            dump: false,
+            exit_code: String::new(),
+        }
+    }
+
+    /// The options to kill with, or None if the exit code doesn't fit in a u32.
+    pub fn options(&self) -> Option<sys::process::KillOptions> {
+        let exit_code = if self.exit_code.is_empty() {
+            1
+        } else {
+            self.exit_code.parse().ok()?
+        };
+        Some(sys::process::KillOptions {
+            force: self.force,
+            tree: self.tree,
+            job: self.job,
+            exit_code,
+        })
+    }
+}
+
+/// An external tool filled in for the selected row, or a script action, as offered by
+/// the `x` menu.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToolCommand {
+    pub name: String,
+    pub run: ToolRun,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolRun {
+    Command { argv: Vec<String>, detach: bool },
+    /// A script action by its index, called with the row's values.
+    Script(usize),
+}
+
+/// Two tabs side by side. The focused pane shows `App::current_tab` and gets the keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitView {
+    /// The tab of the other pane.
+    pub other: Tab,
+    pub focus_left: bool,
+}
+
+/// A poll timer, for skipping its ticks while idle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Poll {
+    Data,
+    Services,
+    Metrics,
+}
+
+/// What the event loop leaves the UI for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Suspend {
+    /// Ctrl+Z: an interactive shell, or the configured suspend command.
+    Shell,
+    /// An external tool's program and arguments, with its output left up until Enter.
+    Command(Vec<String>),
+}
+
+/// A place the "go to" menu can jump to from the selected row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GoToTarget {
+    /// The process in the Locker tab.
+    Process(u32),
+    /// The Nexus tab narrowed to the process's sockets.
+    Connections(u32),
+    /// One service in the Controller tab.
+    Service(String),
+    /// The Controller tab narrowed to the services hosted by the process.
+    HostedServices(u32),
+    /// The file lock search, filled in with the folder of the process's executable.
+    LockedFiles(String),
+}
+
+impl GoToTarget {
+    pub fn label(&self) -> String {
+        match self {
+            GoToTarget::Process(pid) => format!("Locker: process {}", pid),
+            GoToTarget::Connections(pid) => format!("Nexus: connections of PID {}", pid),
+            GoToTarget::Service(name) => format!("Controller: service {}", name),
+            GoToTarget::HostedServices(pid) => format!("Controller: services in PID {}", pid),
+            GoToTarget::LockedFiles(dir) => format!("Handle search: locks in {}", dir),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Modal {
-    KillConfirmation {
-        pid: u32,
-        name: String,
+    KillConfirmation(KillConfirmation),
+    /// Descendants a confirmed tree kill takes down, shown for a last look before it runs.
+    KillTreePreview {
+        confirmation: KillConfirmation,
+        tree: Vec<sys::process::KillMember>,
+        scroll: usize,
     },
     HandleSearch {
         input: String,
+        /// The paths `results` are for, as they were searched. Delete and rename act on
+        /// these, whatever the input says by now.
+        searched: Vec<String>,
         results: Vec<LockingProcess>,
         selected: usize,
         loading: bool,
         error: Option<String>,
         is_directory: bool,
+        /// Files checked, or scanned once a directory scan is done.
         files_scanned: Option<usize>,
+        /// Files a running directory scan goes through.
+        scan_total: Option<usize>,
     },
     ProcessDetails(ProcessDetails),
     ExportFormat,
+    PortLookup {
+        input: String,
+        results: Vec<sys::network::ConnectionInfo>,
+        selected: usize,
+        error: Option<String>,
+        searched: bool,
+    },
+    NamedPipes {
+        pipes: Vec<sys::pipe::PipeInfo>,
+        selected: usize,
+        error: Option<String>,
+    },
+    /// Logon sessions, with the processes running in each.
+    LogonSessions {
+        sessions: Vec<sys::logon::LogonSession>,
+        selected: usize,
+        error: Option<String>,
+        /// A disconnect or log off of the selected session waiting for `y`.
+        confirm: Option<SessionCommand>,
+    },
+    DnsCache {
+        entries: Vec<sys::dns::DnsCacheEntry>,
+        hosts: Vec<sys::dns::HostsEntry>,
+        selected: usize,
+        show_hosts: bool,
+        error: Option<String>,
+    },
+    Profiles {
+        selected: usize,
+        naming: Option<String>,
+        error: Option<String>,
+    },
+    Snapshots {
+        snapshots: Vec<snapshot::SnapshotFile>,
+        selected: usize,
+        base: Option<usize>,
+        error: Option<String>,
+    },
+    SnapshotDiff {
+        title: String,
+        entries: Vec<snapshot::DiffEntry>,
+        selected: usize,
+    },
+    ErrorLog {
+        selected: usize,
+    },
+    /// How long each collector's recent runs took; redrawn live.
+    Timings,
+    RecorderHistory {
+        events: Vec<snapshot::HistoryEvent>,
+        selected: usize,
+        error: Option<String>,
+    },
+    Notifications {
+        selected: usize,
+    },
+    StopDependents {
+        name: String,
+        display_name: String,
+        dependents: Vec<sys::service::DependentService>,
+    },
+    /// Second confirmation before `action` kills a protected process or stops a protected
+    /// service: `name` has to be typed.
+    Guardrail {
+        name: String,
+        typed: String,
+        action: PendingAction,
+    },
+    /// Confirms killing what locks the searched `paths` and then deleting them, or
+    /// renaming the one path to `rename` as typed.
+    Unlock {
+        paths: Vec<String>,
+        lockers: Vec<LockingProcess>,
+        rename: Option<String>,
+    },
+    CreateService {
+        name: String,
+        display_name: String,
+        binary_path: String,
+        /// Index into `sys::service::START_TYPES`
+        start_type: usize,
+        /// Focused field: name, display name, binary path, start type
+        field: usize,
+        error: Option<String>,
+    },
+    RemoteLookup(RemoteLookup),
+    Threads(ThreadView),
+    GoTo {
+        targets: Vec<GoToTarget>,
+        selected: usize,
+    },
+    Tools {
+        tools: Vec<ToolCommand>,
+        /// The selected row's values, for script actions.
+        values: Vec<(&'static str, String)>,
+        selected: usize,
+    },
+    ConnectionDetails {
+        conn: sys::network::ConnectionInfo,
+        owner: Option<sys::network::SocketOwnerInfo>,
+        error: Option<String>,
+    },
+    ServiceDetails {
+        service: sys::service::ServiceInfo,
+        recovery: Option<sys::service::FailureActions>,
+        /// Recovery row being edited: first, second, subsequent failures, reset period
+        field: usize,
+        dirty: bool,
+        error: Option<String>,
+    },
+    DeleteService {
+        name: String,
+        display_name: String,
+        /// The service name has to be typed back before the delete goes through
+        confirm: String,
+        error: Option<String>,
+    },
+    ImportServices {
+        /// File to import, typed in; loaded on Enter
+        path: String,
+        /// The loaded file, listed for confirmation before anything is applied
+        loaded: Option<crate::export::ServiceConfigFile>,
+        error: Option<String>,
+    },
+    /// An action was refused for lack of admin rights. Offers to restart Aperture elevated.
+    ElevationRequired {
+        task: AdminTask,
+        /// The modal the action was tried from, restored on Esc
+        previous: Option<Box<Modal>>,
+        error: Option<String>,
+    },
 }
 
 pub struct AppState {
     pub locker: state::locker::LockerState,
     pub controller: state::controller::ControllerState,
     pub nexus: state::nexus::NexusState,
+    pub perf: state::perf::PerfState,
+    pub errors: state::errors::ErrorLog,
+    pub notifications: state::notifications::Notifications,
+    pub timings: state::timings::Timings,
 }
 
 impl AppState {
@@ -87,6 +596,27 @@ impl AppState {
             locker: state::locker::LockerState::new(),
             controller: state::controller::ControllerState::new(),
             nexus: state::nexus::NexusState::new(),
+            perf: state::perf::PerfState::new(),
+            errors: state::errors::ErrorLog::new(),
+            notifications: state::notifications::Notifications::new(),
+            timings: state::timings::Timings::new(),
+        }
+    }
+
+    /// The state behind `tab`, for dispatching without matching on the tab.
+    pub fn tab(&self, tab: Tab) -> &dyn TabView {
+        match tab {
+            Tab::Locker => &self.locker,
+            Tab::Controller => &self.controller,
+            Tab::Nexus => &self.nexus,
+        }
+    }
+
+    pub fn tab_mut(&mut self, tab: Tab) -> &mut dyn TabView {
+        match tab {
+            Tab::Locker => &mut self.locker,
+            Tab::Controller => &mut self.controller,
+            Tab::Nexus => &mut self.nexus,
         }
     }
 }
@@ -95,12 +625,65 @@ pub struct App {
     pub current_tab: Tab,
     pub state: AppState,
     pub is_elevated: bool,
+    /// Host name, Windows version and boot time, for the header.
+    pub machine: sys::system::MachineInfo,
+    /// Everything that kills, stops or changes something is refused; set by `--read-only`,
+    /// the `read_only` setting, or a config file that can't be loaded.
+    pub read_only: bool,
+    /// The config file exists but couldn't be read or parsed, so it isn't saved over.
+    config_unreadable: bool,
     pub search_mode: bool,
     pub search_query: String,
-    pub status_message: Option<String>,
+    /// Typing a `?` find pattern, which moves the cursor instead of hiding rows.
+    pub find_mode: bool,
+    /// The last find pattern, reused by `n`/`N`. Empty when there is none.
+    pub find_query: String,
     pub modal: Option<Modal>,
     pub handle_search_input_mode: bool,
+    /// Index into the recent handle searches while browsing them with Up/Down.
+    handle_search_recall: Option<usize>,
+    /// Completions of the path being typed and the one shown, cycled with Tab.
+    handle_search_completion: Option<(Vec<String>, usize)>,
+    /// File the last export wrote, to check for locks on it.
+    pub last_export: Option<String>,
+    /// Number of the latest file lock search. A directory scan stops once it's outdated.
+    handle_scan: Arc<AtomicU64>,
+    /// Locking processes shut down from the file lock search, until they're restarted.
+    pub locker_restart: Option<Arc<sys::handle::RestartSession>>,
+    /// Paths from the file lock search checked until they're free.
+    pub lock_watch: Option<state::lock_watch::LockWatch>,
     pub pending_gg: bool,
+    /// Count typed before a motion, e.g. the 15 of `15j`.
+    pub pending_count: Option<usize>,
+    pub pending_mark: Option<MarkPrefix>,
+    /// When the pending count or mark key was pressed, for `flush_pending_keys`.
+    pub pending_since: Instant,
+    pub config: config::Config,
+    /// What the policy file allows; everything without one.
+    pub policy: Policy,
+    pub metrics_tx: Option<tokio::sync::watch::Sender<String>>,
+    pub event_tx: Option<tokio::sync::mpsc::Sender<AppEvent>>,
+    /// Outcome of each tab's latest poll.
+    pub refresh_status: std::collections::HashMap<Tab, RefreshStatus>,
+    /// What happened on the tabs off screen, for the badges on their titles.
+    pub tab_badges: std::collections::HashMap<Tab, TabBadge>,
+    /// The configured port watches and which of them failed the last Nexus poll.
+    pub port_watches: state::port_watch::PortWatches,
+    /// Set to leave the UI for a shell or tool. The event loop owns the terminal, so it
+    /// does the suspending.
+    pub suspend_requested: Option<Suspend>,
+    /// Loaded at startup; shared with the threads script actions run on.
+    pub scripts: std::sync::Arc<crate::scripting::Scripts>,
+    /// Tabs whose script columns are being computed.
+    script_columns_running: Vec<Tab>,
+    /// Set while two tabs are shown side by side.
+    pub split: Option<SplitView>,
+    /// `Z`: the focused list fills the screen, without the header, strips and sidebar.
+    pub zoomed: bool,
+    /// Last key press, for slowing polling down when idle.
+    pub last_input: Instant,
+    /// When each poll timer last refreshed.
+    last_polls: std::collections::HashMap<Poll, Instant>,
 }
 
 impl App {
@@ -109,459 +692,4437 @@ impl App {
             current_tab: Tab::Locker,
             state: AppState::new(),
             is_elevated: false,
+            machine: sys::system::machine_info(),
+            read_only: false,
+            config_unreadable: false,
             search_mode: false,
             search_query: String::new(),
-            status_message: None,
+            find_mode: false,
+            find_query: String::new(),
             modal: None,
             handle_search_input_mode: false,
+            handle_search_recall: None,
+            handle_search_completion: None,
+            last_export: None,
+            handle_scan: Arc::new(AtomicU64::new(0)),
+            locker_restart: None,
+            lock_watch: None,
             pending_gg: false,
+            pending_count: None,
+            pending_mark: None,
+            pending_since: Instant::now(),
+            config: config::Config::default(),
+            policy: Policy::default(),
+            metrics_tx: None,
+            event_tx: None,
+            refresh_status: std::collections::HashMap::new(),
+            tab_badges: std::collections::HashMap::new(),
+            port_watches: state::port_watch::PortWatches::default(),
+            suspend_requested: None,
+            scripts: std::sync::Arc::default(),
+            script_columns_running: Vec::new(),
+            split: None,
+            zoomed: false,
+            last_input: Instant::now(),
+            last_polls: std::collections::HashMap::new(),
         }
     }
 
-    pub fn check_elevation(&mut self) {
-        self.is_elevated = sys::process::is_elevated();
-        if !self.is_elevated {
-            self.status_message =
-                Some("Running without admin - some actions unavailable".to_string());
+    pub fn load_config(&mut self) {
+        match config::Config::load() {
+            Ok(config) => self.config = config,
+            Err(e) => {
+                // The file may have asked for read-only mode, so don't assume it didn't
+                self.read_only = true;
+                self.config_unreadable = true;
+                let message = format!("Failed to load config: {} - running read-only", e);
+                self.notify(Severity::Error, message);
+            }
         }
-    }
-
-    pub fn next_tab(&mut self) {
-        let tabs = Tab::all();
-        let idx = tabs.iter().position(|&t| t == self.current_tab).unwrap();
-        self.current_tab = tabs[(idx + 1) % tabs.len()];
-    }
+        crate::i18n::set_language(self.config.language);
+        self.load_policy();
+        if self.config.low_priority
+            && let Err(e) = sys::process::lower_own_priority()
+        {
+            self.notify(Severity::Warning, format!("Could not lower priority: {}", e));
+        }
+        self.set_fuzzy_search(self.config.fuzzy_search);
+        self.state.locker.object_thresholds = self.config.object_thresholds;
+        self.port_watches.sync(&self.config.port_watches);
+        let page_step = self.config.page_step.filter(|&rows| rows > 0);
+        self.state.locker.table.page_step = page_step;
+        self.state.controller.table.page_step = page_step;
+        self.state.nexus.table.page_step = page_step;
 
-    pub fn prev_tab(&mut self) {
-        let tabs = Tab::all();
-        let idx = tabs.iter().position(|&t| t == self.current_tab).unwrap();
-        self.current_tab = tabs[(idx + tabs.len() - 1) % tabs.len()];
+        let (locker, mut errors) = RowColors::compile(&self.config.colors.locker);
+        let (controller, more) = RowColors::compile(&self.config.colors.controller);
+        errors.extend(more);
+        let (nexus, more) = RowColors::compile(&self.config.colors.nexus);
+        errors.extend(more);
+        self.state.locker.table.colors = locker;
+        self.state.controller.table.colors = controller;
+        self.state.nexus.table.colors = nexus;
+        for error in errors {
+            self.notify(Severity::Warning, error);
+        }
+        self.load_scripts();
     }
 
-    pub fn select_next(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_next(&self.search_query),
-            Tab::Controller => self.state.controller.select_next(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_next(&self.search_query),
+    fn load_scripts(&mut self) {
+        let (scripts, errors) = crate::scripting::Scripts::load();
+        for error in errors {
+            self.notify(Severity::Warning, error);
         }
+        self.scripts = std::sync::Arc::new(scripts);
     }
 
-    pub fn select_prev(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_prev(&self.search_query),
-            Tab::Controller => self.state.controller.select_prev(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_prev(&self.search_query),
+    /// Saves the config, unless the file on disk couldn't be loaded: writing the defaults
+    /// and a setting or two over it would lose whatever the user had in there.
+    fn save_config(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.config_unreadable {
+            return Err("the config file couldn't be loaded, so it is left as it is".into());
         }
+        self.config.save()
     }
 
-    pub fn select_page_up(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_page_up(&self.search_query),
-            Tab::Controller => self.state.controller.select_page_up(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_page_up(&self.search_query),
-        }
+    fn set_fuzzy_search(&mut self, fuzzy: bool) {
+        self.config.fuzzy_search = fuzzy;
+        self.state.locker.table.fuzzy = fuzzy;
+        self.state.controller.table.fuzzy = fuzzy;
+        self.state.nexus.table.fuzzy = fuzzy;
     }
 
-    pub fn select_page_down(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_page_down(&self.search_query),
-            Tab::Controller => self.state.controller.select_page_down(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_page_down(&self.search_query),
+    /// Switches `/` searches between substring and fuzzy matching and remembers the choice.
+    pub fn toggle_fuzzy_search(&mut self) {
+        self.set_fuzzy_search(!self.config.fuzzy_search);
+        if let Err(e) = self.save_config() {
+            self.notify(Severity::Error, format!("Failed to save config: {}", e));
         }
     }
 
-    pub fn select_first(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_first(&self.search_query),
-            Tab::Controller => self.state.controller.select_first(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_first(&self.search_query),
+    pub fn check_elevation(&mut self) {
+        self.is_elevated = sys::process::is_elevated();
+        if self.is_elevated {
+            // Lets OpenProcess reach protected/SYSTEM processes so their metrics populate
+            if let Err(e) = sys::process::enable_debug_privilege() {
+                self.notify(
+                    Severity::Warning,
+                    format!("Could not enable SeDebugPrivilege: {}", e),
+                );
+            }
+        } else {
+            self.notify(
+                Severity::Warning,
+                "Running without admin - some actions unavailable",
+            );
         }
     }
 
-    pub fn select_last(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.select_last(&self.search_query),
-            Tab::Controller => self.state.controller.select_last(&self.search_query),
-            Tab::Nexus => self.state.nexus.select_last(&self.search_query),
+    /// Tab/Shift+Tab: the next or previous tab, skipping the one the other pane shows.
+    fn step_tab(&mut self, forward: bool) {
+        let tabs = Tab::all();
+        let mut idx = tabs.iter().position(|&t| t == self.current_tab).unwrap();
+        loop {
+            idx = if forward {
+                (idx + 1) % tabs.len()
+            } else {
+                (idx + tabs.len() - 1) % tabs.len()
+            };
+            if self.split.is_none_or(|split| split.other != tabs[idx]) {
+                break;
+            }
         }
+        self.current_tab = tabs[idx];
+        self.clear_seen_badges();
     }
 
-    pub fn on_enter(&mut self) {
-        if self.current_tab == Tab::Controller
-            && self.is_elevated {
-                self.state
-                    .controller
-                    .toggle_selected_service(&self.search_query);
-            }
+    pub fn next_tab(&mut self) {
+        self.step_tab(true);
     }
 
-    pub fn enter_search_mode(&mut self) {
-        self.search_mode = true;
-        self.search_query.clear();
+    pub fn prev_tab(&mut self) {
+        self.step_tab(false);
     }
 
-    pub fn exit_search_mode(&mut self) {
-        // Store the search query as the active filter before exiting
-        let query = self.search_query.clone();
-        match self.current_tab {
-            Tab::Locker => self.state.locker.set_filter(query),
-            Tab::Controller => self.state.controller.set_filter(query),
-            Tab::Nexus => self.state.nexus.set_filter(query),
+    /// Shows `tab` in the focused pane, or focuses the other pane if it already shows it.
+    pub fn set_tab(&mut self, tab: Tab) {
+        if self.split.is_some_and(|split| split.other == tab) {
+            self.switch_pane();
+        } else {
+            self.current_tab = tab;
         }
-        self.search_mode = false;
-        self.search_query.clear();
+        self.clear_seen_badges();
     }
 
-    pub fn clear_current_filter(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.clear_filter(),
-            Tab::Controller => self.state.controller.clear_filter(),
-            Tab::Nexus => self.state.nexus.clear_filter(),
-        }
+    /// `V`: shows a second tab beside the current one, or goes back to one tab. The
+    /// Locker pairs with the Nexus and the others with the Locker.
+    pub fn toggle_split(&mut self) {
+        self.split = match self.split {
+            Some(_) => None,
+            None => Some(SplitView {
+                other: if self.current_tab == Tab::Locker { Tab::Nexus } else { Tab::Locker },
+                focus_left: true,
+            }),
+        };
+        self.clear_seen_badges();
     }
 
-    pub fn has_active_filter(&self) -> bool {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.active_filter.is_some(),
-            Tab::Controller => self.state.controller.active_filter.is_some(),
-            Tab::Nexus => self.state.nexus.active_filter.is_some(),
+    /// Ctrl+W: moves the focus to the other pane.
+    pub fn switch_pane(&mut self) {
+        if let Some(split) = &mut self.split {
+            std::mem::swap(&mut split.other, &mut self.current_tab);
+            split.focus_left = !split.focus_left;
         }
     }
 
-    pub fn handle_search_char(&mut self, c: char) {
-        self.search_query.push(c);
+    /// Whether `tab` is on screen, in the focused pane or the other one.
+    pub fn is_visible(&self, tab: Tab) -> bool {
+        self.current_tab == tab || self.split.is_some_and(|split| split.other == tab)
     }
 
-    pub fn handle_search_backspace(&mut self) {
-        self.search_query.pop();
+    /// Drops the badges of the tabs now on screen.
+    fn clear_seen_badges(&mut self) {
+        let (current, split) = (self.current_tab, self.split);
+        self.tab_badges
+            .retain(|&tab, _| tab != current && split.is_none_or(|split| split.other != tab));
     }
 
-    pub fn show_kill_confirmation(&mut self) {
-        if self.current_tab == Tab::Locker
-            && let Some(process) = self.state.locker.get_selected_process(&self.search_query) {
-                self.modal = Some(Modal::KillConfirmation {
-                    pid: process.pid,
-                    name: process.name.clone(),
-                });
-            }
+    /// What `tab`'s badge counts, as of its latest poll.
+    fn activity(&self, tab: Tab) -> Activity {
+        match tab {
+            Tab::Locker => Activity::default(),
+            Tab::Controller => Activity {
+                changes: self.state.controller.total_changes(),
+                flapping: self.state.controller.flapping_count(),
+                ..Activity::default()
+            },
+            Tab::Nexus => Activity {
+                listeners: self.state.nexus.listeners(),
+                ..Activity::default()
+            },
+        }
     }
 
-    pub fn confirm_kill(&mut self) {
-        if let Some(Modal::KillConfirmation { pid, .. }) = &self.modal {
-            let pid = *pid;
-            if let Err(e) = sys::process::kill_process(pid) {
-                self.status_message = Some(format!("Failed to kill process: {}", e));
-            } else {
-                self.status_message = Some(format!("Process {} killed", pid));
-                self.refresh_current_tab();
-            }
-        }
-        self.modal = None;
+    pub fn select_next(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_next(&self.search_query);
     }
 
-    pub fn cancel_modal(&mut self) {
-        self.modal = None;
+    pub fn select_prev(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_prev(&self.search_query);
     }
 
-    pub fn open_handle_search(&mut self) {
-        self.modal = Some(Modal::HandleSearch {
-            input: String::new(),
-            results: Vec::new(),
-            selected: 0,
-            loading: false,
-            error: None,
-            is_directory: false,
-            files_scanned: None,
-        });
-        self.handle_search_input_mode = false;
+    pub fn select_page_up(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_page_up(&self.search_query);
     }
 
-    pub fn enter_handle_search_input_mode(&mut self) {
-        self.handle_search_input_mode = true;
+    pub fn select_page_down(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_page_down(&self.search_query);
     }
 
-    pub fn exit_handle_search_input_mode(&mut self) {
-        self.handle_search_input_mode = false;
+    pub fn select_screen_up(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_screen_up(&self.search_query);
     }
 
-    pub fn handle_search_modal_char(&mut self, c: char) {
-        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
-            input.push(c);
-        }
+    pub fn select_screen_down(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_screen_down(&self.search_query);
     }
 
-    pub fn handle_search_modal_backspace(&mut self) {
+    /// `{count}j` / `{count}k`: moves `rows` rows, stopping at the ends instead of wrapping.
+    pub fn move_by(&mut self, rows: usize, down: bool) {
+        let tab = self.state.tab_mut(self.current_tab);
+        if down {
+            tab.move_down(&self.search_query, rows);
+        } else {
+            tab.move_up(&self.search_query, rows);
+        }
+    }
+
+    /// `{count}G`: jumps to the Nth row, 1-based.
+    pub fn select_row(&mut self, row: usize) {
+        let tab = self.state.tab_mut(self.current_tab);
+        tab.select_first(&self.search_query);
+        tab.move_down(&self.search_query, row.saturating_sub(1));
+    }
+
+    // Digits first build a count; a single digit only becomes a preset once it's clear no
+    // motion follows, so typing `15j` doesn't flip presets 1 and 5 on the way
+    const KEY_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(600);
+    const MAX_COUNT: usize = 99_999;
+
+    pub fn push_count_digit(&mut self, digit: char) {
+        let value = digit.to_digit(10).unwrap_or(0) as usize;
+        match self.pending_count {
+            // A leading 0 isn't a count, it clears the preset right away
+            None if value == 0 => self.apply_preset(0),
+            None => {
+                self.pending_count = Some(value);
+                self.pending_since = Instant::now();
+            }
+            Some(count) => {
+                self.pending_count = Some((count * 10 + value).min(Self::MAX_COUNT));
+                self.pending_since = Instant::now();
+            }
+        }
+    }
+
+    /// Takes the pending count for a motion key.
+    pub fn take_count(&mut self) -> Option<usize> {
+        self.pending_count.take()
+    }
+
+    /// Resolves a pending count that no motion used: a single digit applies its preset.
+    pub fn flush_count(&mut self) {
+        if let Some(count) = self.pending_count.take()
+            && count < 10 {
+                self.apply_preset(count);
+            }
+    }
+
+    /// Called on every tick. Resolves a count or mark key that nothing followed in time,
+    /// so a lone digit still applies its preset and a lone `m` or `'` is dropped.
+    pub fn flush_pending_keys(&mut self) {
+        if self.pending_since.elapsed() < Self::KEY_SEQUENCE_TIMEOUT {
+            return;
+        }
+        self.flush_count();
+        self.pending_mark = None;
+    }
+
+    pub fn start_mark(&mut self, prefix: MarkPrefix) {
+        self.pending_count = None;
+        self.pending_mark = Some(prefix);
+        self.pending_since = Instant::now();
+    }
+
+    pub fn finish_mark(&mut self, prefix: MarkPrefix, mark: char) {
+        let tab = self.state.tab_mut(self.current_tab);
+        let result = match prefix {
+            MarkPrefix::Set => tab.set_mark(mark),
+            MarkPrefix::Jump => tab.jump_to_mark(&self.search_query, mark),
+        };
+        match result {
+            Ok(()) if prefix == MarkPrefix::Set => {
+                self.notify(Severity::Info, format!("Mark '{}' set", mark));
+            }
+            Ok(()) => {}
+            Err(e) => self.notify(Severity::Warning, e.to_string()),
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_first(&self.search_query);
+    }
+
+    pub fn select_last(&mut self) {
+        self.state
+            .tab_mut(self.current_tab)
+            .select_last(&self.search_query);
+    }
+
+    fn load_policy(&mut self) {
+        let path = match &self.config.policy_file {
+            Some(path) => path.clone(),
+            None => match policy::default_path() {
+                Ok(path) => path,
+                Err(_) => return,
+            },
+        };
+        let (policy, error) = Policy::load(&path);
+        self.policy = policy;
+        if let Some(e) = error {
+            let message = format!("Policy {}: {} - denying all actions", path.display(), e);
+            self.notify(Severity::Error, message);
+        }
+    }
+
+    /// Whether the policy allows `action` on `target`, saying why not if it doesn't.
+    pub fn permits(&mut self, action: policy::Action, target: &str) -> bool {
+        let allowed = self.policy.allows(action, target);
+        if !allowed {
+            self.notify(Severity::Warning, Policy::denial(action, target));
+        }
+        allowed
+    }
+
+    /// Whether the policy allows everything `action` does.
+    fn permits_action(&mut self, action: &PendingAction) -> bool {
+        use policy::Action;
+        match action {
+            PendingAction::Kill {
+                name,
+                members,
+                dump,
+                ..
+            } => {
+                std::iter::once(name)
+                    .chain(members.iter().map(|member| &member.name))
+                    .all(|name| self.permits(Action::KillProcess, name))
+                    && (dump.is_none() || self.permits(Action::DumpProcess, name))
+            }
+            PendingAction::Dump { name, .. } => self.permits(Action::DumpProcess, name),
+            PendingAction::StartService(name) => self.permits(Action::StartService, name),
+            PendingAction::StopService(name) => self.permits(Action::StopService, name),
+            PendingAction::StopServiceTree { name, dependents } => dependents
+                .iter()
+                .chain(std::iter::once(name))
+                .all(|service| self.permits(Action::StopService, service)),
+            PendingAction::ApplyServiceConfigs(configs) => configs
+                .iter()
+                .all(|config| self.permits(Action::ConfigureService, &config.service_name)),
+            PendingAction::StartCapture(_) => self.permits(Action::Capture, ""),
+            PendingAction::ShutdownLockers(session) => session
+                .names
+                .iter()
+                .all(|name| self.permits(Action::KillProcess, name)),
+            PendingAction::Unlock { lockers, .. } => lockers
+                .iter()
+                .all(|(_, name, _)| self.permits(Action::KillProcess, name)),
+            PendingAction::CloseHandles { name, .. } => self.permits(Action::CloseHandle, name),
+            PendingAction::EndSession { user, .. } => self.permits(Action::EndSession, user),
+            PendingAction::StopCapture(_) | PendingAction::RestartLockers(_) => true,
+        }
+    }
+
+    /// True, after saying so, when read-only mode rules out `action`.
+    pub fn refuse_read_only(&mut self, action: &str) -> bool {
+        if self.read_only {
+            let message = format!("{} is disabled in read-only mode", action);
+            self.notify(Severity::Warning, message);
+        }
+        self.read_only
+    }
+
+    /// Whether Aperture runs elevated and isn't read-only. If not elevated, explains why
+    /// `task` needs admin rights in a modal that offers to relaunch elevated.
+    pub fn require_admin(&mut self, task: AdminTask) -> bool {
+        if self.refuse_read_only(task.action()) {
+            return false;
+        }
+        if self.is_elevated {
+            return true;
+        }
+        self.modal = Some(Modal::ElevationRequired {
+            task,
+            previous: self.modal.take().map(Box::new),
+            error: None,
+        });
+        false
+    }
+
+    /// Closes the elevation modal, back to the modal it was opened from.
+    pub fn close_elevation_required(&mut self) {
+        if let Some(Modal::ElevationRequired { previous, .. }) = self.modal.take() {
+            self.modal = previous.map(|modal| *modal);
+        }
+    }
+
+    /// Starts an elevated copy of Aperture. Returns true once it started and this one
+    /// should exit.
+    pub fn relaunch_elevated(&mut self) -> bool {
+        match sys::shell::relaunch_elevated() {
+            Ok(()) => true,
+            Err(e) => {
+                if let Some(Modal::ElevationRequired { error, .. }) = &mut self.modal {
+                    *error = Some(e.to_string());
+                }
+                false
+            }
+        }
+    }
+
+    /// Starts the selected service if it is stopped, or stops it if it is running.
+    pub fn toggle_selected_service(&mut self) {
+        if let Some(service) = self.state.controller.selected_service(&self.search_query) {
+            let name = service.service_name.clone();
+            let display_name = service.display_name.clone();
+            let status = service.status.clone();
+            if self.state.controller.pending_services.contains_key(&name)
+                || !matches!(status.as_str(), "Running" | "Stopped")
+                || !self.require_admin(AdminTask::ControlService)
+            {
+                return;
+            }
+            match status.as_str() {
+                "Running" => self.request_stop_service(name, display_name),
+                "Stopped" => self.start_action(PendingAction::StartService(name)),
+                _ => {}
+            }
+        }
+    }
+
+    /// Stops a service, first asking for confirmation when other running services depend on it.
+    fn request_stop_service(&mut self, name: String, display_name: String) {
+        match sys::service::active_dependents(&name) {
+            Ok(dependents) if !dependents.is_empty() => {
+                self.modal = Some(Modal::StopDependents {
+                    name,
+                    display_name,
+                    dependents,
+                });
+            }
+            Ok(_) => self.start_guarded_action(PendingAction::StopService(name)),
+            Err(e) => {
+                // Still try the stop - the SCM refuses it if dependents are running
+                self.report_error("Service dependents", &e);
+                self.start_guarded_action(PendingAction::StopService(name));
+            }
+        }
+    }
+
+    pub fn confirm_stop_dependents(&mut self) {
+        if let Some(Modal::StopDependents {
+            name, dependents, ..
+        }) = self.modal.take()
+        {
+            let dependents = dependents.into_iter().map(|d| d.service_name).collect();
+            self.start_guarded_action(PendingAction::StopServiceTree { name, dependents });
+        }
+    }
+
+    /// The protected process or service `action` would kill or stop, if any.
+    fn guarded_name(&self, action: &PendingAction) -> Option<String> {
+        let guardrails = &self.config.guardrails;
+        match action {
+            PendingAction::Kill { name, members, .. } => std::iter::once(name)
+                .chain(members.iter().map(|member| &member.name))
+                .find(|name| guardrails.is_protected_process(name))
+                .cloned(),
+            PendingAction::CloseHandles { name, .. } | PendingAction::Dump { name, .. }
+                if guardrails.is_protected_process(name) =>
+            {
+                Some(name.clone())
+            }
+            PendingAction::StopService(name) if guardrails.is_protected_service(name) => {
+                Some(name.clone())
+            }
+            PendingAction::StopServiceTree { name, dependents } => dependents
+                .iter()
+                .chain(std::iter::once(name))
+                .find(|service| guardrails.is_protected_service(service))
+                .cloned(),
+            PendingAction::ShutdownLockers(session) => session
+                .names
+                .iter()
+                .find(|name| guardrails.is_protected_process(name))
+                .cloned(),
+            PendingAction::Unlock { lockers, .. } => lockers
+                .iter()
+                .map(|(_, name, _)| name)
+                .find(|name| guardrails.is_protected_process(name))
+                .cloned(),
+            PendingAction::ApplyServiceConfigs(configs) => configs
+                .iter()
+                .map(|config| &config.service_name)
+                .find(|name| guardrails.is_protected_service(name))
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    /// Runs `action`, first asking for the name of the protected process or service it
+    /// kills, stops or reconfigures, if there is one.
+    fn start_guarded_action(&mut self, action: PendingAction) {
+        // Don't ask for a name the policy won't act on
+        if !self.permits_action(&action) {
+            return;
+        }
+        match self.guarded_name(&action) {
+            Some(name) => {
+                self.modal = Some(Modal::Guardrail {
+                    name,
+                    typed: String::new(),
+                    action,
+                });
+            }
+            None => self.start_action(action),
+        }
+    }
+
+    pub fn guardrail_char(&mut self, c: char) {
+        if let Some(Modal::Guardrail { typed, .. }) = &mut self.modal {
+            typed.push(c);
+        }
+    }
+
+    pub fn guardrail_backspace(&mut self) {
+        if let Some(Modal::Guardrail { typed, .. }) = &mut self.modal {
+            typed.pop();
+        }
+    }
+
+    /// Runs the guarded action once its name has been typed, ignoring case.
+    pub fn confirm_guardrail(&mut self) {
+        let Some(Modal::Guardrail { name, typed, .. }) = &self.modal else {
+            return;
+        };
+        if !typed.trim().eq_ignore_ascii_case(name) {
+            let message = format!("Type {} to confirm, or Esc to cancel", name);
+            self.notify(Severity::Warning, message);
+            return;
+        }
+        if let Some(Modal::Guardrail { action, .. }) = self.modal.take() {
+            self.start_action(action);
+        }
+    }
+
+    /// Runs a kill or service operation on a blocking task and marks its row as busy.
+    /// The result comes back through `AppEvent::ActionDone`.
+    pub fn start_action(&mut self, action: PendingAction) {
+        // Every background action changes something, so read-only mode rules them all out
+        if self.refuse_read_only("Killing, dumping and changing processes and services")
+            || !self.permits_action(&action)
+        {
+            return;
+        }
+        match &action {
+            PendingAction::Kill { pid, .. }
+            | PendingAction::Dump { pid, .. }
+            | PendingAction::CloseHandles { pid, .. } => {
+                self.state.locker.pending_kills.insert(*pid);
+            }
+            PendingAction::StartService(name) => {
+                self.state.controller.mark_pending(name, "Starting");
+            }
+            PendingAction::StopService(name) => {
+                self.state.controller.mark_pending(name, "Stopping");
+            }
+            PendingAction::StopServiceTree { name, dependents } => {
+                for dependent in dependents {
+                    self.state.controller.mark_pending(dependent, "Stopping");
+                }
+                self.state.controller.mark_pending(name, "Stopping");
+            }
+            PendingAction::ApplyServiceConfigs(configs) => {
+                for config in configs {
+                    self.state.controller.mark_pending(&config.service_name, "Configuring");
+                }
+            }
+            PendingAction::StartCapture(capture) => {
+                self.state.nexus.capture = Some(capture.clone());
+            }
+            PendingAction::StopCapture(_) => {
+                self.state.nexus.capture = None;
+            }
+            PendingAction::Unlock { lockers, .. } => {
+                self.state.locker.pending_kills.extend(lockers.iter().map(|(pid, ..)| *pid));
+            }
+            PendingAction::ShutdownLockers(_)
+            | PendingAction::RestartLockers(_)
+            | PendingAction::EndSession { .. } => {}
+        }
+
+        let Some(tx) = self.event_tx.clone() else {
+            // No event loop to report back to - run inline
+            let result = Self::run_action(&action);
+            self.finish_action(action, result);
+            return;
+        };
+        tokio::task::spawn_blocking(move || {
+            let result = Self::run_action(&action);
+            let _ = tx.blocking_send(AppEvent::ActionDone(action, result));
+        });
+    }
+
+    fn run_action(action: &PendingAction) -> crate::error::Result<()> {
+        match action {
+            PendingAction::Kill {
+                pid,
+                created,
+                options,
+                dump,
+                members,
+                ..
+            } => {
+                if let Some(target) = dump {
+                    sys::dump::write_dump(*pid, &target.path, target.dump_type)?;
+                }
+                sys::process::kill_options(*pid, *created, options, members)
+            }
+            PendingAction::Dump { pid, target, .. } => {
+                sys::dump::write_dump(*pid, &target.path, target.dump_type)
+            }
+            PendingAction::StartService(name) => {
+                sys::service::start_service(name)?;
+                let reached = sys::service::wait_until_running(name, SERVICE_WAIT_TIMEOUT)?;
+                Self::expect_status(name, "Running", &reached)
+            }
+            PendingAction::StopService(name) => Self::stop_and_wait(name),
+            PendingAction::StopServiceTree { name, dependents } => {
+                // Dependents are already in stop order, the requested service goes last
+                for dependent in dependents {
+                    Self::stop_and_wait(dependent)?;
+                }
+                Self::stop_and_wait(name)
+            }
+            PendingAction::ApplyServiceConfigs(configs) => {
+                let failures: Vec<String> = configs
+                    .iter()
+                    .filter_map(|config| sys::service::apply_config(config).err())
+                    .map(|e| e.to_string())
+                    .collect();
+                if failures.is_empty() {
+                    Ok(())
+                } else {
+                    Err(crate::error::ApertureError::Other(format!(
+                        "{} of {} services failed: {}",
+                        failures.len(),
+                        configs.len(),
+                        failures.join("; ")
+                    )))
+                }
+            }
+            PendingAction::StartCapture(capture) => sys::capture::start(capture),
+            PendingAction::StopCapture(capture) => sys::capture::stop(capture),
+            PendingAction::ShutdownLockers(session) => session.shutdown(),
+            PendingAction::RestartLockers(session) => session.restart(),
+            PendingAction::CloseHandles {
+                pid,
+                started,
+                handles,
+                ..
+            } => {
+                sys::handle::close_remote_handles(*pid, *started, handles)
+            }
+            PendingAction::Unlock {
+                paths,
+                lockers,
+                rename,
+            } => {
+                for (pid, name, started) in lockers {
+                    // Without a creation time a reused PID can't be told apart
+                    let Some(started) = started else {
+                        return Err(crate::error::ApertureError::Other(format!(
+                            "Cannot tell whether PID {} is still {}",
+                            pid, name
+                        )));
+                    };
+                    if let Err(e) = sys::process::kill_process(*pid, *started) {
+                        // ERROR_INVALID_PARAMETER - it exited since the search
+                        if e.code() != Some(87) {
+                            return Err(e);
+                        }
+                    }
+                }
+                let paths: Vec<&str> = paths.iter().map(String::as_str).collect();
+                sys::handle::wait_until_unlocked(&paths, UNLOCK_WAIT_TIMEOUT)?;
+                match (rename, paths.as_slice()) {
+                    (Some(new_name), [path]) => sys::handle::rename_path(path, new_name),
+                    _ => paths.iter().try_for_each(|path| sys::handle::delete_path(path)),
+                }
+            }
+            PendingAction::EndSession { id, command, .. } => match command {
+                SessionCommand::Disconnect => sys::logon::disconnect_session(*id),
+                SessionCommand::LogOff => sys::logon::log_off_session(*id),
+            },
+        }
+    }
+
+    // Start/stop only queue the request; wait for the SCM to report the new state
+    fn stop_and_wait(name: &str) -> crate::error::Result<()> {
+        if let Err(e) = sys::service::stop_service(name) {
+            // ERROR_SERVICE_NOT_ACTIVE - it went down on its own, e.g. with an earlier dependent
+            if e.code() != Some(1062) {
+                return Err(e);
+            }
+        }
+        let reached = sys::service::wait_until_stopped(name, SERVICE_WAIT_TIMEOUT)?;
+        Self::expect_status(name, "Stopped", &reached)
+    }
+
+    fn expect_status(name: &str, expected: &str, reached: &str) -> crate::error::Result<()> {
+        if reached == expected {
+            Ok(())
+        } else {
+            Err(crate::error::ApertureError::Other(format!(
+                "{} is still {} after {}s",
+                name,
+                reached,
+                SERVICE_WAIT_TIMEOUT.as_secs()
+            )))
+        }
+    }
+
+    pub fn finish_action(&mut self, action: PendingAction, result: crate::error::Result<()>) {
+        match action {
+            PendingAction::Kill {
+                pid,
+                name,
+                options,
+                dump,
+                ..
+            } => {
+                self.state.locker.pending_kills.remove(&pid);
+                match result {
+                    Ok(()) => {
+                        let what = if options.job {
+                            "Job of process"
+                        } else if options.tree {
+                            "Process tree"
+                        } else {
+                            "Process"
+                        };
+                        let mut message = format!("{} {} ({}) killed", what, pid, name);
+                        if let Some(target) = dump {
+                            message.push_str(&format!(", dump saved to {}", target.path.display()));
+                        }
+                        self.notify(Severity::Success, message);
+                        self.refresh_tab(Tab::Locker);
+                    }
+                    Err(e) => {
+                        let message = if e.is_access_denied() && !options.force {
+                            format!("Access denied killing PID {} - try a force kill", pid)
+                        } else if e.is_access_denied() {
+                            format!("Access denied killing PID {} - it may be protected", pid)
+                        } else {
+                            format!("Failed to kill process: {}", e)
+                        };
+                        self.notify(Severity::Error, message);
+                        self.report_error("Kill process", &e);
+                    }
+                }
+            }
+            PendingAction::Dump { pid, name, target } => {
+                self.state.locker.pending_kills.remove(&pid);
+                match result {
+                    Ok(()) => self.notify(
+                        Severity::Success,
+                        format!("Dump of {} ({}) saved to {}", pid, name, target.path.display()),
+                    ),
+                    Err(e) => {
+                        self.notify(Severity::Error, format!("Failed to dump process: {}", e));
+                        self.report_error("Dump process", &e);
+                    }
+                }
+            }
+            PendingAction::StartService(name) => self.finish_service_action(&name, "started", result),
+            PendingAction::StopService(name) => self.finish_service_action(&name, "stopped", result),
+            PendingAction::StopServiceTree { name, dependents } => {
+                for dependent in &dependents {
+                    self.state.controller.pending_services.remove(dependent);
+                }
+                let verb = format!("stopped along with {} dependent services", dependents.len());
+                self.finish_service_action(&name, &verb, result);
+            }
+            PendingAction::ApplyServiceConfigs(configs) => {
+                for config in &configs {
+                    self.state.controller.pending_services.remove(&config.service_name);
+                }
+                match result {
+                    Ok(()) => self.notify(
+                        Severity::Success,
+                        format!("Applied imported settings to {} services", configs.len()),
+                    ),
+                    Err(e) => {
+                        self.notify(Severity::Error, format!("Import incomplete: {}", e));
+                        self.report_error("Import service configuration", &e);
+                    }
+                }
+                self.refresh_tab(Tab::Controller);
+            }
+            PendingAction::StartCapture(capture) => match result {
+                Ok(()) => self.notify(
+                    Severity::Info,
+                    format!(
+                        "Capturing {} with {} - press C to stop",
+                        capture.target.describe(),
+                        capture.tool.as_str()
+                    ),
+                ),
+                Err(e) => {
+                    self.state.nexus.capture = None;
+                    self.notify(Severity::Error, format!("Capture failed to start: {}", e));
+                    self.report_error("Packet capture", &e);
+                }
+            },
+            PendingAction::StopCapture(capture) => match result {
+                Ok(()) => self.notify(
+                    Severity::Success,
+                    format!("Capture saved to {}", capture.output_path().display()),
+                ),
+                Err(e) => {
+                    self.notify(Severity::Error, format!("Failed to stop capture: {}", e));
+                    self.report_error("Packet capture", &e);
+                }
+            },
+            PendingAction::ShutdownLockers(session) => match result {
+                Ok(()) => {
+                    let names = session.names.join(", ");
+                    self.notify(
+                        Severity::Success,
+                        format!("Shut down {} - press R in the file lock search to restart", names),
+                    );
+                    self.locker_restart = Some(session);
+                    self.execute_handle_search();
+                }
+                Err(e) => {
+                    let names = session.names.join(", ");
+                    let message = format!("Failed to shut down {}: {}", names, e);
+                    self.notify(Severity::Error, message);
+                    self.report_error("Shut down locking process", &e);
+                }
+            },
+            PendingAction::Unlock {
+                paths,
+                lockers,
+                rename,
+            } => {
+                for (pid, ..) in &lockers {
+                    self.state.locker.pending_kills.remove(pid);
+                }
+                let what = match paths.as_slice() {
+                    [path] => path.clone(),
+                    paths => format!("{} paths", paths.len()),
+                };
+                match (result, rename) {
+                    (Ok(()), Some(new_name)) => {
+                        self.notify(Severity::Success, format!("Renamed {} to {}", what, new_name));
+                    }
+                    (Ok(()), None) => self.notify(Severity::Success, format!("Deleted {}", what)),
+                    (Err(e), _) => {
+                        self.notify(Severity::Error, format!("Failed to unlock {}: {}", what, e));
+                        self.report_error("Unlock file", &e);
+                    }
+                }
+                if !lockers.is_empty() {
+                    self.refresh_tab(Tab::Locker);
+                }
+            }
+            PendingAction::CloseHandles {
+                pid, name, handles, ..
+            } => {
+                self.state.locker.pending_kills.remove(&pid);
+                match result {
+                    Ok(()) => {
+                        let message =
+                            format!("Closed {} handles in {} ({})", handles.len(), name, pid);
+                        self.notify(Severity::Success, message);
+                        self.execute_handle_search();
+                    }
+                    Err(e) => {
+                        self.notify(Severity::Error, format!("Failed to close handles: {}", e));
+                        self.report_error("Close handles", &e);
+                    }
+                }
+            }
+            PendingAction::EndSession { id, user, command } => {
+                let who = if user.is_empty() { String::new() } else { format!(" ({})", user) };
+                match result {
+                    Ok(()) => {
+                        let done = match command {
+                            SessionCommand::Disconnect => "Disconnected",
+                            SessionCommand::LogOff => "Logged off",
+                        };
+                        self.notify(Severity::Success, format!("{} session {}{}", done, id, who));
+                        self.refresh_logon_sessions();
+                    }
+                    Err(e) => {
+                        let verb = command.as_str().to_lowercase();
+                        let message = format!("Failed to {} session {}{}: {}", verb, id, who, e);
+                        self.notify(Severity::Error, message);
+                        self.report_error("End session", &e);
+                    }
+                }
+            }
+            PendingAction::RestartLockers(session) => match result {
+                Ok(()) => {
+                    let message = format!("Restarted {}", session.names.join(", "));
+                    self.notify(Severity::Success, message);
+                    self.refresh_tab(Tab::Locker);
+                }
+                Err(e) => {
+                    let message = format!("Failed to restart {}: {}", session.names.join(", "), e);
+                    self.notify(Severity::Error, message);
+                    self.report_error("Restart locking process", &e);
+                }
+            },
+        }
+    }
+
+    /// Checks the watched ports against the listeners the Nexus just read, saying so when
+    /// one goes down or comes back.
+    fn check_port_watches(&mut self) {
+        let connections: Vec<_> = self.state.nexus.table.live_rows().collect();
+        let (down, up) = self.port_watches.check(&connections, &sys::system::local_clock());
+        for message in down {
+            self.notify(Severity::Error, message);
+        }
+        for message in up {
+            self.notify(Severity::Success, message);
+        }
+    }
+
+    /// Watches the selected listener's port and process, or stops watching it if it is.
+    pub fn toggle_port_watch(&mut self) {
+        let Some(conn) = self.state.nexus.selected_connection(&self.search_query) else {
+            return;
+        };
+        if conn.state != "LISTENING" {
+            self.notify(Severity::Warning, "Only listening TCP sockets can be watched");
+            return;
+        }
+        let watch = config::PortWatch {
+            port: conn.local_port,
+            process: conn.process_name.clone(),
+        };
+        let watches = &mut self.config.port_watches;
+        let message = match watches.iter().position(|w| *w == watch) {
+            Some(index) => {
+                watches.remove(index);
+                format!("Stopped watching {}", watch.label())
+            }
+            None => {
+                let message = format!("Watching {} - a banner shows if it stops", watch.label());
+                watches.push(watch);
+                message
+            }
+        };
+        self.port_watches.sync(&self.config.port_watches);
+        self.check_port_watches();
+        match self.save_config() {
+            Ok(()) => self.notify(Severity::Success, message),
+            Err(e) => self.notify(Severity::Error, format!("Failed to save config: {}", e)),
+        }
+    }
+
+    /// Starts a packet capture filtered to the selected connection, or stops the running one.
+    /// The trace goes next to exports, in the Documents folder.
+    pub fn toggle_capture(&mut self) {
+        if !self.require_admin(AdminTask::Capture) {
+            return;
+        }
+        if let Some(capture) = self.state.nexus.capture.clone() {
+            self.start_action(PendingAction::StopCapture(capture));
+            return;
+        }
+        let Some(conn) = self.state.nexus.selected_connection(&self.search_query) else {
+            return;
+        };
+        let target = state::nexus::capture_target(conn);
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = PathBuf::from(format!(
+            "{}aperture_capture_{}.etl",
+            crate::export::export_dir(),
+            timestamp
+        ));
+        let capture = sys::capture::Capture::new(sys::capture::CaptureTool::detect(), target, path);
+        self.start_action(PendingAction::StartCapture(capture));
+    }
+
+    /// Stops a running capture before exit; pktmon and netsh traces outlive the process.
+    pub fn stop_capture_on_exit(&mut self) {
+        if let Some(capture) = self.state.nexus.capture.take()
+            && let Err(e) = sys::capture::stop(&capture) {
+                eprintln!("Failed to stop packet capture: {e}");
+            }
+    }
+
+    fn finish_service_action(&mut self, name: &str, verb: &str, result: crate::error::Result<()>) {
+        self.state.controller.pending_services.remove(name);
+        match result {
+            Ok(()) => self.notify(Severity::Success, format!("Service {} {}", name, verb)),
+            Err(e) => {
+                self.notify(Severity::Error, format!("Failed to toggle service: {}", e));
+                self.report_error("Service control", &e);
+            }
+        }
+        // Refresh either way - a timed-out operation may still have changed the state
+        self.refresh_tab(Tab::Controller);
+    }
+
+    /// Records a failure in the error log, which is shown in the error panel (`!`).
+    pub fn report_error(&mut self, source: &str, error: &crate::error::ApertureError) {
+        self.state
+            .errors
+            .record(source, error.to_string(), error.code());
+    }
+
+    /// Shows a toast and records it in the notification history.
+    pub fn notify(&mut self, severity: Severity, message: impl Into<String>) {
+        self.state.notifications.push(severity, message.into());
+    }
+
+    pub fn open_notifications(&mut self) {
+        self.modal = Some(Modal::Notifications { selected: 0 });
+    }
+
+    pub fn notifications_select_next(&mut self) {
+        let len = self.state.notifications.history.len();
+        if let Some(Modal::Notifications { selected }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + 1) % len;
+            }
+    }
+
+    pub fn notifications_select_prev(&mut self) {
+        let len = self.state.notifications.history.len();
+        if let Some(Modal::Notifications { selected }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + len - 1) % len;
+            }
+    }
+
+    pub fn notifications_select_first(&mut self) {
+        if let Some(Modal::Notifications { selected }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn notifications_select_last(&mut self) {
+        let len = self.state.notifications.history.len();
+        if let Some(Modal::Notifications { selected }) = &mut self.modal {
+            *selected = len.saturating_sub(1);
+        }
+    }
+
+    pub fn clear_notifications(&mut self) {
+        self.state.notifications.clear_history();
+        if let Some(Modal::Notifications { selected }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn open_timings(&mut self) {
+        self.modal = Some(Modal::Timings);
+    }
+
+    pub fn open_error_log(&mut self) {
+        self.state.errors.mark_seen();
+        self.modal = Some(Modal::ErrorLog { selected: 0 });
+    }
+
+    pub fn error_log_select_next(&mut self) {
+        let len = self.state.errors.entries.len();
+        if let Some(Modal::ErrorLog { selected }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + 1) % len;
+            }
+    }
+
+    pub fn error_log_select_prev(&mut self) {
+        let len = self.state.errors.entries.len();
+        if let Some(Modal::ErrorLog { selected }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + len - 1) % len;
+            }
+    }
+
+    pub fn error_log_select_first(&mut self) {
+        if let Some(Modal::ErrorLog { selected }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn error_log_select_last(&mut self) {
+        let len = self.state.errors.entries.len();
+        if let Some(Modal::ErrorLog { selected }) = &mut self.modal {
+            *selected = len.saturating_sub(1);
+        }
+    }
+
+    pub fn clear_error_log(&mut self) {
+        self.state.errors.clear();
+        if let Some(Modal::ErrorLog { selected }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn enter_search_mode(&mut self) {
+        self.search_mode = true;
+        self.search_query.clear();
+    }
+
+    pub fn exit_search_mode(&mut self) {
+        // Store the search query as the active filter before exiting
+        let query = self.search_query.clone();
+        self.state.tab_mut(self.current_tab).set_filter(query);
+        self.search_mode = false;
+        self.search_query.clear();
+    }
+
+    /// `F`: keeps the selected row listed, greyed out, even after it disappears.
+    pub fn toggle_freeze(&mut self) {
+        let Some((label, frozen)) = self
+            .state
+            .tab_mut(self.current_tab)
+            .toggle_freeze(&self.search_query)
+        else {
+            return;
+        };
+        let message = if frozen {
+            format!("Frozen: {}", label)
+        } else {
+            format!("Unfrozen: {}", label)
+        };
+        self.notify(Severity::Info, message);
+    }
+
+    pub fn enter_find_mode(&mut self) {
+        self.find_mode = true;
+        self.find_query.clear();
+    }
+
+    /// Enter keeps the pattern for `n`/`N`; Esc drops it.
+    pub fn exit_find_mode(&mut self, keep: bool) {
+        self.find_mode = false;
+        if !keep {
+            self.find_query.clear();
+        } else if !self.find_query.is_empty() && !self.find(true, true) {
+            self.notify(Severity::Warning, format!("Pattern not found: {}", self.find_query));
+        }
+    }
+
+    pub fn handle_find_char(&mut self, c: char) {
+        self.find_query.push(c);
+        // Incremental: stay on the current row while it still matches
+        self.find(true, true);
+    }
+
+    pub fn handle_find_backspace(&mut self) {
+        self.find_query.pop();
+        if !self.find_query.is_empty() {
+            self.find(true, true);
+        }
+    }
+
+    /// `n` / `N`: the next or previous row matching the find pattern.
+    pub fn find_next(&mut self, forward: bool) {
+        if !self.find(forward, false) {
+            self.notify(Severity::Warning, format!("Pattern not found: {}", self.find_query));
+        }
+    }
+
+    fn find(&mut self, forward: bool, include_current: bool) -> bool {
+        if self.find_query.is_empty() {
+            return false;
+        }
+        self.state.tab_mut(self.current_tab).find(
+            &self.search_query,
+            &self.find_query,
+            forward,
+            include_current,
+        )
+    }
+
+    pub fn clear_current_filter(&mut self) {
+        self.state.tab_mut(self.current_tab).clear_filter();
+    }
+
+    /// Number keys 1-9 toggle the current tab's filter presets; 0 clears the preset.
+    pub fn apply_preset(&mut self, slot: usize) {
+        let tab = self.state.tab(self.current_tab);
+        let presets = tab.presets(&self.config);
+
+        let preset = match slot.checked_sub(1).and_then(|i| presets.get(i)) {
+            // Pressing the active preset's key again turns it off
+            Some(preset) if tab.preset() != Some(preset) => Some(preset.clone()),
+            Some(_) => None,
+            None if slot == 0 => None,
+            None => return,
+        };
+
+        match &preset {
+            Some(p) => self.notify(Severity::Info, format!("Preset: {} ({})", p.name, p.query)),
+            None => self.notify(Severity::Info, "Preset cleared"),
+        }
+        self.state.tab_mut(self.current_tab).set_preset(preset);
+    }
+
+    pub fn has_active_filter(&self) -> bool {
+        self.state.tab(self.current_tab).has_filter()
+    }
+
+    pub fn handle_search_char(&mut self, c: char) {
+        self.search_query.push(c);
+    }
+
+    pub fn handle_search_backspace(&mut self) {
+        self.search_query.pop();
+    }
+
+    pub fn show_kill_confirmation(&mut self) {
+        if self.current_tab == Tab::Locker
+            && let Some(process) = self.state.locker.get_selected_process(&self.search_query) {
+                self.modal = Some(Modal::KillConfirmation(KillConfirmation::new(
+                    process.pid,
+                    process.name.clone(),
+                )));
+            }
+    }
+
+    pub fn confirm_kill(&mut self) {
+        let Some(Modal::KillConfirmation(confirmation)) = &self.modal else {
+            return;
+        };
+        let Some(options) = confirmation.options() else {
+            self.notify(Severity::Warning, "Exit code must be a number up to 4294967295");
+            return;
+        };
+        // A job kill ends the job's processes, not the tree, so only a tree kill is previewed
+        if options.tree && !options.job {
+            match sys::process::process_tree(confirmation.pid) {
+                Ok(tree) => {
+                    let confirmation = confirmation.clone();
+                    self.modal = Some(Modal::KillTreePreview {
+                        confirmation,
+                        tree,
+                        scroll: 0,
+                    });
+                }
+                Err(e) => {
+                    self.notify(Severity::Error, format!("Cannot list child processes: {}", e));
+                }
+            }
+            return;
+        }
+        self.start_kill(None);
+    }
+
+    /// Kills the process in the kill confirmation with the options picked there, and
+    /// `members` with it if they were shown already.
+    fn start_kill(&mut self, members: Option<Vec<sys::process::KillMember>>) {
+        let Some(Modal::KillConfirmation(confirmation)) = &self.modal else {
+            return;
+        };
+        let Some(options) = confirmation.options() else {
+            return;
+        };
+        let dump = if confirmation.dump {
+            match self.dump_target(confirmation.pid, &confirmation.name) {
+                Ok(target) => Some(target),
+                Err(e) => {
+                    self.notify(Severity::Error, format!("Cannot write dump: {}", e));
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        // Whatever the kill takes down with it is checked like the process itself
+        let members = match members.map_or_else(
+            || sys::process::kill_members(confirmation.pid, &options),
+            Ok,
+        ) {
+            Ok(members) => members,
+            Err(e) => {
+                let message = format!("Cannot list the processes the kill takes down: {}", e);
+                self.notify(Severity::Error, message);
+                return;
+            }
+        };
+        if let Some(Modal::KillConfirmation(KillConfirmation {
+            pid, created, name, ..
+        })) = self.modal.take()
+            && !self.state.locker.pending_kills.contains(&pid) {
+                self.start_guarded_action(PendingAction::Kill {
+                    pid,
+                    created,
+                    name,
+                    options,
+                    dump,
+                    members,
+                });
+            }
+    }
+
+    /// Goes ahead with the previewed tree kill, killing the processes shown and no others.
+    pub fn confirm_kill_tree(&mut self) {
+        if let Some(Modal::KillTreePreview { confirmation, tree, .. }) = self.modal.take() {
+            self.modal = Some(Modal::KillConfirmation(confirmation));
+            self.start_kill(Some(tree));
+        }
+    }
+
+    /// Backs out of the tree preview to the kill options.
+    pub fn cancel_kill_tree(&mut self) {
+        if let Some(Modal::KillTreePreview { confirmation, .. }) = self.modal.take() {
+            self.modal = Some(Modal::KillConfirmation(confirmation));
+        }
+    }
+
+    pub fn kill_tree_scroll(&mut self, down: bool) {
+        if let Some(Modal::KillTreePreview { tree, scroll, .. }) = &mut self.modal {
+            *scroll = if down {
+                (*scroll + 1).min(tree.len().saturating_sub(1))
+            } else {
+                scroll.saturating_sub(1)
+            };
+        }
+    }
+
+    pub fn toggle_kill_dump(&mut self) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal {
+            confirmation.dump = !confirmation.dump;
+        }
+    }
+
+    /// Dump file for a process in the configured folder, named after it and the current time.
+    fn dump_target(
+        &self,
+        pid: u32,
+        name: &str,
+    ) -> Result<DumpTarget, Box<dyn std::error::Error>> {
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)?
+            .as_secs();
+        let stem = name.strip_suffix(".exe").unwrap_or(name);
+        let file_name = format!("{}_{}_{}.dmp", stem, pid, timestamp);
+        Ok(DumpTarget {
+            path: self.config.dump.dir()?.join(file_name),
+            dump_type: self.config.dump.dump_type,
+        })
+    }
+
+    /// Writes a dump of the selected Locker process without killing it.
+    pub fn dump_selected_process(&mut self) {
+        if self.current_tab != Tab::Locker {
+            return;
+        }
+        let Some((pid, name)) = self
+            .state
+            .locker
+            .get_selected_process(&self.search_query)
+            .map(|p| (p.pid, p.name.clone()))
+        else {
+            return;
+        };
+        if self.state.locker.pending_kills.contains(&pid) {
+            return;
+        }
+        match self.dump_target(pid, &name) {
+            Ok(target) => self.start_guarded_action(PendingAction::Dump { pid, name, target }),
+            Err(e) => self.notify(Severity::Error, format!("Cannot write dump: {}", e)),
+        }
+    }
+
+    pub fn toggle_kill_force(&mut self) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal {
+            confirmation.force = !confirmation.force;
+        }
+    }
+
+    pub fn toggle_kill_tree(&mut self) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal {
+            confirmation.tree = !confirmation.tree;
+        }
+    }
+
+    pub fn toggle_kill_job(&mut self) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal {
+            confirmation.job = !confirmation.job;
+        }
+    }
+
+    pub fn kill_exit_code_char(&mut self, c: char) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal
+            && c.is_ascii_digit()
+            && confirmation.exit_code.len() < 10 {
+                confirmation.exit_code.push(c);
+            }
+    }
+
+    pub fn kill_exit_code_backspace(&mut self) {
+        if let Some(Modal::KillConfirmation(confirmation)) = &mut self.modal {
+            confirmation.exit_code.pop();
+        }
+    }
+
+    pub fn open_create_service(&mut self) {
+        self.modal = Some(Modal::CreateService {
+            name: String::new(),
+            display_name: String::new(),
+            binary_path: String::new(),
+            start_type: 0,
+            field: 0,
+            error: None,
+        });
+    }
+
+    pub fn create_service_next_field(&mut self) {
+        if let Some(Modal::CreateService { field, .. }) = &mut self.modal {
+            *field = (*field + 1) % 4;
+        }
+    }
+
+    pub fn create_service_prev_field(&mut self) {
+        if let Some(Modal::CreateService { field, .. }) = &mut self.modal {
+            *field = (*field + 3) % 4;
+        }
+    }
+
+    pub fn create_service_cycle_start_type(&mut self) {
+        if let Some(Modal::CreateService {
+            start_type, field, ..
+        }) = &mut self.modal
+            && *field == 3 {
+                *start_type = (*start_type + 1) % sys::service::START_TYPES.len();
+            }
+    }
+
+    pub fn create_service_char(&mut self, c: char) {
+        if let Some(Modal::CreateService {
+            name,
+            display_name,
+            binary_path,
+            field,
+            error,
+            ..
+        }) = &mut self.modal
+        {
+            match field {
+                0 => name.push(c),
+                1 => display_name.push(c),
+                2 => binary_path.push(c),
+                _ => {}
+            }
+            *error = None;
+        }
+    }
+
+    pub fn create_service_backspace(&mut self) {
+        if let Some(Modal::CreateService {
+            name,
+            display_name,
+            binary_path,
+            field,
+            ..
+        }) = &mut self.modal
+        {
+            match field {
+                0 => name.pop(),
+                1 => display_name.pop(),
+                2 => binary_path.pop(),
+                _ => None,
+            };
+        }
+    }
+
+    pub fn submit_create_service(&mut self) {
+        if self.refuse_read_only("Creating services") {
+            return;
+        }
+        let Some(Modal::CreateService {
+            name,
+            display_name,
+            binary_path,
+            start_type,
+            error,
+            ..
+        }) = &mut self.modal
+        else {
+            return;
+        };
+
+        let name = name.trim().to_string();
+        let binary_path = binary_path.trim().to_string();
+        // Display name defaults to the service name, as with sc.exe
+        let display_name = match display_name.trim() {
+            "" => name.clone(),
+            d => d.to_string(),
+        };
+        if name.is_empty() || binary_path.is_empty() {
+            *error = Some("Service name and binary path are required".to_string());
+            return;
+        }
+        if name.contains(['/', '\\']) {
+            *error = Some("Service name cannot contain / or \\".to_string());
+            return;
+        }
+
+        if !self.policy.allows(policy::Action::CreateService, &name) {
+            *error = Some(Policy::denial(policy::Action::CreateService, &name));
+            return;
+        }
+
+        let start = sys::service::START_TYPES[*start_type];
+        match sys::service::create_service(&name, &display_name, &binary_path, start) {
+            Ok(()) => {
+                self.modal = None;
+                self.notify(Severity::Success, format!("Service {} created ({})", name, start));
+                self.refresh_tab(Tab::Controller);
+            }
+            Err(e) => {
+                *error = Some(e.to_string());
+                self.report_error("Create service", &e);
+            }
+        }
+    }
+
+    pub fn open_delete_service(&mut self) {
+        if let Some(service) = self.state.controller.selected_service(&self.search_query) {
+            self.modal = Some(Modal::DeleteService {
+                name: service.service_name.clone(),
+                display_name: service.display_name.clone(),
+                confirm: String::new(),
+                error: None,
+            });
+        }
+    }
+
+    pub fn delete_service_char(&mut self, c: char) {
+        if let Some(Modal::DeleteService { confirm, error, .. }) = &mut self.modal {
+            confirm.push(c);
+            *error = None;
+        }
+    }
+
+    pub fn delete_service_backspace(&mut self) {
+        if let Some(Modal::DeleteService { confirm, .. }) = &mut self.modal {
+            confirm.pop();
+        }
+    }
+
+    pub fn confirm_delete_service(&mut self) {
+        if self.refuse_read_only("Deleting services") {
+            return;
+        }
+        let Some(Modal::DeleteService {
+            name,
+            confirm,
+            error,
+            ..
+        }) = &mut self.modal
+        else {
+            return;
+        };
+
+        if confirm.trim() != name.as_str() {
+            *error = Some(format!("Type \"{}\" to confirm", name));
+            return;
+        }
+
+        if !self.policy.allows(policy::Action::DeleteService, name) {
+            *error = Some(Policy::denial(policy::Action::DeleteService, name));
+            return;
+        }
+
+        let name = name.clone();
+        match sys::service::delete_service(&name) {
+            Ok(()) => {
+                self.modal = None;
+                self.notify(
+                    Severity::Success,
+                    format!("Service {} marked for deletion", name),
+                );
+                self.refresh_tab(Tab::Controller);
+            }
+            Err(e) => {
+                *error = Some(e.to_string());
+                self.report_error("Delete service", &e);
+            }
+        }
+    }
+
+    pub fn cancel_modal(&mut self) {
+        if matches!(self.modal, Some(Modal::HandleSearch { loading: true, .. })) {
+            // Nobody is waiting for the directory scan anymore
+            self.handle_scan.fetch_add(1, Ordering::SeqCst);
+        }
+        self.modal = None;
+    }
+
+    pub fn open_handle_search(&mut self) {
+        self.open_handle_search_with(String::new());
+    }
+
+    /// Opens the file lock search with `path` filled in, ready for Enter.
+    pub fn open_handle_search_with(&mut self, path: String) {
+        self.modal = Some(Modal::HandleSearch {
+            input: path,
+            searched: Vec::new(),
+            results: Vec::new(),
+            selected: 0,
+            loading: false,
+            error: None,
+            is_directory: false,
+            files_scanned: None,
+            scan_total: None,
+        });
+        self.handle_search_input_mode = false;
+        self.handle_search_recall = None;
+    }
+
+    /// Fills the file lock search with the file the last export wrote, which a
+    /// spreadsheet or editor may still hold open.
+    pub fn fill_handle_search_from_export(&mut self) {
+        let Some(path) = self.last_export.clone() else {
+            self.notify(Severity::Warning, "Nothing exported yet");
+            return;
+        };
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
+            *input = path;
+        }
+    }
+
+    /// Closes the file lock search and selects the chosen locking process in the Locker.
+    pub fn go_to_locking_process(&mut self) {
+        let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &self.modal
+        else {
+            return;
+        };
+        let Some(pid) = results.get(*selected).map(|p| p.pid) else {
+            return;
+        };
+        self.modal = None;
+        self.go_to(GoToTarget::Process(pid));
+    }
+
+    /// Which of how many matches Tab is showing, while cycling through them.
+    pub fn handle_search_completion(&self) -> Option<(usize, usize)> {
+        let (candidates, shown) = self.handle_search_completion.as_ref()?;
+        let Some(Modal::HandleSearch { input, .. }) = &self.modal else {
+            return None;
+        };
+        let partial = input.rsplit('\n').next().unwrap_or("");
+        (candidates[*shown] == partial).then_some((*shown + 1, candidates.len()))
+    }
+
+    /// Starts another path below the one being typed.
+    pub fn handle_search_new_path(&mut self) {
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal
+            && !input.is_empty()
+            && !input.ends_with('\n')
+        {
+            input.push('\n');
+        }
+    }
+
+    /// Removes the path being typed, going back to the end of the one above.
+    pub fn handle_search_remove_path(&mut self) {
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
+            let start = input.trim_end_matches('\n').rfind('\n').unwrap_or(0);
+            input.truncate(start);
+        }
+    }
+
+    /// Completes the path being typed from the file system. A second Tab cycles through
+    /// the matches when they share nothing more.
+    pub fn complete_handle_search_path(&mut self) {
+        let Some(Modal::HandleSearch { input, .. }) = &mut self.modal else {
+            return;
+        };
+        let start = input.rfind('\n').map_or(0, |i| i + 1);
+        let partial = &input[start..];
+        let completed = match &mut self.handle_search_completion {
+            // Still showing a completion: move on to the next
+            Some((candidates, shown)) if candidates[*shown] == partial => {
+                *shown = (*shown + 1) % candidates.len();
+                candidates[*shown].clone()
+            }
+            _ => {
+                let candidates = complete_path(partial);
+                let prefix = common_prefix(&candidates);
+                match candidates.len() {
+                    0 => return,
+                    1 => candidates[0].clone(),
+                    _ if prefix.len() > partial.len() => prefix,
+                    _ => {
+                        let first = candidates[0].clone();
+                        self.handle_search_completion = Some((candidates, 0));
+                        first
+                    }
+                }
+            }
+        };
+        input.replace_range(start.., &completed);
+    }
+
+    /// Replaces the path being typed with an older (Up) or newer (Down) recent search,
+    /// going back to an empty path past the newest.
+    pub fn recall_handle_search(&mut self, older: bool) {
+        let recent = &self.config.handle_search.recent;
+        let recall = match (self.handle_search_recall, older) {
+            (None, true) if !recent.is_empty() => Some(0),
+            (None, _) => return,
+            (Some(i), true) => Some((i + 1).min(recent.len() - 1)),
+            (Some(0), false) => None,
+            (Some(i), false) => Some(i - 1),
+        };
+        let path = recall.map(|i| recent[i].clone()).unwrap_or_default();
+        self.handle_search_recall = recall;
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
+            *input = path;
+        }
+    }
+
+    /// Moves a searched path to the front of the recent searches.
+    fn remember_handle_search(&mut self, path: &str) {
+        let recent = &mut self.config.handle_search.recent;
+        recent.retain(|p| p != path);
+        recent.insert(0, path.to_string());
+        recent.truncate(config::HandleSearchConfig::RECENT_LEN);
+        if let Err(e) = self.save_config() {
+            self.notify(Severity::Error, format!("Failed to save config: {}", e));
+        }
+    }
+
+    /// Saves the path in the handle search as a favorite, or removes it if it is one.
+    pub fn toggle_handle_search_favorite(&mut self) {
+        let Some(Modal::HandleSearch { input, .. }) = &self.modal else {
+            return;
+        };
+        let path = input.trim().to_string();
+        if path.is_empty() {
+            return;
+        }
+        let favorites = &mut self.config.handle_search.favorites;
+        let message = match favorites.iter().position(|p| *p == path) {
+            Some(index) => {
+                favorites.remove(index);
+                format!("Removed {} from favorites", path)
+            }
+            None => {
+                favorites.push(path.clone());
+                format!("Saved {} as favorite {}", path, favorites.len())
+            }
+        };
+        match self.save_config() {
+            Ok(()) => self.notify(Severity::Success, message),
+            Err(e) => self.notify(Severity::Error, format!("Failed to save config: {}", e)),
+        }
+    }
+
+    /// Searches the `number`th favorite, counting from 1.
+    pub fn search_handle_favorite(&mut self, number: usize) {
+        let Some(path) = self.config.handle_search.favorites.get(number - 1).cloned() else {
+            return;
+        };
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
+            *input = path;
+        }
+        self.execute_handle_search();
+    }
+
+    pub fn enter_handle_search_input_mode(&mut self) {
+        self.handle_search_input_mode = true;
+    }
+
+    pub fn exit_handle_search_input_mode(&mut self) {
+        self.handle_search_input_mode = false;
+    }
+
+    pub fn handle_search_modal_char(&mut self, c: char) {
+        self.handle_search_recall = None;
+        if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
+            input.push(c);
+        }
+    }
+
+    pub fn handle_search_modal_backspace(&mut self) {
+        self.handle_search_recall = None;
         if let Some(Modal::HandleSearch { input, .. }) = &mut self.modal {
             input.pop();
         }
     }
 
-    pub fn execute_handle_search(&mut self) {
-        let file_paths: Vec<String> = match &self.modal {
-            Some(Modal::HandleSearch { input, .. }) => input
-                .lines()
-                .filter(|l| !l.is_empty())
-                .map(|s| s.to_string())
-                .collect(),
-            _ => return,
+    pub fn execute_handle_search(&mut self) {
+        let file_paths: Vec<String> = match &self.modal {
+            Some(Modal::HandleSearch { input, .. }) => input
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|s| s.to_string())
+                .collect(),
+            _ => return,
+        };
+
+        if file_paths.is_empty() {
+            if let Some(Modal::HandleSearch { error, .. }) = &mut self.modal {
+                *error = Some("Enter file path(s)".to_string());
+            }
+            return;
+        }
+        if let Some(missing) = file_paths.iter().find(|p| !sys::handle::path_exists(p))
+            && let Some(Modal::HandleSearch { error, .. }) = &mut self.modal
+        {
+            *error = Some(format!("Not found: {}", missing));
+            return;
+        }
+
+        let input_str = file_paths.join("\n");
+        self.handle_search_recall = None;
+        self.remember_handle_search(&input_str);
+        let first_path = file_paths.first().map(|p| p.as_str()).unwrap_or("");
+        let path = std::path::Path::new(first_path);
+
+        // A drive root on its own is searched as a whole volume, for a drive that won't eject
+        let volume = match file_paths.as_slice() {
+            [path] => sys::handle::volume_letter(path),
+            _ => None,
+        };
+        let is_directory = path.is_dir() && volume.is_none();
+
+        self.modal = Some(Modal::HandleSearch {
+            input: input_str.clone(),
+            searched: file_paths.clone(),
+            results: Vec::new(),
+            selected: 0,
+            loading: true,
+            error: None,
+            is_directory,
+            files_scanned: None,
+            scan_total: None,
+        });
+
+        // A new search makes any directory scan still running stop at its next chunk
+        let scan = self.handle_scan.fetch_add(1, Ordering::SeqCst) + 1;
+        if is_directory {
+            self.start_directory_scan(scan, first_path.to_string());
+        } else {
+            let file_refs: Vec<&str> = file_paths.iter().map(|s| s.as_str()).collect();
+            let result = match volume {
+                Some(letter) => sys::handle::find_volume_lockers(letter),
+                None => sys::handle::find_locking_processes(&file_refs),
+            };
+            self.modal = Some(match result {
+                Ok(locking_procs) => Modal::HandleSearch {
+                    input: input_str,
+                    searched: file_paths,
+                    results: locking_procs,
+                    selected: 0,
+                    loading: false,
+                    error: None,
+                    is_directory,
+                    files_scanned: None,
+                    scan_total: None,
+                },
+                Err(e) => Modal::HandleSearch {
+                    input: input_str,
+                    searched: Vec::new(),
+                    results: Vec::new(),
+                    selected: 0,
+                    loading: false,
+                    error: Some(e.to_string()),
+                    is_directory: false,
+                    files_scanned: None,
+                    scan_total: None,
+                },
+            });
+        }
+    }
+
+    /// Scans a directory on a blocking task, streaming what each chunk of files finds back
+    /// through `AppEvent::DirectoryScan` so the modal fills in as it goes.
+    fn start_directory_scan(&mut self, scan: u64, directory: String) {
+        let config = self.config.handle_search.clone();
+        let Some(tx) = self.event_tx.clone() else {
+            // No event loop to report back to - scan inline
+            let result = Self::with_scan_limits(&config, |limits| {
+                sys::handle::find_locking_processes_in_directory(&directory, limits)
+            });
+            let progress = result.map(|(lockers, total)| sys::handle::ScanProgress {
+                lockers,
+                checked: total,
+                total,
+            });
+            self.finish_directory_scan(scan, progress);
+            return;
+        };
+        let current = self.handle_scan.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = Self::with_scan_limits(&config, |limits| {
+                sys::handle::scan_directory(&directory, limits, |progress| {
+                    current.load(Ordering::SeqCst) == scan
+                        && tx.blocking_send(AppEvent::DirectoryScan(scan, Ok(progress))).is_ok()
+                })
+            });
+            if let Err(e) = result {
+                let _ = tx.blocking_send(AppEvent::DirectoryScan(scan, Err(e)));
+            }
+        });
+    }
+
+    /// Runs `scan` with the folder scan limits `config` sets.
+    fn with_scan_limits<T>(
+        config: &config::HandleSearchConfig,
+        scan: impl FnOnce(&sys::handle::ScanLimits) -> T,
+    ) -> T {
+        let exclude = |path: &std::path::Path| config.excludes(path);
+        scan(&sys::handle::ScanLimits {
+            max_files: config.max_files,
+            exclude: &exclude,
+        })
+    }
+
+    /// Adds a chunk of a directory scan to the file lock search, unless it has moved on
+    /// to another search since.
+    pub fn finish_directory_scan(
+        &mut self,
+        scan: u64,
+        result: crate::error::Result<sys::handle::ScanProgress>,
+    ) {
+        if scan != self.handle_scan.load(Ordering::SeqCst) {
+            return;
+        }
+        let Some(Modal::HandleSearch {
+            results,
+            loading,
+            error,
+            files_scanned,
+            scan_total,
+            ..
+        }) = &mut self.modal
+        else {
+            return;
+        };
+        match result {
+            Ok(progress) => {
+                sys::handle::merge_lockers(results, progress.lockers);
+                *files_scanned = Some(progress.checked);
+                *scan_total = Some(progress.total);
+                if progress.checked >= progress.total {
+                    *loading = false;
+                    *scan_total = None;
+                }
+            }
+            Err(e) => {
+                *loading = false;
+                *scan_total = None;
+                *error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn handle_search_modal_select_next(&mut self) {
+        if let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = (*selected + 1) % results.len();
+            }
+    }
+
+    pub fn handle_search_modal_select_prev(&mut self) {
+        if let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = (*selected + results.len() - 1) % results.len();
+            }
+    }
+
+    pub fn handle_search_modal_select_first(&mut self) {
+        if let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = 0;
+            }
+    }
+
+    pub fn handle_search_modal_select_last(&mut self) {
+        if let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = results.len() - 1;
+            }
+    }
+
+    pub fn kill_selected_locking_process(&mut self) {
+        if let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &self.modal
+            && let Some(proc) = results.get(*selected) {
+                let pid = proc.pid;
+                let name = proc.name.clone();
+                self.modal = Some(Modal::KillConfirmation(KillConfirmation::new(pid, name)));
+            }
+    }
+
+    /// Asks the selected locking process to close through Restart Manager, the way an
+    /// installer would, keeping the session to restart it afterwards.
+    pub fn shutdown_selected_locking_process(&mut self) {
+        let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &self.modal
+        else {
+            return;
+        };
+        let Some(process) = results.get(*selected).cloned() else {
+            return;
+        };
+        match sys::handle::RestartSession::open(std::slice::from_ref(&process)) {
+            Ok(session) => {
+                self.start_guarded_action(PendingAction::ShutdownLockers(Arc::new(session)));
+            }
+            Err(e) => {
+                let message = format!("Failed to shut down {}: {}", process.name, e);
+                self.notify(Severity::Error, message);
+                self.report_error("Shut down locking process", &e);
+            }
+        }
+    }
+
+    /// Closes the handles the search found in the selected process, releasing the files
+    /// or keys without killing it. Restart Manager results come without handles.
+    pub fn close_selected_locking_handles(&mut self) {
+        let Some(Modal::HandleSearch {
+            results, selected, ..
+        }) = &self.modal
+        else {
+            return;
+        };
+        let Some(process) = results.get(*selected) else {
+            return;
+        };
+        if process.handles.is_empty() {
+            let message = format!(
+                "No handles to close in {} - search a volume or registry key for them",
+                process.name
+            );
+            self.notify(Severity::Warning, message);
+            return;
+        }
+        let action = PendingAction::CloseHandles {
+            pid: process.pid,
+            name: process.name.clone(),
+            started: process.started,
+            handles: process.handles.clone(),
+        };
+        self.start_guarded_action(action);
+    }
+
+    /// Restarts the processes the file lock search last shut down.
+    pub fn restart_locking_processes(&mut self) {
+        match self.locker_restart.take() {
+            Some(session) => self.start_action(PendingAction::RestartLockers(session)),
+            None => self.notify(Severity::Info, "Nothing shut down to restart"),
+        }
+    }
+
+    /// Asks to kill what locks the searched paths and then delete them, or rename the
+    /// path when `rename` is set. Renaming takes a single path, prefilled as the new name.
+    pub fn unlock_searched_paths(&mut self, rename: bool) {
+        let Some(Modal::HandleSearch {
+            searched,
+            results,
+            loading,
+            ..
+        }) = &self.modal
+        else {
+            return;
+        };
+        // The lockers only cover the paths searched, and only once the search is done
+        if *loading {
+            self.notify(Severity::Warning, "Wait for the search to finish");
+            return;
+        }
+        if searched.is_empty() {
+            self.notify(Severity::Warning, "Search first");
+            return;
+        }
+        let paths = searched.clone();
+        let rename = match (rename, paths.as_slice()) {
+            (false, _) => None,
+            (true, [path]) => Some(
+                std::path::Path::new(path)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+            ),
+            (true, _) => {
+                self.notify(Severity::Warning, "Rename works on a single path");
+                return;
+            }
+        };
+        if paths.iter().any(|path| {
+            sys::handle::is_registry_path(path) || sys::handle::volume_letter(path).is_some()
+        }) {
+            self.notify(Severity::Warning, "Delete and rename work on files and folders");
+            return;
+        }
+        if let Some(missing) = paths.iter().find(|path| !std::path::Path::new(path).exists()) {
+            let message = format!("Not found: {}", missing);
+            self.notify(Severity::Warning, message);
+            return;
+        }
+        self.modal = Some(Modal::Unlock {
+            paths,
+            lockers: results.clone(),
+            rename,
+        });
+    }
+
+    pub fn unlock_char(&mut self, c: char) {
+        if let Some(Modal::Unlock {
+            rename: Some(name), ..
+        }) = &mut self.modal
+        {
+            name.push(c);
+        }
+    }
+
+    pub fn unlock_backspace(&mut self) {
+        if let Some(Modal::Unlock {
+            rename: Some(name), ..
+        }) = &mut self.modal
+        {
+            name.pop();
+        }
+    }
+
+    /// Kills the lockers and deletes or renames in the background, through the guardrail.
+    pub fn confirm_unlock(&mut self) {
+        let Some(Modal::Unlock {
+            paths,
+            lockers,
+            rename,
+        }) = self.modal.take()
+        else {
+            return;
+        };
+        if let Some(name) = &rename
+            && (name.trim().is_empty() || name.contains(['\\', '/', ':']))
+        {
+            self.notify(Severity::Warning, "Enter a file name, without a folder");
+            self.modal = Some(Modal::Unlock {
+                paths,
+                lockers,
+                rename,
+            });
+            return;
+        }
+        let lockers = lockers
+            .into_iter()
+            .map(|locker| (locker.pid, locker.name, locker.started))
+            .collect();
+        self.start_guarded_action(PendingAction::Unlock {
+            paths,
+            lockers,
+            rename: rename.map(|name| name.trim().to_string()),
+        });
+    }
+
+    /// Watches the searched paths until no process locks them, or stops watching them.
+    pub fn toggle_lock_watch(&mut self) {
+        let Some(Modal::HandleSearch { input, .. }) = &self.modal else {
+            return;
+        };
+        let paths: Vec<String> = input
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        if let Some(watch) = &self.lock_watch
+            && watch.paths == paths
+        {
+            let message = format!("Stopped watching {}", watch.label());
+            self.lock_watch = None;
+            self.notify(Severity::Info, message);
+            return;
+        }
+        if paths.is_empty() {
+            if let Some(Modal::HandleSearch { error, .. }) = &mut self.modal {
+                *error = Some("Enter file path(s)".to_string());
+            }
+            return;
+        }
+        let watch = state::lock_watch::LockWatch::new(paths);
+        let message = format!("Watching {} - you'll be told when it's free", watch.label());
+        self.lock_watch = Some(watch);
+        self.notify(Severity::Info, message);
+        self.check_lock_watch();
+    }
+
+    /// Checks the watched paths in the background once the interval has passed.
+    pub fn check_lock_watch(&mut self) {
+        if self.lock_watch.as_ref().is_some_and(|watch| watch.expired()) {
+            self.lock_watch = None;
+        }
+        let interval = Duration::from_secs(self.config.handle_search.watch_interval_secs.max(1));
+        let Some(watch) = self.lock_watch.as_mut().filter(|watch| watch.due(interval)) else {
+            return;
+        };
+        let paths = watch.paths.clone();
+        let config = self.config.handle_search.clone();
+        let Some(tx) = self.event_tx.clone() else {
+            let result = Self::count_lockers(&paths, &config);
+            self.finish_lock_watch(result);
+            return;
+        };
+        watch.checking = true;
+        tokio::task::spawn_blocking(move || {
+            let result = Self::count_lockers(&paths, &config);
+            let _ = tx.blocking_send(AppEvent::LockWatch(result));
+        });
+    }
+
+    /// Processes locking any of `paths`, scanning the files of folders as `config` says and
+    /// all of a volume.
+    fn count_lockers(
+        paths: &[String],
+        config: &config::HandleSearchConfig,
+    ) -> crate::error::Result<usize> {
+        if let [path] = paths
+            && let Some(letter) = sys::handle::volume_letter(path)
+        {
+            return Ok(sys::handle::find_volume_lockers(letter)?.len());
+        }
+        let mut pids = std::collections::HashSet::new();
+        let (folders, files): (Vec<&String>, Vec<&String>) =
+            paths.iter().partition(|path| std::path::Path::new(path).is_dir());
+        for folder in folders {
+            let (lockers, _) = Self::with_scan_limits(config, |limits| {
+                sys::handle::find_locking_processes_in_directory(folder, limits)
+            })?;
+            pids.extend(lockers.iter().map(|locker| locker.pid));
+        }
+        let files: Vec<&str> = files.iter().map(|path| path.as_str()).collect();
+        let lockers = sys::handle::find_locking_processes(&files)?;
+        pids.extend(lockers.iter().map(|locker| locker.pid));
+        Ok(pids.len())
+    }
+
+    pub fn finish_lock_watch(&mut self, result: crate::error::Result<usize>) {
+        let Some(watch) = self.lock_watch.as_mut() else {
+            return;
+        };
+        match result {
+            Ok(lockers) => {
+                if watch.record(lockers) && self.config.handle_search.watch_toast {
+                    let message = format!("{} is free - no process locks it", watch.label());
+                    self.notify(Severity::Success, message);
+                }
+            }
+            Err(e) => {
+                let message = format!("Stopped watching {}: {}", watch.label(), e);
+                self.lock_watch = None;
+                self.notify(Severity::Error, message);
+                self.report_error("Lock watch", &e);
+            }
+        }
+    }
+
+    pub fn refresh_current_tab(&mut self) {
+        self.refresh_tab(self.current_tab);
+    }
+
+    /// No key pressed for `idle_polling.after_secs`.
+    pub fn is_idle(&self) -> bool {
+        let idle = &self.config.idle_polling;
+        idle.enabled && self.last_input.elapsed() >= Duration::from_secs(idle.after_secs)
+    }
+
+    /// Whether a tick of `poll`'s timer should refresh. While idle, ticks are skipped until
+    /// `idle_polling.interval_secs` have passed since the timer last refreshed.
+    pub fn poll_due(&mut self, poll: Poll) -> bool {
+        let now = Instant::now();
+        let interval = Duration::from_secs(self.config.idle_polling.interval_secs);
+        if self.is_idle()
+            && self
+                .last_polls
+                .get(&poll)
+                .is_some_and(|last| now.duration_since(*last) < interval)
+        {
+            return false;
+        }
+        self.last_polls.insert(poll, now);
+        true
+    }
+
+    /// How old data may get before the status bar flags it, allowing for idle polling.
+    pub fn stale_after(&self) -> Duration {
+        if self.is_idle() {
+            RefreshStatus::STALE_AFTER
+                + Duration::from_secs(self.config.idle_polling.interval_secs)
+        } else {
+            RefreshStatus::STALE_AFTER
+        }
+    }
+
+    /// Records a key press. The first one after an idle spell refreshes everything at once,
+    /// since what's shown can be a whole idle interval old.
+    pub fn note_input(&mut self) {
+        let was_idle = self.is_idle();
+        self.last_input = Instant::now();
+        if was_idle {
+            self.refresh_all_tabs();
+            self.update_metrics();
+        }
+    }
+
+    pub fn refresh_all_tabs(&mut self) {
+        // Load data for all tabs so switching is instant
+        for &tab in Tab::all() {
+            self.refresh_tab(tab);
+        }
+    }
+
+    pub fn refresh_tab(&mut self, tab: Tab) {
+        let before = self.activity(tab);
+        let view = self.state.tab_mut(tab);
+        let started = Instant::now();
+        let result = view.refresh();
+        let source = view.source();
+        self.state.timings.record(source, started.elapsed());
+        // The first poll has nothing to compare with, and a tab on screen needs no badge
+        let polled = self.refresh_status.get(&tab).is_some_and(|s| s.succeeded_at.is_some());
+        if polled && !self.is_visible(tab) {
+            let after = self.activity(tab);
+            let badge = self.tab_badges.entry(tab).or_default();
+            badge.record(&before, &after);
+            badge.urgent |= result.is_err();
+        }
+        let status = self.refresh_status.entry(tab).or_default();
+        match &result {
+            Ok(()) => {
+                status.succeeded_at = Some(Instant::now());
+                status.succeeded_clock = Some(sys::system::local_clock());
+                status.error = None;
+            }
+            Err(e) => status.error = Some(e.to_string()),
+        }
+        // A failed poll says nothing about the ports, so watches keep their last state
+        if tab == Tab::Nexus && result.is_ok() {
+            self.check_port_watches();
+        }
+        if let Err(e) = result {
+            self.report_error(source, &e);
+        }
+        self.update_script_columns(tab);
+    }
+
+    /// Recomputes the script columns of `tab` for its current rows on a blocking task,
+    /// since script functions run as long as their time limit lets them. The values come
+    /// back through `AppEvent::ScriptColumns`; a tab still waiting for its last ones is
+    /// skipped.
+    fn update_script_columns(&mut self, tab: Tab) {
+        if self.script_columns_running.contains(&tab) {
+            return;
+        }
+        let scripts = self.scripts.clone();
+        let compute: Box<dyn FnOnce() -> crate::error::Result<ScriptValues> + Send> = match tab {
+            Tab::Locker => {
+                let Some(rows) = script_rows(&scripts, tab, &mut self.state.locker.table) else {
+                    return;
+                };
+                Box::new(move || script_values(&scripts, tab, rows).map(ScriptValues::Locker))
+            }
+            Tab::Controller => {
+                let Some(rows) = script_rows(&scripts, tab, &mut self.state.controller.table)
+                else {
+                    return;
+                };
+                Box::new(move || script_values(&scripts, tab, rows).map(ScriptValues::Controller))
+            }
+            Tab::Nexus => {
+                let Some(rows) = script_rows(&scripts, tab, &mut self.state.nexus.table) else {
+                    return;
+                };
+                Box::new(move || script_values(&scripts, tab, rows).map(ScriptValues::Nexus))
+            }
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            self.finish_script_columns(tab, compute());
+            return;
+        };
+        self.script_columns_running.push(tab);
+        tokio::task::spawn_blocking(move || {
+            let _ = tx.blocking_send(AppEvent::ScriptColumns(tab, compute()));
+        });
+    }
+
+    /// Shows the script column values computed for `tab`. A failing call leaves the
+    /// columns empty.
+    pub fn finish_script_columns(
+        &mut self,
+        tab: Tab,
+        result: crate::error::Result<ScriptValues>,
+    ) {
+        self.script_columns_running.retain(|&running| running != tab);
+        match result {
+            Ok(ScriptValues::Locker(values)) => self.state.locker.table.script_values = values,
+            Ok(ScriptValues::Controller(values)) => {
+                self.state.controller.table.script_values = values
+            }
+            Ok(ScriptValues::Nexus(values)) => self.state.nexus.table.script_values = values,
+            Err(e) => {
+                match tab {
+                    Tab::Locker => self.state.locker.table.script_values.clear(),
+                    Tab::Controller => self.state.controller.table.script_values.clear(),
+                    Tab::Nexus => self.state.nexus.table.script_values.clear(),
+                }
+                self.report_error("Script column", &e);
+            }
+        }
+    }
+
+    pub fn update_metrics(&mut self) {
+        match self.state.timings.time("System metrics", sys::system::sample_system) {
+            Ok(sample) => self.state.perf.update(sample),
+            Err(e) => self.report_error("System metrics", &e),
+        }
+        match self.state.timings.time("Interface metrics", sys::system::sample_interfaces) {
+            Ok(samples) => self.state.nexus.update_interfaces(samples),
+            Err(e) => self.report_error("Interface metrics", &e),
+        }
+
+        self.refresh_thread_view();
+
+        // Update metrics for all processes, not just current tab
+        let rows = &mut self.state.locker.table.rows;
+        let result = self
+            .state
+            .timings
+            .time("Process metrics", || sys::process::update_process_metrics(rows));
+        if let Err(e) = result {
+            self.report_error("Process metrics", &e);
+        }
+        self.state.locker.record_usage();
+        self.state.locker.refresh_tombstones();
+        self.fetch_command_lines();
+        self.scan_jobs();
+        self.check_firewall();
+        // Re-sort if sorted by metrics that change dynamically
+        if matches!(
+            self.state.locker.table.sort_key,
+            state::locker::SortKey::Memory
+                | state::locker::SortKey::PrivateBytes
+                | state::locker::SortKey::Cpu
+                | state::locker::SortKey::Gpu
+                | state::locker::SortKey::GpuMemory
+                | state::locker::SortKey::DiskIo
+                // Start times and object counts are only read with the metrics
+                | state::locker::SortKey::Started
+                | state::locker::SortKey::Handles
+                | state::locker::SortKey::GdiObjects
+                | state::locker::SortKey::UserObjects
+        ) {
+            self.state.locker.sort_processes();
+        }
+
+        if let Some(tx) = &self.metrics_tx {
+            tx.send_replace(crate::metrics::render(&self.state));
+        }
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        self.state.tab_mut(self.current_tab).cycle_sort_key();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.state.tab_mut(self.current_tab).toggle_sort_order();
+    }
+
+    /// Alt+1..9: sort by the Nth header column of the current tab, flipping the order on repeat.
+    pub fn sort_by_column(&mut self, column: usize) {
+        if !self.state.tab_mut(self.current_tab).sort_by_column(column) {
+            self.notify(Severity::Info, format!("Column {} can't be sorted", column));
+        }
+    }
+
+    /// Passes a key to the current tab. Returns false if the tab doesn't use it.
+    pub fn handle_tab_key(&mut self, code: crossterm::event::KeyCode) -> bool {
+        self.state.tab_mut(self.current_tab).handle_key(code)
+    }
+
+    /// Runs the command the current tab binds to `code`, if any.
+    pub fn run_tab_command(&mut self, code: crossterm::event::KeyCode) {
+        let Some(command) = self.state.tab(self.current_tab).command(code) else {
+            return;
+        };
+        match command {
+            TabCommand::ProcessDetails => self.show_process_details(),
+            TabCommand::Kill => {
+                if self.require_admin(AdminTask::KillProcess) {
+                    self.show_kill_confirmation();
+                }
+            }
+            TabCommand::Dump => {
+                if self.require_admin(AdminTask::DumpProcess) {
+                    self.dump_selected_process();
+                }
+            }
+            TabCommand::Threads => self.show_threads(),
+            TabCommand::LogonSessions => self.open_logon_sessions(),
+            TabCommand::ServiceDetails => self.show_service_details(),
+            TabCommand::ToggleService => self.toggle_selected_service(),
+            TabCommand::CreateService => {
+                if self.require_admin(AdminTask::ChangeServices) {
+                    self.open_create_service();
+                }
+            }
+            TabCommand::DeleteService => {
+                if self.require_admin(AdminTask::ChangeServices) {
+                    self.open_delete_service();
+                }
+            }
+            TabCommand::ConnectionDetails => self.show_connection_details(),
+            TabCommand::NamedPipes => self.open_named_pipes(),
+            TabCommand::DnsCache => self.open_dns_cache(),
+            TabCommand::RemoteLookup => self.open_remote_lookup(),
+            TabCommand::ToggleCapture => self.toggle_capture(),
+            TabCommand::TogglePortWatch => self.toggle_port_watch(),
+        }
+    }
+
+    pub fn show_process_details(&mut self) {
+        if self.current_tab == Tab::Locker {
+            if let Some(process) = self.state.locker.get_selected_process(&self.search_query) {
+                let pid = process.pid;
+                let name = process.name.clone();
+                let path = process.path.clone();
+                let parent_pid = process.parent_pid;
+                let start_time = process.start_time;
+                let cpu_usage = if process.cpu_usage > 0.0 {
+                    process.cpu_usage
+                } else {
+                    process.last_cpu_usage
+                };
+                let memory_mb = if process.memory_mb > 0.0 {
+                    process.memory_mb
+                } else {
+                    process.last_memory_mb
+                };
+                
+                // Get detailed info
+                let (command_line, environment, modules, error) = 
+                    sys::process::get_process_details(pid);
+                let windows = self.process_windows(pid);
+                let memory = sys::process::memory_counters(pid).ok();
+                
+                self.modal = Some(Modal::ProcessDetails(ProcessDetails {
+                    pid,
+                    name,
+                    path,
+                    command_line,
+                    environment,
+                    modules,
+                    parent_pid,
+                    cpu_usage,
+                    memory_mb,
+                    memory,
+                    start_time,
+                    error,
+                    windows,
+                    selected_window: 0,
+                }));
+            }
+        }
+    }
+
+    fn process_windows(&mut self, pid: u32) -> Vec<sys::window::WindowInfo> {
+        sys::window::windows_for_pid(pid).unwrap_or_else(|e| {
+            self.report_error("Window list", &e);
+            Vec::new()
+        })
+    }
+
+    pub fn process_window_select_next(&mut self) {
+        if let Some(Modal::ProcessDetails(details)) = &mut self.modal
+            && !details.windows.is_empty() {
+                details.selected_window = (details.selected_window + 1) % details.windows.len();
+            }
+    }
+
+    pub fn process_window_select_prev(&mut self) {
+        if let Some(Modal::ProcessDetails(details)) = &mut self.modal
+            && !details.windows.is_empty() {
+                let len = details.windows.len();
+                details.selected_window = (details.selected_window + len - 1) % len;
+            }
+    }
+
+    fn selected_process_window(&self) -> Option<sys::window::WindowInfo> {
+        match &self.modal {
+            Some(Modal::ProcessDetails(details)) => {
+                details.windows.get(details.selected_window).cloned()
+            }
+            _ => None,
+        }
+    }
+
+    /// Re-reads the window list after an action moved, hid or closed a window.
+    fn refresh_process_windows(&mut self) {
+        let Some(Modal::ProcessDetails(details)) = &self.modal else {
+            return;
+        };
+        let windows = self.process_windows(details.pid);
+        if let Some(Modal::ProcessDetails(details)) = &mut self.modal {
+            details.selected_window = details.selected_window.min(windows.len().saturating_sub(1));
+            details.windows = windows;
+        }
+    }
+
+    pub fn focus_process_window(&mut self) {
+        let Some(window) = self.selected_process_window() else {
+            return;
+        };
+        if let Err(e) = sys::window::bring_to_front(&window) {
+            self.notify(Severity::Warning, e.to_string());
+        }
+        self.refresh_process_windows();
+    }
+
+    pub fn minimize_process_window(&mut self) {
+        let Some(window) = self.selected_process_window() else {
+            return;
+        };
+        sys::window::minimize(&window);
+        self.refresh_process_windows();
+    }
+
+    pub fn close_process_window(&mut self) {
+        if self.refuse_read_only("Closing windows") {
+            return;
+        }
+        let Some(window) = self.selected_process_window() else {
+            return;
+        };
+        let Some(Modal::ProcessDetails(details)) = &self.modal else {
+            return;
+        };
+        let name = details.name.clone();
+        if !self.permits(policy::Action::CloseWindow, &name) {
+            return;
+        }
+        match sys::window::close(&window) {
+            Ok(()) => self.notify(Severity::Info, format!("Asked \"{}\" to close", window.title)),
+            Err(e) => {
+                self.notify(Severity::Error, format!("Failed to close window: {}", e));
+                self.report_error("Close window", &e);
+            }
+        }
+        // WM_CLOSE is only posted, so the window may still be there on this refresh
+        self.refresh_process_windows();
+    }
+
+    pub fn show_threads(&mut self) {
+        if self.current_tab != Tab::Locker {
+            return;
+        }
+        let Some(process) = self.state.locker.get_selected_process(&self.search_query) else {
+            return;
+        };
+        let (threads, error) = match sys::thread::enumerate_threads(process.pid) {
+            Ok(threads) => (threads, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+        self.modal = Some(Modal::Threads(ThreadView {
+            pid: process.pid,
+            name: process.name.clone(),
+            threads,
+            selected: 0,
+            sampled_at: Instant::now(),
+            error,
+        }));
+    }
+
+    /// Re-reads the open thread list, keeping the cursor on the same TID.
+    fn refresh_thread_view(&mut self) {
+        let Some(Modal::Threads(view)) = &mut self.modal else {
+            return;
+        };
+        match sys::thread::enumerate_threads(view.pid) {
+            Ok(mut threads) => {
+                let now = Instant::now();
+                sys::thread::update_cpu_usage(
+                    &mut threads,
+                    &view.threads,
+                    now.duration_since(view.sampled_at),
+                );
+                let selected_tid = view.threads.get(view.selected).map(|t| t.tid);
+                view.selected = selected_tid
+                    .and_then(|tid| threads.iter().position(|t| t.tid == tid))
+                    .unwrap_or(view.selected.min(threads.len().saturating_sub(1)));
+                view.threads = threads;
+                view.sampled_at = now;
+                view.error = None;
+            }
+            Err(e) => view.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn thread_select_next(&mut self) {
+        if let Some(Modal::Threads(view)) = &mut self.modal
+            && !view.threads.is_empty() {
+                view.selected = (view.selected + 1) % view.threads.len();
+            }
+    }
+
+    pub fn thread_select_prev(&mut self) {
+        if let Some(Modal::Threads(view)) = &mut self.modal
+            && !view.threads.is_empty() {
+                view.selected = (view.selected + view.threads.len() - 1) % view.threads.len();
+            }
+    }
+
+    pub fn thread_select_first(&mut self) {
+        if let Some(Modal::Threads(view)) = &mut self.modal {
+            view.selected = 0;
+        }
+    }
+
+    pub fn thread_select_last(&mut self) {
+        if let Some(Modal::Threads(view)) = &mut self.modal {
+            view.selected = view.threads.len().saturating_sub(1);
+        }
+    }
+
+    /// Suspends (or with `resume`, resumes) the selected thread.
+    pub fn set_selected_thread_suspended(&mut self, suspend: bool) {
+        if !matches!(self.modal, Some(Modal::Threads(_)))
+            || self.refuse_read_only("Suspending and resuming threads")
+        {
+            return;
+        }
+        let Some(Modal::Threads(view)) = &self.modal else {
+            return;
+        };
+        let Some(thread) = view.threads.get(view.selected) else {
+            return;
+        };
+        let (pid, tid, name) = (view.pid, thread.tid, view.name.clone());
+        if !self.permits(policy::Action::SuspendThread, &name) {
+            return;
+        }
+        // A suspended csrss or lsass thread can hang the whole session, and there is no
+        // modal to type the name in without closing the thread list
+        if suspend && self.config.guardrails.is_protected_process(&name) {
+            let message = format!("{} is protected; its threads can't be suspended", name);
+            self.notify(Severity::Warning, message);
+            return;
+        }
+        let result = if suspend {
+            sys::thread::suspend_thread(pid, tid)
+        } else {
+            sys::thread::resume_thread(pid, tid)
+        };
+        match result {
+            Ok(count) => {
+                let verb = if suspend { "suspended" } else { "resumed" };
+                self.notify(
+                    Severity::Success,
+                    format!("Thread {} {} (suspend count {})", tid, verb, count),
+                );
+            }
+            Err(e) => {
+                let action = if suspend { "Suspend thread" } else { "Resume thread" };
+                self.notify(Severity::Error, format!("{} failed: {}", action, e));
+                self.report_error(action, &e);
+            }
+        }
+        self.refresh_thread_view();
+    }
+
+    /// Opens the "go to" menu with the jumps available from the selected row.
+    pub fn open_go_to(&mut self) {
+        let mut targets = Vec::new();
+        match self.current_tab {
+            Tab::Locker => {
+                if let Some(process) = self.state.locker.get_selected_process(&self.search_query) {
+                    let pid = process.pid;
+                    if self.state.nexus.table.rows.iter().any(|c| c.pid == pid) {
+                        targets.push(GoToTarget::Connections(pid));
+                    }
+                    if !self.state.controller.services_for_pid(pid).is_empty() {
+                        targets.push(GoToTarget::HostedServices(pid));
+                    }
+                    if let Some(dir) = process
+                        .path
+                        .as_deref()
+                        .and_then(|path| std::path::Path::new(path).parent())
+                    {
+                        targets.push(GoToTarget::LockedFiles(dir.display().to_string()));
+                    }
+                }
+            }
+            Tab::Controller => {
+                if let Some(service) = self.state.controller.selected_service(&self.search_query)
+                    && service.pid != 0
+                {
+                    targets.push(GoToTarget::Process(service.pid));
+                    targets.push(GoToTarget::Connections(service.pid));
+                }
+            }
+            Tab::Nexus => {
+                if let Some(conn) = self.state.nexus.selected_connection(&self.search_query) {
+                    targets.push(GoToTarget::Process(conn.pid));
+                    if let Some(name) = &conn.service_name {
+                        targets.push(GoToTarget::Service(name.clone()));
+                    }
+                }
+            }
+        }
+
+        if targets.is_empty() {
+            self.notify(Severity::Warning, "Nothing to go to from this row");
+            return;
+        }
+        self.modal = Some(Modal::GoTo {
+            targets,
+            selected: 0,
+        });
+    }
+
+    /// Opens the external tools menu with the configured commands the selected row can
+    /// fill in.
+    pub fn open_tools(&mut self) {
+        let mut values = self.state.tab(self.current_tab).tool_values(&self.search_query);
+        if values.is_empty() {
+            self.notify(Severity::Warning, "Nothing selected");
+            return;
+        }
+        // Only the Locker knows paths, so look the owning process up there for the others
+        if !values.iter().any(|(key, _)| *key == "path")
+            && let Some((_, pid)) = values.iter().find(|(key, _)| *key == "pid")
+            && let Some(path) = self
+                .state
+                .locker
+                .table
+                .rows
+                .iter()
+                .find(|process| process.pid.to_string() == *pid)
+                .and_then(|process| process.path.clone())
+        {
+            values.push(("path", path));
+        }
+
+        let mut tools: Vec<ToolCommand> = self
+            .config
+            .tools
+            .commands
+            .iter()
+            .filter(|tool| tool.tabs.is_empty() || tool.tabs.contains(&self.current_tab))
+            .filter_map(|tool| {
+                Some(ToolCommand {
+                    name: tool.name.clone(),
+                    run: ToolRun::Command {
+                        argv: tool.fill(&values)?,
+                        detach: tool.detach,
+                    },
+                })
+            })
+            .collect();
+        tools.extend(self.scripts.actions(self.current_tab).into_iter().map(|(i, action)| {
+            ToolCommand {
+                name: action.name.clone(),
+                run: ToolRun::Script(i),
+            }
+        }));
+        if tools.is_empty() {
+            self.notify(Severity::Warning, "No external tool applies to this row");
+            return;
+        }
+        self.modal = Some(Modal::Tools {
+            tools,
+            values,
+            selected: 0,
+        });
+    }
+
+    pub fn tools_select_next(&mut self) {
+        if let Some(Modal::Tools { tools, selected, .. }) = &mut self.modal
+            && *selected + 1 < tools.len()
+        {
+            *selected += 1;
+        }
+    }
+
+    pub fn tools_select_prev(&mut self) {
+        if let Some(Modal::Tools { selected, .. }) = &mut self.modal {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    /// Runs the chosen tool: detached ones in their own window, others in place of the UI
+    /// until they exit. Script actions run in the background and report when done.
+    pub fn confirm_tool(&mut self) {
+        // Tools and script actions can run anything, including what read-only rules out
+        if self.refuse_read_only("Running tools") {
+            return;
+        }
+        let Some(Modal::Tools { tools, values, selected }) = self.modal.take() else {
+            return;
+        };
+        let Some(tool) = tools.into_iter().nth(selected) else {
+            return;
+        };
+        if !self.permits(policy::Action::RunTool, &tool.name) {
+            return;
+        }
+        match tool.run {
+            ToolRun::Command { argv, detach: false } => {
+                self.suspend_requested = Some(Suspend::Command(argv));
+            }
+            ToolRun::Command { argv, detach: true } => {
+                match sys::shell::start_detached(&argv) {
+                    Ok(()) => self.notify(Severity::Info, format!("Started {}", tool.name)),
+                    Err(e) => self.report_error("External tool", &e),
+                }
+            }
+            ToolRun::Script(action) => {
+                let Some(tx) = self.event_tx.clone() else {
+                    let result = self.scripts.run_action(action, &values);
+                    self.finish_script_action(tool.name, result);
+                    return;
+                };
+                let scripts = self.scripts.clone();
+                tokio::task::spawn_blocking(move || {
+                    let result = scripts.run_action(action, &values);
+                    let _ = tx.blocking_send(AppEvent::ScriptDone(tool.name, result));
+                });
+            }
+        }
+    }
+
+    /// Shows what a script action returned, or that it finished.
+    pub fn finish_script_action(&mut self, name: String, result: crate::error::Result<String>) {
+        match result {
+            Ok(message) if message.is_empty() => {
+                self.notify(Severity::Info, format!("{} done", name))
+            }
+            Ok(message) => self.notify(Severity::Info, message),
+            Err(e) => self.report_error("Script action", &e),
+        }
+    }
+
+    pub fn go_to_select_next(&mut self) {
+        if let Some(Modal::GoTo { targets, selected }) = &mut self.modal
+            && *selected + 1 < targets.len() {
+                *selected += 1;
+            }
+    }
+
+    pub fn go_to_select_prev(&mut self) {
+        if let Some(Modal::GoTo { selected, .. }) = &mut self.modal {
+            *selected = selected.saturating_sub(1);
+        }
+    }
+
+    pub fn confirm_go_to(&mut self) {
+        let Some(Modal::GoTo { targets, selected }) = &self.modal else {
+            return;
+        };
+        let Some(target) = targets.get(*selected).cloned() else {
+            return;
+        };
+        self.modal = None;
+        self.go_to(target);
+    }
+
+    fn go_to(&mut self, target: GoToTarget) {
+        match target {
+            GoToTarget::Process(pid) => {
+                if !self.state.locker.focus_pid(pid) {
+                    self.notify(Severity::Warning, format!("Process {} not found", pid));
+                    return;
+                }
+                self.set_tab(Tab::Locker);
+            }
+            GoToTarget::Connections(pid) => {
+                self.state.nexus.table.active_filter = None;
+                self.state.nexus.set_preset(Some(state::presets::FilterPreset::pid(pid)));
+                self.set_tab(Tab::Nexus);
+            }
+            GoToTarget::Service(name) => {
+                if !self.state.controller.focus_service(&name) {
+                    self.notify(Severity::Warning, format!("Service {} not found", name));
+                    return;
+                }
+                self.set_tab(Tab::Controller);
+            }
+            GoToTarget::HostedServices(pid) => {
+                self.state.controller.table.active_filter = None;
+                self.state.controller.set_preset(Some(state::presets::FilterPreset::pid(pid)));
+                self.set_tab(Tab::Controller);
+            }
+            GoToTarget::LockedFiles(dir) => self.open_handle_search_with(dir),
+        }
+    }
+
+    pub fn show_connection_details(&mut self) {
+        if let Some(conn) = self.state.nexus.selected_connection(&self.search_query) {
+            let conn = conn.clone();
+            let (owner, error) = match sys::network::socket_owner_info(&conn) {
+                Ok(owner) => (Some(owner), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            self.modal = Some(Modal::ConnectionDetails { conn, owner, error });
+        }
+    }
+
+    pub fn open_remote_lookup(&mut self) {
+        let Some(conn) = self.state.nexus.selected_connection(&self.search_query) else {
+            return;
+        };
+        if !state::nexus::has_remote(conn) || state::nexus::remote_scope(&conn.remote_addr) == "none" {
+            self.notify(Severity::Warning, "This socket has no remote address");
+            return;
+        }
+        self.modal = Some(Modal::RemoteLookup(RemoteLookup {
+            ip: conn.remote_addr.clone(),
+            selected: 0,
+            whois: None,
+            running: false,
+            scroll: 0,
+            error: None,
+        }));
+    }
+
+    pub fn remote_lookup_select_next(&mut self) {
+        let len = self.config.lookup.urls.len() + 1;
+        if let Some(Modal::RemoteLookup(lookup)) = &mut self.modal
+            && lookup.selected + 1 < len {
+                lookup.selected += 1;
+            }
+    }
+
+    pub fn remote_lookup_select_prev(&mut self) {
+        if let Some(Modal::RemoteLookup(lookup)) = &mut self.modal {
+            lookup.selected = lookup.selected.saturating_sub(1);
+        }
+    }
+
+    pub fn remote_lookup_scroll(&mut self, down: bool) {
+        if let Some(Modal::RemoteLookup(lookup)) = &mut self.modal
+            && let Some(lines) = &lookup.whois {
+                lookup.scroll = if down {
+                    (lookup.scroll + 10).min(lines.len().saturating_sub(1))
+                } else {
+                    lookup.scroll.saturating_sub(10)
+                };
+            }
+    }
+
+    /// Opens the selected URL in the browser, or starts the whois lookup in the background.
+    pub fn run_remote_lookup(&mut self) {
+        let Some(Modal::RemoteLookup(lookup)) = &mut self.modal else {
+            return;
+        };
+
+        if let Some(target) = self.config.lookup.urls.get(lookup.selected) {
+            let url = target.url.replace("{ip}", &lookup.ip);
+            match sys::shell::open_url(&url) {
+                Ok(()) => {
+                    let message = format!("Opened {} for {}", target.name, lookup.ip);
+                    self.modal = None;
+                    self.notify(Severity::Info, message);
+                }
+                Err(e) => lookup.error = Some(e.to_string()),
+            }
+            return;
+        }
+
+        if lookup.running {
+            return;
+        }
+        lookup.running = true;
+        lookup.error = None;
+        let ip = lookup.ip.clone();
+        let command = self.config.lookup.whois_command.clone();
+        let Some(tx) = self.event_tx.clone() else {
+            let result = sys::shell::run_whois(&command, &ip);
+            self.finish_whois(ip, result);
+            return;
+        };
+        tokio::task::spawn_blocking(move || {
+            let result = sys::shell::run_whois(&command, &ip);
+            let _ = tx.blocking_send(AppEvent::WhoisDone(ip, result));
+        });
+    }
+
+    pub fn finish_whois(&mut self, ip: String, result: crate::error::Result<String>) {
+        // The modal may have been closed or moved on to another address meanwhile
+        if let Some(Modal::RemoteLookup(lookup)) = &mut self.modal
+            && lookup.ip == ip {
+                lookup.running = false;
+                match result {
+                    Ok(output) => {
+                        lookup.whois = Some(output.lines().map(|l| l.to_string()).collect());
+                        lookup.scroll = 0;
+                    }
+                    Err(e) => lookup.error = Some(e.to_string()),
+                }
+                return;
+            }
+        if let Err(e) = result {
+            self.report_error("whois", &e);
+        }
+    }
+
+    /// Reads the command lines of new processes in the background while the Locker shows
+    /// them. Each process is read once; the PEB reads are too slow for every poll.
+    pub fn fetch_command_lines(&mut self) {
+        let locker = &mut self.state.locker;
+        if !locker.show_command_line || locker.fetching_command_lines {
+            return;
+        }
+        let pids = locker.command_lines_to_fetch();
+        if pids.is_empty() {
+            return;
+        }
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        locker.fetching_command_lines = true;
+        tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let command_lines = pids
+                .into_iter()
+                .map(|pid| (pid, sys::process::query_command_line(pid).ok()))
+                .collect();
+            let _ = tx.blocking_send(AppEvent::Timing("Command lines", started.elapsed()));
+            let _ = tx.blocking_send(AppEvent::CommandLines(command_lines));
+        });
+    }
+
+    /// Rescans job objects in the background every few seconds while the Locker groups
+    /// processes by job.
+    pub fn scan_jobs(&mut self) {
+        if !self.state.locker.jobs_due() {
+            return;
+        }
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        self.state.locker.scanning_jobs = true;
+        tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let result = sys::job::enumerate_jobs();
+            let _ = tx.blocking_send(AppEvent::Timing("Job scan", started.elapsed()));
+            let _ = tx.blocking_send(AppEvent::Jobs(result));
+        });
+    }
+
+    pub fn finish_job_scan(&mut self, result: crate::error::Result<Vec<sys::job::JobInfo>>) {
+        match result {
+            Ok(jobs) => self.state.locker.set_jobs(jobs),
+            Err(e) => {
+                // Would fail the same way every few seconds, so stop grouping
+                self.state.locker.job_scan_failed();
+                if self.state.locker.show_jobs {
+                    self.state.locker.toggle_jobs();
+                }
+                self.notify(Severity::Error, format!("Cannot scan job objects: {}", e));
+                self.report_error("Job scan", &e);
+            }
+        }
+    }
+
+    /// Checks listening sockets against the firewall policy in the background.
+    pub fn check_firewall(&mut self) {
+        let Some(listeners) = self.state.nexus.firewall_due() else {
+            return;
+        };
+        let Some(tx) = self.event_tx.clone() else {
+            return;
+        };
+        self.state.nexus.checking_firewall = true;
+        tokio::task::spawn_blocking(move || {
+            let started = Instant::now();
+            let result = sys::firewall::check_listeners(listeners);
+            let _ = tx.blocking_send(AppEvent::Timing("Firewall check", started.elapsed()));
+            let _ = tx.blocking_send(AppEvent::Firewall(result));
+        });
+    }
+
+    pub fn finish_firewall_check(
+        &mut self,
+        result: crate::error::Result<
+            Vec<(sys::firewall::Listener, sys::firewall::FirewallCheck)>,
+        >,
+    ) {
+        match result {
+            Ok(checks) => self.state.nexus.set_firewall(checks),
+            Err(e) => {
+                self.state.nexus.firewall_check_failed();
+                self.report_error("Firewall check", &e);
+            }
+        }
+    }
+
+    /// Fetches what the status bar hint needs for the selected row and isn't loaded yet:
+    /// the service description, or a reverse DNS name for the remote address in the
+    /// background. Called before each draw, so misses are cached to avoid re-querying.
+    pub fn prefetch_selection_hint(&mut self) {
+        match self.current_tab {
+            Tab::Locker => {}
+            Tab::Controller => {
+                let controller = &mut self.state.controller;
+                let Some(name) = controller
+                    .selected_service(&self.search_query)
+                    .map(|s| s.service_name.clone())
+                else {
+                    return;
+                };
+                controller.descriptions.entry(name).or_insert_with_key(|name| {
+                    sys::service::query_description(name).unwrap_or_default()
+                });
+            }
+            Tab::Nexus => {
+                let nexus = &mut self.state.nexus;
+                let Some(ip) = nexus
+                    .selected_connection(&self.search_query)
+                    .map(|c| c.remote_addr.clone())
+                else {
+                    return;
+                };
+                if nexus.host_names.contains_key(&ip)
+                    || !matches!(state::nexus::remote_scope(&ip), "external" | "private")
+                {
+                    return;
+                }
+                let Some(tx) = self.event_tx.clone() else {
+                    return;
+                };
+                nexus.host_names.insert(ip.clone(), None);
+                tokio::task::spawn_blocking(move || {
+                    let host = sys::dns::reverse_lookup(&ip);
+                    let _ = tx.blocking_send(AppEvent::HostResolved(ip, host));
+                });
+            }
+        }
+    }
+
+    pub fn finish_host_lookup(&mut self, ip: String, host: Option<String>) {
+        self.state.nexus.host_names.insert(ip, host);
+    }
+
+    pub fn show_service_details(&mut self) {
+        if let Some(service) = self.state.controller.selected_service(&self.search_query) {
+            let service = service.clone();
+            let (recovery, error) = match sys::service::query_failure_actions(&service.service_name) {
+                Ok(recovery) => (Some(recovery), None),
+                Err(e) => (None, Some(e.to_string())),
+            };
+            self.modal = Some(Modal::ServiceDetails {
+                service,
+                recovery,
+                field: 0,
+                dirty: false,
+                error,
+            });
+        }
+    }
+
+    pub fn service_details_select_next(&mut self) {
+        if let Some(Modal::ServiceDetails { field, .. }) = &mut self.modal {
+            *field = (*field + 1) % 4;
+        }
+    }
+
+    pub fn service_details_select_prev(&mut self) {
+        if let Some(Modal::ServiceDetails { field, .. }) = &mut self.modal {
+            *field = (*field + 3) % 4;
+        }
+    }
+
+    pub fn toggle_recovery_action(&mut self) {
+        if let Some(Modal::ServiceDetails {
+            recovery: Some(recovery),
+            field,
+            dirty,
+            ..
+        }) = &mut self.modal
+            && let Some((action, delay)) = recovery.actions.get_mut(*field)
+        {
+            *action = action.toggle();
+            // A restart with no delay can spin on a crashing service
+            if *action == sys::service::RecoveryAction::Restart && *delay == 0 {
+                *delay = 60_000;
+            }
+            *dirty = true;
+        }
+    }
+
+    /// Steps the selected restart delay by 10 seconds, or the reset period by an hour.
+    pub fn adjust_recovery_value(&mut self, increase: bool) {
+        if let Some(Modal::ServiceDetails {
+            recovery: Some(recovery),
+            field,
+            dirty,
+            ..
+        }) = &mut self.modal
+        {
+            if let Some((_, delay)) = recovery.actions.get_mut(*field) {
+                *delay = if increase {
+                    delay.saturating_add(10_000)
+                } else {
+                    delay.saturating_sub(10_000)
+                };
+            } else if recovery.reset_period_secs == u32::MAX {
+                // "Never" steps down to one day
+                if !increase {
+                    recovery.reset_period_secs = 86_400;
+                }
+            } else {
+                recovery.reset_period_secs = if increase {
+                    recovery.reset_period_secs.saturating_add(3600)
+                } else {
+                    recovery.reset_period_secs.saturating_sub(3600)
+                };
+            }
+            *dirty = true;
+        }
+    }
+
+    pub fn save_service_recovery(&mut self) {
+        if self.refuse_read_only("Changing service recovery") {
+            return;
+        }
+        let Some(Modal::ServiceDetails {
+            service,
+            recovery: Some(recovery),
+            dirty,
+            error,
+            ..
+        }) = &mut self.modal
+        else {
+            return;
+        };
+        if !*dirty {
+            return;
+        }
+        if !self.policy.allows(policy::Action::ConfigureService, &service.service_name) {
+            *error = Some(Policy::denial(policy::Action::ConfigureService, &service.service_name));
+            return;
+        }
+
+        match sys::service::set_failure_actions(&service.service_name, recovery) {
+            Ok(()) => {
+                *dirty = false;
+                *error = None;
+                let name = service.service_name.clone();
+                self.notify(Severity::Success, format!("Recovery options saved for {}", name));
+            }
+            Err(e) => {
+                *error = Some(e.to_string());
+                self.report_error("Service recovery", &e);
+            }
+        }
+    }
+
+    pub fn export_to_json(&mut self) {
+        match crate::export::export_to_json(
+            &self.state.locker,
+            &self.state.controller,
+            &self.state.nexus,
+        ) {
+            Ok(path) => {
+                self.notify(Severity::Success, format!("Exported to {}", path));
+                self.last_export = Some(path);
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    pub fn export_to_csv(&mut self) {
+        match crate::export::export_to_csv(
+            &self.state.locker,
+            &self.state.controller,
+            &self.state.nexus,
+        ) {
+            Ok(path) => {
+                self.notify(Severity::Success, format!("Exported to {}", path));
+                self.last_export = Some(path);
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    /// Writes the start type, account and recovery options of the services the Controller
+    /// shows to a JSON file, for `import_services` to apply elsewhere.
+    pub fn export_service_configs(&mut self) {
+        let names: Vec<String> = self
+            .state
+            .controller
+            .filtered_services(&self.search_query)
+            .into_iter()
+            .map(|(_, s)| s.service_name)
+            .collect();
+        let mut configs = Vec::new();
+        let mut failed = 0;
+        for name in &names {
+            match sys::service::query_config(name) {
+                Ok(config) => configs.push(config),
+                Err(e) => {
+                    failed += 1;
+                    self.report_error("Export service configuration", &e);
+                }
+            }
+        }
+        if configs.is_empty() {
+            self.notify(Severity::Error, "No service configuration could be read");
+            return;
+        }
+
+        let count = configs.len();
+        match crate::export::export_service_configs(configs) {
+            Ok(path) => {
+                let mut message = format!("Exported {} service configurations to {}", count, path);
+                if failed > 0 {
+                    message.push_str(&format!(" ({} couldn't be read, see !)", failed));
+                }
+                self.notify(Severity::Success, message);
+                self.last_export = Some(path);
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    pub fn open_import_services(&mut self) {
+        self.modal = Some(Modal::ImportServices {
+            path: crate::export::export_dir(),
+            loaded: None,
+            error: None,
+        });
+    }
+
+    pub fn import_services_char(&mut self, c: char) {
+        if let Some(Modal::ImportServices {
+            path,
+            loaded: None,
+            error,
+        }) = &mut self.modal
+        {
+            path.push(c);
+            *error = None;
+        }
+    }
+
+    pub fn import_services_backspace(&mut self) {
+        if let Some(Modal::ImportServices {
+            path,
+            loaded: None,
+            ..
+        }) = &mut self.modal
+        {
+            path.pop();
+        }
+    }
+
+    /// Reads the typed file, then lists its services for confirmation.
+    pub fn load_import_services(&mut self) {
+        let Some(Modal::ImportServices {
+            path,
+            loaded,
+            error,
+        }) = &mut self.modal
+        else {
+            return;
+        };
+        // Paths copied from Explorer come quoted
+        let file = PathBuf::from(path.trim().trim_matches('"'));
+        match crate::export::load_service_configs(&file) {
+            Ok(data) if data.services.is_empty() => {
+                *error = Some("The file lists no services".to_string());
+            }
+            Ok(data) => *loaded = Some(data),
+            Err(e) => *error = Some(format!("Cannot read {}: {}", file.display(), e)),
+        }
+    }
+
+    pub fn confirm_import_services(&mut self) {
+        let Some(Modal::ImportServices {
+            loaded: Some(data),
+            ..
+        }) = &self.modal
+        else {
+            return;
+        };
+        let configs = data.services.clone();
+        if !self.require_admin(AdminTask::ChangeServices) {
+            return;
+        }
+        self.modal = None;
+        self.start_guarded_action(PendingAction::ApplyServiceConfigs(configs));
+    }
+
+    pub fn open_port_lookup(&mut self) {
+        self.modal = Some(Modal::PortLookup {
+            input: String::new(),
+            results: Vec::new(),
+            selected: 0,
+            error: None,
+            searched: false,
+        });
+    }
+
+    pub fn port_lookup_char(&mut self, c: char) {
+        // Port numbers only, so j/k and other letters stay free for navigation
+        if let Some(Modal::PortLookup { input, .. }) = &mut self.modal
+            && c.is_ascii_digit()
+            && input.len() < 5
+        {
+            input.push(c);
+        }
+    }
+
+    pub fn port_lookup_backspace(&mut self) {
+        if let Some(Modal::PortLookup { input, .. }) = &mut self.modal {
+            input.pop();
+        }
+    }
+
+    pub fn execute_port_lookup(&mut self) {
+        if let Some(Modal::PortLookup {
+            input,
+            results,
+            selected,
+            error,
+            searched,
+        }) = &mut self.modal
+        {
+            let port = match input.parse::<u16>() {
+                Ok(port) if port > 0 => port,
+                _ => {
+                    *error = Some("Enter a port number (1-65535)".to_string());
+                    return;
+                }
+            };
+
+            match sys::network::find_port_owners(port) {
+                Ok(owners) => {
+                    *results = owners;
+                    *error = None;
+                }
+                Err(e) => {
+                    results.clear();
+                    *error = Some(e.to_string());
+                }
+            }
+            *selected = 0;
+            *searched = true;
+        }
+    }
+
+    pub fn port_lookup_select_next(&mut self) {
+        if let Some(Modal::PortLookup {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = (*selected + 1) % results.len();
+            }
+    }
+
+    pub fn port_lookup_select_prev(&mut self) {
+        if let Some(Modal::PortLookup {
+            results, selected, ..
+        }) = &mut self.modal
+            && !results.is_empty() {
+                *selected = (*selected + results.len() - 1) % results.len();
+            }
+    }
+
+    pub fn kill_selected_port_owner(&mut self) {
+        if let Some(Modal::PortLookup {
+            results, selected, ..
+        }) = &self.modal
+            && let Some(conn) = results.get(*selected) {
+                let pid = conn.pid;
+                let name = conn
+                    .process_name
+                    .clone()
+                    .unwrap_or_else(|| format!("PID {}", pid));
+                self.modal = Some(Modal::KillConfirmation(KillConfirmation::new(pid, name)));
+            }
+    }
+
+    pub fn close_selected_port_connection(&mut self) {
+        if self.refuse_read_only("Closing TCP connections") {
+            return;
+        }
+        let conn = match &self.modal {
+            Some(Modal::PortLookup {
+                results, selected, ..
+            }) => results.get(*selected).cloned(),
+            _ => None,
+        };
+
+        if let Some(conn) = conn {
+            let remote = format!("{}:{}", conn.remote_addr, conn.remote_port);
+            if !self.permits(policy::Action::CloseConnection, &remote) {
+                return;
+            }
+            match sys::network::close_tcp_connection(&conn) {
+                Ok(()) => {
+                    self.notify(Severity::Success, format!(
+                        "Closed {}:{} -> {}:{}",
+                        conn.local_addr, conn.local_port, conn.remote_addr, conn.remote_port
+                    ));
+                    self.execute_port_lookup();
+                }
+                Err(e) => {
+                    self.notify(
+                        Severity::Error,
+                        format!("Failed to close connection: {}", e),
+                    );
+                }
+            }
+        }
+    }
+
+    pub fn open_named_pipes(&mut self) {
+        self.modal = Some(match sys::pipe::enumerate_named_pipes() {
+            Ok(pipes) => Modal::NamedPipes {
+                pipes,
+                selected: 0,
+                error: None,
+            },
+            Err(e) => Modal::NamedPipes {
+                pipes: Vec::new(),
+                selected: 0,
+                error: Some(e.to_string()),
+            },
+        });
+    }
+
+    pub fn refresh_named_pipes(&mut self) {
+        // Keep the cursor on the same pipe name across refreshes
+        let selected_name = match &self.modal {
+            Some(Modal::NamedPipes { pipes, selected, .. }) => {
+                pipes.get(*selected).map(|p| p.name.clone())
+            }
+            _ => return,
+        };
+
+        self.open_named_pipes();
+
+        if let Some(Modal::NamedPipes { pipes, selected, .. }) = &mut self.modal
+            && let Some(name) = selected_name
+            && let Some(idx) = pipes.iter().position(|p| p.name == name)
+        {
+            *selected = idx;
+        }
+    }
+
+    pub fn named_pipes_select_next(&mut self) {
+        if let Some(Modal::NamedPipes { pipes, selected, .. }) = &mut self.modal
+            && !pipes.is_empty() {
+                *selected = (*selected + 1) % pipes.len();
+            }
+    }
+
+    pub fn named_pipes_select_prev(&mut self) {
+        if let Some(Modal::NamedPipes { pipes, selected, .. }) = &mut self.modal
+            && !pipes.is_empty() {
+                *selected = (*selected + pipes.len() - 1) % pipes.len();
+            }
+    }
+
+    pub fn named_pipes_select_first(&mut self) {
+        if let Some(Modal::NamedPipes { selected, .. }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn named_pipes_select_last(&mut self) {
+        if let Some(Modal::NamedPipes { pipes, selected, .. }) = &mut self.modal {
+            *selected = pipes.len().saturating_sub(1);
+        }
+    }
+
+    /// `U`: lists the logon sessions, each with the Locker's processes running in it.
+    pub fn open_logon_sessions(&mut self) {
+        self.modal = Some(match sys::logon::enumerate_sessions() {
+            Ok(mut sessions) => {
+                for process in self.state.locker.live_processes() {
+                    if let Some(id) = sys::logon::process_session(process.pid)
+                        && let Some(session) = sessions.iter_mut().find(|s| s.id == id)
+                    {
+                        session.processes.push((process.pid, process.name.clone()));
+                    }
+                }
+                for session in &mut sessions {
+                    session.processes.sort_by_key(|(_, name)| name.to_lowercase());
+                }
+                Modal::LogonSessions {
+                    sessions,
+                    selected: 0,
+                    error: None,
+                    confirm: None,
+                }
+            }
+            Err(e) => Modal::LogonSessions {
+                sessions: Vec::new(),
+                selected: 0,
+                error: Some(e.to_string()),
+                confirm: None,
+            },
+        });
+    }
+
+    /// Reloads the sessions if their modal is open, keeping the cursor on the same one.
+    pub fn refresh_logon_sessions(&mut self) {
+        let selected_id = match &self.modal {
+            Some(Modal::LogonSessions { sessions, selected, .. }) => {
+                sessions.get(*selected).map(|s| s.id)
+            }
+            _ => return,
+        };
+
+        self.open_logon_sessions();
+
+        if let Some(Modal::LogonSessions { sessions, selected, .. }) = &mut self.modal
+            && let Some(id) = selected_id
+            && let Some(idx) = sessions.iter().position(|s| s.id == id)
+        {
+            *selected = idx;
+        }
+    }
+
+    pub fn logon_sessions_select_next(&mut self) {
+        if let Some(Modal::LogonSessions { sessions, selected, .. }) = &mut self.modal
+            && !sessions.is_empty() {
+                *selected = (*selected + 1) % sessions.len();
+            }
+    }
+
+    pub fn logon_sessions_select_prev(&mut self) {
+        if let Some(Modal::LogonSessions { sessions, selected, .. }) = &mut self.modal
+            && !sessions.is_empty() {
+                *selected = (*selected + sessions.len() - 1) % sessions.len();
+            }
+    }
+
+    pub fn logon_sessions_select_first(&mut self) {
+        if let Some(Modal::LogonSessions { selected, .. }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn logon_sessions_select_last(&mut self) {
+        if let Some(Modal::LogonSessions { sessions, selected, .. }) = &mut self.modal {
+            *selected = sessions.len().saturating_sub(1);
+        }
+    }
+
+    /// `D` / `L`: asks to disconnect or log off the selected session. Other users'
+    /// sessions need admin; Aperture's own only needs read-only mode off.
+    pub fn request_session_command(&mut self, command: SessionCommand) {
+        let Some(Modal::LogonSessions { sessions, selected, .. }) = &self.modal else {
+            return;
+        };
+        let Some(session) = sessions.get(*selected) else {
+            return;
+        };
+        if session.user.is_empty() && command == SessionCommand::LogOff {
+            self.notify(Severity::Warning, "Nobody is logged on to this session");
+            return;
+        }
+        if session.state == "Disconnected" && command == SessionCommand::Disconnect {
+            self.notify(Severity::Warning, "This session is already disconnected");
+            return;
+        }
+        let own = sys::logon::current_session() == Some(session.id);
+        let allowed = if own {
+            !self.refuse_read_only(command.as_str())
+        } else {
+            self.require_admin(AdminTask::EndSession)
+        };
+        if allowed && let Some(Modal::LogonSessions { confirm, .. }) = &mut self.modal {
+            *confirm = Some(command);
+        }
+    }
+
+    pub fn cancel_session_command(&mut self) {
+        if let Some(Modal::LogonSessions { confirm, .. }) = &mut self.modal {
+            *confirm = None;
+        }
+    }
+
+    /// Runs the confirmed disconnect or log off in the background.
+    pub fn confirm_session_command(&mut self) {
+        let Some(Modal::LogonSessions {
+            sessions,
+            selected,
+            confirm,
+            ..
+        }) = &mut self.modal
+        else {
+            return;
+        };
+        let (Some(command), Some(session)) = (confirm.take(), sessions.get(*selected)) else {
+            return;
+        };
+        let action = PendingAction::EndSession {
+            id: session.id,
+            user: session.user.clone(),
+            command,
+        };
+        self.start_guarded_action(action);
+    }
+
+    pub fn open_dns_cache(&mut self) {
+        let show_hosts = matches!(
+            &self.modal,
+            Some(Modal::DnsCache {
+                show_hosts: true,
+                ..
+            })
+        );
+
+        let (entries, cache_error) = match sys::dns::enumerate_dns_cache() {
+            Ok(entries) => (entries, None),
+            Err(e) => (Vec::new(), Some(format!("DNS cache: {}", e))),
+        };
+        let (hosts, hosts_error) = match sys::dns::read_hosts_file() {
+            Ok(hosts) => (hosts, None),
+            Err(e) => (Vec::new(), Some(format!("hosts file: {}", e))),
+        };
+
+        self.modal = Some(Modal::DnsCache {
+            entries,
+            hosts,
+            selected: 0,
+            show_hosts,
+            error: cache_error.or(hosts_error),
+        });
+    }
+
+    pub fn flush_dns_cache(&mut self) {
+        if self.refuse_read_only("Flushing the DNS cache")
+            || !self.permits(policy::Action::FlushDns, "")
+        {
+            return;
+        }
+        match sys::dns::flush_dns_cache() {
+            Ok(()) => {
+                self.notify(Severity::Success, "DNS resolver cache flushed");
+                self.open_dns_cache();
+            }
+            Err(e) => {
+                self.notify(Severity::Error, format!("Failed to flush DNS cache: {}", e));
+            }
+        }
+    }
+
+    pub fn toggle_dns_hosts_view(&mut self) {
+        if let Some(Modal::DnsCache {
+            show_hosts,
+            selected,
+            ..
+        }) = &mut self.modal
+        {
+            *show_hosts = !*show_hosts;
+            *selected = 0;
+        }
+    }
+
+    fn dns_modal_len(&self) -> usize {
+        match &self.modal {
+            Some(Modal::DnsCache {
+                entries,
+                hosts,
+                show_hosts,
+                ..
+            }) => {
+                if *show_hosts {
+                    hosts.len()
+                } else {
+                    entries.len()
+                }
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn dns_select_next(&mut self) {
+        let len = self.dns_modal_len();
+        if let Some(Modal::DnsCache { selected, .. }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + 1) % len;
+            }
+    }
+
+    pub fn dns_select_prev(&mut self) {
+        let len = self.dns_modal_len();
+        if let Some(Modal::DnsCache { selected, .. }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + len - 1) % len;
+            }
+    }
+
+    pub fn dns_select_first(&mut self) {
+        if let Some(Modal::DnsCache { selected, .. }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn dns_select_last(&mut self) {
+        let len = self.dns_modal_len();
+        if let Some(Modal::DnsCache { selected, .. }) = &mut self.modal {
+            *selected = len.saturating_sub(1);
+        }
+    }
+
+    pub fn open_profiles(&mut self) {
+        self.modal = Some(Modal::Profiles {
+            selected: 0,
+            naming: None,
+            error: None,
+        });
+    }
+
+    pub fn profiles_select_next(&mut self) {
+        let len = self.config.profiles.len();
+        if let Some(Modal::Profiles { selected, .. }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + 1) % len;
+            }
+    }
+
+    pub fn profiles_select_prev(&mut self) {
+        let len = self.config.profiles.len();
+        if let Some(Modal::Profiles { selected, .. }) = &mut self.modal
+            && len > 0 {
+                *selected = (*selected + len - 1) % len;
+            }
+    }
+
+    pub fn profiles_select_first(&mut self) {
+        if let Some(Modal::Profiles { selected, .. }) = &mut self.modal {
+            *selected = 0;
+        }
+    }
+
+    pub fn profiles_select_last(&mut self) {
+        let len = self.config.profiles.len();
+        if let Some(Modal::Profiles { selected, .. }) = &mut self.modal {
+            *selected = len.saturating_sub(1);
+        }
+    }
+
+    pub fn is_naming_profile(&self) -> bool {
+        matches!(
+            &self.modal,
+            Some(Modal::Profiles {
+                naming: Some(_),
+                ..
+            })
+        )
+    }
+
+    pub fn start_naming_profile(&mut self) {
+        if let Some(Modal::Profiles { naming, error, .. }) = &mut self.modal {
+            *naming = Some(String::new());
+            *error = None;
+        }
+    }
+
+    pub fn cancel_naming_profile(&mut self) {
+        if let Some(Modal::Profiles { naming, .. }) = &mut self.modal {
+            *naming = None;
+        }
+    }
+
+    pub fn profile_name_char(&mut self, c: char) {
+        if let Some(Modal::Profiles {
+            naming: Some(name), ..
+        }) = &mut self.modal
+        {
+            name.push(c);
+        }
+    }
+
+    pub fn profile_name_backspace(&mut self) {
+        if let Some(Modal::Profiles {
+            naming: Some(name), ..
+        }) = &mut self.modal
+        {
+            name.pop();
+        }
+    }
+
+    /// Saves the current tab, filters, sort settings and layout toggles under the typed name.
+    pub fn save_current_profile(&mut self) {
+        let name = match &self.modal {
+            Some(Modal::Profiles {
+                naming: Some(name), ..
+            }) => name.trim().to_string(),
+            _ => return,
+        };
+
+        if name.is_empty() {
+            if let Some(Modal::Profiles { error, .. }) = &mut self.modal {
+                *error = Some("Enter a profile name".to_string());
+            }
+            return;
+        }
+
+        self.config.upsert_profile(config::Profile {
+            name: name.clone(),
+            tab: self.current_tab,
+            locker: self.state.locker.view(),
+            controller: self.state.controller.view(),
+            nexus: self.state.nexus.view(),
+        });
+
+        let saved = self.save_config();
+        let position = self
+            .config
+            .profiles
+            .iter()
+            .position(|p| p.name == name)
+            .unwrap_or(0);
+
+        if let Some(Modal::Profiles {
+            selected,
+            naming,
+            error,
+        }) = &mut self.modal
+        {
+            *selected = position;
+            *naming = None;
+            *error = saved.err().map(|e| format!("Failed to save config: {}", e));
+        }
+    }
+
+    pub fn apply_selected_profile(&mut self) {
+        let profile = match &self.modal {
+            Some(Modal::Profiles { selected, .. }) => self.config.profiles.get(*selected).cloned(),
+            _ => None,
         };
 
-        if file_paths.is_empty() {
-            if let Some(Modal::HandleSearch { error, .. }) = &mut self.modal {
-                *error = Some("Enter file path(s)".to_string());
+        if let Some(profile) = profile {
+            self.set_tab(profile.tab);
+            self.state.locker.apply_view(&profile.locker);
+            self.state.controller.apply_view(&profile.controller);
+            self.state.nexus.apply_view(&profile.nexus);
+            self.notify(Severity::Info, format!("Loaded profile '{}'", profile.name));
+            self.modal = None;
+        }
+    }
+
+    /// Puts the tabs back as the last session left them. Call once their data is loaded.
+    pub fn restore_session(&mut self) {
+        match crate::session::Session::load() {
+            Ok(Some(session)) => {
+                self.current_tab = session.tab;
+                self.clear_seen_badges();
+                session.apply(&mut self.state);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.notify(Severity::Warning, format!("Could not restore session: {}", e));
             }
+        }
+    }
+
+    pub fn save_session(&self) -> Result<(), Box<dyn std::error::Error>> {
+        crate::session::Session::capture(self.current_tab, &self.state).save()
+    }
+
+    pub fn delete_selected_profile(&mut self) {
+        let index = match &self.modal {
+            Some(Modal::Profiles { selected, .. }) => *selected,
+            _ => return,
+        };
+        if index >= self.config.profiles.len() {
             return;
         }
 
-        let input_str = file_paths.join("\n");
-        let first_path = file_paths.first().map(|p| p.as_str()).unwrap_or("");
-        let path = std::path::Path::new(first_path);
+        self.config.profiles.remove(index);
+        let saved = self.save_config();
+        let len = self.config.profiles.len();
 
-        let is_directory = path.is_dir();
+        if let Some(Modal::Profiles {
+            selected, error, ..
+        }) = &mut self.modal
+        {
+            *selected = (*selected).min(len.saturating_sub(1));
+            *error = saved.err().map(|e| format!("Failed to save config: {}", e));
+        }
+    }
 
-        self.modal = Some(Modal::HandleSearch {
-            input: input_str.clone(),
-            results: Vec::new(),
-            selected: 0,
-            loading: true,
-            error: None,
-            is_directory,
-            files_scanned: None,
+    pub fn open_snapshots(&mut self) {
+        self.modal = Some(match snapshot::list_snapshots() {
+            Ok(snapshots) => Modal::Snapshots {
+                snapshots,
+                selected: 0,
+                base: None,
+                error: None,
+            },
+            Err(e) => Modal::Snapshots {
+                snapshots: Vec::new(),
+                selected: 0,
+                base: None,
+                error: Some(e.to_string()),
+            },
         });
+    }
 
-        if is_directory {
-            let result = sys::handle::find_locking_processes_in_directory(first_path);
-            self.modal = Some(match result {
-                Ok((locking_procs, scanned_count)) => Modal::HandleSearch {
-                    input: input_str,
-                    results: locking_procs,
-                    selected: 0,
-                    loading: false,
-                    error: None,
-                    is_directory,
-                    files_scanned: Some(scanned_count),
-                },
-                Err(e) => Modal::HandleSearch {
-                    input: input_str,
-                    results: Vec::new(),
-                    selected: 0,
-                    loading: false,
-                    error: Some(e.to_string()),
-                    is_directory: false,
-                    files_scanned: None,
-                },
-            });
-        } else {
-            let file_refs: Vec<&str> = file_paths.iter().map(|s| s.as_str()).collect();
-            let result = sys::handle::find_locking_processes(&file_refs);
-            self.modal = Some(match result {
-                Ok(locking_procs) => Modal::HandleSearch {
-                    input: input_str,
-                    results: locking_procs,
-                    selected: 0,
-                    loading: false,
-                    error: None,
-                    is_directory,
-                    files_scanned: None,
-                },
-                Err(e) => Modal::HandleSearch {
-                    input: input_str,
-                    results: Vec::new(),
-                    selected: 0,
-                    loading: false,
-                    error: Some(e.to_string()),
-                    is_directory: false,
-                    files_scanned: None,
-                },
-            });
+    pub fn take_snapshot(&mut self) {
+        let result = snapshot::Snapshot::capture(
+            &self.state.locker,
+            &self.state.controller,
+            &self.state.nexus,
+        )
+        .and_then(|s| s.save());
+
+        match result {
+            Ok(path) => {
+                self.notify(
+                    Severity::Success,
+                    format!("Snapshot saved to {}", path.display()),
+                );
+                self.open_snapshots();
+            }
+            Err(e) => {
+                if let Some(Modal::Snapshots { error, .. }) = &mut self.modal {
+                    *error = Some(format!("Failed to save snapshot: {}", e));
+                }
+            }
         }
     }
 
-    pub fn handle_search_modal_select_next(&mut self) {
-        if let Some(Modal::HandleSearch {
-            results, selected, ..
+    pub fn snapshots_select_next(&mut self) {
+        if let Some(Modal::Snapshots {
+            snapshots, selected, ..
         }) = &mut self.modal
-            && !results.is_empty() {
-                *selected = (*selected + 1) % results.len();
+            && !snapshots.is_empty() {
+                *selected = (*selected + 1) % snapshots.len();
             }
     }
 
-    pub fn handle_search_modal_select_prev(&mut self) {
-        if let Some(Modal::HandleSearch {
-            results, selected, ..
+    pub fn snapshots_select_prev(&mut self) {
+        if let Some(Modal::Snapshots {
+            snapshots, selected, ..
         }) = &mut self.modal
-            && !results.is_empty() {
-                *selected = (*selected + results.len() - 1) % results.len();
+            && !snapshots.is_empty() {
+                *selected = (*selected + snapshots.len() - 1) % snapshots.len();
             }
     }
 
-    pub fn handle_search_modal_select_first(&mut self) {
-        if let Some(Modal::HandleSearch {
-            results, selected, ..
-        }) = &mut self.modal
-            && !results.is_empty() {
-                *selected = 0;
-            }
+    pub fn snapshots_select_first(&mut self) {
+        if let Some(Modal::Snapshots { selected, .. }) = &mut self.modal {
+            *selected = 0;
+        }
     }
 
-    pub fn handle_search_modal_select_last(&mut self) {
-        if let Some(Modal::HandleSearch {
-            results, selected, ..
+    pub fn snapshots_select_last(&mut self) {
+        if let Some(Modal::Snapshots {
+            snapshots, selected, ..
         }) = &mut self.modal
-            && !results.is_empty() {
-                *selected = results.len() - 1;
-            }
+        {
+            *selected = snapshots.len().saturating_sub(1);
+        }
     }
 
-    pub fn kill_selected_locking_process(&mut self) {
-        if let Some(Modal::HandleSearch {
-            results, selected, ..
-        }) = &self.modal
-            && let Some(proc) = results.get(*selected) {
-                let pid = proc.pid;
-                let name = proc.name.clone();
-                self.modal = Some(Modal::KillConfirmation { pid, name });
+    /// Marks the selected snapshot as the baseline for a snapshot-to-snapshot diff.
+    pub fn toggle_snapshot_base(&mut self) {
+        if let Some(Modal::Snapshots {
+            snapshots,
+            selected,
+            base,
+            ..
+        }) = &mut self.modal
+            && !snapshots.is_empty() {
+                *base = if *base == Some(*selected) {
+                    None
+                } else {
+                    Some(*selected)
+                };
             }
     }
 
-    pub fn refresh_current_tab(&mut self) {
-        match self.current_tab {
-            Tab::Locker => {
-                if let Ok(processes) = sys::process::enumerate_processes() {
-                    self.state.locker.update_processes(processes);
+    pub fn delete_selected_snapshot(&mut self) {
+        let path = match &self.modal {
+            Some(Modal::Snapshots {
+                snapshots, selected, ..
+            }) => match snapshots.get(*selected) {
+                Some(s) => s.path.clone(),
+                None => return,
+            },
+            _ => return,
+        };
+
+        match std::fs::remove_file(&path) {
+            Ok(()) => self.open_snapshots(),
+            Err(e) => {
+                if let Some(Modal::Snapshots { error, .. }) = &mut self.modal {
+                    *error = Some(format!("Failed to delete snapshot: {}", e));
                 }
             }
-            Tab::Controller => {
-                if let Ok(services) = sys::service::enumerate_services() {
-                    self.state.controller.update_services(services);
-                }
+        }
+    }
+
+    /// Diffs the selected snapshot against the live state, or against the marked
+    /// baseline snapshot when one is set (always older -> newer).
+    pub fn diff_selected_snapshot(&mut self) {
+        let (selected_file, base_file) = match &self.modal {
+            Some(Modal::Snapshots {
+                snapshots,
+                selected,
+                base,
+                ..
+            }) => match snapshots.get(*selected) {
+                Some(s) => (
+                    s.clone(),
+                    base.filter(|b| b != selected)
+                        .and_then(|b| snapshots.get(b).cloned()),
+                ),
+                None => return,
+            },
+            _ => return,
+        };
+
+        match self.compute_snapshot_diff(&selected_file, base_file.as_ref()) {
+            Ok((title, entries)) => {
+                self.modal = Some(Modal::SnapshotDiff {
+                    title,
+                    entries,
+                    selected: 0,
+                });
             }
-            Tab::Nexus => {
-                if let Ok(connections) = sys::network::enumerate_connections() {
-                    self.state.nexus.update_connections(connections);
+            Err(e) => {
+                if let Some(Modal::Snapshots { error, .. }) = &mut self.modal {
+                    *error = Some(format!("Failed to diff snapshot: {}", e));
                 }
             }
         }
     }
 
-    pub fn refresh_all_tabs(&mut self) {
-        // Load data for all tabs so switching is instant
-        if let Ok(processes) = sys::process::enumerate_processes() {
-            self.state.locker.update_processes(processes);
-        }
-        if let Ok(services) = sys::service::enumerate_services() {
-            self.state.controller.update_services(services);
-        }
-        if let Ok(connections) = sys::network::enumerate_connections() {
-            self.state.nexus.update_connections(connections);
+    fn compute_snapshot_diff(
+        &self,
+        selected_file: &snapshot::SnapshotFile,
+        base_file: Option<&snapshot::SnapshotFile>,
+    ) -> Result<(String, Vec<snapshot::DiffEntry>), Box<dyn std::error::Error>> {
+        let selected_snapshot = snapshot::Snapshot::load(&selected_file.path)?;
+        match base_file {
+            Some(base_file) => {
+                let base_snapshot = snapshot::Snapshot::load(&base_file.path)?;
+                let (older, newer) = if base_snapshot.timestamp <= selected_snapshot.timestamp {
+                    (base_snapshot, selected_snapshot)
+                } else {
+                    (selected_snapshot, base_snapshot)
+                };
+                Ok((
+                    format!("snapshot_{} -> snapshot_{}", older.timestamp, newer.timestamp),
+                    snapshot::diff(&older, &newer),
+                ))
+            }
+            None => {
+                let live = snapshot::Snapshot::capture(
+                    &self.state.locker,
+                    &self.state.controller,
+                    &self.state.nexus,
+                )?;
+                Ok((
+                    format!("snapshot_{} -> live", selected_snapshot.timestamp),
+                    snapshot::diff(&selected_snapshot, &live),
+                ))
+            }
         }
     }
 
-    pub fn update_metrics(&mut self) {
-        // Update metrics for all processes, not just current tab
-        let _ = sys::process::update_process_metrics(&mut self.state.locker.processes);
-        // Re-sort if sorted by metrics that change dynamically
-        if matches!(
-            self.state.locker.sort_key,
-            state::locker::SortKey::Memory | state::locker::SortKey::Cpu
-        ) {
-            self.state.locker.sort_processes();
-        }
+    /// Status of the background recorder service, if it is installed.
+    pub fn recorder_status(&self) -> Option<&str> {
+        self.state
+            .controller
+            .table
+            .rows
+            .iter()
+            .find(|s| s.service_name == crate::service_mode::SERVICE_NAME)
+            .map(|s| s.status.as_str())
     }
 
-    pub fn cycle_sort_key(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.cycle_sort_key(),
-            Tab::Controller => self.state.controller.cycle_sort_key(),
-            Tab::Nexus => self.state.nexus.cycle_sort_key(),
-        }
+    pub fn open_recorder_history(&mut self) {
+        // Enough to cover a busy day without loading the whole log
+        const HISTORY_LIMIT: usize = 5000;
+
+        self.modal = Some(match snapshot::load_history_events(HISTORY_LIMIT) {
+            Ok(events) => Modal::RecorderHistory {
+                events,
+                selected: 0,
+                error: None,
+            },
+            Err(e) => Modal::RecorderHistory {
+                events: Vec::new(),
+                selected: 0,
+                error: Some(e.to_string()),
+            },
+        });
     }
 
-    pub fn toggle_sort_order(&mut self) {
-        match self.current_tab {
-            Tab::Locker => self.state.locker.toggle_sort_order(),
-            Tab::Controller => self.state.controller.toggle_sort_order(),
-            Tab::Nexus => self.state.nexus.toggle_sort_order(),
-        }
+    pub fn recorder_history_select_next(&mut self) {
+        if let Some(Modal::RecorderHistory {
+            events, selected, ..
+        }) = &mut self.modal
+            && !events.is_empty() {
+                *selected = (*selected + 1) % events.len();
+            }
     }
 
-    pub fn toggle_tree_mode(&mut self) {
-        if self.current_tab == Tab::Locker {
-            self.state.locker.toggle_tree_mode();
-        }
+    pub fn recorder_history_select_prev(&mut self) {
+        if let Some(Modal::RecorderHistory {
+            events, selected, ..
+        }) = &mut self.modal
+            && !events.is_empty() {
+                *selected = (*selected + events.len() - 1) % events.len();
+            }
     }
 
-    pub fn toggle_expand(&mut self) {
-        if self.current_tab == Tab::Locker {
-            self.state.locker.toggle_expand();
+    pub fn recorder_history_select_first(&mut self) {
+        if let Some(Modal::RecorderHistory { selected, .. }) = &mut self.modal {
+            *selected = 0;
         }
     }
 
-    pub fn show_process_details(&mut self) {
-        if self.current_tab == Tab::Locker {
-            if let Some(process) = self.state.locker.get_selected_process(&self.search_query) {
-                let pid = process.pid;
-                let name = process.name.clone();
-                let path = process.path.clone();
-                let parent_pid = process.parent_pid;
-                let cpu_usage = if process.cpu_usage > 0.0 {
-                    process.cpu_usage
-                } else {
-                    process.last_cpu_usage
-                };
-                let memory_mb = if process.memory_mb > 0.0 {
-                    process.memory_mb
-                } else {
-                    process.last_memory_mb
-                };
-                
-                // Get detailed info
-                let (command_line, environment, modules, error) = 
-                    sys::process::get_process_details(pid);
-                
-                self.modal = Some(Modal::ProcessDetails(ProcessDetails {
-                    pid,
-                    name,
-                    path,
-                    command_line,
-                    environment,
-                    modules,
-                    parent_pid,
-                    cpu_usage,
-                    memory_mb,
-                    error,
-                }));
-            }
+    pub fn recorder_history_select_last(&mut self) {
+        if let Some(Modal::RecorderHistory {
+            events, selected, ..
+        }) = &mut self.modal
+        {
+            *selected = events.len().saturating_sub(1);
         }
     }
 
-    pub fn export_to_json(&mut self) {
-        match crate::export::export_to_json(
-            &self.state.locker,
-            &self.state.controller,
-            &self.state.nexus,
-        ) {
-            Ok(path) => {
-                self.status_message = Some(format!("Exported to {}", path));
+    pub fn snapshot_diff_select_next(&mut self) {
+        if let Some(Modal::SnapshotDiff {
+            entries, selected, ..
+        }) = &mut self.modal
+            && !entries.is_empty() {
+                *selected = (*selected + 1) % entries.len();
             }
-            Err(e) => {
-                self.status_message = Some(format!("Export failed: {}", e));
+    }
+
+    pub fn snapshot_diff_select_prev(&mut self) {
+        if let Some(Modal::SnapshotDiff {
+            entries, selected, ..
+        }) = &mut self.modal
+            && !entries.is_empty() {
+                *selected = (*selected + entries.len() - 1) % entries.len();
             }
+    }
+
+    pub fn snapshot_diff_select_first(&mut self) {
+        if let Some(Modal::SnapshotDiff { selected, .. }) = &mut self.modal {
+            *selected = 0;
         }
     }
 
-    pub fn export_to_csv(&mut self) {
-        match crate::export::export_to_csv(
-            &self.state.locker,
-            &self.state.controller,
-            &self.state.nexus,
-        ) {
-            Ok(path) => {
-                self.status_message = Some(format!("Exported to {}", path));
-            }
-            Err(e) => {
-                self.status_message = Some(format!("Export failed: {}", e));
-            }
+    pub fn snapshot_diff_select_last(&mut self) {
+        if let Some(Modal::SnapshotDiff {
+            entries, selected, ..
+        }) = &mut self.modal
+        {
+            *selected = entries.len().saturating_sub(1);
         }
     }
 
@@ -569,3 +5130,84 @@ impl App {
         self.modal = Some(Modal::ExportFormat);
     }
 }
+
+/// A row's id and its values as script functions get them.
+type ScriptRow<Id> = (Id, Vec<(&'static str, String)>);
+
+/// Sets `table`'s script column headers and returns its rows for the script functions,
+/// or None, with the values cleared, when `tab` has no script columns.
+fn script_rows<T: state::table::TableRow<K>, K: Copy + PartialEq>(
+    scripts: &crate::scripting::Scripts,
+    tab: Tab,
+    table: &mut state::table::TableState<T, K>,
+) -> Option<Vec<ScriptRow<T::Id>>> {
+    let columns = scripts.columns(tab);
+    table.script_columns = columns
+        .iter()
+        .map(|column| (column.name.clone(), column.width))
+        .collect();
+    if columns.is_empty() {
+        table.script_values.clear();
+        return None;
+    }
+    Some(table.rows.iter().map(|row| (row.id(), row.values())).collect())
+}
+
+/// `tab`'s script column values for `rows`, one call per row and column. Stops at the
+/// first failing call.
+fn script_values<Id: Eq + std::hash::Hash>(
+    scripts: &crate::scripting::Scripts,
+    tab: Tab,
+    rows: Vec<ScriptRow<Id>>,
+) -> crate::error::Result<HashMap<Id, Vec<String>>> {
+    rows.into_iter()
+        .map(|(id, values)| Ok((id, scripts.column_values(tab, &values)?)))
+        .collect()
+}
+
+/// Paths that `partial` can be completed to: the entries of its folder whose names start
+/// with what follows the last separator, ignoring case. Folders end in `\`.
+fn complete_path(partial: &str) -> Vec<String> {
+    let Some(split) = partial.rfind(['\\', '/']) else {
+        return Vec::new();
+    };
+    let (dir, prefix) = partial.split_at(split + 1);
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let prefix = prefix.to_lowercase();
+    let mut candidates: Vec<String> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !name.to_lowercase().starts_with(&prefix) {
+                return None;
+            }
+            let separator = if entry.path().is_dir() { "\\" } else { "" };
+            Some(format!("{}{}{}", dir, name, separator))
+        })
+        .collect();
+    candidates.sort_by_key(|candidate| candidate.to_lowercase());
+    candidates
+}
+
+/// The longest start all `candidates` share, ignoring case, in the first one's case.
+fn common_prefix(candidates: &[String]) -> String {
+    let Some(first) = candidates.first() else {
+        return String::new();
+    };
+    let mut prefix = String::new();
+    for (i, c) in first.chars().enumerate() {
+        let shared = candidates[1..].iter().all(|other| {
+            other
+                .chars()
+                .nth(i)
+                .is_some_and(|o| o.to_lowercase().eq(c.to_lowercase()))
+        });
+        if !shared {
+            break;
+        }
+        prefix.push(c);
+    }
+    prefix
+}
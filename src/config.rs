@@ -0,0 +1,618 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::app::Tab;
+use crate::i18n::Language;
+use crate::state::colors::{self, ColorRule};
+use crate::state::controller::ControllerView;
+use crate::state::locker::{LockerView, ObjectThresholds};
+use crate::state::nexus::NexusView;
+use crate::state::presets::{self, FilterPreset};
+use crate::sys::dump::DumpType;
+use crate::sys::network::ConnectionInfo;
+
+/// A named set of tab, filter, sort and layout settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    pub tab: Tab,
+    pub locker: LockerView,
+    pub controller: ControllerView,
+    pub nexus: NexusView,
+}
+
+impl Profile {
+    /// Filter, sort key and sort order of the tab the profile opens on.
+    pub fn summary(&self) -> (Option<&str>, &'static str, &'static str) {
+        match self.tab {
+            Tab::Locker => (
+                self.locker.filter.as_deref(),
+                self.locker.sort_key.as_str(),
+                self.locker.sort_order.as_str(),
+            ),
+            Tab::Controller => (
+                self.controller.filter.as_deref(),
+                self.controller.sort_key.as_str(),
+                self.controller.sort_order.as_str(),
+            ),
+            Tab::Nexus => (
+                self.nexus.filter.as_deref(),
+                self.nexus.sort_key.as_str(),
+                self.nexus.sort_order.as_str(),
+            ),
+        }
+    }
+}
+
+/// Embedded Prometheus exporter settings. Disabled unless turned on in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_metrics_address")]
+    pub address: String,
+}
+
+fn default_metrics_address() -> String {
+    "127.0.0.1:9182".to_string()
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_metrics_address(),
+        }
+    }
+}
+
+/// Named-pipe JSON-RPC control interface settings. Disabled unless turned on in the config file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_rpc_pipe_name")]
+    pub pipe_name: String,
+}
+
+fn default_rpc_pipe_name() -> String {
+    "aperture".to_string()
+}
+
+impl Default for RpcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pipe_name: default_rpc_pipe_name(),
+        }
+    }
+}
+
+/// Filter presets per tab, bound to the number keys 1-9 in list order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresetConfig {
+    #[serde(default = "presets::default_locker_presets")]
+    pub locker: Vec<FilterPreset>,
+    #[serde(default = "presets::default_controller_presets")]
+    pub controller: Vec<FilterPreset>,
+    #[serde(default = "presets::default_nexus_presets")]
+    pub nexus: Vec<FilterPreset>,
+}
+
+impl Default for PresetConfig {
+    fn default() -> Self {
+        Self {
+            locker: presets::default_locker_presets(),
+            controller: presets::default_controller_presets(),
+            nexus: presets::default_nexus_presets(),
+        }
+    }
+}
+
+/// Row coloring rules per tab, tried in list order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColorConfig {
+    #[serde(default = "colors::default_locker_rules")]
+    pub locker: Vec<ColorRule>,
+    #[serde(default = "colors::default_controller_rules")]
+    pub controller: Vec<ColorRule>,
+    #[serde(default = "colors::default_nexus_rules")]
+    pub nexus: Vec<ColorRule>,
+}
+
+impl Default for ColorConfig {
+    fn default() -> Self {
+        Self {
+            locker: colors::default_locker_rules(),
+            controller: colors::default_controller_rules(),
+            nexus: colors::default_nexus_rules(),
+        }
+    }
+}
+
+/// A web page to check a remote IP in. `{ip}` in the URL is replaced with the address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupUrl {
+    pub name: String,
+    pub url: String,
+}
+
+/// Remote IP lookups offered for Nexus connections.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LookupConfig {
+    #[serde(default = "default_lookup_urls")]
+    pub urls: Vec<LookupUrl>,
+    /// Command line for a local whois, with `{ip}` replaced by the address.
+    #[serde(default = "default_whois_command")]
+    pub whois_command: String,
+}
+
+fn default_lookup_urls() -> Vec<LookupUrl> {
+    [
+        ("AbuseIPDB", "https://www.abuseipdb.com/check/{ip}"),
+        ("Shodan", "https://www.shodan.io/host/{ip}"),
+        ("VirusTotal", "https://www.virustotal.com/gui/ip-address/{ip}"),
+    ]
+    .into_iter()
+    .map(|(name, url)| LookupUrl {
+        name: name.to_string(),
+        url: url.to_string(),
+    })
+    .collect()
+}
+
+fn default_whois_command() -> String {
+    "whois -nobanner {ip}".to_string()
+}
+
+impl Default for LookupConfig {
+    fn default() -> Self {
+        Self {
+            urls: default_lookup_urls(),
+            whois_command: default_whois_command(),
+        }
+    }
+}
+
+/// A command the `x` menu runs for the selected row. `{name}` style placeholders are
+/// replaced with the row's values, e.g. `sc qc {service}`. The program is started
+/// directly, not through a shell.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalTool {
+    pub name: String,
+    pub command: String,
+    /// Tabs the tool is offered on; every tab when empty.
+    #[serde(default)]
+    pub tabs: Vec<Tab>,
+    /// Start it in a window of its own and stay in Aperture, for GUI tools.
+    #[serde(default)]
+    pub detach: bool,
+}
+
+impl ExternalTool {
+    fn new(name: &str, command: &str, detach: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            command: command.to_string(),
+            tabs: Vec::new(),
+            detach,
+        }
+    }
+
+    /// The program and its arguments with their placeholders filled in from `values`, or
+    /// None if the command uses one the row has no value for or is empty. The command is
+    /// split into words before filling, so a value stays inside its argument whatever it
+    /// contains: process command lines and service names are up to whoever started or
+    /// installed them, and must not get to add arguments or shell syntax.
+    pub fn fill(&self, values: &[(&str, String)]) -> Option<Vec<String>> {
+        let argv = split_words(&self.command)
+            .iter()
+            .map(|word| fill_word(word, values))
+            .collect::<Option<Vec<_>>>()?;
+        (!argv.is_empty()).then_some(argv)
+    }
+}
+
+/// Splits a command at spaces and tabs outside double quotes, dropping the quotes.
+fn split_words(command: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut quoted = false;
+    for c in command.chars() {
+        match c {
+            '"' => {
+                quoted = !quoted;
+                // `""` is an empty argument, so the word exists once a quote opens
+                word.get_or_insert_with(String::new);
+            }
+            ' ' | '\t' if !quoted => words.extend(word.take()),
+            _ => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    words
+}
+
+/// One word of a command with its placeholders filled in, or None if one has no value.
+/// Braces around anything but a plain word are kept.
+fn fill_word(word: &str, values: &[(&str, String)]) -> Option<String> {
+    let mut filled = String::new();
+    let mut rest = word;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        let placeholder = rest[start + 1..]
+            .find('}')
+            .map(|len| &rest[start + 1..start + 1 + len]);
+        match placeholder {
+            Some(key) if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric()) => {
+                let (_, value) = values.iter().find(|(name, _)| *name == key)?;
+                filled.push_str(value);
+                rest = &rest[start + key.len() + 2..];
+            }
+            _ => {
+                filled.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    Some(filled)
+}
+
+/// Commands offered on the selected row.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolConfig {
+    #[serde(default = "default_tools")]
+    pub commands: Vec<ExternalTool>,
+}
+
+fn default_tools() -> Vec<ExternalTool> {
+    // Tools whose placeholders a row can't fill are left out of its menu, so these
+    // suit every tab
+    vec![
+        ExternalTool::new("Open in Process Explorer", "procexp.exe /s:{pid}", true),
+        ExternalTool::new("Loaded modules", "tasklist /m /fi \"PID eq {pid}\"", false),
+        ExternalTool::new("Service configuration", "sc qc {service}", false),
+    ]
+}
+
+impl Default for ToolConfig {
+    fn default() -> Self {
+        Self {
+            commands: default_tools(),
+        }
+    }
+}
+
+/// Paths the file lock search remembers: recent searches, newest first, and favorites.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HandleSearchConfig {
+    #[serde(default)]
+    pub recent: Vec<String>,
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    /// Seconds between checks of a watched path.
+    #[serde(default = "default_watch_interval_secs")]
+    pub watch_interval_secs: u64,
+    /// Pop up a toast when a watched path is free, not just the status bar.
+    #[serde(default = "default_watch_toast")]
+    pub watch_toast: bool,
+    /// Files and subfolders a folder scan skips, as `*`/`?` patterns matched against the
+    /// name, or against the whole path when the pattern has a `\`.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Files a folder scan checks at most.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+}
+
+fn default_max_files() -> usize {
+    10_000
+}
+
+fn default_watch_interval_secs() -> u64 {
+    5
+}
+
+fn default_watch_toast() -> bool {
+    true
+}
+
+impl Default for HandleSearchConfig {
+    fn default() -> Self {
+        Self {
+            recent: Vec::new(),
+            favorites: Vec::new(),
+            watch_interval_secs: default_watch_interval_secs(),
+            watch_toast: default_watch_toast(),
+            exclude: Vec::new(),
+            max_files: default_max_files(),
+        }
+    }
+}
+
+impl HandleSearchConfig {
+    /// Recent searches kept.
+    pub const RECENT_LEN: usize = 20;
+    /// Favorites picked with 1-9.
+    pub const FAVORITE_KEYS: usize = 9;
+
+    /// Whether a folder scan skips `path`.
+    pub fn excludes(&self, path: &std::path::Path) -> bool {
+        let full = path.to_string_lossy();
+        let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        self.exclude.iter().any(|pattern| {
+            let text = if pattern.contains(['\\', '/']) { &full } else { &name };
+            crate::state::matcher::wildcard_match(&pattern.replace('/', "\\"), text)
+        })
+    }
+}
+
+/// A port that must stay LISTENING, optionally owned by a process matching `process`.
+/// Checked on every Nexus poll, with a red banner on every tab while it isn't.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortWatch {
+    pub port: u16,
+    /// `*`/`?` pattern matched against the owning process's name or, for svchost.exe, the
+    /// service behind the socket, ignoring case. Unset accepts any process.
+    #[serde(default)]
+    pub process: Option<String>,
+}
+
+impl PortWatch {
+    pub fn label(&self) -> String {
+        match &self.process {
+            Some(process) => format!("port {} ({})", self.port, process),
+            None => format!("port {}", self.port),
+        }
+    }
+
+    /// Whether `conn` is a listener that satisfies the watch.
+    pub fn accepts(&self, conn: &ConnectionInfo) -> bool {
+        conn.state == "LISTENING"
+            && conn.local_port == self.port
+            && self.process.as_deref().is_none_or(|pattern| {
+                [&conn.process_name, &conn.service_name]
+                    .into_iter()
+                    .flatten()
+                    .any(|name| crate::state::matcher::wildcard_match(pattern, name))
+            })
+    }
+}
+
+/// Processes and services Windows can't do without. Killing or stopping one asks for its
+/// name to be typed on top of the usual confirmation. Names are matched ignoring case;
+/// empty lists turn the guardrail off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailConfig {
+    #[serde(default = "default_protected_processes")]
+    pub processes: Vec<String>,
+    /// Service names (not display names), e.g. `RpcSs`.
+    #[serde(default = "default_protected_services")]
+    pub services: Vec<String>,
+}
+
+fn default_protected_processes() -> Vec<String> {
+    [
+        "System",
+        "Registry",
+        "smss.exe",
+        "csrss.exe",
+        "wininit.exe",
+        "winlogon.exe",
+        "services.exe",
+        "lsass.exe",
+        "lsaiso.exe",
+        "svchost.exe",
+        "dwm.exe",
+        "MsMpEng.exe",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_protected_services() -> Vec<String> {
+    [
+        "RpcSs",
+        "RpcEptMapper",
+        "DcomLaunch",
+        "LSM",
+        "SamSs",
+        "PlugPlay",
+        "Power",
+        "EventLog",
+        "Winmgmt",
+        "ProfSvc",
+        "gpsvc",
+        "CryptSvc",
+        "BFE",
+        "mpssvc",
+        "WinDefend",
+        "nsi",
+        "Dhcp",
+        "Dnscache",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for GuardrailConfig {
+    fn default() -> Self {
+        Self {
+            processes: default_protected_processes(),
+            services: default_protected_services(),
+        }
+    }
+}
+
+impl GuardrailConfig {
+    pub fn is_protected_process(&self, name: &str) -> bool {
+        self.processes.iter().any(|p| p.eq_ignore_ascii_case(name))
+    }
+
+    pub fn is_protected_service(&self, name: &str) -> bool {
+        self.services.iter().any(|s| s.eq_ignore_ascii_case(name))
+    }
+}
+
+/// Slower polling once no key has been pressed for a while, for a session left running on
+/// a server. Any key goes back to the normal rates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdlePollingConfig {
+    #[serde(default = "default_idle_enabled")]
+    pub enabled: bool,
+    /// Seconds without a key press before polling slows down.
+    #[serde(default = "default_idle_after_secs")]
+    pub after_secs: u64,
+    /// Seconds between refreshes while idle.
+    #[serde(default = "default_idle_interval_secs")]
+    pub interval_secs: u64,
+}
+
+fn default_idle_enabled() -> bool {
+    true
+}
+
+fn default_idle_after_secs() -> u64 {
+    60
+}
+
+fn default_idle_interval_secs() -> u64 {
+    10
+}
+
+impl Default for IdlePollingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_idle_enabled(),
+            after_secs: default_idle_after_secs(),
+            interval_secs: default_idle_interval_secs(),
+        }
+    }
+}
+
+/// Where process dumps go and how much they capture.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DumpConfig {
+    /// Folder for dump files. Defaults to %LOCALAPPDATA%\Aperture\dumps.
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub dump_type: DumpType,
+}
+
+impl DumpConfig {
+    pub fn dir(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        if let Some(folder) = &self.folder {
+            return Ok(PathBuf::from(folder));
+        }
+        // Full dumps can be gigabytes, so keep them out of the roaming profile
+        let dir = dirs::data_local_dir().ok_or("Could not locate the local data directory")?;
+        Ok(dir.join("Aperture").join("dumps"))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub rpc: RpcConfig,
+    #[serde(default)]
+    pub presets: PresetConfig,
+    #[serde(default)]
+    pub colors: ColorConfig,
+    /// Match `/` searches as subsequences (fzf-style) instead of substrings.
+    #[serde(default)]
+    pub fuzzy_search: bool,
+    #[serde(default)]
+    pub lookup: LookupConfig,
+    #[serde(default)]
+    pub dump: DumpConfig,
+    #[serde(default)]
+    pub tools: ToolConfig,
+    /// Warning levels for the Locker's handle, GDI and USER object columns.
+    #[serde(default)]
+    pub object_thresholds: ObjectThresholds,
+    /// Rows Ctrl+D/U move by. Unset pages by half the visible rows.
+    #[serde(default)]
+    pub page_step: Option<usize>,
+    /// Command Ctrl+Z runs through the shell instead of opening an interactive one.
+    #[serde(default)]
+    pub suspend_command: Option<String>,
+    /// UI language: `en`, `de` or `es`.
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub idle_polling: IdlePollingConfig,
+    /// Run at BELOW_NORMAL priority so Aperture never competes with what it monitors.
+    #[serde(default)]
+    pub low_priority: bool,
+    /// Save the tabs' filters, sorts and cursors on exit and restore them on start.
+    #[serde(default)]
+    pub restore_session: bool,
+    #[serde(default)]
+    pub guardrails: GuardrailConfig,
+    /// Refuse every action that kills, stops or changes something, as `--read-only` does.
+    #[serde(default)]
+    pub read_only: bool,
+    /// Policy file to enforce instead of %APPDATA%\Aperture\policy.json, e.g. one shared
+    /// by a fleet.
+    #[serde(default)]
+    pub policy_file: Option<PathBuf>,
+    #[serde(default)]
+    pub handle_search: HandleSearchConfig,
+    #[serde(default)]
+    pub port_watches: Vec<PortWatch>,
+}
+
+impl Config {
+    /// Loads the config file, falling back to an empty config if it does not exist yet.
+    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
+        let path = config_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = config_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(&path, json)?;
+        Ok(())
+    }
+
+    /// Adds a profile, replacing any existing profile with the same name.
+    pub fn upsert_profile(&mut self, profile: Profile) {
+        match self
+            .profiles
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(&profile.name))
+        {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+        self.profiles.sort_by_key(|p| p.name.to_lowercase());
+    }
+}
+
+/// Per-user data directory, %APPDATA%\Aperture.
+pub fn app_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = dirs::config_dir().ok_or("Could not locate the config directory")?;
+    Ok(dir.join("Aperture"))
+}
+
+fn config_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(app_dir()?.join("config.json"))
+}
@@ -0,0 +1,145 @@
+use std::fmt;
+
+/// Errors raised by the Win32 layer in `sys`, keeping the original error code
+/// and what was being attempted when it failed.
+#[derive(Debug)]
+pub enum ApertureError {
+    /// A Win32 / HRESULT failure. `code` is the Win32 error code when the HRESULT wraps one.
+    Win32 {
+        context: String,
+        code: u32,
+        message: String,
+    },
+    Io {
+        context: String,
+        source: std::io::Error,
+    },
+    /// Failures that don't come with an OS error code (unsupported input, missing exports, ...).
+    Other(String),
+}
+
+pub type Result<T> = std::result::Result<T, ApertureError>;
+
+impl ApertureError {
+    /// Builds an error from a raw Win32 error code, as returned by APIs that don't set GetLastError.
+    pub fn win32(context: impl Into<String>, code: u32) -> Self {
+        let message = windows::core::Error::from(windows::core::HRESULT::from_win32(code))
+            .message()
+            .trim()
+            .to_string();
+        ApertureError::Win32 {
+            context: context.into(),
+            code,
+            message,
+        }
+    }
+
+    /// The Win32 error code, if there is one (5 = access denied, 87 = invalid parameter, ...).
+    pub fn code(&self) -> Option<u32> {
+        match self {
+            ApertureError::Win32 { code, .. } => Some(*code),
+            ApertureError::Io { source, .. } => source.raw_os_error().map(|c| c as u32),
+            ApertureError::Other(_) => None,
+        }
+    }
+
+    pub fn is_access_denied(&self) -> bool {
+        self.code() == Some(5)
+    }
+
+    fn with_context(self, context: String) -> Self {
+        match self {
+            ApertureError::Win32 { code, message, .. } => ApertureError::Win32 {
+                context,
+                code,
+                message,
+            },
+            ApertureError::Io { source, .. } => ApertureError::Io { context, source },
+            ApertureError::Other(message) => ApertureError::Other(format!("{}: {}", context, message)),
+        }
+    }
+}
+
+impl fmt::Display for ApertureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApertureError::Win32 {
+                context,
+                code,
+                message,
+            } => {
+                if context.is_empty() {
+                    write!(f, "{} (error {})", message, code)
+                } else {
+                    write!(f, "{}: {} (error {})", context, message, code)
+                }
+            }
+            ApertureError::Io { context, source } => {
+                if context.is_empty() {
+                    write!(f, "{}", source)
+                } else {
+                    write!(f, "{}: {}", context, source)
+                }
+            }
+            ApertureError::Other(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ApertureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApertureError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<windows::core::Error> for ApertureError {
+    fn from(e: windows::core::Error) -> Self {
+        let hresult = e.code().0 as u32;
+        // HRESULT_FROM_WIN32 puts the Win32 code in the low word under facility 7
+        let code = if hresult & 0xFFFF_0000 == 0x8007_0000 {
+            hresult & 0xFFFF
+        } else {
+            hresult
+        };
+        ApertureError::Win32 {
+            context: String::new(),
+            code,
+            message: e.message().trim().to_string(),
+        }
+    }
+}
+
+impl From<std::io::Error> for ApertureError {
+    fn from(e: std::io::Error) -> Self {
+        ApertureError::Io {
+            context: String::new(),
+            source: e,
+        }
+    }
+}
+
+impl From<String> for ApertureError {
+    fn from(message: String) -> Self {
+        ApertureError::Other(message)
+    }
+}
+
+impl From<&str> for ApertureError {
+    fn from(message: &str) -> Self {
+        ApertureError::Other(message.to_string())
+    }
+}
+
+/// Attaches what was being attempted to an error on its way up.
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+}
+
+impl<T, E: Into<ApertureError>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.into().with_context(context.into()))
+    }
+}
@@ -7,7 +7,7 @@ use crate::state::locker::LockerState;
 use crate::state::nexus::NexusState;
 use crate::sys::network::ConnectionInfo;
 use crate::sys::process::ProcessInfo;
-use crate::sys::service::ServiceInfo;
+use crate::sys::service::{ServiceConfig, ServiceInfo};
 
 #[derive(Serialize)]
 pub struct ExportData {
@@ -28,9 +28,9 @@ pub fn export_to_json(
 
     let data = ExportData {
         timestamp: format!("{}", timestamp),
-        processes: locker_state.processes.clone(),
-        services: controller_state.services.clone(),
-        connections: nexus_state.connections.clone(),
+        processes: locker_state.table.rows.clone(),
+        services: controller_state.table.rows.clone(),
+        connections: nexus_state.table.rows.clone(),
     };
 
     let json = serde_json::to_string_pretty(&data)?;
@@ -62,21 +62,26 @@ pub fn export_to_csv(
     writer.write_record(&["Type", "ID", "Name", "Status", "Details"])?;
 
     // Write processes
-    for process in &locker_state.processes {
+    for process in &locker_state.table.rows {
         writer.write_record(&[
             "Process",
             &process.pid.to_string(),
             &process.name,
             &format!(
-                "CPU: {:.1}%, Mem: {:.1} MB",
-                process.cpu_usage, process.memory_mb
+                "CPU: {:.1}%, Mem: {:.1} MB, GPU: {:.1}%, GPU Mem: {:.1} MB, Disk R/W: {:.0}/{:.0} B/s",
+                process.cpu_usage,
+                process.memory_mb,
+                process.gpu_usage,
+                process.gpu_memory_mb,
+                process.io_read_bps,
+                process.io_write_bps
             ),
             &process.path.as_deref().unwrap_or("-"),
         ])?;
     }
 
     // Write services
-    for service in &controller_state.services {
+    for service in &controller_state.table.rows {
         writer.write_record(&[
             "Service",
             &service.pid.to_string(),
@@ -90,7 +95,7 @@ pub fn export_to_csv(
     }
 
     // Write connections
-    for conn in &nexus_state.connections {
+    for conn in &nexus_state.table.rows {
         writer.write_record(&[
             "Connection",
             &conn.pid.to_string(),
@@ -108,6 +113,54 @@ pub fn export_to_csv(
     Ok(path.to_string_lossy().to_string())
 }
 
+/// Service settings written by `export_service_configs`, read back for an import.
+#[derive(Debug, Clone, Serialize, serde::Deserialize)]
+pub struct ServiceConfigFile {
+    pub timestamp: String,
+    /// Machine the settings were read on.
+    pub computer: String,
+    pub services: Vec<ServiceConfig>,
+}
+
+pub fn export_service_configs(
+    services: Vec<ServiceConfig>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+
+    let data = ServiceConfigFile {
+        timestamp: format!("{}", timestamp),
+        computer: std::env::var("COMPUTERNAME").unwrap_or_default(),
+        services,
+    };
+    let json = serde_json::to_string_pretty(&data)?;
+
+    let filename = format!("aperture_services_{}.json", timestamp);
+    let path = get_export_path(&filename)?;
+    std::fs::write(&path, json)?;
+
+    Ok(path.to_string_lossy().to_string())
+}
+
+pub fn load_service_configs(
+    path: &std::path::Path,
+) -> Result<ServiceConfigFile, Box<dyn std::error::Error>> {
+    let json = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+/// Folder exports are written to, with a trailing separator, to start the import path from.
+pub fn export_dir() -> String {
+    let mut dir = get_export_path("")
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if !dir.ends_with(std::path::MAIN_SEPARATOR) {
+        dir.push(std::path::MAIN_SEPARATOR);
+    }
+    dir
+}
+
 fn get_export_path(filename: &str) -> Result<std::path::PathBuf, Box<dyn std::error::Error>> {
     // Try to get the Documents folder
     if let Some(home) = dirs::home_dir() {
@@ -0,0 +1,1176 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// UI language, chosen with `language` in the config file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    De,
+    Es,
+}
+
+impl Language {
+    /// Translations keyed by the English text. English has none, it is the key itself.
+    fn bundle(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::En => &[],
+            Language::De => DE,
+            Language::Es => ES,
+        }
+    }
+}
+
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Language {
+    match LANGUAGE.load(Ordering::Relaxed) {
+        1 => Language::De,
+        2 => Language::Es,
+        _ => Language::En,
+    }
+}
+
+/// Translates an English UI string into the configured language. Strings a bundle
+/// doesn't have stay in English.
+pub fn tr(text: &'static str) -> &'static str {
+    language()
+        .bundle()
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translated)| translated)
+}
+
+/// Translates a template and fills its `{}` placeholders with `args` in order. The
+/// placeholders are positional, so a translation has to keep them in the English order.
+pub fn tr_fmt(text: &'static str, args: &[&dyn Display]) -> String {
+    let mut args = args.iter();
+    let mut parts = tr(text).split("{}");
+    let mut filled = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if let Some(arg) = args.next() {
+            filled.push_str(&arg.to_string());
+        }
+        filled.push_str(part);
+    }
+    filled
+}
+
+const DE: &[(&str, &str)] = &[
+    // Tab descriptions
+    ("Find and kill processes holding file locks", "Prozesse mit Dateisperren finden und beenden"),
+    ("Start, stop, and manage Windows services", "Windows-Dienste starten, stoppen und verwalten"),
+    ("Monitor active network connections", "Aktive Netzwerkverbindungen überwachen"),
+    // Sidebar
+    ("Shortcuts", "Tastenkürzel"),
+    ("Keys", "Tasten"),
+    ("Navigation", "Navigation"),
+    ("Actions", "Aktionen"),
+    ("System", "System"),
+    ("Quit", "Beenden"),
+    ("Move", "Bewegen"),
+    ("Page", "Seite"),
+    ("First/Last", "Erste/Letzte"),
+    ("Switch", "Wechseln"),
+    ("Search", "Suchen"),
+    ("Sort", "Sortieren"),
+    ("Sort column", "Nach Spalte"),
+    ("FindLocks", "Sperren"),
+    ("PortOwner", "Port-Besitzer"),
+    ("Go To", "Gehe zu"),
+    ("Tools", "Werkzeuge"),
+    ("Profiles", "Profile"),
+    ("Snapshots", "Snapshots"),
+    ("Errors", "Fehler"),
+    ("Messages", "Meldungen"),
+    ("Timings", "Laufzeiten"),
+    ("Presets", "Vorlagen"),
+    ("Shell", "Shell"),
+    ("Split", "Teilen"),
+    ("Zoom", "Zoom"),
+    ("[ZOOM - Z to restore]", "[ZOOM - Z stellt wieder her]"),
+    ("[READ-ONLY]", "[NUR LESEN]"),
+    ("Other pane", "Anderer Bereich"),
+    ("(C-w to focus)", "(C-w zum Wechseln)"),
+    ("Refresh", "Aktualisieren"),
+    ("ClearFilt", "Filter weg"),
+    ("Export", "Exportieren"),
+    ("Exit", "Beenden"),
+    ("FILTER", "FILTER"),
+    ("TreeView", "Baum"),
+    ("Expand", "Aufklappen"),
+    ("Details", "Details"),
+    ("Kill", "Beenden"),
+    ("Dump", "Dump"),
+    ("Threads", "Threads"),
+    ("Cmdline", "Befehlszeile"),
+    ("Jobs", "Jobs"),
+    ("Top", "Top"),
+    ("CPU/core", "CPU/Kern"),
+    ("Sessions", "Sitzungen"),
+    ("Scroll path", "Pfad rollen"),
+    ("Toggle", "Umschalten"),
+    ("New Service", "Neuer Dienst"),
+    ("Delete", "Löschen"),
+    ("By host", "Nach Host"),
+    ("Lingering", "Hängend"),
+    ("Hide local", "Lokal aus"),
+    ("Lookup IP", "IP prüfen"),
+    ("TCP/UDP only", "Nur TCP/UDP"),
+    ("Pipes", "Pipes"),
+    ("Capture", "Mitschnitt"),
+    ("Watch port", "Port überwachen"),
+    ("PORT DOWN", "PORT AUSGEFALLEN"),
+    ("{}: {} since {}", "{}: {} seit {}"),
+    // Status bar
+    ("Sort: {}", "Sortierung: {}"),
+    ("[FILTER ACTIVE]", "[FILTER AKTIV]"),
+    ("[{} new errors - press !]", "[{} neue Fehler - ! drücken]"),
+    ("[!] No admin", "[!] Kein Admin"),
+    ("[idle - polling every {}s]", "[Leerlauf - Abfrage alle {}s]"),
+    ("[{} free]", "[{} frei]"),
+    ("[watching {}: {} locks]", "[beobachte {}: {} Sperren]"),
+    ("[watching {}]", "[beobachte {}]"),
+    ("self {}% {}MB", "selbst {}% {}MB"),
+    ("not refreshed yet", "noch nicht aktualisiert"),
+    ("refreshed {}", "aktualisiert {}"),
+    ("refresh failed, refreshed {}", "Aktualisierung fehlgeschlagen, zuletzt {}"),
+    ("Failed to refresh the {}: {}", "{} konnte nicht aktualisiert werden: {}"),
+    ("showing data from {}", "Daten von {}"),
+    ("no data yet", "noch keine Daten"),
+    ("{}s ago", "vor {}s"),
+    ("{}m ago", "vor {}m"),
+    ("{}h ago", "vor {}h"),
+    ("{}d ago", "vor {}d"),
+    // Refresh sources
+    ("Process list", "Prozessliste"),
+    ("Service list", "Dienstliste"),
+    ("Connection list", "Verbindungsliste"),
+    // Modals
+    ("Confirmation", "Bestätigung"),
+    ("Confirm Kill Process", "Prozess beenden bestätigen"),
+    ("Kill \"{}\" (PID: {})?", "\"{}\" (PID: {}) beenden?"),
+    ("Every process in its job is killed too.", "Alle Prozesse seines Jobs werden mitbeendet."),
+    ("Its child processes are killed too.", "Seine Kindprozesse werden mitbeendet."),
+    ("This action cannot be undone.", "Dies kann nicht rückgängig gemacht werden."),
+    ("Also stops services: {}", "Stoppt auch die Dienste: {}"),
+    ("[f] Force (SeDebugPrivilege)", "[f] Erzwingen (SeDebugPrivilege)"),
+    ("[t] Kill process tree", "[t] Prozessbaum beenden"),
+    ("[j] Kill the whole job", "[j] Ganzen Job beenden"),
+    ("[d] Write a {} dump first", "[d] Vorher {}-Dump schreiben"),
+    ("Exit code (0-9, Backspace): ", "Exitcode (0-9, Rücktaste): "),
+    ("[Y] Yes", "[Y] Ja"),
+    ("[N] No", "[N] Nein"),
+    ("Confirm Kill Process Tree", "Prozessbaum beenden bestätigen"),
+    (
+        "Kill \"{}\" (PID: {}) and {} descendants:",
+        "\"{}\" (PID: {}) und {} Nachfahren beenden:",
+    ),
+    (
+        "No child processes, only this one is killed.",
+        "Keine Kindprozesse, nur dieser wird beendet.",
+    ),
+    ("[protected]", "[geschützt]"),
+    ("[Y] Kill all", "[Y] Alle beenden"),
+    ("[j/k] Scroll", "[j/k] Blättern"),
+    ("[Esc] Back", "[Esc] Zurück"),
+    ("[N] Cancel", "[N] Abbrechen"),
+    ("[Esc] Cancel", "[Esc] Abbrechen"),
+    ("[Enter] Confirm", "[Enter] Bestätigen"),
+    ("[y] Confirm", "[y] Bestätigen"),
+    ("Protected", "Geschützt"),
+    ("Type {} to confirm:", "Zum Bestätigen {} eingeben:"),
+    (
+        "{} is critical to Windows. Killing it can crash or reboot the machine.",
+        "{} ist für Windows unverzichtbar. Beenden kann das System abstürzen oder neu \
+         starten lassen.",
+    ),
+    (
+        "{} is critical to Windows. Its memory can hold passwords and keys, which a dump \
+         writes to disk.",
+        "{} ist für Windows unverzichtbar. Sein Speicher kann Kennwörter und Schlüssel \
+         enthalten, die ein Dump auf die Festplatte schreibt.",
+    ),
+    (
+        "{} is critical to Windows. Stopping it can break logon, networking or other services.",
+        "{} ist für Windows unverzichtbar. Anhalten kann Anmeldung, Netzwerk oder andere \
+         Dienste stören.",
+    ),
+    (
+        "{} is critical to Windows. The imported settings can disable it and break logon, \
+         networking or other services.",
+        "{} ist für Windows unverzichtbar. Die importierten Einstellungen können ihn \
+         deaktivieren und Anmeldung, Netzwerk oder andere Dienste stören.",
+    ),
+    ("Stop Service With Dependents", "Dienst mit abhängigen Diensten stoppen"),
+    (
+        "{} running service(s) depend on \"{}\". They will be stopped in this order:",
+        "{} laufende(r) Dienst(e) hängen von \"{}\" ab. Sie werden in dieser Reihenfolge \
+         gestoppt:",
+    ),
+    ("... and {} more", "... und {} weitere"),
+    ("[Y] Stop all", "[Y] Alle stoppen"),
+    ("Not Elevated", "Nicht erhöht"),
+    ("Administrator Rights Required", "Administratorrechte erforderlich"),
+    (
+        "{} needs Aperture to run as administrator.",
+        "{} erfordert, dass Aperture als Administrator läuft.",
+    ),
+    (
+        "Restarting elevated opens Aperture in a new window after the UAC prompt and closes \
+         this one.",
+        "Ein erhöhter Neustart öffnet Aperture nach der UAC-Abfrage in einem neuen Fenster \
+         und schließt dieses.",
+    ),
+    ("Restart failed: {}", "Neustart fehlgeschlagen: {}"),
+    ("[Enter] Restart as administrator", "[Enter] Als Administrator neu starten"),
+    ("Killing processes", "Das Beenden von Prozessen"),
+    ("Starting and stopping services", "Das Starten und Stoppen von Diensten"),
+    (
+        "Creating, deleting and reconfiguring services",
+        "Das Anlegen, Löschen und Ändern von Diensten",
+    ),
+    ("Closing TCP connections", "Das Schließen von TCP-Verbindungen"),
+    ("Capturing packets", "Das Mitschneiden von Paketen"),
+    (
+        "Processes of services and other users only grant PROCESS_TERMINATE to an elevated \
+         token with SeDebugPrivilege enabled.",
+        "Prozesse von Diensten und anderen Benutzern gewähren PROCESS_TERMINATE nur einem \
+         erhöhten Token mit aktiviertem SeDebugPrivilege.",
+    ),
+    (
+        "The Service Control Manager grants SERVICE_START and SERVICE_STOP on most services \
+         to administrators only.",
+        "Der Dienststeuerungs-Manager gewährt SERVICE_START und SERVICE_STOP für die meisten \
+         Dienste nur Administratoren.",
+    ),
+    (
+        "Creating and deleting services and changing their configuration needs \
+         SC_MANAGER_CREATE_SERVICE, DELETE and SERVICE_CHANGE_CONFIG, which only \
+         administrators get.",
+        "Anlegen, Löschen und Umkonfigurieren von Diensten braucht SC_MANAGER_CREATE_SERVICE, \
+         DELETE und SERVICE_CHANGE_CONFIG, die nur Administratoren erhalten.",
+    ),
+    (
+        "SetTcpEntry only resets connections for an elevated caller.",
+        "SetTcpEntry setzt Verbindungen nur für einen erhöhten Aufrufer zurück.",
+    ),
+    (
+        "pktmon and netsh trace start kernel capture sessions, which need administrator rights.",
+        "pktmon und netsh trace starten Kernel-Mitschnittsitzungen, die Administratorrechte \
+         brauchen.",
+    ),
+    // Header
+    (" up {}", " läuft seit {}"),
+    (" (booted {})", " (gestartet {})"),
+    ("[!] Admin", "[!] Admin"),
+    // Search box
+    (" ? find [Enter] ", " ? finden [Enter] "),
+    (" / fuzzy [Tab] ", " / unscharf [Tab] "),
+    (" / exact [Tab] ", " / genau [Tab] "),
+    ("Find: {}", "Finden: {}"),
+    ("Search: {}", "Suche: {}"),
+    // Create and delete service
+    ("Create Service", "Dienst anlegen"),
+    (
+        "Own-process service running as LocalSystem",
+        "Dienst mit eigenem Prozess, läuft als LocalSystem",
+    ),
+    ("Service name", "Dienstname"),
+    ("Display name", "Anzeigename"),
+    ("Binary path", "Programmpfad"),
+    ("Start type", "Starttyp"),
+    ("Error: {}", "Fehler: {}"),
+    ("[Tab] Next Field", "[Tab] Nächstes Feld"),
+    ("[Space] Start Type", "[Leertaste] Starttyp"),
+    ("[Enter] Create", "[Enter] Anlegen"),
+    ("Delete Service", "Dienst löschen"),
+    ("Delete \"{}\" ({})?", "\"{}\" ({}) löschen?"),
+    (
+        "The service is removed from the registry. This cannot be undone.",
+        "Der Dienst wird aus der Registrierung entfernt. Dies kann nicht rückgängig gemacht \
+         werden.",
+    ),
+    ("Type \"{}\" to confirm:", "Zum Bestätigen \"{}\" eingeben:"),
+    ("[Enter] Delete", "[Enter] Löschen"),
+    ("Error:", "Fehler:"),
+    // Import service configuration
+    ("Import Service Configuration", "Dienstkonfiguration importieren"),
+    (
+        "Settings exported with [s] in the export menu (e).",
+        "Mit [s] im Exportmenü (e) exportierte Einstellungen.",
+    ),
+    ("File:", "Datei:"),
+    ("Apply the settings of {} service(s)?", "Die Einstellungen von {} Dienst(en) übernehmen?"),
+    (
+        "Apply the settings of {} service(s) from {}?",
+        "Die Einstellungen von {} Dienst(en) von {} übernehmen?",
+    ),
+    ("Auto (Delayed)", "Automatisch (verzögert)"),
+    ("restart", "neu starten"),
+    ("reboot", "Neustart"),
+    ("run", "ausführen"),
+    ("[Y] Apply", "[Y] Übernehmen"),
+    ("[Enter] Load", "[Enter] Laden"),
+    ("Import", "Import"),
+    // Stop dependents, unlock
+    ("Running", "Läuft"),
+    ("No process locks it at the last search.", "Bei der letzten Suche sperrte kein Prozess."),
+    ("Kill {} locking processes:", "{} sperrende Prozesse beenden:"),
+    ("Then delete for good, without the Recycle Bin:", "Dann endgültig löschen, ohne Papierkorb:"),
+    ("Then rename:", "Dann umbenennen:"),
+    ("to", "in"),
+    // Key hints
+    ("[j/k] Navigate", "[j/k] Navigieren"),
+    ("[Esc] Close", "[Esc] Schließen"),
+    ("[r] Refresh", "[r] Aktualisieren"),
+    ("[Enter] Search", "[Enter] Suchen"),
+    ("[c] Clear", "[c] Leeren"),
+    ("[K] Kill", "[K] Beenden"),
+    ("[D] Delete", "[D] Löschen"),
+    ("[x] Shut down (admin)", "[x] Herunterfahren (Admin)"),
+    ("[x] Shut down", "[x] Herunterfahren"),
+    ("[w] Watch", "[w] Überwachen"),
+    ("[s] Suspend", "[s] Anhalten"),
+    ("[s] Favorite", "[s] Favorit"),
+    ("[r] Resume", "[r] Fortsetzen"),
+    ("[m] Mark Base", "[m] Basis markieren"),
+    ("[j/k] Select", "[j/k] Auswählen"),
+    ("[h] History", "[h] Verlauf"),
+    ("[e] Last export", "[e] Letzter Export"),
+    ("[c] Reset", "[c] Zurücksetzen"),
+    ("[c] Close conn", "[c] Verb. schließen"),
+    ("[c] Capture", "[c] Mitschneiden"),
+    ("[a] Save Current", "[a] Aktuelles speichern"),
+    ("[Up/Down] Recent", "[Up/Down] Zuletzt"),
+    ("[Tab] Complete", "[Tab] Vervollständigen"),
+    ("[Space] Toggle Restart", "[Space] Neustart umschalten"),
+    ("[PgUp/PgDn] Scroll", "[PgUp/PgDn] Blättern"),
+    ("[N] Unlock+rename", "[N] Entsperren+umbenennen"),
+    ("[L] Log off", "[L] Abmelden"),
+    ("[K] Kill (admin)", "[K] Beenden (Admin)"),
+    ("[J] Go to", "[J] Gehe zu"),
+    ("[F] Flush DNS", "[F] DNS leeren"),
+    ("[Enter] Save", "[Enter] Speichern"),
+    ("[Enter] Run", "[Enter] Ausführen"),
+    ("[Enter] Open/Run", "[Enter] Öffnen/Ausführen"),
+    ("[Enter] Go", "[Enter] Los"),
+    ("[D] Unlock+delete", "[D] Entsperren+löschen"),
+    ("[D] Disconnect", "[D] Trennen"),
+    ("[C] Close handles (admin)", "[C] Handles schließen (Admin)"),
+    ("[C] Close handles", "[C] Handles schließen"),
+    ("[C-u] Remove path", "[C-u] Pfad entfernen"),
+    ("[C-n] Add path", "[C-n] Pfad hinzufügen"),
+    ("[/] Edit Path", "[/] Pfad bearbeiten"),
+    ("[+/-] Adjust Time", "[+/-] Zeit anpassen"),
+    // Handle search
+    ("[registry key]", "[Registrierungsschlüssel]"),
+    ("[not found]", "[nicht gefunden]"),
+    ("[volume]", "[Laufwerk]"),
+    ("[folder]", "[Ordner]"),
+    ("[network folder]", "[Netzwerkordner]"),
+    ("[file]", "[Datei]"),
+    ("[network file]", "[Netzwerkdatei]"),
+    ("(enter path)", "(Pfad eingeben)"),
+    ("Find Locking Processes", "Sperrende Prozesse finden"),
+    ("Path:", "Pfad:"),
+    ("{}/{} matches", "{}/{} Treffer"),
+    ("{} files (limit reached)", "{} Dateien (Limit erreicht)"),
+    ("{} files", "{} Dateien"),
+    ("Checked {} of {} files ({}%)...", "{} von {} Dateien geprüft ({}%)..."),
+    ("Scanning {} files...", "{} Dateien werden durchsucht..."),
+    ("Scanning directory...", "Ordner wird durchsucht..."),
+    ("Searching...", "Suche läuft..."),
+    ("Found {} locks so far:", "Bisher {} Sperren gefunden:"),
+    ("Scanned {} - no locks found.", "{} durchsucht - keine Sperren gefunden."),
+    ("No locking processes found.", "Keine sperrenden Prozesse gefunden."),
+    ("Scanned {} - Found {} locks:", "{} durchsucht - {} Sperren gefunden:"),
+    ("Found {} locks:", "{} Sperren gefunden:"),
+    ("Locking processes ({}):", "Sperrende Prozesse ({}):"),
+    ("Favorites:", "Favoriten:"),
+    ("[R] Restart {}", "[R] {} neu starten"),
+    ("Handle Search", "Handle-Suche"),
+    // Process details
+    ("Name:", "Name:"),
+    ("PID:", "PID:"),
+    ("Parent:", "Eltern:"),
+    ("CPU:", "CPU:"),
+    ("Memory:", "Speicher:"),
+    ("Commit:", "Commit:"),
+    ("Peak:", "Spitze:"),
+    ("Average:", "Mittel:"),
+    ("Started:", "Gestartet:"),
+    ("Command:", "Befehl:"),
+    ("Services:", "Dienste:"),
+    ("Ports:", "Ports:"),
+    ("Network:", "Netzwerk:"),
+    ("Windows:", "Fenster:"),
+    ("Process Details", "Prozessdetails"),
+    ("of one core", "eines Kerns"),
+    ("of all cores", "aller Kerne"),
+    ("{} working set (peak {}), {} private", "{} Arbeitssatz (Spitze {}), {} privat"),
+    ("{} (peak {}), {} page faults", "{} (Spitze {}), {} Seitenfehler"),
+    ("CPU {}% at {}, memory {} MB at {}", "CPU {}% um {}, Speicher {} MB um {}"),
+    ("CPU {}%, memory {} MB", "CPU {}%, Speicher {} MB"),
+    ("over {} samples since {}", "über {} Messungen seit {}"),
+    ("+{} more", "+{} weitere"),
+    ("none", "keine"),
+    ("{} established", "{} hergestellt"),
+    ("Windows ({}):", "Fenster ({}):"),
+    ("minimized", "minimiert"),
+    ("Loaded Modules (first 10):", "Geladene Module (erste 10):"),
+    ("Modules: (access denied)", "Module: (Zugriff verweigert)"),
+    (
+        "[j/k] Window  [Enter] Front  [m] Minimize  [c] Close window",
+        "[j/k] Fenster  [Enter] Nach vorn  [m] Minimieren  [c] Fenster schließen",
+    ),
+    // Remote lookup, durations
+    ("never", "nie"),
+    ("{} day(s)", "{} Tag(e)"),
+    ("Look up {}", "{} nachschlagen"),
+    ("Running whois...", "whois läuft..."),
+    ("Run whois ({})", "whois ausführen ({})"),
+    ("Open {} in browser", "{} im Browser öffnen"),
+    ("Remote IP Lookup", "Remote-IP-Suche"),
+    // Go to, tools, threads
+    ("(new window)", "(neues Fenster)"),
+    ("(script)", "(Skript)"),
+    ("Runs with the selected row", "Läuft mit der ausgewählten Zeile"),
+    ("External Tools", "Externe Werkzeuge"),
+    ("{} threads", "{} Threads"),
+    ("State", "Status"),
+    ("Base", "Basis"),
+    ("Priority", "Priorität"),
+    ("Core%", "Kern%"),
+    ("CPU time", "CPU-Zeit"),
+    ("Threads: {} (PID: {})", "Threads: {} (PID: {})"),
+    // Connection details
+    ("Connection Details", "Verbindungsdetails"),
+    ("Protocol:", "Protokoll:"),
+    ("Local:", "Lokal:"),
+    ("Remote:", "Remote:"),
+    ("State:", "Status:"),
+    ("Process:", "Prozess:"),
+    ("Created:", "Erstellt:"),
+    ("Service tag:", "Dienst-Tag:"),
+    ("Firewall:", "Firewall:"),
+    ("connectionless", "verbindungslos"),
+    ("Allowed", "Erlaubt"),
+    ("Blocked", "Blockiert"),
+    ("Connection", "Verbindung"),
+    // Service details
+    ("Service Details", "Dienstdetails"),
+    ("Display:", "Anzeige:"),
+    ("Status:", "Status:"),
+    ("Start type:", "Starttyp:"),
+    ("Type:", "Typ:"),
+    ("Recovery (unsaved)", "Wiederherstellung (nicht gespeichert)"),
+    ("Recovery", "Wiederherstellung"),
+    ("First failure:", "Erster Fehler:"),
+    ("Second failure:", "Zweiter Fehler:"),
+    ("Subsequent failures:", "Weitere Fehler:"),
+    ("after {}", "nach {}"),
+    ("Take no action", "Keine Aktion"),
+    ("Restart the service", "Dienst neu starten"),
+    ("Restart the computer", "Computer neu starten"),
+    ("Run a program", "Programm ausführen"),
+    ("Reset fail count:", "Fehlerzähler zurücksetzen:"),
+    ("flapping: {} starts in {} min", "flatternd: {} Starts in {} Min."),
+    ("Status history", "Statusverlauf"),
+    ("Status history ({} changes seen{})", "Statusverlauf ({} Änderungen gesehen{})"),
+    ("No changes since Aperture started", "Keine Änderungen seit dem Start von Aperture"),
+    // Port lookup
+    ("Who Owns This Port?", "Wem gehört dieser Port?"),
+    ("Port:", "Port:"),
+    ("Type a port number and press Enter", "Portnummer eingeben und Enter drücken"),
+    ("Nothing is bound to port {}.", "An Port {} ist nichts gebunden."),
+    ("Sockets on port {} ({}):", "Sockets an Port {} ({}):"),
+    ("Port Lookup", "Port-Suche"),
+    // Pipes, sessions
+    ("Named Pipes", "Named Pipes"),
+    ("{} pipes", "{} Pipes"),
+    ("Instances", "Instanzen"),
+    ("Name", "Name"),
+    ("IPC Endpoints", "IPC-Endpunkte"),
+    ("Logon Sessions", "Anmeldesitzungen"),
+    ("Station", "Station"),
+    ("User", "Benutzer"),
+    ("Client", "Client"),
+    ("Logon", "Anmeldung"),
+    ("Idle", "Leerlauf"),
+    ("Procs", "Proz."),
+    ("Processes in session {}:", "Prozesse in Sitzung {}:"),
+    ("Disconnect session {} ({})?", "Sitzung {} ({}) trennen?"),
+    (
+        "The user stays logged on and their programs keep running.",
+        "Der Benutzer bleibt angemeldet und seine Programme laufen weiter.",
+    ),
+    ("Log off session {} ({})?", "Sitzung {} ({}) abmelden?"),
+    (
+        "Its programs close and unsaved work is lost.",
+        "Ihre Programme werden geschlossen und nicht Gespeichertes geht verloren.",
+    ),
+    ("nobody", "niemand"),
+    ("This is the session Aperture runs in.", "In dieser Sitzung läuft Aperture."),
+    ("Users", "Benutzer"),
+    // Snapshots, recorder, timings
+    ("Recorder service: {}", "Aufzeichnungsdienst: {}"),
+    (
+        "Recorder service: not installed (aperture --install-service)",
+        "Aufzeichnungsdienst: nicht installiert (aperture --install-service)",
+    ),
+    (
+        "No snapshots yet - press [c] to capture the current state",
+        "Noch keine Snapshots - [c] hält den aktuellen Zustand fest",
+    ),
+    ("[base]", "[Basis]"),
+    ("[service]", "[Dienst]"),
+    ("[Enter] Diff vs Base", "[Enter] Vergleich mit Basis"),
+    ("[Enter] Diff vs Live", "[Enter] Vergleich mit aktuell"),
+    ("Snapshot & Diff", "Snapshot & Vergleich"),
+    ("Recorded Changes ({} most recent)", "Aufgezeichnete Änderungen ({} neueste)"),
+    (
+        "Nothing recorded yet - install the recorder with aperture --install-service",
+        "Noch nichts aufgezeichnet - den Aufzeichnungsdienst mit aperture --install-service \
+         installieren",
+    ),
+    ("Recorder History", "Aufzeichnungsverlauf"),
+    ("Collector timings", "Laufzeiten der Sammler"),
+    ("Collector", "Sammler"),
+    ("Last", "Letzte"),
+    ("Avg", "Mittel"),
+    ("Max", "Max"),
+    ("Runs", "Läufe"),
+    ("Ran", "Gelaufen"),
+    ("Last {} runs", "Letzte {} Läufe"),
+    ("Nothing has run yet", "Noch nichts gelaufen"),
+    // Error log, notifications, diff
+    ("Errors ({})", "Fehler ({})"),
+    ("No errors recorded", "Keine Fehler aufgezeichnet"),
+    ("code {}", "Code {}"),
+    ("Error Log", "Fehlerprotokoll"),
+    ("Notifications ({})", "Meldungen ({})"),
+    ("No notifications yet", "Noch keine Meldungen"),
+    ("Notification History", "Meldungsverlauf"),
+    ("{} added", "{} hinzugefügt"),
+    ("{} removed", "{} entfernt"),
+    ("{} changed", "{} geändert"),
+    ("No differences", "Keine Unterschiede"),
+    ("Diff", "Vergleich"),
+    // Profiles
+    ("Saved Profiles", "Gespeicherte Profile"),
+    (
+        "No profiles saved yet - press [a] to save the current view",
+        "Noch keine Profile - [a] speichert die aktuelle Ansicht",
+    ),
+    ("Tab", "Reiter"),
+    ("Filter / Sort", "Filter / Sortierung"),
+    ("Save as:", "Speichern als:"),
+    // Name resolution, export
+    ("Hosts File Overrides ({})", "Einträge der Hosts-Datei ({})"),
+    ("DNS Resolver Cache ({} records)", "DNS-Auflösungscache ({} Einträge)"),
+    ("Address", "Adresse"),
+    ("Hostname", "Hostname"),
+    ("Type", "Typ"),
+    ("Data", "Daten"),
+    ("hosts", "Hosts"),
+    ("[h] DNS Cache", "[h] DNS-Cache"),
+    ("[h] Hosts File", "[h] Hosts-Datei"),
+    ("Name Resolution", "Namensauflösung"),
+    ("Export Data", "Daten exportieren"),
+    ("Export to JSON", "Als JSON exportieren"),
+    ("Export to CSV", "Als CSV exportieren"),
+    ("Export shown services' settings", "Einstellungen der angezeigten Dienste exportieren"),
+    ("Import service settings", "Diensteinstellungen importieren"),
+    ("Cancel", "Abbrechen"),
+    // Status bar
+    ("FIND: {} - n/N", "SUCHE: {} - n/N"),
+    ("{} frozen", "{} eingefroren"),
+    // Elevation
+    ("Writing process dumps", "Das Schreiben von Prozessdumps"),
+    (
+        "Disconnecting and logging off other users' sessions",
+        "Das Trennen und Abmelden der Sitzungen anderer Benutzer",
+    ),
+    (
+        "MiniDumpWriteDump reads the whole address space, and processes of services and other \
+         users only grant PROCESS_VM_READ to an elevated token with SeDebugPrivilege enabled.",
+        "MiniDumpWriteDump liest den ganzen Adressraum, und Prozesse von Diensten und anderen \
+         Benutzern gewähren PROCESS_VM_READ nur einem erhöhten Token mit aktiviertem \
+         SeDebugPrivilege.",
+    ),
+    (
+        "Remote Desktop Services only grants WTS_SECURITY_DISCONNECT and WTS_SECURITY_LOGOFF on \
+         other users' sessions to administrators.",
+        "Remotedesktopdienste gewähren WTS_SECURITY_DISCONNECT und WTS_SECURITY_LOGOFF für \
+         Sitzungen anderer Benutzer nur Administratoren.",
+    ),
+];
+
+const ES: &[(&str, &str)] = &[
+    // Tab descriptions
+    (
+        "Find and kill processes holding file locks",
+        "Buscar y terminar procesos que bloquean archivos",
+    ),
+    (
+        "Start, stop, and manage Windows services",
+        "Iniciar, detener y administrar servicios de Windows",
+    ),
+    ("Monitor active network connections", "Supervisar las conexiones de red activas"),
+    // Sidebar
+    ("Shortcuts", "Atajos"),
+    ("Keys", "Teclas"),
+    ("Navigation", "Navegación"),
+    ("Actions", "Acciones"),
+    ("System", "Sistema"),
+    ("Quit", "Salir"),
+    ("Move", "Mover"),
+    ("Page", "Página"),
+    ("First/Last", "Primero/Último"),
+    ("Switch", "Cambiar"),
+    ("Search", "Buscar"),
+    ("Sort", "Ordenar"),
+    ("Sort column", "Ordenar col."),
+    ("FindLocks", "Bloqueos"),
+    ("PortOwner", "Dueño puerto"),
+    ("Go To", "Ir a"),
+    ("Tools", "Herramientas"),
+    ("Profiles", "Perfiles"),
+    ("Snapshots", "Instantáneas"),
+    ("Errors", "Errores"),
+    ("Messages", "Mensajes"),
+    ("Timings", "Tiempos"),
+    ("Presets", "Filtros"),
+    ("Shell", "Consola"),
+    ("Split", "Dividir"),
+    ("Zoom", "Ampliar"),
+    ("[ZOOM - Z to restore]", "[AMPLIADO - Z para restaurar]"),
+    ("[READ-ONLY]", "[SOLO LECTURA]"),
+    ("Other pane", "Otro panel"),
+    ("(C-w to focus)", "(C-w para enfocar)"),
+    ("Refresh", "Actualizar"),
+    ("ClearFilt", "Quitar filtro"),
+    ("Export", "Exportar"),
+    ("Exit", "Salir"),
+    ("FILTER", "FILTRO"),
+    ("TreeView", "Árbol"),
+    ("Expand", "Expandir"),
+    ("Details", "Detalles"),
+    ("Kill", "Terminar"),
+    ("Dump", "Volcado"),
+    ("Threads", "Hilos"),
+    ("Cmdline", "Comando"),
+    ("Jobs", "Trabajos"),
+    ("Top", "Top"),
+    ("CPU/core", "CPU/núcleo"),
+    ("Sessions", "Sesiones"),
+    ("Scroll path", "Desplazar ruta"),
+    ("Toggle", "Alternar"),
+    ("New Service", "Nuevo servicio"),
+    ("Delete", "Eliminar"),
+    ("By host", "Por host"),
+    ("Lingering", "Persistentes"),
+    ("Hide local", "Ocultar local"),
+    ("Lookup IP", "Buscar IP"),
+    ("TCP/UDP only", "Solo TCP/UDP"),
+    ("Pipes", "Tuberías"),
+    ("Capture", "Capturar"),
+    ("Watch port", "Vigilar puerto"),
+    ("PORT DOWN", "PUERTO CAÍDO"),
+    ("{}: {} since {}", "{}: {} desde {}"),
+    // Status bar
+    ("Sort: {}", "Orden: {}"),
+    ("[FILTER ACTIVE]", "[FILTRO ACTIVO]"),
+    ("[{} new errors - press !]", "[{} errores nuevos - pulse !]"),
+    ("[!] No admin", "[!] Sin admin"),
+    ("[idle - polling every {}s]", "[inactivo - sondeo cada {}s]"),
+    ("[{} free]", "[{} libre]"),
+    ("[watching {}: {} locks]", "[vigilando {}: {} bloqueos]"),
+    ("[watching {}]", "[vigilando {}]"),
+    ("self {}% {}MB", "propio {}% {}MB"),
+    ("not refreshed yet", "aún sin actualizar"),
+    ("refreshed {}", "actualizado {}"),
+    ("refresh failed, refreshed {}", "error al actualizar, última vez {}"),
+    ("Failed to refresh the {}: {}", "No se pudo actualizar: {}: {}"),
+    ("showing data from {}", "datos de las {}"),
+    ("no data yet", "aún sin datos"),
+    ("{}s ago", "hace {}s"),
+    ("{}m ago", "hace {}m"),
+    ("{}h ago", "hace {}h"),
+    ("{}d ago", "hace {}d"),
+    // Refresh sources
+    ("Process list", "Lista de procesos"),
+    ("Service list", "Lista de servicios"),
+    ("Connection list", "Lista de conexiones"),
+    // Modals
+    ("Confirmation", "Confirmación"),
+    ("Confirm Kill Process", "Confirmar terminar proceso"),
+    ("Kill \"{}\" (PID: {})?", "¿Terminar \"{}\" (PID: {})?"),
+    (
+        "Every process in its job is killed too.",
+        "También se terminan todos los procesos de su trabajo.",
+    ),
+    ("Its child processes are killed too.", "También se terminan sus procesos hijos."),
+    ("This action cannot be undone.", "Esta acción no se puede deshacer."),
+    ("Also stops services: {}", "También detiene los servicios: {}"),
+    ("[f] Force (SeDebugPrivilege)", "[f] Forzar (SeDebugPrivilege)"),
+    ("[t] Kill process tree", "[t] Terminar árbol de procesos"),
+    ("[j] Kill the whole job", "[j] Terminar todo el trabajo"),
+    ("[d] Write a {} dump first", "[d] Escribir antes un volcado {}"),
+    ("Exit code (0-9, Backspace): ", "Código de salida (0-9, Retroceso): "),
+    ("[Y] Yes", "[Y] Sí"),
+    ("[N] No", "[N] No"),
+    ("Confirm Kill Process Tree", "Confirmar terminar árbol de procesos"),
+    (
+        "Kill \"{}\" (PID: {}) and {} descendants:",
+        "Terminar \"{}\" (PID: {}) y {} descendientes:",
+    ),
+    (
+        "No child processes, only this one is killed.",
+        "Sin procesos hijos, solo se termina este.",
+    ),
+    ("[protected]", "[protegido]"),
+    ("[Y] Kill all", "[Y] Terminar todos"),
+    ("[j/k] Scroll", "[j/k] Desplazar"),
+    ("[Esc] Back", "[Esc] Volver"),
+    ("[N] Cancel", "[N] Cancelar"),
+    ("[Esc] Cancel", "[Esc] Cancelar"),
+    ("[Enter] Confirm", "[Enter] Confirmar"),
+    ("[y] Confirm", "[y] Confirmar"),
+    ("Protected", "Protegido"),
+    ("Type {} to confirm:", "Escriba {} para confirmar:"),
+    (
+        "{} is critical to Windows. Killing it can crash or reboot the machine.",
+        "{} es crítico para Windows. Terminarlo puede bloquear o reiniciar el equipo.",
+    ),
+    (
+        "{} is critical to Windows. Its memory can hold passwords and keys, which a dump \
+         writes to disk.",
+        "{} es crítico para Windows. Su memoria puede contener contraseñas y claves, que un \
+         volcado escribe en disco.",
+    ),
+    (
+        "{} is critical to Windows. Stopping it can break logon, networking or other services.",
+        "{} es crítico para Windows. Detenerlo puede romper el inicio de sesión, la red u \
+         otros servicios.",
+    ),
+    (
+        "{} is critical to Windows. The imported settings can disable it and break logon, \
+         networking or other services.",
+        "{} es crítico para Windows. La configuración importada puede deshabilitarlo y romper \
+         el inicio de sesión, la red u otros servicios.",
+    ),
+    ("Stop Service With Dependents", "Detener servicio con dependientes"),
+    (
+        "{} running service(s) depend on \"{}\". They will be stopped in this order:",
+        "{} servicio(s) en ejecución dependen de \"{}\". Se detendrán en este orden:",
+    ),
+    ("... and {} more", "... y {} más"),
+    ("[Y] Stop all", "[Y] Detener todos"),
+    ("Not Elevated", "Sin elevar"),
+    ("Administrator Rights Required", "Se requieren derechos de administrador"),
+    (
+        "{} needs Aperture to run as administrator.",
+        "{}: Aperture debe ejecutarse como administrador.",
+    ),
+    (
+        "Restarting elevated opens Aperture in a new window after the UAC prompt and closes \
+         this one.",
+        "Reiniciar elevado abre Aperture en una ventana nueva tras el aviso de UAC y cierra \
+         esta.",
+    ),
+    ("Restart failed: {}", "Error al reiniciar: {}"),
+    ("[Enter] Restart as administrator", "[Enter] Reiniciar como administrador"),
+    ("Killing processes", "Terminar procesos"),
+    ("Starting and stopping services", "Iniciar y detener servicios"),
+    (
+        "Creating, deleting and reconfiguring services",
+        "Crear, eliminar y reconfigurar servicios",
+    ),
+    ("Closing TCP connections", "Cerrar conexiones TCP"),
+    ("Capturing packets", "Capturar paquetes"),
+    (
+        "Processes of services and other users only grant PROCESS_TERMINATE to an elevated \
+         token with SeDebugPrivilege enabled.",
+        "Los procesos de servicios y de otros usuarios solo conceden PROCESS_TERMINATE a un \
+         token elevado con SeDebugPrivilege habilitado.",
+    ),
+    (
+        "The Service Control Manager grants SERVICE_START and SERVICE_STOP on most services \
+         to administrators only.",
+        "El Administrador de control de servicios solo concede SERVICE_START y SERVICE_STOP \
+         de la mayoría de servicios a los administradores.",
+    ),
+    (
+        "Creating and deleting services and changing their configuration needs \
+         SC_MANAGER_CREATE_SERVICE, DELETE and SERVICE_CHANGE_CONFIG, which only \
+         administrators get.",
+        "Crear, eliminar y reconfigurar servicios requiere SC_MANAGER_CREATE_SERVICE, DELETE \
+         y SERVICE_CHANGE_CONFIG, que solo obtienen los administradores.",
+    ),
+    (
+        "SetTcpEntry only resets connections for an elevated caller.",
+        "SetTcpEntry solo restablece conexiones para un llamador elevado.",
+    ),
+    (
+        "pktmon and netsh trace start kernel capture sessions, which need administrator rights.",
+        "pktmon y netsh trace inician sesiones de captura del kernel, que requieren derechos \
+         de administrador.",
+    ),
+    // Header
+    (" up {}", " activo {}"),
+    (" (booted {})", " (arrancado {})"),
+    ("[!] Admin", "[!] Admin"),
+    // Search box
+    (" ? find [Enter] ", " ? buscar [Enter] "),
+    (" / fuzzy [Tab] ", " / aproximada [Tab] "),
+    (" / exact [Tab] ", " / exacta [Tab] "),
+    ("Find: {}", "Buscar: {}"),
+    ("Search: {}", "Búsqueda: {}"),
+    // Create and delete service
+    ("Create Service", "Crear servicio"),
+    (
+        "Own-process service running as LocalSystem",
+        "Servicio con proceso propio, ejecutado como LocalSystem",
+    ),
+    ("Service name", "Nombre del servicio"),
+    ("Display name", "Nombre para mostrar"),
+    ("Binary path", "Ruta del binario"),
+    ("Start type", "Tipo de inicio"),
+    ("Error: {}", "Error: {}"),
+    ("[Tab] Next Field", "[Tab] Campo siguiente"),
+    ("[Space] Start Type", "[Espacio] Tipo de inicio"),
+    ("[Enter] Create", "[Enter] Crear"),
+    ("Delete Service", "Eliminar servicio"),
+    ("Delete \"{}\" ({})?", "¿Eliminar \"{}\" ({})?"),
+    (
+        "The service is removed from the registry. This cannot be undone.",
+        "El servicio se elimina del registro. No se puede deshacer.",
+    ),
+    ("Type \"{}\" to confirm:", "Escriba \"{}\" para confirmar:"),
+    ("[Enter] Delete", "[Enter] Eliminar"),
+    ("Error:", "Error:"),
+    // Import service configuration
+    ("Import Service Configuration", "Importar configuración de servicios"),
+    (
+        "Settings exported with [s] in the export menu (e).",
+        "Configuración exportada con [s] en el menú de exportación (e).",
+    ),
+    ("File:", "Archivo:"),
+    ("Apply the settings of {} service(s)?", "¿Aplicar la configuración de {} servicio(s)?"),
+    (
+        "Apply the settings of {} service(s) from {}?",
+        "¿Aplicar la configuración de {} servicio(s) de {}?",
+    ),
+    ("Auto (Delayed)", "Automático (retrasado)"),
+    ("restart", "reiniciar"),
+    ("reboot", "reinicio"),
+    ("run", "ejecutar"),
+    ("[Y] Apply", "[Y] Aplicar"),
+    ("[Enter] Load", "[Enter] Cargar"),
+    ("Import", "Importar"),
+    // Stop dependents, unlock
+    ("Running", "En ejecución"),
+    (
+        "No process locks it at the last search.",
+        "Ningún proceso lo bloqueaba en la última búsqueda.",
+    ),
+    ("Kill {} locking processes:", "Terminar {} procesos que bloquean:"),
+    (
+        "Then delete for good, without the Recycle Bin:",
+        "Después eliminar definitivamente, sin la Papelera:",
+    ),
+    ("Then rename:", "Después cambiar el nombre:"),
+    ("to", "a"),
+    // Key hints
+    ("[j/k] Navigate", "[j/k] Navegar"),
+    ("[Esc] Close", "[Esc] Cerrar"),
+    ("[r] Refresh", "[r] Actualizar"),
+    ("[Enter] Search", "[Enter] Buscar"),
+    ("[c] Clear", "[c] Limpiar"),
+    ("[K] Kill", "[K] Terminar"),
+    ("[D] Delete", "[D] Eliminar"),
+    ("[x] Shut down (admin)", "[x] Apagar (admin)"),
+    ("[x] Shut down", "[x] Apagar"),
+    ("[w] Watch", "[w] Vigilar"),
+    ("[s] Suspend", "[s] Suspender"),
+    ("[s] Favorite", "[s] Favorito"),
+    ("[r] Resume", "[r] Reanudar"),
+    ("[m] Mark Base", "[m] Marcar base"),
+    ("[j/k] Select", "[j/k] Seleccionar"),
+    ("[h] History", "[h] Historial"),
+    ("[e] Last export", "[e] Última exportación"),
+    ("[c] Reset", "[c] Restablecer"),
+    ("[c] Close conn", "[c] Cerrar conexión"),
+    ("[c] Capture", "[c] Capturar"),
+    ("[a] Save Current", "[a] Guardar actual"),
+    ("[Up/Down] Recent", "[Up/Down] Recientes"),
+    ("[Tab] Complete", "[Tab] Completar"),
+    ("[Space] Toggle Restart", "[Space] Alternar reinicio"),
+    ("[PgUp/PgDn] Scroll", "[PgUp/PgDn] Desplazar"),
+    ("[N] Unlock+rename", "[N] Desbloquear+renombrar"),
+    ("[L] Log off", "[L] Cerrar sesión"),
+    ("[K] Kill (admin)", "[K] Terminar (admin)"),
+    ("[J] Go to", "[J] Ir a"),
+    ("[F] Flush DNS", "[F] Vaciar DNS"),
+    ("[Enter] Save", "[Enter] Guardar"),
+    ("[Enter] Run", "[Enter] Ejecutar"),
+    ("[Enter] Open/Run", "[Enter] Abrir/Ejecutar"),
+    ("[Enter] Go", "[Enter] Ir"),
+    ("[D] Unlock+delete", "[D] Desbloquear+eliminar"),
+    ("[D] Disconnect", "[D] Desconectar"),
+    ("[C] Close handles (admin)", "[C] Cerrar handles (admin)"),
+    ("[C] Close handles", "[C] Cerrar handles"),
+    ("[C-u] Remove path", "[C-u] Quitar ruta"),
+    ("[C-n] Add path", "[C-n] Añadir ruta"),
+    ("[/] Edit Path", "[/] Editar ruta"),
+    ("[+/-] Adjust Time", "[+/-] Ajustar tiempo"),
+    // Handle search
+    ("[registry key]", "[clave de registro]"),
+    ("[not found]", "[no encontrado]"),
+    ("[volume]", "[volumen]"),
+    ("[folder]", "[carpeta]"),
+    ("[network folder]", "[carpeta de red]"),
+    ("[file]", "[archivo]"),
+    ("[network file]", "[archivo de red]"),
+    ("(enter path)", "(introducir ruta)"),
+    ("Find Locking Processes", "Buscar procesos que bloquean"),
+    ("Path:", "Ruta:"),
+    ("{}/{} matches", "{}/{} coincidencias"),
+    ("{} files (limit reached)", "{} archivos (límite alcanzado)"),
+    ("{} files", "{} archivos"),
+    ("Checked {} of {} files ({}%)...", "Comprobados {} de {} archivos ({}%)..."),
+    ("Scanning {} files...", "Examinando {} archivos..."),
+    ("Scanning directory...", "Examinando carpeta..."),
+    ("Searching...", "Buscando..."),
+    ("Found {} locks so far:", "{} bloqueos encontrados hasta ahora:"),
+    ("Scanned {} - no locks found.", "Examinados {} - no se encontraron bloqueos."),
+    ("No locking processes found.", "No se encontraron procesos que bloqueen."),
+    ("Scanned {} - Found {} locks:", "Examinados {} - {} bloqueos encontrados:"),
+    ("Found {} locks:", "{} bloqueos encontrados:"),
+    ("Locking processes ({}):", "Procesos que bloquean ({}):"),
+    ("Favorites:", "Favoritos:"),
+    ("[R] Restart {}", "[R] Reiniciar {}"),
+    ("Handle Search", "Búsqueda de handles"),
+    // Process details
+    ("Name:", "Nombre:"),
+    ("PID:", "PID:"),
+    ("Parent:", "Padre:"),
+    ("CPU:", "CPU:"),
+    ("Memory:", "Memoria:"),
+    ("Commit:", "Commit:"),
+    ("Peak:", "Pico:"),
+    ("Average:", "Media:"),
+    ("Started:", "Iniciado:"),
+    ("Command:", "Comando:"),
+    ("Services:", "Servicios:"),
+    ("Ports:", "Puertos:"),
+    ("Network:", "Red:"),
+    ("Windows:", "Ventanas:"),
+    ("Process Details", "Detalles del proceso"),
+    ("of one core", "de un núcleo"),
+    ("of all cores", "de todos los núcleos"),
+    ("{} working set (peak {}), {} private", "{} conjunto de trabajo (pico {}), {} privado"),
+    ("{} (peak {}), {} page faults", "{} (pico {}), {} fallos de página"),
+    ("CPU {}% at {}, memory {} MB at {}", "CPU {}% a las {}, memoria {} MB a las {}"),
+    ("CPU {}%, memory {} MB", "CPU {}%, memoria {} MB"),
+    ("over {} samples since {}", "en {} muestras desde las {}"),
+    ("+{} more", "+{} más"),
+    ("none", "ninguno"),
+    ("{} established", "{} establecidas"),
+    ("Windows ({}):", "Ventanas ({}):"),
+    ("minimized", "minimizada"),
+    ("Loaded Modules (first 10):", "Módulos cargados (primeros 10):"),
+    ("Modules: (access denied)", "Módulos: (acceso denegado)"),
+    (
+        "[j/k] Window  [Enter] Front  [m] Minimize  [c] Close window",
+        "[j/k] Ventana  [Enter] Al frente  [m] Minimizar  [c] Cerrar ventana",
+    ),
+    // Remote lookup, durations
+    ("never", "nunca"),
+    ("{} day(s)", "{} día(s)"),
+    ("Look up {}", "Consultar {}"),
+    ("Running whois...", "Ejecutando whois..."),
+    ("Run whois ({})", "Ejecutar whois ({})"),
+    ("Open {} in browser", "Abrir {} en el navegador"),
+    ("Remote IP Lookup", "Consulta de IP remota"),
+    // Go to, tools, threads
+    ("(new window)", "(nueva ventana)"),
+    ("(script)", "(script)"),
+    ("Runs with the selected row", "Se ejecuta con la fila seleccionada"),
+    ("External Tools", "Herramientas externas"),
+    ("{} threads", "{} hilos"),
+    ("State", "Estado"),
+    ("Base", "Base"),
+    ("Priority", "Prioridad"),
+    ("Core%", "Núcleo%"),
+    ("CPU time", "Tiempo CPU"),
+    ("Threads: {} (PID: {})", "Hilos: {} (PID: {})"),
+    // Connection details
+    ("Connection Details", "Detalles de la conexión"),
+    ("Protocol:", "Protocolo:"),
+    ("Local:", "Local:"),
+    ("Remote:", "Remoto:"),
+    ("State:", "Estado:"),
+    ("Process:", "Proceso:"),
+    ("Created:", "Creado:"),
+    ("Service tag:", "Etiqueta serv.:"),
+    ("Firewall:", "Firewall:"),
+    ("connectionless", "sin conexión"),
+    ("Allowed", "Permitido"),
+    ("Blocked", "Bloqueado"),
+    ("Connection", "Conexión"),
+    // Service details
+    ("Service Details", "Detalles del servicio"),
+    ("Display:", "Mostrar:"),
+    ("Status:", "Estado:"),
+    ("Start type:", "Tipo inicio:"),
+    ("Type:", "Tipo:"),
+    ("Recovery (unsaved)", "Recuperación (sin guardar)"),
+    ("Recovery", "Recuperación"),
+    ("First failure:", "Primer error:"),
+    ("Second failure:", "Segundo error:"),
+    ("Subsequent failures:", "Errores posteriores:"),
+    ("after {}", "tras {}"),
+    ("Take no action", "No realizar ninguna acción"),
+    ("Restart the service", "Reiniciar el servicio"),
+    ("Restart the computer", "Reiniciar el equipo"),
+    ("Run a program", "Ejecutar un programa"),
+    ("Reset fail count:", "Restablecer errores:"),
+    ("flapping: {} starts in {} min", "inestable: {} inicios en {} min"),
+    ("Status history", "Historial de estados"),
+    ("Status history ({} changes seen{})", "Historial de estados ({} cambios vistos{})"),
+    ("No changes since Aperture started", "Sin cambios desde que se inició Aperture"),
+    // Port lookup
+    ("Who Owns This Port?", "¿Quién usa este puerto?"),
+    ("Port:", "Puerto:"),
+    ("Type a port number and press Enter", "Escriba un número de puerto y pulse Enter"),
+    ("Nothing is bound to port {}.", "Nada está enlazado al puerto {}."),
+    ("Sockets on port {} ({}):", "Sockets en el puerto {} ({}):"),
+    ("Port Lookup", "Búsqueda de puerto"),
+    // Pipes, sessions
+    ("Named Pipes", "Tuberías con nombre"),
+    ("{} pipes", "{} tuberías"),
+    ("Instances", "Instancias"),
+    ("Name", "Nombre"),
+    ("IPC Endpoints", "Extremos IPC"),
+    ("Logon Sessions", "Sesiones de inicio"),
+    ("Station", "Estación"),
+    ("User", "Usuario"),
+    ("Client", "Cliente"),
+    ("Logon", "Inicio"),
+    ("Idle", "Inactivo"),
+    ("Procs", "Procs"),
+    ("Processes in session {}:", "Procesos en la sesión {}:"),
+    ("Disconnect session {} ({})?", "¿Desconectar la sesión {} ({})?"),
+    (
+        "The user stays logged on and their programs keep running.",
+        "El usuario sigue conectado y sus programas siguen en ejecución.",
+    ),
+    ("Log off session {} ({})?", "¿Cerrar la sesión {} ({})?"),
+    (
+        "Its programs close and unsaved work is lost.",
+        "Sus programas se cierran y se pierde el trabajo sin guardar.",
+    ),
+    ("nobody", "nadie"),
+    ("This is the session Aperture runs in.", "Es la sesión en la que se ejecuta Aperture."),
+    ("Users", "Usuarios"),
+    // Snapshots, recorder, timings
+    ("Recorder service: {}", "Servicio de grabación: {}"),
+    (
+        "Recorder service: not installed (aperture --install-service)",
+        "Servicio de grabación: no instalado (aperture --install-service)",
+    ),
+    (
+        "No snapshots yet - press [c] to capture the current state",
+        "Aún no hay instantáneas - pulse [c] para capturar el estado actual",
+    ),
+    ("[base]", "[base]"),
+    ("[service]", "[servicio]"),
+    ("[Enter] Diff vs Base", "[Enter] Comparar con base"),
+    ("[Enter] Diff vs Live", "[Enter] Comparar con actual"),
+    ("Snapshot & Diff", "Instantánea y comparación"),
+    ("Recorded Changes ({} most recent)", "Cambios grabados ({} más recientes)"),
+    (
+        "Nothing recorded yet - install the recorder with aperture --install-service",
+        "Aún no hay nada grabado - instale el grabador con aperture --install-service",
+    ),
+    ("Recorder History", "Historial del grabador"),
+    ("Collector timings", "Tiempos de los recolectores"),
+    ("Collector", "Recolector"),
+    ("Last", "Último"),
+    ("Avg", "Media"),
+    ("Max", "Máx"),
+    ("Runs", "Ejecuc."),
+    ("Ran", "Ejecutado"),
+    ("Last {} runs", "Últimas {} ejecuciones"),
+    ("Nothing has run yet", "Aún no se ha ejecutado nada"),
+    // Error log, notifications, diff
+    ("Errors ({})", "Errores ({})"),
+    ("No errors recorded", "No hay errores registrados"),
+    ("code {}", "código {}"),
+    ("Error Log", "Registro de errores"),
+    ("Notifications ({})", "Notificaciones ({})"),
+    ("No notifications yet", "Aún no hay notificaciones"),
+    ("Notification History", "Historial de notificaciones"),
+    ("{} added", "{} añadidos"),
+    ("{} removed", "{} eliminados"),
+    ("{} changed", "{} cambiados"),
+    ("No differences", "Sin diferencias"),
+    ("Diff", "Comparación"),
+    // Profiles
+    ("Saved Profiles", "Perfiles guardados"),
+    (
+        "No profiles saved yet - press [a] to save the current view",
+        "Aún no hay perfiles - pulse [a] para guardar la vista actual",
+    ),
+    ("Tab", "Pestaña"),
+    ("Filter / Sort", "Filtro / Orden"),
+    ("Save as:", "Guardar como:"),
+    // Name resolution, export
+    ("Hosts File Overrides ({})", "Entradas del archivo hosts ({})"),
+    ("DNS Resolver Cache ({} records)", "Caché de resolución DNS ({} registros)"),
+    ("Address", "Dirección"),
+    ("Hostname", "Nombre de host"),
+    ("Type", "Tipo"),
+    ("Data", "Datos"),
+    ("hosts", "hosts"),
+    ("[h] DNS Cache", "[h] Caché DNS"),
+    ("[h] Hosts File", "[h] Archivo hosts"),
+    ("Name Resolution", "Resolución de nombres"),
+    ("Export Data", "Exportar datos"),
+    ("Export to JSON", "Exportar a JSON"),
+    ("Export to CSV", "Exportar a CSV"),
+    ("Export shown services' settings", "Exportar la configuración de los servicios mostrados"),
+    ("Import service settings", "Importar configuración de servicios"),
+    ("Cancel", "Cancelar"),
+    // Status bar
+    ("FIND: {} - n/N", "BUSCAR: {} - n/N"),
+    ("{} frozen", "{} congelados"),
+    // Elevation
+    ("Writing process dumps", "Escribir volcados de procesos"),
+    (
+        "Disconnecting and logging off other users' sessions",
+        "Desconectar y cerrar las sesiones de otros usuarios",
+    ),
+    (
+        "MiniDumpWriteDump reads the whole address space, and processes of services and other \
+         users only grant PROCESS_VM_READ to an elevated token with SeDebugPrivilege enabled.",
+        "MiniDumpWriteDump lee todo el espacio de direcciones, y los procesos de servicios y de \
+         otros usuarios solo conceden PROCESS_VM_READ a un token elevado con SeDebugPrivilege \
+         habilitado.",
+    ),
+    (
+        "Remote Desktop Services only grants WTS_SECURITY_DISCONNECT and WTS_SECURITY_LOGOFF on \
+         other users' sessions to administrators.",
+        "Servicios de Escritorio remoto solo concede WTS_SECURITY_DISCONNECT y \
+         WTS_SECURITY_LOGOFF sobre las sesiones de otros usuarios a los administradores.",
+    ),
+];
@@ -1,20 +1,30 @@
 mod app;
+mod config;
+mod error;
+mod i18n;
+mod snapshot;
 mod export;
+mod metrics;
+mod rpc;
+mod policy;
+mod scripting;
+mod session;
+mod service_mode;
 mod state;
 mod sys;
+mod tabs;
+mod terminal;
 mod ui;
 
 use std::io;
 use std::time::Duration;
 
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
-use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
-};
 use ratatui::{backend::CrosstermBackend, Terminal};
 use tokio::sync::mpsc;
 
 use app::{App, AppEvent};
+use state::notifications::Severity;
 
 const TICK_RATE_MS: u64 = 100;
 const DATA_POLL_INTERVAL_MS: u64 = 2000;
@@ -23,10 +33,28 @@ const METRICS_INTERVAL_MS: u64 = 1000;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    crossterm::execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
+    match std::env::args().nth(1).as_deref() {
+        Some("--install-service") => {
+            service_mode::install()?;
+            println!("Installed and started the {} service", service_mode::SERVICE_NAME);
+            return Ok(());
+        }
+        Some("--uninstall-service") => {
+            service_mode::uninstall()?;
+            println!("Removed the {} service", service_mode::SERVICE_NAME);
+            return Ok(());
+        }
+        Some("--service") => {
+            // Started by the service control manager - no terminal attached.
+            // The dispatcher blocks until the service stops; nothing else runs on the runtime.
+            return service_mode::run();
+        }
+        _ => {}
+    }
+
+    terminal::install_crash_guards();
+    terminal::enter()?;
+    let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
     let (tx, mut rx) = mpsc::channel::<AppEvent>(32);
@@ -78,16 +106,61 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     });
 
     let mut app = App::new();
+    app.event_tx = Some(tx.clone());
     app.check_elevation();
+    app.load_config();
+    app.read_only |= app.config.read_only || std::env::args().any(|arg| arg == "--read-only");
+
+    if app.config.metrics.enabled {
+        match tokio::net::TcpListener::bind(&app.config.metrics.address).await {
+            Ok(listener) => {
+                let (metrics_tx, metrics_rx) = tokio::sync::watch::channel(String::new());
+                tokio::spawn(metrics::serve(listener, metrics_rx));
+                app.metrics_tx = Some(metrics_tx);
+            }
+            Err(e) => {
+                let message = format!(
+                    "Metrics endpoint failed to bind {}: {}",
+                    app.config.metrics.address, e
+                );
+                app.notify(Severity::Error, message);
+            }
+        }
+    }
 
     // Load all data at startup so all tabs have data immediately
     app.refresh_all_tabs();
+    if app.config.restore_session {
+        app.restore_session();
+    }
+
+    // The sender is kept alive here even when RPC is disabled so the receiver never closes
+    let (rpc_tx, mut rpc_rx) = mpsc::channel::<rpc::RpcCall>(16);
+    if app.config.rpc.enabled {
+        let pipe_name = app.config.rpc.pipe_name.clone();
+        match rpc::listen(&pipe_name) {
+            Ok(first) => {
+                tokio::spawn(rpc::serve(first, pipe_name, rpc_tx.clone()));
+            }
+            Err(e) => {
+                app.notify(
+                    Severity::Error,
+                    format!("RPC pipe \\\\.\\pipe\\{} unavailable: {}", pipe_name, e),
+                );
+            }
+        }
+    }
 
-    let res = run_app(&mut terminal, &mut app, &mut rx).await;
+    let res = run_app(&mut terminal, &mut app, &mut rx, &mut rpc_rx).await;
+    drop(rpc_tx);
 
-    disable_raw_mode()?;
-    crossterm::execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    terminal::restore();
+    app.stop_capture_on_exit();
+    if app.config.restore_session
+        && let Err(e) = app.save_session()
+    {
+        eprintln!("Failed to save session: {e}");
+    }
 
     if let Err(err) = res {
         eprintln!("Error: {err}");
@@ -100,28 +173,38 @@ async fn run_app(
     terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
     app: &mut App,
     rx: &mut mpsc::Receiver<AppEvent>,
+    rpc_rx: &mut mpsc::Receiver<rpc::RpcCall>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     loop {
+        app.prefetch_selection_hint();
         terminal.draw(|f| ui::render(f, app))?;
 
         tokio::select! {
             Some(event) = rx.recv() => {
                 match event {
-                    AppEvent::Tick => {}
+                    AppEvent::Tick => {
+                        app.state.notifications.expire();
+                        app.flush_pending_keys();
+                        app.check_lock_watch();
+                    }
                     AppEvent::PollData => {
                         // Refresh all tabs so data is always current when switching
-                        app.refresh_all_tabs();
+                        if app.poll_due(app::Poll::Data) {
+                            app.refresh_all_tabs();
+                        }
                     }
                     AppEvent::PollServices => {
-                        // Fast polling for services - only update if on Controller tab
-                        if app.current_tab == app::Tab::Controller {
-                            if let Ok(services) = sys::service::enumerate_services() {
-                                app.state.controller.update_services(services);
-                            }
+                        // Fast polling for services - only while the Controller is on screen
+                        if app.is_visible(app::Tab::Controller)
+                            && app.poll_due(app::Poll::Services)
+                        {
+                            app.refresh_tab(app::Tab::Controller);
                         }
                     }
                     AppEvent::MetricsTick => {
-                        app.update_metrics();
+                        if app.poll_due(app::Poll::Metrics) {
+                            app.update_metrics();
+                        }
                     }
                     AppEvent::ServiceUpdate(services) => {
                         app.state.controller.update_services(services);
@@ -132,17 +215,69 @@ async fn run_app(
                     AppEvent::NetworkUpdate(connections) => {
                         app.state.nexus.update_connections(connections);
                     }
+                    AppEvent::ActionDone(action, result) => {
+                        app.finish_action(action, result);
+                    }
+                    AppEvent::WhoisDone(ip, result) => {
+                        app.finish_whois(ip, result);
+                    }
+                    AppEvent::HostResolved(ip, host) => {
+                        app.finish_host_lookup(ip, host);
+                    }
+                    AppEvent::CommandLines(command_lines) => {
+                        app.state.locker.set_command_lines(command_lines);
+                    }
+                    AppEvent::Jobs(result) => {
+                        app.finish_job_scan(result);
+                    }
+                    AppEvent::Firewall(result) => {
+                        app.finish_firewall_check(result);
+                    }
+                    AppEvent::Timing(name, elapsed) => {
+                        app.state.timings.record(name, elapsed);
+                    }
+                    AppEvent::ScriptDone(name, result) => {
+                        app.finish_script_action(name, result);
+                    }
+                    AppEvent::ScriptColumns(tab, result) => {
+                        app.finish_script_columns(tab, result);
+                    }
+                    AppEvent::DirectoryScan(scan, result) => {
+                        app.finish_directory_scan(scan, result);
+                    }
+                    AppEvent::LockWatch(result) => {
+                        app.finish_lock_watch(result);
+                    }
                 }
             }
+            Some(call) = rpc_rx.recv() => {
+                let result = rpc::dispatch(app, &call.method, &call.params);
+                let _ = call.reply.send(result);
+            }
             _ = async {
                 event::poll(Duration::from_millis(TICK_RATE_MS)).ok();
             } => {
                 if event::poll(Duration::from_millis(0))?
                     && let Event::Key(key) = event::read()?
-                        && key.kind == KeyEventKind::Press
-                            && handle_key_event(app, key)? {
-                                return Ok(());
-                            }
+                    && key.kind == KeyEventKind::Press
+                {
+                    app.note_input();
+                    if handle_key_event(app, key)? {
+                        return Ok(());
+                    }
+                }
+                if let Some(suspend) = app.suspend_requested.take() {
+                    let command = match suspend {
+                        app::Suspend::Shell => {
+                            app.config.suspend_command.as_deref().map(sys::shell::shell_command)
+                        }
+                        app::Suspend::Command(argv) => sys::shell::program_command(&argv),
+                    };
+                    if let Err(e) = terminal::suspend(command) {
+                        app.notify(Severity::Error, format!("Shell failed: {}", e));
+                    }
+                    terminal.clear()?;
+                }
             }
         }
     }
@@ -154,6 +289,234 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
 
     if let Some(modal) = &app.modal {
         match modal {
+            app::Modal::CreateService { .. } => {
+                match code {
+                    KeyCode::Esc => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.submit_create_service();
+                    }
+                    KeyCode::Tab | KeyCode::Down => {
+                        app.create_service_next_field();
+                    }
+                    KeyCode::BackTab | KeyCode::Up => {
+                        app.create_service_prev_field();
+                    }
+                    KeyCode::Left | KeyCode::Right | KeyCode::Char(' ')
+                        if matches!(app.modal, Some(app::Modal::CreateService { field: 3, .. })) =>
+                    {
+                        app.create_service_cycle_start_type();
+                    }
+                    KeyCode::Char(c) => {
+                        app.create_service_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        app.create_service_backspace();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::RemoteLookup(_) => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.remote_lookup_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.remote_lookup_select_prev();
+                    }
+                    KeyCode::PageDown => {
+                        app.remote_lookup_scroll(true);
+                    }
+                    KeyCode::PageUp => {
+                        app.remote_lookup_scroll(false);
+                    }
+                    KeyCode::Enter => {
+                        app.run_remote_lookup();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::GoTo { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.go_to_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.go_to_select_prev();
+                    }
+                    KeyCode::Enter => {
+                        app.confirm_go_to();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::Tools { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.tools_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.tools_select_prev();
+                    }
+                    KeyCode::Enter => {
+                        app.confirm_tool();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::ConnectionDetails { .. } => {
+                if matches!(code, KeyCode::Esc | KeyCode::Char('q')) {
+                    app.cancel_modal();
+                }
+            }
+            app::Modal::ServiceDetails { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.service_details_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.service_details_select_prev();
+                    }
+                    KeyCode::Char(' ') => {
+                        app.toggle_recovery_action();
+                    }
+                    KeyCode::Char('+') | KeyCode::Char('=') | KeyCode::Right => {
+                        app.adjust_recovery_value(true);
+                    }
+                    KeyCode::Char('-') | KeyCode::Left => {
+                        app.adjust_recovery_value(false);
+                    }
+                    KeyCode::Enter if app.require_admin(app::AdminTask::ChangeServices) => {
+                        app.save_service_recovery();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::DeleteService { .. } => {
+                match code {
+                    KeyCode::Esc => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.confirm_delete_service();
+                    }
+                    KeyCode::Char(c) => {
+                        app.delete_service_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        app.delete_service_backspace();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::ImportServices { loaded: None, .. } => {
+                match code {
+                    KeyCode::Esc => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.load_import_services();
+                    }
+                    KeyCode::Char(c) => {
+                        app.import_services_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        app.import_services_backspace();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::ImportServices { .. } => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        app.confirm_import_services();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::ElevationRequired { .. } => {
+                match code {
+                    KeyCode::Enter | KeyCode::Char('r') if app.relaunch_elevated() => {
+                        return Ok(true);
+                    }
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.close_elevation_required();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::StopDependents { .. } => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => {
+                        app.confirm_stop_dependents();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::Guardrail { .. } => {
+                match code {
+                    KeyCode::Esc => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.confirm_guardrail();
+                    }
+                    KeyCode::Backspace => {
+                        app.guardrail_backspace();
+                    }
+                    KeyCode::Char(c) => {
+                        app.guardrail_char(c);
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::Unlock { rename: None, .. } => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        app.confirm_unlock();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::Unlock { .. } => {
+                match code {
+                    KeyCode::Esc => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.confirm_unlock();
+                    }
+                    KeyCode::Backspace => {
+                        app.unlock_backspace();
+                    }
+                    KeyCode::Char(c) => {
+                        app.unlock_char(c);
+                    }
+                    _ => {}
+                }
+            }
             app::Modal::KillConfirmation { .. } => {
                 match code {
                     KeyCode::Char('y') | KeyCode::Char('Y') => {
@@ -162,6 +525,41 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                     KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
                         app.cancel_modal();
                     }
+                    KeyCode::Char('f') => {
+                        app.toggle_kill_force();
+                    }
+                    KeyCode::Char('t') => {
+                        app.toggle_kill_tree();
+                    }
+                    KeyCode::Char('j') => {
+                        app.toggle_kill_job();
+                    }
+                    KeyCode::Char('d') => {
+                        app.toggle_kill_dump();
+                    }
+                    KeyCode::Char(c) if c.is_ascii_digit() => {
+                        app.kill_exit_code_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        app.kill_exit_code_backspace();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::KillTreePreview { .. } => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        app.confirm_kill_tree();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_kill_tree();
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.kill_tree_scroll(true);
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.kill_tree_scroll(false);
+                    }
                     _ => {}
                 }
             }
@@ -171,10 +569,29 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                         KeyCode::Esc => {
                             app.exit_handle_search_input_mode();
                         }
+                        // Pasted lines arrive as keys, with more queued behind each Enter
+                        KeyCode::Enter if event::poll(Duration::ZERO)? => {
+                            app.handle_search_new_path();
+                        }
                         KeyCode::Enter => {
                             app.exit_handle_search_input_mode();
                             app.execute_handle_search();
                         }
+                        KeyCode::Char('n') if modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.handle_search_new_path();
+                        }
+                        KeyCode::Char('u') if modifiers.contains(KeyModifiers::CONTROL) => {
+                            app.handle_search_remove_path();
+                        }
+                        KeyCode::Tab => {
+                            app.complete_handle_search_path();
+                        }
+                        KeyCode::Up => {
+                            app.recall_handle_search(true);
+                        }
+                        KeyCode::Down => {
+                            app.recall_handle_search(false);
+                        }
                         KeyCode::Char(c) => {
                             app.handle_search_modal_char(c);
                         }
@@ -221,10 +638,60 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                         }
                         KeyCode::Char('K') => {
                             app.pending_gg = false;
-                            if app.is_elevated {
+                            if app.require_admin(app::AdminTask::KillProcess) {
                                 app.kill_selected_locking_process();
                             }
                         }
+                        KeyCode::Char('C') => {
+                            app.pending_gg = false;
+                            if app.require_admin(app::AdminTask::KillProcess) {
+                                app.close_selected_locking_handles();
+                            }
+                        }
+                        KeyCode::Char('x') => {
+                            app.pending_gg = false;
+                            if app.require_admin(app::AdminTask::KillProcess) {
+                                app.shutdown_selected_locking_process();
+                            }
+                        }
+                        KeyCode::Char('R') => {
+                            app.pending_gg = false;
+                            if !app.refuse_read_only("Restarting processes") {
+                                app.restart_locking_processes();
+                            }
+                        }
+                        KeyCode::Char('w') => {
+                            app.pending_gg = false;
+                            app.toggle_lock_watch();
+                        }
+                        KeyCode::Char('D') => {
+                            app.pending_gg = false;
+                            if app.require_admin(app::AdminTask::KillProcess) {
+                                app.unlock_searched_paths(false);
+                            }
+                        }
+                        KeyCode::Char('N') => {
+                            app.pending_gg = false;
+                            if app.require_admin(app::AdminTask::KillProcess) {
+                                app.unlock_searched_paths(true);
+                            }
+                        }
+                        KeyCode::Char('s') => {
+                            app.pending_gg = false;
+                            app.toggle_handle_search_favorite();
+                        }
+                        KeyCode::Char('e') => {
+                            app.pending_gg = false;
+                            app.fill_handle_search_from_export();
+                        }
+                        KeyCode::Char('J') => {
+                            app.pending_gg = false;
+                            app.go_to_locking_process();
+                        }
+                        KeyCode::Char(c @ '1'..='9') => {
+                            app.pending_gg = false;
+                            app.search_handle_favorite(c as usize - '0' as usize);
+                        }
                         KeyCode::Backspace => {
                             app.pending_gg = false;
                             app.handle_search_modal_backspace();
@@ -235,22 +702,485 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                     }
                 }
             }
+            app::Modal::Threads(_) => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.thread_select_next();
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.thread_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        app.thread_select_first();
+                    }
+                    KeyCode::Char('G') => {
+                        app.thread_select_last();
+                    }
+                    KeyCode::Char('s') => {
+                        app.set_selected_thread_suspended(true);
+                    }
+                    KeyCode::Char('r') => {
+                        app.set_selected_thread_suspended(false);
+                    }
+                    _ => {}
+                }
+            }
             app::Modal::ProcessDetails(details) => {
                 match code {
                     KeyCode::Esc | KeyCode::Char('q') => {
                         app.cancel_modal();
                     }
                     KeyCode::Char('K') => {
-                        if app.is_elevated {
-                            app.modal = Some(app::Modal::KillConfirmation {
-                                pid: details.pid,
-                                name: details.name.clone(),
-                            });
+                        let confirmation =
+                            app::KillConfirmation::new(details.pid, details.name.clone());
+                        if app.require_admin(app::AdminTask::KillProcess) {
+                            app.modal = Some(app::Modal::KillConfirmation(confirmation));
+                        }
+                    }
+                    KeyCode::Char('j') | KeyCode::Down => {
+                        app.process_window_select_next();
+                    }
+                    KeyCode::Char('k') | KeyCode::Up => {
+                        app.process_window_select_prev();
+                    }
+                    KeyCode::Enter => {
+                        app.focus_process_window();
+                    }
+                    KeyCode::Char('m') => {
+                        app.minimize_process_window();
+                    }
+                    KeyCode::Char('c') => {
+                        app.close_process_window();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::PortLookup { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Enter => {
+                        app.execute_port_lookup();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.port_lookup_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.port_lookup_select_prev();
+                    }
+                    KeyCode::Char('K') if app.require_admin(app::AdminTask::KillProcess) => {
+                        app.kill_selected_port_owner();
+                    }
+                    KeyCode::Char('c') if app.require_admin(app::AdminTask::CloseConnection) => {
+                        app.close_selected_port_connection();
+                    }
+                    KeyCode::Char(c) => {
+                        app.port_lookup_char(c);
+                    }
+                    KeyCode::Backspace => {
+                        app.port_lookup_backspace();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::NamedPipes { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.named_pipes_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.named_pipes_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.named_pipes_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
                         }
                     }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.named_pipes_select_last();
+                    }
+                    KeyCode::Char('r') => {
+                        app.pending_gg = false;
+                        app.refresh_named_pipes();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::LogonSessions {
+                confirm: Some(_), ..
+            } => {
+                match code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                        app.confirm_session_command();
+                    }
+                    KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc | KeyCode::Char('q') => {
+                        app.cancel_session_command();
+                    }
                     _ => {}
                 }
             }
+            app::Modal::LogonSessions { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.logon_sessions_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.logon_sessions_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.logon_sessions_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.logon_sessions_select_last();
+                    }
+                    KeyCode::Char('r') => {
+                        app.pending_gg = false;
+                        app.refresh_logon_sessions();
+                    }
+                    KeyCode::Char('D') => {
+                        app.pending_gg = false;
+                        app.request_session_command(app::SessionCommand::Disconnect);
+                    }
+                    KeyCode::Char('L') => {
+                        app.pending_gg = false;
+                        app.request_session_command(app::SessionCommand::LogOff);
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::DnsCache { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.dns_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.dns_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.dns_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.dns_select_last();
+                    }
+                    KeyCode::Char('h') => {
+                        app.pending_gg = false;
+                        app.toggle_dns_hosts_view();
+                    }
+                    KeyCode::Char('F') => {
+                        app.pending_gg = false;
+                        app.flush_dns_cache();
+                    }
+                    KeyCode::Char('r') => {
+                        app.pending_gg = false;
+                        app.open_dns_cache();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::Profiles { .. } => {
+                if app.is_naming_profile() {
+                    match code {
+                        KeyCode::Esc => {
+                            app.cancel_naming_profile();
+                        }
+                        KeyCode::Enter => {
+                            app.save_current_profile();
+                        }
+                        KeyCode::Char(c) => {
+                            app.profile_name_char(c);
+                        }
+                        KeyCode::Backspace => {
+                            app.profile_name_backspace();
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match code {
+                        KeyCode::Esc | KeyCode::Char('q') => {
+                            app.pending_gg = false;
+                            app.cancel_modal();
+                        }
+                        KeyCode::Down | KeyCode::Char('j') => {
+                            app.pending_gg = false;
+                            app.profiles_select_next();
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => {
+                            app.pending_gg = false;
+                            app.profiles_select_prev();
+                        }
+                        KeyCode::Char('g') => {
+                            if app.pending_gg {
+                                app.profiles_select_first();
+                                app.pending_gg = false;
+                            } else {
+                                app.pending_gg = true;
+                            }
+                        }
+                        KeyCode::Char('G') => {
+                            app.pending_gg = false;
+                            app.profiles_select_last();
+                        }
+                        KeyCode::Enter => {
+                            app.pending_gg = false;
+                            app.apply_selected_profile();
+                        }
+                        KeyCode::Char('a') => {
+                            app.pending_gg = false;
+                            app.start_naming_profile();
+                        }
+                        KeyCode::Char('D') => {
+                            app.pending_gg = false;
+                            app.delete_selected_profile();
+                        }
+                        _ => {
+                            app.pending_gg = false;
+                        }
+                    }
+                }
+            }
+            app::Modal::Snapshots { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.snapshots_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.snapshots_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.snapshots_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.snapshots_select_last();
+                    }
+                    KeyCode::Char('c') => {
+                        app.pending_gg = false;
+                        app.take_snapshot();
+                    }
+                    KeyCode::Char('m') => {
+                        app.pending_gg = false;
+                        app.toggle_snapshot_base();
+                    }
+                    KeyCode::Enter => {
+                        app.pending_gg = false;
+                        app.diff_selected_snapshot();
+                    }
+                    KeyCode::Char('D') => {
+                        app.pending_gg = false;
+                        app.delete_selected_snapshot();
+                    }
+                    KeyCode::Char('h') => {
+                        app.pending_gg = false;
+                        app.open_recorder_history();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::RecorderHistory { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.open_snapshots();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.recorder_history_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.recorder_history_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.recorder_history_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.recorder_history_select_last();
+                    }
+                    KeyCode::Char('r') => {
+                        app.pending_gg = false;
+                        app.open_recorder_history();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::ErrorLog { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.error_log_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.error_log_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.error_log_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.error_log_select_last();
+                    }
+                    KeyCode::Char('c') => {
+                        app.pending_gg = false;
+                        app.clear_error_log();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::Timings => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('I') => {
+                        app.cancel_modal();
+                    }
+                    KeyCode::Char('c') => {
+                        app.state.timings.clear();
+                    }
+                    _ => {}
+                }
+            }
+            app::Modal::Notifications { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        app.pending_gg = false;
+                        app.cancel_modal();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.notifications_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.notifications_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.notifications_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.notifications_select_last();
+                    }
+                    KeyCode::Char('c') => {
+                        app.pending_gg = false;
+                        app.clear_notifications();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
+            app::Modal::SnapshotDiff { .. } => {
+                match code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        // Back to the snapshot list
+                        app.pending_gg = false;
+                        app.open_snapshots();
+                    }
+                    KeyCode::Down | KeyCode::Char('j') => {
+                        app.pending_gg = false;
+                        app.snapshot_diff_select_next();
+                    }
+                    KeyCode::Up | KeyCode::Char('k') => {
+                        app.pending_gg = false;
+                        app.snapshot_diff_select_prev();
+                    }
+                    KeyCode::Char('g') => {
+                        if app.pending_gg {
+                            app.snapshot_diff_select_first();
+                            app.pending_gg = false;
+                        } else {
+                            app.pending_gg = true;
+                        }
+                    }
+                    KeyCode::Char('G') => {
+                        app.pending_gg = false;
+                        app.snapshot_diff_select_last();
+                    }
+                    _ => {
+                        app.pending_gg = false;
+                    }
+                }
+            }
             app::Modal::ExportFormat => {
                 match code {
                     KeyCode::Esc | KeyCode::Char('q') => {
@@ -264,6 +1194,13 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                         app.export_to_csv();
                         app.cancel_modal();
                     }
+                    KeyCode::Char('s') => {
+                        app.export_service_configs();
+                        app.cancel_modal();
+                    }
+                    KeyCode::Char('i') => {
+                        app.open_import_services();
+                    }
                     _ => {}
                 }
             }
@@ -271,6 +1208,17 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
         return Ok(false);
     }
 
+    if app.find_mode {
+        match code {
+            KeyCode::Esc => app.exit_find_mode(false),
+            KeyCode::Enter => app.exit_find_mode(true),
+            KeyCode::Char(c) => app.handle_find_char(c),
+            KeyCode::Backspace => app.handle_find_backspace(),
+            _ => {}
+        }
+        return Ok(false);
+    }
+
     if app.search_mode {
         match code {
             KeyCode::Esc => {
@@ -282,6 +1230,9 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
             KeyCode::Backspace => {
                 app.handle_search_backspace();
             }
+            KeyCode::Tab => {
+                app.toggle_fuzzy_search();
+            }
             KeyCode::Enter => {
                 // Exit search mode and persist the filter
                 app.exit_search_mode();
@@ -291,6 +1242,24 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
         return Ok(false);
     }
 
+    // `m` or `'` takes the next letter as a mark; any other key just cancels it
+    if let Some(prefix) = app.pending_mark.take() {
+        if let KeyCode::Char(c @ 'a'..='z') = code {
+            app.finish_mark(prefix, c);
+        }
+        return Ok(false);
+    }
+
+    // Counts only apply to motions; anything else resolves a lone digit to its preset first
+    let count = match code {
+        KeyCode::Char('0'..='9') if !modifiers.contains(KeyModifiers::ALT) => None,
+        KeyCode::Down | KeyCode::Up | KeyCode::Char('j' | 'k' | 'G') => app.take_count(),
+        _ => {
+            app.flush_count();
+            None
+        }
+    };
+
     // Handle Ctrl+D and Ctrl+U for page navigation
     if modifiers.contains(KeyModifiers::CONTROL) {
         match code {
@@ -302,34 +1271,76 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
                 app.select_page_up();
                 return Ok(false);
             }
+            KeyCode::Char('z') => {
+                // A shell or the suspend command can change anything
+                if !app.refuse_read_only("Suspending to a shell") {
+                    app.suspend_requested = Some(app::Suspend::Shell);
+                }
+                return Ok(false);
+            }
+            KeyCode::Char('w') => {
+                app.switch_pane();
+                return Ok(false);
+            }
             _ => {}
         }
     }
 
+    // Keys only the current tab uses
+    if app.handle_tab_key(code) {
+        return Ok(false);
+    }
+
     match code {
         KeyCode::Char('q') => return Ok(true),
         KeyCode::Tab => app.next_tab(),
         KeyCode::BackTab => app.prev_tab(),
-        KeyCode::Down | KeyCode::Char('j') => app.select_next(),
-        KeyCode::Up | KeyCode::Char('k') => app.select_prev(),
-        KeyCode::Enter => app.on_enter(),
+        KeyCode::Char('V') => app.toggle_split(),
+        KeyCode::Char('Z') => app.zoomed = !app.zoomed,
+        KeyCode::Down | KeyCode::Char('j') => match count {
+            Some(rows) => app.move_by(rows, true),
+            None => app.select_next(),
+        },
+        KeyCode::Up | KeyCode::Char('k') => match count {
+            Some(rows) => app.move_by(rows, false),
+            None => app.select_prev(),
+        },
         KeyCode::Char('r') => app.refresh_current_tab(),
         KeyCode::Char('/') => app.enter_search_mode(),
+        KeyCode::Char('?') => app.enter_find_mode(),
+        KeyCode::Char('F') => app.toggle_freeze(),
+        // n/N repeat a find; without one, n keeps its tab meaning
+        KeyCode::Char('n') if !app.find_query.is_empty() => app.find_next(true),
+        KeyCode::Char('N') if !app.find_query.is_empty() => app.find_next(false),
         KeyCode::Char('f') => {
             app.open_handle_search();
         }
-        KeyCode::Char('d') => {
-            if app.current_tab == app::Tab::Locker {
-                app.show_process_details();
-            }
-        }
         KeyCode::Char('e') => {
             app.open_export_modal();
         }
-        KeyCode::Char('K') => {
-            if app.current_tab == app::Tab::Locker && app.is_elevated {
-                app.show_kill_confirmation();
-            }
+        KeyCode::Char('o') => {
+            app.open_port_lookup();
+        }
+        KeyCode::Char('P') => {
+            app.open_profiles();
+        }
+        KeyCode::Char('b') => {
+            app.open_snapshots();
+        }
+        KeyCode::Char('!') => {
+            app.open_error_log();
+        }
+        KeyCode::Char('I') => {
+            app.open_timings();
+        }
+        KeyCode::Char('E') => {
+            app.open_notifications();
+        }
+        KeyCode::Char('m') => {
+            app.start_mark(app::MarkPrefix::Set);
+        }
+        KeyCode::Char('\'') => {
+            app.start_mark(app::MarkPrefix::Jump);
         }
         KeyCode::Char('s') => {
             // Check if Shift is held (uppercase S)
@@ -343,15 +1354,17 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
             // Shift+S - toggle sort order
             app.toggle_sort_order();
         }
-        KeyCode::Char('t') => {
-            if app.current_tab == app::Tab::Locker {
-                app.toggle_tree_mode();
-            }
+        KeyCode::Char('J') => {
+            app.open_go_to();
         }
-        KeyCode::Char(' ') => {
-            if app.current_tab == app::Tab::Locker && app.state.locker.tree_mode {
-                app.toggle_expand();
-            }
+        KeyCode::Char('x') => {
+            app.open_tools();
+        }
+        KeyCode::Char(c @ '1'..='9') if modifiers.contains(KeyModifiers::ALT) => {
+            app.sort_by_column(c as usize - '0' as usize);
+        }
+        KeyCode::Char(c @ '0'..='9') => {
+            app.push_count_digit(c);
         }
         KeyCode::Char('g') => {
             if app.pending_gg {
@@ -365,16 +1378,29 @@ fn handle_key_event(app: &mut App, key: event::KeyEvent) -> Result<bool, Box<dyn
         }
         KeyCode::Char('G') => {
             app.pending_gg = false;
-            app.select_last();
+            match count {
+                Some(row) => app.select_row(row),
+                None => app.select_last(),
+            }
         }
+        KeyCode::Home => app.select_first(),
+        KeyCode::End => app.select_last(),
+        KeyCode::PageUp => app.select_screen_up(),
+        KeyCode::PageDown => app.select_screen_down(),
         KeyCode::Esc => {
             app.pending_gg = false;
-            if app.has_active_filter() {
+            if !app.find_query.is_empty() {
+                app.find_query.clear();
+            } else if app.has_active_filter() {
                 app.clear_current_filter();
+            } else {
+                app.state.notifications.dismiss();
             }
         }
         _ => {
             app.pending_gg = false;
+            // Keys that open something for the current tab's selection
+            app.run_tab_command(code);
         }
     }
 
@@ -0,0 +1,140 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+use crate::app::AppState;
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders the current state in the Prometheus text exposition format.
+pub fn render(state: &AppState) -> String {
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# HELP aperture_system_cpu_percent Total CPU usage.");
+    let _ = writeln!(out, "# TYPE aperture_system_cpu_percent gauge");
+    let _ = writeln!(out, "aperture_system_cpu_percent {:.2}", state.perf.cpu_percent);
+
+    let _ = writeln!(out, "# HELP aperture_system_memory_used_bytes Physical memory in use.");
+    let _ = writeln!(out, "# TYPE aperture_system_memory_used_bytes gauge");
+    let _ = writeln!(out, "aperture_system_memory_used_bytes {}", state.perf.memory_used);
+
+    let _ = writeln!(out, "# HELP aperture_system_memory_total_bytes Total physical memory.");
+    let _ = writeln!(out, "# TYPE aperture_system_memory_total_bytes gauge");
+    let _ = writeln!(out, "aperture_system_memory_total_bytes {}", state.perf.memory_total);
+
+    let _ = writeln!(out, "# HELP aperture_network_receive_bytes_per_second Network download rate.");
+    let _ = writeln!(out, "# TYPE aperture_network_receive_bytes_per_second gauge");
+    let _ = writeln!(out, "aperture_network_receive_bytes_per_second {:.0}", state.perf.net_rx_bps);
+
+    let _ = writeln!(out, "# HELP aperture_network_transmit_bytes_per_second Network upload rate.");
+    let _ = writeln!(out, "# TYPE aperture_network_transmit_bytes_per_second gauge");
+    let _ = writeln!(out, "aperture_network_transmit_bytes_per_second {:.0}", state.perf.net_tx_bps);
+
+    let _ = writeln!(out, "# HELP aperture_processes Number of running processes.");
+    let _ = writeln!(out, "# TYPE aperture_processes gauge");
+    let _ = writeln!(out, "aperture_processes {}", state.locker.live_processes().count());
+
+    // BTreeMap keeps label sets in a stable order between scrapes
+    let mut services: BTreeMap<&str, usize> = BTreeMap::new();
+    for service in state.controller.table.live_rows() {
+        *services.entry(service.status.as_str()).or_default() += 1;
+    }
+    let _ = writeln!(out, "# HELP aperture_services Number of services by status.");
+    let _ = writeln!(out, "# TYPE aperture_services gauge");
+    for (status, count) in &services {
+        let _ = writeln!(out, "aperture_services{{status=\"{}\"}} {}", escape_label(status), count);
+    }
+
+    let mut connections: BTreeMap<(&str, &str), usize> = BTreeMap::new();
+    for conn in state.nexus.table.live_rows() {
+        *connections
+            .entry((conn.protocol.as_str(), conn.state.as_str()))
+            .or_default() += 1;
+    }
+    let _ = writeln!(out, "# HELP aperture_connections Number of sockets by protocol and state.");
+    let _ = writeln!(out, "# TYPE aperture_connections gauge");
+    for ((protocol, conn_state), count) in &connections {
+        let _ = writeln!(
+            out,
+            "aperture_connections{{protocol=\"{}\",state=\"{}\"}} {}",
+            escape_label(protocol),
+            escape_label(conn_state),
+            count
+        );
+    }
+
+    let _ = writeln!(out, "# HELP aperture_process_cpu_percent CPU usage per process.");
+    let _ = writeln!(out, "# TYPE aperture_process_cpu_percent gauge");
+    for p in state.locker.live_processes() {
+        let _ = writeln!(
+            out,
+            "aperture_process_cpu_percent{{pid=\"{}\",name=\"{}\"}} {:.2}",
+            p.pid,
+            escape_label(&p.name),
+            p.cpu_usage
+        );
+    }
+
+    let _ = writeln!(out, "# HELP aperture_process_memory_bytes Working set per process.");
+    let _ = writeln!(out, "# TYPE aperture_process_memory_bytes gauge");
+    for p in state.locker.live_processes() {
+        let _ = writeln!(
+            out,
+            "aperture_process_memory_bytes{{pid=\"{}\",name=\"{}\"}} {:.0}",
+            p.pid,
+            escape_label(&p.name),
+            p.memory_mb * 1024.0 * 1024.0
+        );
+    }
+
+    out
+}
+
+// How long a client gets to send its request before the connection is dropped
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Serves the latest rendered metrics on GET /metrics until the sender is dropped.
+/// Only a minimal subset of HTTP/1.1 is handled - one request per connection.
+pub async fn serve(listener: TcpListener, metrics: watch::Receiver<String>) {
+    loop {
+        let Ok((mut stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let latest = metrics.clone();
+
+        tokio::spawn(async move {
+            let mut buf = [0u8; 2048];
+            let Ok(Ok(n)) = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf)).await else {
+                return;
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let path = request.split_whitespace().nth(1).unwrap_or("");
+
+            let response = if request.starts_with("GET ") && (path == "/metrics" || path == "/") {
+                let body = latest.borrow().clone();
+                format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+            } else {
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+            };
+
+            let _ = stream.write_all(response.as_bytes()).await;
+            let _ = stream.shutdown().await;
+        });
+
+        if metrics.has_changed().is_err() {
+            break;
+        }
+    }
+}
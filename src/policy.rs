@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::matcher::wildcard_match;
+
+/// An action the policy file can allow or deny.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    KillProcess,
+    /// Writing a dump of a process, on its own or before a kill.
+    DumpProcess,
+    StartService,
+    StopService,
+    CreateService,
+    DeleteService,
+    /// Recovery settings and imported service configurations.
+    ConfigureService,
+    CloseConnection,
+    CloseWindow,
+    /// Closing file or registry handles inside another process.
+    CloseHandle,
+    /// Suspending or resuming a thread of a process.
+    SuspendThread,
+    Capture,
+    FlushDns,
+    /// Disconnecting or logging off a logon session.
+    EndSession,
+    /// External tools and script actions.
+    RunTool,
+}
+
+impl Action {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Action::KillProcess => "kill_process",
+            Action::DumpProcess => "dump_process",
+            Action::StartService => "start_service",
+            Action::StopService => "stop_service",
+            Action::CreateService => "create_service",
+            Action::DeleteService => "delete_service",
+            Action::ConfigureService => "configure_service",
+            Action::CloseConnection => "close_connection",
+            Action::CloseWindow => "close_window",
+            Action::CloseHandle => "close_handle",
+            Action::SuspendThread => "suspend_thread",
+            Action::Capture => "capture",
+            Action::FlushDns => "flush_dns",
+            Action::EndSession => "end_session",
+            Action::RunTool => "run_tool",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    #[default]
+    Allow,
+    Deny,
+}
+
+/// Allows or denies `actions` on the targets matching `target`: a process name, service
+/// name, tool name or remote `address:port`, with `*` and `?` wildcards, ignoring case.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub decision: Decision,
+    /// Empty covers every action.
+    #[serde(default)]
+    pub actions: Vec<Action>,
+    #[serde(default = "default_target")]
+    pub target: String,
+}
+
+fn default_target() -> String {
+    "*".to_string()
+}
+
+/// Which actions may run on what, for machines under change control. The first rule
+/// matching an action and its target decides; `default` decides when none does.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Policy {
+    #[serde(default)]
+    pub default: Decision,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+/// %APPDATA%\Aperture\policy.json, unless the config names another file.
+pub fn default_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(crate::config::app_dir()?.join("policy.json"))
+}
+
+impl Policy {
+    /// Reads the policy at `path`. No file allows everything; a file that can't be read
+    /// or parsed denies everything, so a broken policy never loosens it. Returns the
+    /// reason in that case.
+    pub fn load(path: &Path) -> (Self, Option<String>) {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return (Self::default(), None),
+            Err(e) => return (Self::deny_all(), Some(e.to_string())),
+        };
+        match serde_json::from_str(&contents) {
+            Ok(policy) => (policy, None),
+            Err(e) => (Self::deny_all(), Some(e.to_string())),
+        }
+    }
+
+    fn deny_all() -> Self {
+        Self {
+            default: Decision::Deny,
+            rules: Vec::new(),
+        }
+    }
+
+    /// Why `action` on `target` was refused, for the user.
+    pub fn denial(action: Action, target: &str) -> String {
+        match target {
+            "" => format!("Policy denies {}", action.as_str()),
+            target => format!("Policy denies {} on {}", action.as_str(), target),
+        }
+    }
+
+    pub fn allows(&self, action: Action, target: &str) -> bool {
+        let decision = self
+            .rules
+            .iter()
+            .find(|rule| {
+                (rule.actions.is_empty() || rule.actions.contains(&action))
+                    && wildcard_match(&rule.target, target)
+            })
+            .map_or(self.default, |rule| rule.decision);
+        decision == Decision::Allow
+    }
+}
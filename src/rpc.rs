@@ -0,0 +1,231 @@
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::windows::named_pipe::{NamedPipeServer, ServerOptions};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::app::{App, PendingAction};
+use crate::policy::{self, Policy};
+use crate::state::notifications::Severity;
+use crate::sys;
+
+// JSON-RPC 2.0 error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+/// A request forwarded from a pipe client to the UI loop, which owns the app state.
+pub struct RpcCall {
+    pub method: String,
+    pub params: Value,
+    pub reply: oneshot::Sender<Result<Value, RpcError>>,
+}
+
+fn pipe_path(pipe_name: &str) -> String {
+    format!(r"\\.\pipe\{}", pipe_name)
+}
+
+/// Creates the first instance of \\.\pipe\<name>, failing if another process already owns it.
+/// Remote (SMB) clients are rejected, and the default pipe DACL only grants write
+/// access to the owner, administrators and SYSTEM.
+pub fn listen(pipe_name: &str) -> std::io::Result<NamedPipeServer> {
+    ServerOptions::new()
+        .first_pipe_instance(true)
+        .reject_remote_clients(true)
+        .create(pipe_path(pipe_name))
+}
+
+/// Accepts clients and forwards newline-delimited JSON-RPC requests to the UI loop.
+pub async fn serve(
+    first: NamedPipeServer,
+    pipe_name: String,
+    calls: mpsc::Sender<RpcCall>,
+) -> std::io::Result<()> {
+    let path = pipe_path(&pipe_name);
+    let mut server = first;
+
+    loop {
+        server.connect().await?;
+        let client = server;
+        // Create the next instance before handing this one off so clients never see ERROR_PIPE_BUSY
+        server = ServerOptions::new()
+            .reject_remote_clients(true)
+            .create(&path)?;
+
+        tokio::spawn(handle_client(client, calls.clone()));
+    }
+}
+
+async fn handle_client(client: NamedPipeServer, calls: mpsc::Sender<RpcCall>) {
+    let (reader, mut writer) = tokio::io::split(client);
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = handle_line(&line, &calls).await;
+        let mut out = response.to_string();
+        out.push('\n');
+        if writer.write_all(out.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn handle_line(line: &str, calls: &mpsc::Sender<RpcCall>) -> Value {
+    let request: Value = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(e) => return error_response(Value::Null, RpcError::new(PARSE_ERROR, e.to_string())),
+    };
+
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return error_response(id, RpcError::new(INVALID_REQUEST, "Missing method"));
+    };
+    let params = request.get("params").cloned().unwrap_or(Value::Null);
+
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let call = RpcCall {
+        method: method.to_string(),
+        params,
+        reply: reply_tx,
+    };
+    if calls.send(call).await.is_err() {
+        return error_response(id, RpcError::new(SERVER_ERROR, "Aperture is shutting down"));
+    }
+
+    match reply_rx.await {
+        Ok(Ok(result)) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Ok(Err(e)) => error_response(id, e),
+        Err(_) => error_response(id, RpcError::new(SERVER_ERROR, "Request dropped")),
+    }
+}
+
+fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": error.code, "message": error.message },
+    })
+}
+
+fn param_u32(params: &Value, name: &str) -> Result<u32, RpcError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Expected numeric '{}'", name)))
+}
+
+fn param_str<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    params
+        .get(name)
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Expected string '{}'", name)))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(value).map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))
+}
+
+/// Fails the request when the policy file denies `action` on `target`.
+fn permit(app: &App, action: policy::Action, target: &str) -> Result<(), RpcError> {
+    if app.policy.allows(action, target) {
+        Ok(())
+    } else {
+        Err(RpcError::new(SERVER_ERROR, Policy::denial(action, target)))
+    }
+}
+
+/// The error for a process or service the guardrails protect, which RPC never touches.
+fn protected(name: &str) -> RpcError {
+    RpcError::new(SERVER_ERROR, format!("{} is protected; use Aperture itself", name))
+}
+
+/// Runs a single RPC method against the live app state.
+pub fn dispatch(app: &mut App, method: &str, params: &Value) -> Result<Value, RpcError> {
+    if app.read_only && matches!(method, "kill_process" | "start_service" | "stop_service") {
+        return Err(RpcError::new(SERVER_ERROR, "Aperture is in read-only mode"));
+    }
+    match method {
+        "get_status" => Ok(json!({
+            "elevated": app.is_elevated,
+            "read_only": app.read_only,
+            "processes": app.state.locker.live_processes().count(),
+            "services": app.state.controller.table.live_rows().count(),
+            "connections": app.state.nexus.table.live_rows().count(),
+            "cpu_percent": app.state.perf.cpu_percent,
+            "memory_used": app.state.perf.memory_used,
+            "memory_total": app.state.perf.memory_total,
+        })),
+        "get_processes" => to_value(&app.state.locker.live_processes().collect::<Vec<_>>()),
+        "get_services" => to_value(&app.state.controller.table.live_rows().collect::<Vec<_>>()),
+        "get_connections" => to_value(&app.state.nexus.table.live_rows().collect::<Vec<_>>()),
+        "kill_process" => {
+            let pid = param_u32(params, "pid")?;
+            // The snapshot may not have the PID yet, so ask the process; the policy can't
+            // be checked against a name that isn't known
+            let (name, created) = sys::process::identify(pid).ok_or_else(|| {
+                RpcError::new(SERVER_ERROR, format!("Cannot read the name of process {}", pid))
+            })?;
+            permit(app, policy::Action::KillProcess, &name)?;
+            // Nobody is there to type the name, so protected processes are off limits
+            if app.config.guardrails.is_protected_process(&name) {
+                return Err(protected(&name));
+            }
+            // Terminating can block, so it runs like a kill from the UI and reports back
+            // through the notifications; the reply only says it was queued
+            app.notify(Severity::Info, format!("RPC: killing process {}", pid));
+            app.start_action(PendingAction::Kill {
+                pid,
+                created: Some(created),
+                name,
+                options: sys::process::KillOptions::default(),
+                dump: None,
+                members: Vec::new(),
+            });
+            Ok(json!(true))
+        }
+        "start_service" => {
+            let name = param_str(params, "name")?;
+            permit(app, policy::Action::StartService, name)?;
+            sys::service::start_service(name)
+                .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?;
+            app.notify(Severity::Info, format!("RPC: starting service {}", name));
+            Ok(json!(true))
+        }
+        "stop_service" => {
+            let name = param_str(params, "name")?;
+            permit(app, policy::Action::StopService, name)?;
+            if app.config.guardrails.is_protected_service(name) {
+                return Err(protected(name));
+            }
+            sys::service::stop_service(name)
+                .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string()))?;
+            app.notify(Severity::Info, format!("RPC: stopping service {}", name));
+            Ok(json!(true))
+        }
+        _ => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("Unknown method '{}'", method),
+        )),
+    }
+}
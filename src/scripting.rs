@@ -0,0 +1,354 @@
+use std::path::PathBuf;
+
+use crate::app::Tab;
+
+/// A column a script adds to a tab. Its values come from calling `function` with each
+/// row, and its name doubles as a preset query field.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct ScriptColumn {
+    pub tab: Tab,
+    pub name: String,
+    /// 0 keeps the column out of the table, for fields only filters and colors use.
+    pub width: usize,
+    script: usize,
+    function: String,
+}
+
+/// An entry a script adds to the `x` menu, run with the selected row.
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct ScriptAction {
+    /// None offers it on every tab.
+    pub tab: Option<Tab>,
+    pub name: String,
+    script: usize,
+    function: String,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    columns: Vec<ScriptColumn>,
+    actions: Vec<ScriptAction>,
+}
+
+/// The `.rhai` scripts of the scripts folder, loaded at startup.
+#[derive(Default)]
+pub struct Scripts {
+    registry: Registry,
+    /// Loads scripts and runs actions.
+    #[cfg(feature = "scripting")]
+    engine: rhai::Engine,
+    /// Runs column functions, which get neither `run` nor the registration functions.
+    #[cfg(feature = "scripting")]
+    column_engine: rhai::Engine,
+    #[cfg(feature = "scripting")]
+    asts: Vec<rhai::AST>,
+}
+
+/// %APPDATA%\Aperture\scripts.
+pub fn scripts_dir() -> std::result::Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(crate::config::app_dir()?.join("scripts"))
+}
+
+/// Script files in load order, by name.
+fn script_files() -> Vec<PathBuf> {
+    let Ok(dir) = scripts_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("rhai")))
+        .collect();
+    files.sort();
+    files
+}
+
+impl Scripts {
+    pub fn columns(&self, tab: Tab) -> Vec<&ScriptColumn> {
+        self.registry.columns.iter().filter(|column| column.tab == tab).collect()
+    }
+
+    /// Actions offered on `tab`, with their index for `run_action`.
+    pub fn actions(&self, tab: Tab) -> Vec<(usize, &ScriptAction)> {
+        self.registry
+            .actions
+            .iter()
+            .enumerate()
+            .filter(|(_, action)| action.tab.is_none_or(|t| t == tab))
+            .collect()
+    }
+}
+
+#[cfg(feature = "scripting")]
+mod engine {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use rhai::{CallFnOptions, Dynamic, Engine, Map, Scope};
+
+    use super::{script_files, scripts_dir, Registry, ScriptAction, ScriptColumn, Scripts};
+    use crate::app::Tab;
+    use crate::error::Result;
+
+    /// Operations one call may take, so a runaway loop is stopped rather than spinning a
+    /// thread for good.
+    const MAX_OPERATIONS: u64 = 10_000_000;
+
+    /// How long a column function gets per row; they run after every refresh.
+    const COLUMN_TIME_LIMIT: Duration = Duration::from_millis(250);
+
+    /// How long a script's top level or an action gets.
+    const TIME_LIMIT: Duration = Duration::from_secs(30);
+
+    thread_local! {
+        /// When the script call running on this thread has to stop by.
+        static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    }
+
+    /// Runs `call` with `limit` as its time limit, enforced between script operations.
+    /// A native function that blocks, such as `run`, isn't interrupted.
+    fn with_deadline<R>(limit: Duration, call: impl FnOnce() -> R) -> R {
+        DEADLINE.with(|deadline| deadline.set(Some(Instant::now() + limit)));
+        let result = call();
+        DEADLINE.with(|deadline| deadline.set(None));
+        result
+    }
+
+    /// A tab by its name, as scripts pass it. Anything else, such as "*", is None.
+    fn parse_tab(name: &str) -> Option<Tab> {
+        Tab::all()
+            .iter()
+            .copied()
+            .find(|tab| tab.as_str().eq_ignore_ascii_case(name))
+    }
+
+    /// The `stored`/`store` key-value store, kept in scripts\store.json.
+    fn load_store() -> HashMap<String, String> {
+        scripts_dir()
+            .ok()
+            .and_then(|dir| std::fs::read_to_string(dir.join("store.json")).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default()
+    }
+
+    fn save_store(store: &HashMap<String, String>) {
+        if let Ok(dir) = scripts_dir()
+            && let Ok(json) = serde_json::to_string_pretty(store)
+        {
+            let _ = std::fs::write(dir.join("store.json"), json);
+        }
+    }
+
+    /// Runs a command line through the shell and returns its output, for actions that
+    /// hand a row to another program (curl to a webhook, a ticketing CLI, ...).
+    fn run_command(command: &str) -> String {
+        use std::os::windows::process::CommandExt;
+        match std::process::Command::new(crate::sys::shell::comspec())
+            .arg("/C")
+            .raw_arg(command)
+            .output()
+        {
+            Ok(output) => {
+                let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+                text.push_str(&String::from_utf8_lossy(&output.stderr));
+                text.trim().to_string()
+            }
+            Err(e) => format!("Failed to run {}: {}", command, e),
+        }
+    }
+
+    fn row_map(values: &[(&str, String)]) -> Map {
+        values
+            .iter()
+            .map(|(key, value)| ((*key).into(), Dynamic::from(value.clone())))
+            .collect()
+    }
+
+    type Store = Arc<Mutex<HashMap<String, String>>>;
+
+    /// An engine with the operation and time limits and the helpers every call gets.
+    fn base_engine(store: &Store) -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        engine.on_progress(|operations| {
+            // Reading the clock on every operation would slow scripts down for nothing
+            if operations % 1024 != 0 {
+                return None;
+            }
+            DEADLINE
+                .with(Cell::get)
+                .filter(|deadline| Instant::now() > *deadline)
+                .map(|_| "time limit exceeded".into())
+        });
+        // The terminal belongs to the UI
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+
+        let reader = store.clone();
+        engine.register_fn("stored", move |key: &str| -> String {
+            reader.lock().unwrap().get(key).cloned().unwrap_or_default()
+        });
+        let store = store.clone();
+        engine.register_fn("store", move |key: &str, value: &str| {
+            let mut store = store.lock().unwrap();
+            if value.is_empty() {
+                store.remove(key);
+            } else {
+                store.insert(key.to_string(), value.to_string());
+            }
+            save_store(&store);
+        });
+        engine
+    }
+
+    /// The engine scripts are loaded and actions run with: on top of the base engine, the
+    /// functions scripts register themselves with, and `run`.
+    fn build_engine(
+        registry: &Arc<Mutex<Registry>>,
+        script: &Arc<Mutex<usize>>,
+        store: &Store,
+    ) -> Engine {
+        let mut engine = base_engine(store);
+        let (columns, current) = (registry.clone(), script.clone());
+        engine.register_fn("column", move |tab: &str, name: &str, width: i64, function: &str| {
+            if let Some(tab) = parse_tab(tab) {
+                columns.lock().unwrap().columns.push(ScriptColumn {
+                    tab,
+                    name: name.to_string(),
+                    width: width.clamp(0, 40) as usize,
+                    script: *current.lock().unwrap(),
+                    function: function.to_string(),
+                });
+            }
+        });
+        let (actions, current) = (registry.clone(), script.clone());
+        engine.register_fn("action", move |tab: &str, name: &str, function: &str| {
+            actions.lock().unwrap().actions.push(ScriptAction {
+                tab: parse_tab(tab),
+                name: name.to_string(),
+                script: *current.lock().unwrap(),
+                function: function.to_string(),
+            });
+        });
+        engine.register_fn("run", run_command);
+        engine
+    }
+
+    impl Scripts {
+        /// Compiles every script and runs its top level, which registers its columns and
+        /// actions. Returns a message for each script that failed.
+        pub fn load() -> (Self, Vec<String>) {
+            let registry = Arc::new(Mutex::new(Registry::default()));
+            let current = Arc::new(Mutex::new(0));
+            let store = Arc::new(Mutex::new(load_store()));
+            let engine = build_engine(&registry, &current, &store);
+            let mut asts = Vec::new();
+            let mut errors = Vec::new();
+            for path in script_files() {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+                *current.lock().unwrap() = asts.len();
+                let result = engine.compile_file(path).and_then(|ast| {
+                    with_deadline(TIME_LIMIT, || engine.run_ast(&ast)).map(|_| ast)
+                });
+                match result {
+                    Ok(ast) => asts.push(ast),
+                    Err(e) => {
+                        // Drop what it registered before failing, there's no AST to call
+                        let script = asts.len();
+                        let mut registry = registry.lock().unwrap();
+                        registry.columns.retain(|column| column.script != script);
+                        registry.actions.retain(|action| action.script != script);
+                        errors.push(format!("Script {}: {}", name, e));
+                    }
+                }
+            }
+            let registry = std::mem::take(&mut *registry.lock().unwrap());
+            (
+                Self {
+                    registry,
+                    engine,
+                    column_engine: base_engine(&store),
+                    asts,
+                },
+                errors,
+            )
+        }
+
+        /// Calls a script function with a row on `engine`, without rerunning the script's
+        /// top level, stopping it after `limit`.
+        fn call(
+            &self,
+            engine: &Engine,
+            limit: Duration,
+            script: usize,
+            function: &str,
+            values: &[(&str, String)],
+        ) -> Result<String> {
+            let ast = self.asts.get(script).ok_or("Script not loaded")?;
+            let options = CallFnOptions::new().eval_ast(false);
+            let value: Dynamic = with_deadline(limit, || {
+                engine.call_fn_with_options(
+                    options,
+                    &mut Scope::new(),
+                    ast,
+                    function,
+                    (row_map(values),),
+                )
+            })
+            .map_err(|e| format!("{}: {}", function, e))?;
+            Ok(if value.is_unit() { String::new() } else { value.to_string() })
+        }
+
+        /// Values of `tab`'s script columns for one row, in `columns` order.
+        pub fn column_values(&self, tab: Tab, values: &[(&str, String)]) -> Result<Vec<String>> {
+            self.columns(tab)
+                .iter()
+                .map(|column| {
+                    let engine = &self.column_engine;
+                    self.call(engine, COLUMN_TIME_LIMIT, column.script, &column.function, values)
+                })
+                .collect()
+        }
+
+        /// Runs an action with the selected row. Returns what it returned, to show.
+        pub fn run_action(&self, action: usize, values: &[(&str, String)]) -> Result<String> {
+            let action = self.registry.actions.get(action).ok_or("Unknown script action")?;
+            self.call(&self.engine, TIME_LIMIT, action.script, &action.function, values)
+        }
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+impl Scripts {
+    /// Without the scripting feature nothing is loaded; scripts that exist are reported
+    /// so they aren't silently ignored.
+    pub fn load() -> (Self, Vec<String>) {
+        let errors = match script_files().len() {
+            0 => Vec::new(),
+            n => vec![format!("{} script(s) ignored: built without scripting support", n)],
+        };
+        (Self::default(), errors)
+    }
+
+    pub fn column_values(
+        &self,
+        _tab: Tab,
+        _values: &[(&str, String)],
+    ) -> crate::error::Result<Vec<String>> {
+        Ok(Vec::new())
+    }
+
+    pub fn run_action(
+        &self,
+        _action: usize,
+        _values: &[(&str, String)],
+    ) -> crate::error::Result<String> {
+        Err("Built without scripting support".into())
+    }
+}
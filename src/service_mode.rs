@@ -0,0 +1,233 @@
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::time::{Duration, Instant};
+
+use windows::core::{PCWSTR, PWSTR};
+use windows::Win32::System::Services::{
+    ChangeServiceConfig2W, CloseServiceHandle, ControlService, CreateServiceW, DeleteService,
+    OpenSCManagerW, OpenServiceW, RegisterServiceCtrlHandlerExW, SetServiceStatus,
+    StartServiceCtrlDispatcherW, StartServiceW, SC_MANAGER_CONNECT, SC_MANAGER_CREATE_SERVICE,
+    SERVICE_ACCEPT_SHUTDOWN, SERVICE_ACCEPT_STOP, SERVICE_AUTO_START, SERVICE_CONFIG_DESCRIPTION,
+    SERVICE_CONTROL_INTERROGATE, SERVICE_CONTROL_SHUTDOWN, SERVICE_CONTROL_STOP,
+    SERVICE_DESCRIPTIONW, SERVICE_ERROR_NORMAL, SERVICE_QUERY_STATUS, SERVICE_RUNNING,
+    SERVICE_START, SERVICE_START_PENDING, SERVICE_STATUS, SERVICE_STATUS_CURRENT_STATE,
+    SERVICE_STATUS_HANDLE, SERVICE_STOP, SERVICE_STOPPED, SERVICE_STOP_PENDING,
+    SERVICE_TABLE_ENTRYW, SERVICE_WIN32_OWN_PROCESS,
+};
+
+use crate::snapshot::{self, Snapshot};
+
+pub const SERVICE_NAME: &str = "ApertureRecorder";
+const DISPLAY_NAME: &str = "Aperture Recorder";
+const DESCRIPTION: &str =
+    "Records process, service and connection changes for the Aperture diagnostic TUI.";
+
+// How often the recorder samples the system and logs what changed
+const RECORD_INTERVAL: Duration = Duration::from_secs(60);
+// Every Nth sample is also kept as a full snapshot (15 minutes)
+const SNAPSHOT_EVERY: u32 = 15;
+// One day of 15 minute snapshots
+const SNAPSHOTS_KEPT: usize = 96;
+// DELETE standard access right
+const DELETE_ACCESS: u32 = 0x0001_0000;
+
+static STOP_REQUESTED: AtomicBool = AtomicBool::new(false);
+// SERVICE_STATUS_HANDLE wraps a raw pointer, which cannot live in a static directly
+static STATUS_HANDLE: AtomicIsize = AtomicIsize::new(0);
+
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Registers Aperture as an auto-start LocalSystem service running `aperture --service`.
+pub fn install() -> Result<(), Box<dyn std::error::Error>> {
+    let exe = std::env::current_exe()?;
+    let command = to_wide(&format!("\"{}\" --service", exe.display()));
+    let name = to_wide(SERVICE_NAME);
+    let display_name = to_wide(DISPLAY_NAME);
+    let mut description = to_wide(DESCRIPTION);
+
+    unsafe {
+        let sc_manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), SC_MANAGER_CREATE_SERVICE)?;
+
+        let service = CreateServiceW(
+            sc_manager,
+            PCWSTR(name.as_ptr()),
+            PCWSTR(display_name.as_ptr()),
+            SERVICE_START | SERVICE_QUERY_STATUS | 0x0002, // SERVICE_CHANGE_CONFIG
+            SERVICE_WIN32_OWN_PROCESS,
+            SERVICE_AUTO_START,
+            SERVICE_ERROR_NORMAL,
+            PCWSTR(command.as_ptr()),
+            PCWSTR::null(),
+            None,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            PCWSTR::null(),
+        );
+        let service = match service {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e.into());
+            }
+        };
+
+        let info = SERVICE_DESCRIPTIONW {
+            lpDescription: PWSTR(description.as_mut_ptr()),
+        };
+        let _ = ChangeServiceConfig2W(
+            service,
+            SERVICE_CONFIG_DESCRIPTION,
+            Some(&info as *const _ as *const core::ffi::c_void),
+        );
+
+        let started = StartServiceW(service, None);
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        started?;
+    }
+
+    Ok(())
+}
+
+/// Stops the recorder service if it is running and removes it. Recorded history is left on disk.
+pub fn uninstall() -> Result<(), Box<dyn std::error::Error>> {
+    let name = to_wide(SERVICE_NAME);
+
+    unsafe {
+        let sc_manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), SC_MANAGER_CONNECT)?;
+        let service = match OpenServiceW(
+            sc_manager,
+            PCWSTR(name.as_ptr()),
+            SERVICE_STOP | SERVICE_QUERY_STATUS | DELETE_ACCESS,
+        ) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e.into());
+            }
+        };
+
+        // Fails harmlessly when the service is already stopped
+        let mut status = SERVICE_STATUS::default();
+        let _ = ControlService(service, SERVICE_CONTROL_STOP, &mut status);
+
+        let deleted = DeleteService(service);
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        deleted?;
+    }
+
+    Ok(())
+}
+
+/// Entry point when started by the service control manager. Blocks until the service stops.
+pub fn run() -> Result<(), Box<dyn std::error::Error>> {
+    let mut name = to_wide(SERVICE_NAME);
+    let table = [
+        SERVICE_TABLE_ENTRYW {
+            lpServiceName: PWSTR(name.as_mut_ptr()),
+            lpServiceProc: Some(service_main),
+        },
+        SERVICE_TABLE_ENTRYW::default(),
+    ];
+
+    unsafe {
+        StartServiceCtrlDispatcherW(table.as_ptr())?;
+    }
+    Ok(())
+}
+
+fn report_status(state: SERVICE_STATUS_CURRENT_STATE, wait_hint: Duration) {
+    let handle = SERVICE_STATUS_HANDLE(STATUS_HANDLE.load(Ordering::SeqCst) as *mut _);
+    if handle.is_invalid() {
+        return;
+    }
+
+    let controls_accepted = if state == SERVICE_RUNNING {
+        SERVICE_ACCEPT_STOP | SERVICE_ACCEPT_SHUTDOWN
+    } else {
+        0
+    };
+    let status = SERVICE_STATUS {
+        dwServiceType: SERVICE_WIN32_OWN_PROCESS,
+        dwCurrentState: state,
+        dwControlsAccepted: controls_accepted,
+        dwWin32ExitCode: 0,
+        dwServiceSpecificExitCode: 0,
+        dwCheckPoint: 0,
+        dwWaitHint: wait_hint.as_millis() as u32,
+    };
+    unsafe {
+        let _ = SetServiceStatus(handle, &status);
+    }
+}
+
+unsafe extern "system" fn control_handler(
+    control: u32,
+    _event_type: u32,
+    _event_data: *mut core::ffi::c_void,
+    _context: *mut core::ffi::c_void,
+) -> u32 {
+    match control {
+        SERVICE_CONTROL_STOP | SERVICE_CONTROL_SHUTDOWN => {
+            STOP_REQUESTED.store(true, Ordering::SeqCst);
+            report_status(SERVICE_STOP_PENDING, Duration::from_secs(5));
+            0 // NO_ERROR
+        }
+        SERVICE_CONTROL_INTERROGATE => 0,
+        _ => 120, // ERROR_CALL_NOT_IMPLEMENTED
+    }
+}
+
+unsafe extern "system" fn service_main(_argc: u32, _argv: *mut PWSTR) {
+    let name = to_wide(SERVICE_NAME);
+    let handle =
+        unsafe { RegisterServiceCtrlHandlerExW(PCWSTR(name.as_ptr()), Some(control_handler), None) };
+    let Ok(handle) = handle else {
+        return;
+    };
+    STATUS_HANDLE.store(handle.0 as isize, Ordering::SeqCst);
+
+    report_status(SERVICE_START_PENDING, Duration::from_secs(5));
+    // Same as the TUI, so protected processes show up in recorded snapshots
+    let _ = crate::sys::process::enable_debug_privilege();
+    report_status(SERVICE_RUNNING, Duration::ZERO);
+
+    record_loop();
+
+    report_status(SERVICE_STOPPED, Duration::ZERO);
+}
+
+/// Samples the system every RECORD_INTERVAL, appending what changed to the event log
+/// and keeping a rolling set of full snapshots in the history directory.
+fn record_loop() {
+    let mut previous: Option<Snapshot> = None;
+    let mut samples: u32 = 0;
+
+    while !STOP_REQUESTED.load(Ordering::SeqCst) {
+        if let Ok(current) = Snapshot::collect() {
+            if let Some(prev) = &previous {
+                let changes = snapshot::diff(prev, &current);
+                if !changes.is_empty() {
+                    let _ = snapshot::append_history_events(current.timestamp, &changes);
+                }
+            }
+
+            if samples.is_multiple_of(SNAPSHOT_EVERY) {
+                let _ = current.save_to(&snapshot::history_dir());
+                let _ = snapshot::prune_recorded_snapshots(SNAPSHOTS_KEPT);
+            }
+            samples = samples.wrapping_add(1);
+            previous = Some(current);
+        }
+
+        // Sleep in short steps so a stop request is handled promptly
+        let started = Instant::now();
+        while started.elapsed() < RECORD_INTERVAL && !STOP_REQUESTED.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(250));
+        }
+    }
+}
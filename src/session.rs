@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::{AppState, Tab};
+use crate::state::controller::ControllerView;
+use crate::state::locker::LockerView;
+use crate::state::nexus::{ConnectionKey, NexusView};
+use crate::state::presets::FilterPreset;
+use crate::state::table::TablePosition;
+
+/// One tab as it was left: its view settings, preset and cursor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TabSession<V, Id> {
+    pub view: V,
+    #[serde(default)]
+    pub preset: Option<FilterPreset>,
+    pub position: TablePosition<Id>,
+}
+
+/// Where Aperture was when it last quit, written on exit and restored on the next start
+/// when `restore_session` is on. Kept apart from the config, which is the user's to edit.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub tab: Tab,
+    pub locker: TabSession<LockerView, u32>,
+    pub controller: TabSession<ControllerView, String>,
+    pub nexus: TabSession<NexusView, ConnectionKey>,
+}
+
+fn session_path() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(crate::config::app_dir()?.join("session.json"))
+}
+
+impl Session {
+    pub fn capture(tab: Tab, state: &AppState) -> Self {
+        Self {
+            tab,
+            locker: TabSession {
+                view: state.locker.view(),
+                preset: state.locker.table.preset.clone(),
+                position: state.locker.table.position(),
+            },
+            controller: TabSession {
+                view: state.controller.view(),
+                preset: state.controller.table.preset.clone(),
+                position: state.controller.table.position(),
+            },
+            nexus: TabSession {
+                view: state.nexus.view(),
+                preset: state.nexus.table.preset.clone(),
+                position: state.nexus.table.position(),
+            },
+        }
+    }
+
+    /// Applies the session to loaded tabs, so the cursors can find their rows.
+    pub fn apply(self, state: &mut AppState) {
+        state.locker.table.preset = self.locker.preset;
+        state.locker.table.set_position(self.locker.position);
+        state.locker.apply_view(&self.locker.view);
+
+        state.controller.table.preset = self.controller.preset;
+        state.controller.table.set_position(self.controller.position);
+        state.controller.apply_view(&self.controller.view);
+
+        state.nexus.table.preset = self.nexus.preset;
+        state.nexus.table.set_position(self.nexus.position);
+        state.nexus.apply_view(&self.nexus.view);
+    }
+
+    /// The last session, or None if none was saved yet.
+    pub fn load() -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        let path = session_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let path = session_path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,379 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use crate::state::controller::ControllerState;
+use crate::state::locker::LockerState;
+use crate::state::nexus::NexusState;
+use crate::sys::network::ConnectionInfo;
+use crate::sys::process::ProcessInfo;
+use crate::sys::service::ServiceInfo;
+
+/// Full process/service/connection state at one point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub timestamp: u64,
+    pub processes: Vec<ProcessInfo>,
+    pub services: Vec<ServiceInfo>,
+    pub connections: Vec<ConnectionInfo>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotFile {
+    pub path: PathBuf,
+    pub timestamp: u64,
+    /// Recorded by the background service rather than captured from the TUI.
+    pub recorded: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+impl ChangeKind {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "+",
+            ChangeKind::Removed => "-",
+            ChangeKind::Changed => "~",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffEntry {
+    pub kind: ChangeKind,
+    pub category: &'static str,
+    pub description: String,
+}
+
+/// A change observed by the background recorder, one JSON object per line in events.jsonl.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEvent {
+    pub timestamp: u64,
+    pub kind: String,
+    pub category: String,
+    pub description: String,
+}
+
+impl Snapshot {
+    pub fn capture(
+        locker_state: &LockerState,
+        controller_state: &ControllerState,
+        nexus_state: &NexusState,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+
+        Ok(Self {
+            timestamp,
+            processes: locker_state.table.rows.clone(),
+            services: controller_state.table.rows.clone(),
+            connections: nexus_state.table.rows.clone(),
+        })
+    }
+
+    /// Enumerates processes, services and connections directly, without any UI state.
+    pub fn collect() -> Result<Self, Box<dyn std::error::Error>> {
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_secs();
+
+        Ok(Self {
+            timestamp,
+            processes: crate::sys::process::enumerate_processes()?,
+            services: crate::sys::service::enumerate_services()?,
+            connections: crate::sys::network::enumerate_connections()?,
+        })
+    }
+
+    pub fn save(&self) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        self.save_to(&snapshot_dir()?)
+    }
+
+    pub fn save_to(&self, dir: &std::path::Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(dir)?;
+
+        let path = dir.join(format!("snapshot_{}.json", self.timestamp));
+        std::fs::write(&path, serde_json::to_string(self)?)?;
+        Ok(path)
+    }
+
+    pub fn load(path: &std::path::Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+}
+
+fn snapshot_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    Ok(crate::config::app_dir()?.join("snapshots"))
+}
+
+/// Machine-wide directory the recorder service writes to, %ProgramData%\Aperture\history.
+/// The service runs as LocalSystem, so it cannot use the interactive user's %APPDATA%.
+pub fn history_dir() -> PathBuf {
+    let program_data =
+        std::env::var("ProgramData").unwrap_or_else(|_| "C:\\ProgramData".to_string());
+    PathBuf::from(program_data).join("Aperture").join("history")
+}
+
+fn snapshots_in(dir: &std::path::Path, recorded: bool) -> std::io::Result<Vec<SnapshotFile>> {
+    let mut snapshots = Vec::new();
+    if !dir.exists() {
+        return Ok(snapshots);
+    }
+
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let timestamp = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.strip_prefix("snapshot_"))
+            .and_then(|s| s.parse::<u64>().ok());
+        if let Some(timestamp) = timestamp {
+            snapshots.push(SnapshotFile {
+                path,
+                timestamp,
+                recorded,
+            });
+        }
+    }
+    Ok(snapshots)
+}
+
+/// Lists saved snapshots, including those recorded by the service, newest first.
+pub fn list_snapshots() -> Result<Vec<SnapshotFile>, Box<dyn std::error::Error>> {
+    let mut snapshots = snapshots_in(&snapshot_dir()?, false)?;
+    // The history directory may not be readable when the service was never installed
+    snapshots.extend(snapshots_in(&history_dir(), true).unwrap_or_default());
+
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    Ok(snapshots)
+}
+
+/// Deletes the oldest recorded snapshots beyond `keep`.
+pub fn prune_recorded_snapshots(keep: usize) -> std::io::Result<()> {
+    let mut snapshots = snapshots_in(&history_dir(), true)?;
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
+    for old in snapshots.iter().skip(keep) {
+        std::fs::remove_file(&old.path)?;
+    }
+    Ok(())
+}
+
+const MAX_EVENT_LOG_BYTES: u64 = 8 * 1024 * 1024;
+
+pub fn append_history_events(
+    timestamp: u64,
+    entries: &[DiffEntry],
+) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::Write;
+
+    let dir = history_dir();
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join("events.jsonl");
+
+    // Roll the log over once it gets large, keeping a single previous generation
+    if std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0) > MAX_EVENT_LOG_BYTES {
+        std::fs::rename(&path, dir.join("events.old.jsonl"))?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+
+    for entry in entries {
+        let event = HistoryEvent {
+            timestamp,
+            kind: entry.kind.symbol().to_string(),
+            category: entry.category.to_string(),
+            description: entry.description.clone(),
+        };
+        writeln!(file, "{}", serde_json::to_string(&event)?)?;
+    }
+    Ok(())
+}
+
+/// Reads up to `limit` of the most recent recorder events, newest first.
+pub fn load_history_events(limit: usize) -> Result<Vec<HistoryEvent>, Box<dyn std::error::Error>> {
+    let path = history_dir().join("events.jsonl");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = std::fs::read_to_string(path)?;
+    Ok(contents
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(limit)
+        .collect())
+}
+
+fn connection_key(c: &ConnectionInfo) -> (String, String, u16, String, u16) {
+    (
+        c.protocol.clone(),
+        c.local_addr.clone(),
+        c.local_port,
+        c.remote_addr.clone(),
+        c.remote_port,
+    )
+}
+
+fn describe_connection(c: &ConnectionInfo) -> String {
+    format!(
+        "{} {}:{} -> {}:{} {} [{} {}]",
+        c.protocol,
+        c.local_addr,
+        c.local_port,
+        c.remote_addr,
+        c.remote_port,
+        c.state,
+        c.pid,
+        c.process_name.as_deref().unwrap_or("-")
+    )
+}
+
+/// Compares two snapshots and lists what appeared, disappeared or changed going from `before` to `after`.
+/// Metrics like CPU and memory are ignored since they change on every sample.
+pub fn diff(before: &Snapshot, after: &Snapshot) -> Vec<DiffEntry> {
+    let mut entries = Vec::new();
+
+    // Processes are keyed by PID and name so a reused PID shows up as remove + add
+    let old_procs: HashMap<(u32, &str), &ProcessInfo> = before
+        .processes
+        .iter()
+        .map(|p| ((p.pid, p.name.as_str()), p))
+        .collect();
+    let new_procs: HashMap<(u32, &str), &ProcessInfo> = after
+        .processes
+        .iter()
+        .map(|p| ((p.pid, p.name.as_str()), p))
+        .collect();
+
+    for p in &after.processes {
+        if !old_procs.contains_key(&(p.pid, p.name.as_str())) {
+            entries.push(DiffEntry {
+                kind: ChangeKind::Added,
+                category: "Process",
+                description: format!(
+                    "{} (PID {}, parent {}) {}",
+                    p.name,
+                    p.pid,
+                    p.parent_pid,
+                    p.path.as_deref().unwrap_or("")
+                ),
+            });
+        }
+    }
+    for p in &before.processes {
+        if !new_procs.contains_key(&(p.pid, p.name.as_str())) {
+            entries.push(DiffEntry {
+                kind: ChangeKind::Removed,
+                category: "Process",
+                description: format!("{} (PID {})", p.name, p.pid),
+            });
+        }
+    }
+
+    let old_services: HashMap<&str, &ServiceInfo> = before
+        .services
+        .iter()
+        .map(|s| (s.service_name.as_str(), s))
+        .collect();
+    let new_services: HashMap<&str, &ServiceInfo> = after
+        .services
+        .iter()
+        .map(|s| (s.service_name.as_str(), s))
+        .collect();
+
+    for s in &after.services {
+        match old_services.get(s.service_name.as_str()) {
+            None => entries.push(DiffEntry {
+                kind: ChangeKind::Added,
+                category: "Service",
+                description: format!(
+                    "{} ({}) {}, {}",
+                    s.service_name, s.display_name, s.status, s.start_type
+                ),
+            }),
+            Some(old) => {
+                let mut changes = Vec::new();
+                if old.status != s.status {
+                    changes.push(format!("status {} -> {}", old.status, s.status));
+                }
+                if old.start_type != s.start_type {
+                    changes.push(format!("start {} -> {}", old.start_type, s.start_type));
+                }
+                if old.pid != s.pid && old.status == s.status {
+                    changes.push(format!("PID {} -> {}", old.pid, s.pid));
+                }
+                if !changes.is_empty() {
+                    entries.push(DiffEntry {
+                        kind: ChangeKind::Changed,
+                        category: "Service",
+                        description: format!("{}: {}", s.service_name, changes.join(", ")),
+                    });
+                }
+            }
+        }
+    }
+    for s in &before.services {
+        if !new_services.contains_key(s.service_name.as_str()) {
+            entries.push(DiffEntry {
+                kind: ChangeKind::Removed,
+                category: "Service",
+                description: format!("{} ({})", s.service_name, s.display_name),
+            });
+        }
+    }
+
+    let old_conns: HashMap<_, &ConnectionInfo> = before
+        .connections
+        .iter()
+        .map(|c| (connection_key(c), c))
+        .collect();
+    let new_conns: HashMap<_, &ConnectionInfo> = after
+        .connections
+        .iter()
+        .map(|c| (connection_key(c), c))
+        .collect();
+
+    for c in &after.connections {
+        match old_conns.get(&connection_key(c)) {
+            None => entries.push(DiffEntry {
+                kind: ChangeKind::Added,
+                category: "Connection",
+                description: describe_connection(c),
+            }),
+            Some(old) if old.state != c.state || old.pid != c.pid => entries.push(DiffEntry {
+                kind: ChangeKind::Changed,
+                category: "Connection",
+                description: format!(
+                    "{} (was {} [{}])",
+                    describe_connection(c),
+                    old.state,
+                    old.pid
+                ),
+            }),
+            Some(_) => {}
+        }
+    }
+    for c in &before.connections {
+        if !new_conns.contains_key(&connection_key(c)) {
+            entries.push(DiffEntry {
+                kind: ChangeKind::Removed,
+                category: "Connection",
+                description: describe_connection(c),
+            });
+        }
+    }
+
+    entries
+}
@@ -0,0 +1,40 @@
+use std::collections::HashSet;
+
+/// What a tab's badge is worked out from, taken before and after each of its polls.
+#[derive(Debug, Default)]
+pub struct Activity {
+    /// Service status changes seen so far.
+    pub changes: u32,
+    pub flapping: usize,
+    /// Listening ports by protocol, port and PID.
+    pub listeners: HashSet<(String, u16, u32)>,
+}
+
+/// What happened on a tab while it was off screen, shown on its title as `Nexus (3)`,
+/// or `Controller (2!)` once something needs attention. Cleared when the tab is shown.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TabBadge {
+    /// Service status changes and new listening ports.
+    pub events: usize,
+    /// A poll failed or a service started flapping.
+    pub urgent: bool,
+}
+
+impl TabBadge {
+    /// Adds what changed between two polls.
+    pub fn record(&mut self, before: &Activity, after: &Activity) {
+        self.events += after.changes.saturating_sub(before.changes) as usize
+            + after.listeners.difference(&before.listeners).count();
+        self.urgent |= after.flapping > before.flapping;
+    }
+
+    /// The badge text, None while there's nothing to show.
+    pub fn label(&self) -> Option<String> {
+        match (self.events, self.urgent) {
+            (0, false) => None,
+            (0, true) => Some("(!)".to_string()),
+            (events, true) => Some(format!("({}!)", events)),
+            (events, false) => Some(format!("({})", events)),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use ratatui::style::{Color, Modifier, Style};
+use serde::{Deserialize, Serialize};
+
+use crate::state::presets;
+
+/// Draws the rows matching `when` in `style`. `when` is a preset query (see `FilterPreset`),
+/// where an empty query matches every row. `style` is a color name (`red`, `lightcyan`,
+/// ...), a `#rrggbb` value or a 0-255 palette index, optionally with `bold` and `dim`,
+/// e.g. `red bold` or just `dim`. The first rule of a tab that matches wins.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRule {
+    pub when: String,
+    pub style: String,
+}
+
+impl ColorRule {
+    fn new(when: &str, style: &str) -> Self {
+        Self {
+            when: when.to_string(),
+            style: style.to_string(),
+        }
+    }
+}
+
+/// Locker rows stay white unless a rule says otherwise.
+pub fn default_locker_rules() -> Vec<ColorRule> {
+    Vec::new()
+}
+
+pub fn default_controller_rules() -> Vec<ColorRule> {
+    vec![
+        ColorRule::new("status:=running", "green"),
+        ColorRule::new("status:=stopped", "red"),
+        ColorRule::new("", "yellow"),
+    ]
+}
+
+pub fn default_nexus_rules() -> Vec<ColorRule> {
+    vec![
+        ColorRule::new("state:=close_wait", "red"),
+        ColorRule::new("state:=time_wait", "magenta"),
+        ColorRule::new("proto:=tcp", "green"),
+        ColorRule::new("proto:=udp", "yellow"),
+    ]
+}
+
+/// Parses a rule's style, naming the word it doesn't understand on failure.
+pub fn parse_style(spec: &str) -> Result<Style, String> {
+    let mut style = Style::default();
+    for word in spec.split_whitespace() {
+        style = match word.to_lowercase().as_str() {
+            "bold" => style.add_modifier(Modifier::BOLD),
+            "dim" => style.add_modifier(Modifier::DIM),
+            _ => {
+                let color = word
+                    .parse::<Color>()
+                    .map_err(|_| format!("unknown color or modifier '{}'", word))?;
+                style.fg(color)
+            }
+        };
+    }
+    Ok(style)
+}
+
+/// A tab's color rules with their styles parsed.
+#[derive(Debug, Clone, Default)]
+pub struct RowColors {
+    rules: Vec<(String, Style)>,
+}
+
+impl RowColors {
+    /// Parses `rules`, leaving out the ones whose style is invalid. Returns a message for
+    /// each rule left out.
+    pub fn compile(rules: &[ColorRule]) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        let rules = rules
+            .iter()
+            .filter_map(|rule| match parse_style(&rule.style) {
+                Ok(style) => Some((rule.when.clone(), style)),
+                Err(e) => {
+                    errors.push(format!("Color rule \"{}\": {}", rule.when, e));
+                    None
+                }
+            })
+            .collect();
+        (Self { rules }, errors)
+    }
+
+    /// Style of the first rule matching the row `field` describes.
+    pub fn style(&self, field: impl Fn(&str) -> Option<String>) -> Option<Style> {
+        self.rules
+            .iter()
+            .find(|(when, _)| presets::matches(when, &field))
+            .map(|(_, style)| *style)
+    }
+}
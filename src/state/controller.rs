@@ -1,13 +1,21 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
 use std::time::{Duration, Instant};
 
-use ratatui::widgets::ListState;
-
+use crate::error::Result;
+use crate::state::presets::FilterPreset;
+use crate::state::sort::natural_cmp;
+use crate::state::table::{TableRow, TableState};
 use crate::sys::service::ServiceInfo;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub use crate::state::table::SortOrder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SortKey {
     Name,
     Status,
+    StartType,
     Type,
 }
 
@@ -15,7 +23,8 @@ impl SortKey {
     pub fn next(&self) -> Self {
         match self {
             SortKey::Name => SortKey::Status,
-            SortKey::Status => SortKey::Type,
+            SortKey::Status => SortKey::StartType,
+            SortKey::StartType => SortKey::Type,
             SortKey::Type => SortKey::Name,
         }
     }
@@ -24,29 +33,19 @@ impl SortKey {
         match self {
             SortKey::Name => "Name",
             SortKey::Status => "Status",
+            SortKey::StartType => "Start",
             SortKey::Type => "Type",
         }
     }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortOrder {
-    Ascending,
-    Descending,
-}
 
-impl SortOrder {
-    pub fn toggle(&self) -> Self {
-        match self {
-            SortOrder::Ascending => SortOrder::Descending,
-            SortOrder::Descending => SortOrder::Ascending,
-        }
-    }
-
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            SortOrder::Ascending => "▲",
-            SortOrder::Descending => "▼",
+    /// Sort key for the Nth header column (1-based), as picked with Alt+1..9.
+    pub fn for_column(column: usize) -> Option<Self> {
+        match column {
+            1 => Some(SortKey::Name),
+            2 => Some(SortKey::Status),
+            3 => Some(SortKey::StartType),
+            4 => Some(SortKey::Type),
+            _ => None,
         }
     }
 }
@@ -64,310 +63,569 @@ fn status_priority(status: &str) -> u8 {
     }
 }
 
-pub struct ControllerState {
-    pub services: Vec<ServiceInfo>,
-    pub list_state: ListState,
-    pub active_filter: Option<String>,
-    pub selected_service_name: Option<String>,
-    pub last_navigation: Instant,
+/// The user-facing view settings of the Controller tab, as stored in a profile.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ControllerView {
+    pub filter: Option<String>,
     pub sort_key: SortKey,
     pub sort_order: SortOrder,
-    last_data_hash: u64,
-    is_initial_load: bool,
+    #[serde(default)]
+    pub group_mode: bool,
+}
+
+/// A line of the grouped view: a process hosting several services, or a service.
+pub enum GroupRow {
+    Host {
+        pid: u32,
+        /// Every service the process hosts, including ones the filter hides.
+        services: usize,
+        collapsed: bool,
+    },
+    Service {
+        service: ServiceInfo,
+        /// Listed under a host line rather than on its own.
+        grouped: bool,
+    },
+}
+
+impl GroupRow {
+    fn id(&self) -> String {
+        match self {
+            GroupRow::Host { pid, .. } => host_id(*pid),
+            GroupRow::Service { service, .. } => service.id(),
+        }
+    }
+}
+
+/// Selection id of a host line. Service names can't contain backslashes, so this never
+/// collides with one.
+fn host_id(pid: u32) -> String {
+    format!("\\{}", pid)
+}
+
+pub struct PendingService {
+    pub label: &'static str,
+    pub started: Instant,
+}
+
+/// A status change of a service seen between two polls.
+#[derive(Debug, Clone)]
+pub struct StatusChange {
+    pub from: String,
+    pub to: String,
+    /// Local wall-clock time the change was seen, HH:MM:SS.
+    pub clock: String,
+    pub at: Instant,
+}
+
+impl TableRow<SortKey> for ServiceInfo {
+    type Id = String;
+
+    fn id(&self) -> String {
+        self.service_name.clone()
+    }
+
+    fn search_fields(&self) -> Vec<String> {
+        vec![self.display_name.clone(), self.service_name.clone()]
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(format!("{} {}", self.service_name, self.display_name)),
+            "status" => Some(self.status.clone()),
+            "start" => Some(self.start_type.clone()),
+            "type" => Some(self.service_type.clone()),
+            "pid" => Some(self.pid.to_string()),
+            _ => None,
+        }
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        let mut values = vec![
+            ("service", self.service_name.clone()),
+            ("name", self.display_name.clone()),
+            ("status", self.status.clone()),
+        ];
+        // Stopped services have no process
+        if self.pid != 0 {
+            values.push(("pid", self.pid.to_string()));
+        }
+        values
+    }
+
+    fn compare(&self, other: &Self, key: SortKey) -> Ordering {
+        match key {
+            SortKey::Name => natural_cmp(&self.display_name, &other.display_name),
+            SortKey::Status => status_priority(&self.status).cmp(&status_priority(&other.status)),
+            SortKey::StartType => natural_cmp(&self.start_type, &other.start_type),
+            SortKey::Type => natural_cmp(&self.service_type, &other.service_type),
+        }
+    }
+
+    /// Display name, then service name, so rows with equal keys (e.g. all "Running") keep a
+    /// fixed order between polls.
+    fn tiebreak(&self, other: &Self) -> Ordering {
+        natural_cmp(&self.display_name, &other.display_name)
+            .then_with(|| self.service_name.cmp(&other.service_name))
+    }
+
+    fn hash_into(&self, hasher: &mut DefaultHasher) {
+        self.service_name.hash(hasher);
+        self.status.hash(hasher);
+    }
+}
+
+pub struct ControllerState {
+    pub table: TableState<ServiceInfo, SortKey>,
+    /// Services with a start/stop in flight, shown with progress in place of their status.
+    pub pending_services: std::collections::HashMap<String, PendingService>,
+    /// Service descriptions fetched for the status bar, by service name.
+    pub descriptions: std::collections::HashMap<String, String>,
+    /// Group services that share a process under a line for that process.
+    pub group_mode: bool,
+    pub group_rows: Vec<GroupRow>,
+    /// Host PIDs whose services are hidden in group mode.
+    pub collapsed_hosts: std::collections::HashSet<u32>,
+    /// Observed status changes by service name, oldest first.
+    pub history: std::collections::HashMap<String, std::collections::VecDeque<StatusChange>>,
+    /// Status changes seen this session by service name, including those `history` no
+    /// longer keeps.
+    change_counts: std::collections::HashMap<String, u32>,
+    /// Status of every service at the last poll, to spot changes.
+    last_status: std::collections::HashMap<String, String>,
 }
 
 impl ControllerState {
-    const NAVIGATION_DEBOUNCE: Duration = Duration::from_millis(50);
+    // Status changes kept per service
+    pub const HISTORY_LEN: usize = 20;
+    /// A service that comes back to running this often within `FLAP_WINDOW` is flapping.
+    pub const FLAP_STARTS: usize = 3;
+    pub const FLAP_WINDOW: Duration = Duration::from_secs(10 * 60);
 
     pub fn new() -> Self {
         Self {
-            services: Vec::new(),
-            list_state: ListState::default(),
-            active_filter: None,
-            selected_service_name: None,
-            last_navigation: Instant::now(),
-            sort_key: SortKey::Status,
-            sort_order: SortOrder::Ascending,
-            last_data_hash: 0,
-            is_initial_load: true,
+            table: TableState::new(SortKey::Status, SortOrder::Ascending),
+            pending_services: std::collections::HashMap::new(),
+            descriptions: std::collections::HashMap::new(),
+            group_mode: false,
+            group_rows: Vec::new(),
+            collapsed_hosts: std::collections::HashSet::new(),
+            history: std::collections::HashMap::new(),
+            change_counts: std::collections::HashMap::new(),
+            last_status: std::collections::HashMap::new(),
         }
     }
 
-    fn compute_data_hash(&self, services: &[ServiceInfo]) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        services.len().hash(&mut hasher);
-        for s in services {
-            s.service_name.hash(&mut hasher);
-            s.status.hash(&mut hasher);
+    /// Records services whose status differs from the previous poll. Services that just
+    /// appeared have nothing to compare with and start without history.
+    fn record_transitions(&mut self, services: &[ServiceInfo]) {
+        let now = Instant::now();
+        let mut clock = None;
+        for service in services {
+            if let Some(previous) = self.last_status.get(&service.service_name)
+                && *previous != service.status
+            {
+                let clock = clock.get_or_insert_with(crate::sys::system::local_clock);
+                *self.change_counts.entry(service.service_name.clone()).or_default() += 1;
+                let changes = self.history.entry(service.service_name.clone()).or_default();
+                if changes.len() == Self::HISTORY_LEN {
+                    changes.pop_front();
+                }
+                changes.push_back(StatusChange {
+                    from: previous.clone(),
+                    to: service.status.clone(),
+                    clock: clock.clone(),
+                    at: now,
+                });
+            }
         }
-        hasher.finish()
+        self.last_status = services
+            .iter()
+            .map(|s| (s.service_name.clone(), s.status.clone()))
+            .collect();
     }
 
-    pub fn should_ignore_update(&self) -> bool {
-        if self.is_initial_load {
-            return false;
-        }
-        self.last_navigation.elapsed() < Self::NAVIGATION_DEBOUNCE
+    /// Status changes seen for `name`, oldest first.
+    pub fn status_history(
+        &self,
+        name: &str,
+    ) -> Option<&std::collections::VecDeque<StatusChange>> {
+        self.history.get(name)
+    }
+
+    /// Status changes seen for `name` this session.
+    pub fn change_count(&self, name: &str) -> u32 {
+        self.change_counts.get(name).copied().unwrap_or(0)
+    }
+
+    /// Status changes seen this session, over all services.
+    pub fn total_changes(&self) -> u32 {
+        self.change_counts.values().sum()
     }
 
-    fn mark_navigation(&mut self) {
-        self.last_navigation = Instant::now();
+    /// How often `name` came back to running within the last `FLAP_WINDOW`, if that makes
+    /// it flapping: typically a service that keeps crashing and being restarted by its
+    /// recovery actions.
+    pub fn flapping(&self, name: &str) -> Option<usize> {
+        let starts = self
+            .history
+            .get(name)?
+            .iter()
+            .filter(|change| change.to == "Running" && change.at.elapsed() < Self::FLAP_WINDOW)
+            .count();
+        (starts >= Self::FLAP_STARTS).then_some(starts)
+    }
+
+    /// Number of services flapping right now.
+    pub fn flapping_count(&self) -> usize {
+        self.history
+            .keys()
+            .filter(|name| self.flapping(name).is_some())
+            .count()
     }
 
     pub fn set_filter(&mut self, query: String) {
         // Filter changes are instant - no debounce
-        if query.is_empty() {
-            self.active_filter = None;
-        } else {
-            self.active_filter = Some(query.to_lowercase());
-        }
-
-        self.update_selection_from_name();
+        self.table.set_filter(query);
+        self.update_selection();
     }
 
     pub fn clear_filter(&mut self) {
         // Filter changes are instant - no debounce
-        self.active_filter = None;
-        self.update_selection_from_name();
+        self.table.active_filter = None;
+        self.update_selection();
     }
 
-    pub fn cycle_sort_key(&mut self) {
-        self.sort_key = self.sort_key.next();
-        self.sort_services();
-        self.update_selection_from_name();
+    pub fn view(&self) -> ControllerView {
+        ControllerView {
+            filter: self.table.active_filter.clone(),
+            sort_key: self.table.sort_key,
+            sort_order: self.table.sort_order,
+            group_mode: self.group_mode,
+        }
     }
 
-    pub fn toggle_sort_order(&mut self) {
-        self.sort_order = self.sort_order.toggle();
-        self.sort_services();
-        self.update_selection_from_name();
-    }
-
-    fn sort_services(&mut self) {
-        match self.sort_key {
-            SortKey::Name => {
-                self.services.sort_by(|a, b| {
-                    let cmp = a.display_name.cmp(&b.display_name);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Status => {
-                self.services.sort_by(|a, b| {
-                    let a_priority = status_priority(&a.status);
-                    let b_priority = status_priority(&b.status);
-                    let cmp = a_priority.cmp(&b_priority);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Type => {
-                self.services.sort_by(|a, b| {
-                    let cmp = a.service_type.cmp(&b.service_type);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
+    pub fn apply_view(&mut self, view: &ControllerView) {
+        self.table.active_filter = view.filter.clone();
+        self.table.sort_key = view.sort_key;
+        self.table.sort_order = view.sort_order;
+        self.group_mode = view.group_mode;
+        self.table.sort();
+        self.update_selection();
+    }
+
+    pub fn toggle_group_mode(&mut self) {
+        self.group_mode = !self.group_mode;
+        self.update_selection();
+    }
+
+    /// Collapses or expands the selected host, or the host of the selected service.
+    pub fn toggle_collapse(&mut self) {
+        if !self.group_mode {
+            return;
+        }
+        let Some(idx) = self.table.list_state.selected() else {
+            return;
+        };
+        let pid = match self.group_rows.get(idx) {
+            Some(GroupRow::Host { pid, .. }) => *pid,
+            Some(GroupRow::Service { service, grouped: true }) => service.pid,
+            _ => return,
+        };
+        if !self.collapsed_hosts.remove(&pid) {
+            self.collapsed_hosts.insert(pid);
         }
+        // Keep the cursor on the host line, since a collapsed service has no row
+        self.table.selected = Some(host_id(pid));
+        self.update_selection();
     }
 
-    fn update_selection_from_name(&mut self) {
-        if let Some(ref name) = self.selected_service_name {
-            let filtered = self.get_filtered_indices("");
-            if let Some(new_idx) = filtered.iter().position(|&i| {
-                self.services
-                    .get(i)
-                    .map(|s| &s.service_name == name)
-                    .unwrap_or(false)
-            }) {
-                self.list_state.select(Some(new_idx));
-            } else if !filtered.is_empty() {
-                self.list_state.select(Some(0));
-                self.selected_service_name = filtered.first()
-                    .and_then(|&i| self.services.get(i))
-                    .map(|s| s.service_name.clone());
+    /// Rebuilds the grouped view. Processes hosting two or more services get a line each,
+    /// ordered by their first service in the current sort, with their services below;
+    /// services running alone or stopped follow ungrouped.
+    pub fn build_groups(&mut self, search_query: &str) {
+        let mut hosted: std::collections::HashMap<u32, usize> = std::collections::HashMap::new();
+        for service in self.table.rows.iter().filter(|s| s.pid != 0) {
+            *hosted.entry(service.pid).or_default() += 1;
+        }
+
+        let mut hosts: Vec<u32> = Vec::new();
+        let mut members: std::collections::HashMap<u32, Vec<ServiceInfo>> =
+            std::collections::HashMap::new();
+        let mut alone = Vec::new();
+        for i in self.get_filtered_indices(search_query) {
+            let service = &self.table.rows[i];
+            if hosted.get(&service.pid).is_some_and(|&n| n > 1) {
+                members
+                    .entry(service.pid)
+                    .or_insert_with(|| {
+                        hosts.push(service.pid);
+                        Vec::new()
+                    })
+                    .push(service.clone());
             } else {
-                self.list_state.select(None);
-                self.selected_service_name = None;
+                alone.push(service.clone());
             }
-        } else if !self.services.is_empty() {
-            self.list_state.select(Some(0));
-            self.selected_service_name = self.services.first().map(|s| s.service_name.clone());
         }
+
+        self.group_rows.clear();
+        for pid in hosts {
+            let collapsed = self.collapsed_hosts.contains(&pid);
+            self.group_rows.push(GroupRow::Host {
+                pid,
+                services: hosted[&pid],
+                collapsed,
+            });
+            if !collapsed {
+                let services = members.remove(&pid).unwrap_or_default();
+                self.group_rows.extend(
+                    services
+                        .into_iter()
+                        .map(|service| GroupRow::Service { service, grouped: true }),
+                );
+            }
+        }
+        self.group_rows.extend(
+            alone
+                .into_iter()
+                .map(|service| GroupRow::Service { service, grouped: false }),
+        );
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        self.table.sort_key = self.table.sort_key.next();
+        self.table.sort();
+        self.update_selection();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.table.toggle_sort_order();
+        self.update_selection();
+    }
+
+    /// Sorts by `key`, or flips the order when already sorted by it.
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.table.sort_by(key);
+        self.update_selection();
     }
 
-    fn get_filter(&self, search_query: &str) -> Option<String> {
-        if !search_query.is_empty() {
-            Some(search_query.to_lowercase())
+    fn update_selection(&mut self) {
+        if self.group_mode {
+            self.build_groups("");
+        }
+        let shown = self.shown_ids("");
+        self.table.restore_selection(&shown);
+    }
+
+    /// Ids of the rows on screen, in order: host and service lines in group mode, else
+    /// the filtered list.
+    fn shown_ids(&self, search_query: &str) -> Vec<String> {
+        if self.group_mode {
+            self.group_rows.iter().map(GroupRow::id).collect()
         } else {
-            self.active_filter.clone()
+            self.table.ids(&self.get_filtered_indices(search_query))
         }
     }
 
-    fn matches_filter(&self, service: &ServiceInfo, query: &str) -> bool {
-        service.display_name.to_lowercase().contains(query)
-            || service.service_name.to_lowercase().contains(query)
+    /// Char positions in `text` matched by the current filter, for highlighting.
+    pub fn match_positions(&self, text: &str, search_query: &str) -> Vec<usize> {
+        self.table.match_positions(text, search_query)
+    }
+
+    pub fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        self.table.preset = preset;
+        self.update_selection();
     }
 
     pub fn get_filtered_indices(&self, search_query: &str) -> Vec<usize> {
-        match self.get_filter(search_query) {
-            None => (0..self.services.len()).collect(),
-            Some(query) => self
-                .services
-                .iter()
-                .enumerate()
-                .filter(|(_, s)| self.matches_filter(s, &query))
-                .map(|(i, _)| i)
-                .collect(),
-        }
+        self.table.filtered_indices(search_query, |_| true)
     }
 
     pub fn filtered_services(&self, search_query: &str) -> Vec<(usize, ServiceInfo)> {
-        match self.get_filter(search_query) {
-            None => self
-                .services
-                .iter()
-                .enumerate()
-                .map(|(i, s)| (i, s.clone()))
-                .collect(),
-            Some(query) => self
-                .services
-                .iter()
-                .enumerate()
-                .filter(|(_, s)| self.matches_filter(s, &query))
-                .map(|(i, s)| (i, s.clone()))
-                .collect(),
-        }
+        self.get_filtered_indices(search_query)
+            .into_iter()
+            .map(|i| (i, self.table.rows[i].clone()))
+            .collect()
     }
 
     pub fn update_services(&mut self, services: Vec<ServiceInfo>) {
-        // Check if data actually changed
-        let new_hash = self.compute_data_hash(&services);
+        // Track changes even when the table skips this update, so none are missed
+        self.record_transitions(&services);
 
-        if new_hash == self.last_data_hash {
-            // Data hasn't changed, skip update
+        // Skip unchanged data and updates during active navigation
+        if !self.table.accept_update(&services) {
             return;
         }
-        self.last_data_hash = new_hash;
-
-        // Don't update during active navigation (but always allow initial load)
-        if self.should_ignore_update() {
-            return;
-        }
-
-        self.services = services;
-        self.sort_services();
-        self.update_selection_from_name();
 
-        // Mark initial load as complete
-        self.is_initial_load = false;
+        let mut services = services;
+        self.table.carry_frozen(&mut services);
+        self.table.rows = services;
+        self.table.sort();
+        self.update_selection();
+        self.table.finish_update();
     }
 
     pub fn select_next(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let new_idx = (i + 1) % filtered.len();
-        self.list_state.select(Some(new_idx));
-        self.selected_service_name = filtered
-            .get(new_idx)
-            .and_then(|&idx| self.services.get(idx))
-            .map(|s| s.service_name.clone());
+        let shown = self.shown_ids(search_query);
+        self.table.select_next(&shown);
     }
 
     pub fn select_prev(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let new_idx = (i + filtered.len() - 1) % filtered.len();
-        self.list_state.select(Some(new_idx));
-        self.selected_service_name = filtered
-            .get(new_idx)
-            .and_then(|&idx| self.services.get(idx))
-            .map(|s| s.service_name.clone());
+        let shown = self.shown_ids(search_query);
+        self.table.select_prev(&shown);
     }
 
     pub fn select_page_up(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let page_size = 10;
-        let new_idx = i.saturating_sub(page_size);
-        self.list_state.select(Some(new_idx));
-        self.selected_service_name = filtered
-            .get(new_idx)
-            .and_then(|&idx| self.services.get(idx))
-            .map(|s| s.service_name.clone());
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_up(&shown);
     }
 
     pub fn select_page_down(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_down(&shown);
+    }
+
+    pub fn select_screen_up(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_up(&shown);
+    }
+
+    pub fn select_screen_down(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_down(&shown);
+    }
+
+    pub fn move_up(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_up(&shown, rows);
+    }
+
+    pub fn move_down(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_down(&shown, rows);
+    }
+
+    pub fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        let (shown, matches): (Vec<_>, Vec<bool>) = if self.group_mode {
+            self.group_rows
+                .iter()
+                .map(|row| match row {
+                    GroupRow::Host { .. } => (row.id(), false),
+                    GroupRow::Service { service, .. } => {
+                        (row.id(), self.table.row_matches(service, pattern))
+                    }
+                })
+                .unzip()
+        } else {
+            self.get_filtered_indices(search_query)
+                .into_iter()
+                .map(|i| {
+                    let row = &self.table.rows[i];
+                    (row.id(), self.table.row_matches(row, pattern))
+                })
+                .unzip()
+        };
+        self.table.find(&shown, &matches, forward, include_current)
+    }
+
+    /// Freezes or unfreezes the selected row. Returns its label and new state.
+    pub fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        let row = self.selected_service(search_query)?.clone();
+        let frozen = self.table.toggle_freeze(&row);
+        if !frozen {
+            self.update_selection();
         }
-        let i = self.list_state.selected().unwrap_or(0);
-        let page_size = 10;
-        let new_idx = std::cmp::min(i + page_size, filtered.len().saturating_sub(1));
-        self.list_state.select(Some(new_idx));
-        self.selected_service_name = filtered
-            .get(new_idx)
-            .and_then(|&idx| self.services.get(idx))
-            .map(|s| s.service_name.clone());
+        Some((row.display_name.clone(), frozen))
+    }
+
+    pub fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        let shown = self.shown_ids(search_query);
+        self.table.jump_to_mark(mark, &shown)
     }
 
     pub fn select_first(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if !filtered.is_empty() {
-            self.list_state.select(Some(0));
-            self.selected_service_name = filtered
-                .first()
-                .and_then(|&idx| self.services.get(idx))
-                .map(|s| s.service_name.clone());
-        }
+        let shown = self.shown_ids(search_query);
+        self.table.select_first(&shown);
     }
 
     pub fn select_last(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if !filtered.is_empty() {
-            let last_idx = filtered.len() - 1;
-            self.list_state.select(Some(last_idx));
-            self.selected_service_name = filtered
-                .get(last_idx)
-                .and_then(|&idx| self.services.get(idx))
-                .map(|s| s.service_name.clone());
+        let shown = self.shown_ids(search_query);
+        self.table.select_last(&shown);
+    }
+
+    pub fn mark_pending(&mut self, name: &str, label: &'static str) {
+        self.pending_services.insert(
+            name.to_string(),
+            PendingService {
+                label,
+                started: Instant::now(),
+            },
+        );
+    }
+
+    /// Selects the service named `name`, dropping the filter and preset if they hide it.
+    /// Returns false when no such service exists.
+    pub fn focus_service(&mut self, name: &str) -> bool {
+        let Some(service) = self
+            .table
+            .rows
+            .iter()
+            .find(|s| s.service_name.eq_ignore_ascii_case(name))
+        else {
+            return false;
+        };
+        let id = service.service_name.clone();
+        self.collapsed_hosts.remove(&service.pid);
+        if self.group_mode {
+            self.build_groups("");
         }
+        if !self.shown_ids("").contains(&id) {
+            self.table.active_filter = None;
+            self.table.preset = None;
+        }
+        self.table.selected = Some(id);
+        self.update_selection();
+        true
     }
 
-    pub fn toggle_selected_service(&mut self, search_query: &str) {
-        let filtered = self.get_filtered_indices(search_query);
-        if let Some(idx) = self.list_state.selected()
-            && let Some(&original_idx) = filtered.get(idx)
-                && let Some(service) = self.services.get(original_idx) {
-                    let _ =
-                        crate::sys::service::toggle_service(&service.service_name, &service.status);
-                }
+    /// Names of the running services hosted by `pid`, e.g. the services sharing an svchost.exe.
+    pub fn services_for_pid(&self, pid: u32) -> Vec<&str> {
+        if pid == 0 {
+            return Vec::new();
+        }
+        let mut names: Vec<&str> = self
+            .table
+            .rows
+            .iter()
+            .filter(|s| s.pid == pid)
+            .map(|s| s.service_name.as_str())
+            .collect();
+        names.sort_by(|a, b| natural_cmp(a, b));
+        names
+    }
+
+    pub fn selected_service(&self, search_query: &str) -> Option<&ServiceInfo> {
+        if self.group_mode {
+            match self.group_rows.get(self.table.list_state.selected()?)? {
+                GroupRow::Service { service, .. } => Some(service),
+                GroupRow::Host { .. } => None,
+            }
+        } else {
+            self.table.selected_row(&self.get_filtered_indices(search_query))
+        }
+    }
+
+    /// PID of the selected host line in group mode.
+    pub fn selected_host(&self) -> Option<u32> {
+        match self.group_rows.get(self.table.list_state.selected()?)? {
+            GroupRow::Host { pid, .. } if self.group_mode => Some(*pid),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,65 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+pub struct ErrorEntry {
+    pub source: String,
+    pub message: String,
+    pub code: Option<u32>,
+    pub count: u32,
+    pub last_seen: Instant,
+}
+
+/// Failures from background refreshes and user actions, newest first.
+/// Repeats of the same error are folded into one entry so a failing poll doesn't flood the log.
+pub struct ErrorLog {
+    pub entries: VecDeque<ErrorEntry>,
+    pub unseen: usize,
+}
+
+impl ErrorLog {
+    const MAX_ENTRIES: usize = 200;
+
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+            unseen: 0,
+        }
+    }
+
+    pub fn record(&mut self, source: &str, message: String, code: Option<u32>) {
+        if let Some(pos) = self
+            .entries
+            .iter()
+            .position(|e| e.source == source && e.message == message)
+        {
+            // Move the repeat to the top instead of adding a new row
+            if let Some(mut entry) = self.entries.remove(pos) {
+                entry.count += 1;
+                entry.last_seen = Instant::now();
+                self.entries.push_front(entry);
+            }
+            return;
+        }
+
+        if self.entries.len() == Self::MAX_ENTRIES {
+            self.entries.pop_back();
+        }
+        self.entries.push_front(ErrorEntry {
+            source: source.to_string(),
+            message,
+            code,
+            count: 1,
+            last_seen: Instant::now(),
+        });
+        self.unseen += 1;
+    }
+
+    pub fn mark_seen(&mut self) {
+        self.unseen = 0;
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.unseen = 0;
+    }
+}
@@ -0,0 +1,65 @@
+use std::time::{Duration, Instant};
+
+/// Paths checked every few seconds until no process locks them, so a file move or deploy
+/// can go ahead without rechecking by hand.
+pub struct LockWatch {
+    pub paths: Vec<String>,
+    /// Processes locking the paths at the last check; None before the first.
+    pub lockers: Option<usize>,
+    /// When the paths were found free. Checks stop then.
+    pub freed_at: Option<Instant>,
+    pub checking: bool,
+    checked_at: Option<Instant>,
+}
+
+impl LockWatch {
+    /// How long the status bar keeps saying the paths are free.
+    pub const FREE_BADGE: Duration = Duration::from_secs(60);
+
+    pub fn new(paths: Vec<String>) -> Self {
+        Self {
+            paths,
+            lockers: None,
+            freed_at: None,
+            checking: false,
+            checked_at: None,
+        }
+    }
+
+    /// Whether to check again, `interval` after the last check.
+    pub fn due(&self, interval: Duration) -> bool {
+        !self.checking
+            && self.freed_at.is_none()
+            && self.checked_at.is_none_or(|at| at.elapsed() >= interval)
+    }
+
+    /// Records how many processes a check found. True when that made the paths free.
+    pub fn record(&mut self, lockers: usize) -> bool {
+        self.checking = false;
+        self.checked_at = Some(Instant::now());
+        self.lockers = Some(lockers);
+        if lockers == 0 && self.freed_at.is_none() {
+            self.freed_at = Some(Instant::now());
+            return true;
+        }
+        false
+    }
+
+    /// Free for long enough that the status bar can stop saying so.
+    pub fn expired(&self) -> bool {
+        self.freed_at.is_some_and(|at| at.elapsed() >= Self::FREE_BADGE)
+    }
+
+    /// The first path's file name, and how many others are watched with it.
+    pub fn label(&self) -> String {
+        let first = self.paths.first().map(String::as_str).unwrap_or("");
+        let name = std::path::Path::new(first)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| first.to_string());
+        match self.paths.len() {
+            0 | 1 => name,
+            n => format!("{} +{}", name, n - 1),
+        }
+    }
+}
@@ -1,15 +1,31 @@
-use std::time::{Duration, Instant};
-
-use ratatui::widgets::ListState;
-
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+
+use crate::error::Result;
+use crate::state::presets::FilterPreset;
+use crate::state::sort::natural_cmp;
+use crate::state::table::{TableRow, TableState};
+use crate::ui::text::display_width;
 use crate::sys::process::ProcessInfo;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub use crate::state::table::SortOrder;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SortKey {
     Name,
     Pid,
     Cpu,
     Memory,
+    PrivateBytes,
+    Gpu,
+    GpuMemory,
+    DiskIo,
+    Started,
+    Handles,
+    GdiObjects,
+    UserObjects,
+    Job,
 }
 
 impl SortKey {
@@ -18,7 +34,16 @@ impl SortKey {
             SortKey::Name => SortKey::Pid,
             SortKey::Pid => SortKey::Cpu,
             SortKey::Cpu => SortKey::Memory,
-            SortKey::Memory => SortKey::Name,
+            SortKey::Memory => SortKey::PrivateBytes,
+            SortKey::PrivateBytes => SortKey::Gpu,
+            SortKey::Gpu => SortKey::GpuMemory,
+            SortKey::GpuMemory => SortKey::DiskIo,
+            SortKey::DiskIo => SortKey::Started,
+            SortKey::Started => SortKey::Handles,
+            SortKey::Handles => SortKey::GdiObjects,
+            SortKey::GdiObjects => SortKey::UserObjects,
+            SortKey::UserObjects => SortKey::Job,
+            SortKey::Job => SortKey::Name,
         }
     }
 
@@ -28,32 +53,135 @@ impl SortKey {
             SortKey::Pid => "PID",
             SortKey::Cpu => "CPU",
             SortKey::Memory => "Mem",
+            SortKey::PrivateBytes => "Private",
+            SortKey::Gpu => "GPU",
+            SortKey::GpuMemory => "GPU Mem",
+            SortKey::DiskIo => "Disk",
+            SortKey::Started => "Started",
+            SortKey::Handles => "Handles",
+            SortKey::GdiObjects => "GDI",
+            SortKey::UserObjects => "USER",
+            SortKey::Job => "Job",
         }
     }
+
+    /// Sort key for the Nth header column (1-based), as picked with Alt+1..9.
+    /// Path has no sort key; Read/s and Write/s both sort by total disk I/O.
+    pub fn for_column(column: usize) -> Option<Self> {
+        match column {
+            1 => Some(SortKey::Pid),
+            2 => Some(SortKey::Name),
+            3 => Some(SortKey::Cpu),
+            4 => Some(SortKey::Memory),
+            5 => Some(SortKey::Gpu),
+            6 => Some(SortKey::GpuMemory),
+            7 | 8 => Some(SortKey::DiskIo),
+            9 => Some(SortKey::Started),
+            _ => None,
+        }
+    }
+
+    /// Keys of the optional handle/GDI/USER columns, skipped while those are hidden.
+    pub fn is_object_column(&self) -> bool {
+        matches!(self, SortKey::Handles | SortKey::GdiObjects | SortKey::UserObjects)
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortOrder {
-    Ascending,
-    Descending,
+/// Counts above which the handle, GDI and USER columns turn yellow; at twice the count
+/// they turn red. GDI and USER objects are capped at 10,000 per process by default.
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+pub struct ObjectThresholds {
+    #[serde(default = "default_handle_threshold")]
+    pub handles: u32,
+    #[serde(default = "default_gui_threshold")]
+    pub gdi: u32,
+    #[serde(default = "default_gui_threshold")]
+    pub user: u32,
 }
 
-impl SortOrder {
-    pub fn toggle(&self) -> Self {
-        match self {
-            SortOrder::Ascending => SortOrder::Descending,
-            SortOrder::Descending => SortOrder::Ascending,
+fn default_handle_threshold() -> u32 {
+    10_000
+}
+
+fn default_gui_threshold() -> u32 {
+    5_000
+}
+
+impl Default for ObjectThresholds {
+    fn default() -> Self {
+        Self {
+            handles: default_handle_threshold(),
+            gdi: default_gui_threshold(),
+            user: default_gui_threshold(),
         }
     }
+}
 
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            SortOrder::Ascending => "▲",
-            SortOrder::Descending => "▼",
+/// Which container or VM environment a process image belongs to, by lowercase name.
+/// WSL 2 distros and Hyper-V isolated containers run inside VMs, so the host only sees
+/// their helper processes; process-isolated Windows containers run under CExecSvc.
+fn container_kind(name: &str) -> Option<&'static str> {
+    match name.to_lowercase().as_str() {
+        "wsl.exe" | "wslhost.exe" | "wslservice.exe" | "wslrelay.exe" | "wslg.exe"
+        | "vmmemwsl" => Some("wsl"),
+        "docker.exe" | "dockerd.exe" | "containerd.exe" | "com.docker.backend.exe"
+        | "com.docker.build.exe" | "com.docker.proxy.exe" | "docker desktop.exe"
+        | "vpnkit.exe" => Some("docker"),
+        "vmcompute.exe" | "cexecsvc.exe" => Some("container"),
+        "vmmem" | "vmwp.exe" => Some("vm"),
+        _ => None,
+    }
+}
+
+/// Tags processes that belong to WSL, Docker or Windows containers. Children inherit their
+/// parent's tag, so shells started by wsl.exe or processes inside a container's CExecSvc
+/// are tagged too.
+pub fn tag_containers(processes: &mut [ProcessInfo]) {
+    let by_pid: std::collections::HashMap<u32, (u32, Option<&'static str>)> = processes
+        .iter()
+        .map(|p| (p.pid, (p.parent_pid, container_kind(&p.name))))
+        .collect();
+
+    for process in processes.iter_mut() {
+        let mut pid = process.pid;
+        // Bounded, since PID reuse can make the parent chain loop
+        let mut tag = None;
+        for _ in 0..32 {
+            let Some(&(parent, kind)) = by_pid.get(&pid) else {
+                break;
+            };
+            if kind.is_some() {
+                tag = kind;
+                break;
+            }
+            if parent == 0 || parent == pid {
+                break;
+            }
+            pid = parent;
         }
+        process.container = tag.map(str::to_string);
     }
 }
 
+/// The user-facing view settings of the Locker tab, as stored in a profile.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LockerView {
+    pub filter: Option<String>,
+    pub sort_key: SortKey,
+    pub sort_order: SortOrder,
+    pub tree_mode: bool,
+    #[serde(default)]
+    pub show_object_columns: bool,
+    #[serde(default)]
+    pub show_command_line: bool,
+    #[serde(default)]
+    pub show_jobs: bool,
+    #[serde(default)]
+    pub show_top: bool,
+    #[serde(default)]
+    pub cpu_per_core: bool,
+}
+
 pub struct TreeNode {
     pub process: ProcessInfo,
     pub depth: usize,
@@ -61,48 +189,371 @@ pub struct TreeNode {
     pub has_children: bool,
 }
 
+impl ProcessInfo {
+    /// Seconds since the process started, if its start time could be read.
+    pub fn uptime_secs(&self) -> Option<u64> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::SystemTime::UNIX_EPOCH)
+            .ok()?
+            .as_secs();
+        self.start_time.map(|start| now.saturating_sub(start))
+    }
+}
+
+impl TableRow<SortKey> for ProcessInfo {
+    type Id = u32;
+
+    fn id(&self) -> u32 {
+        self.pid
+    }
+
+    fn search_fields(&self) -> Vec<String> {
+        vec![
+            self.name.clone(),
+            self.path.clone().unwrap_or_default(),
+            self.pid.to_string(),
+            self.command_line.clone().unwrap_or_default(),
+            self.container.clone().unwrap_or_default(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "name" => Some(self.name.clone()),
+            "path" => Some(self.path.clone().unwrap_or_default()),
+            "cmdline" => self.command_line.clone(),
+            "container" => Some(self.container.clone().unwrap_or_default()),
+            "pid" => Some(self.pid.to_string()),
+            "parent" => Some(self.parent_pid.to_string()),
+            "cpu" => Some(self.cpu_usage.max(self.last_cpu_usage).to_string()),
+            "mem" => Some(self.memory_mb.max(self.last_memory_mb).to_string()),
+            "private" => Some(self.private_mb.to_string()),
+            "gpu" => Some(self.gpu_usage.to_string()),
+            "io" => Some((self.io_read_bps + self.io_write_bps).to_string()),
+            "uptime" => self.uptime_secs().map(|secs| secs.to_string()),
+            "handles" => Some(self.handle_count.to_string()),
+            "gdi" => Some(self.gdi_objects.to_string()),
+            "user" => Some(self.user_objects.to_string()),
+            "job" => self.job_id.map(|id| id.to_string()),
+            _ => None,
+        }
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        let mut values = vec![
+            ("pid", self.pid.to_string()),
+            ("name", self.name.clone()),
+            ("parent", self.parent_pid.to_string()),
+        ];
+        if let Some(path) = &self.path {
+            values.push(("path", path.clone()));
+        }
+        if let Some(command_line) = &self.command_line {
+            values.push(("cmdline", command_line.clone()));
+        }
+        values
+    }
+
+    fn compare(&self, other: &Self, key: SortKey) -> Ordering {
+        match key {
+            SortKey::Name => natural_cmp(&self.name, &other.name),
+            SortKey::Pid => self.pid.cmp(&other.pid),
+            SortKey::Cpu => {
+                let a_val = if self.cpu_usage > 0.0 {
+                    self.cpu_usage
+                } else {
+                    self.last_cpu_usage
+                };
+                let b_val = if other.cpu_usage > 0.0 {
+                    other.cpu_usage
+                } else {
+                    other.last_cpu_usage
+                };
+                a_val.partial_cmp(&b_val).unwrap_or(Ordering::Equal)
+            }
+            SortKey::Memory => {
+                let a_val = if self.memory_mb > 0.0 {
+                    self.memory_mb
+                } else {
+                    self.last_memory_mb
+                };
+                let b_val = if other.memory_mb > 0.0 {
+                    other.memory_mb
+                } else {
+                    other.last_memory_mb
+                };
+                a_val.partial_cmp(&b_val).unwrap_or(Ordering::Equal)
+            }
+            SortKey::PrivateBytes => self
+                .private_mb
+                .partial_cmp(&other.private_mb)
+                .unwrap_or(Ordering::Equal),
+            SortKey::Gpu => self
+                .gpu_usage
+                .partial_cmp(&other.gpu_usage)
+                .unwrap_or(Ordering::Equal),
+            SortKey::GpuMemory => self
+                .gpu_memory_mb
+                .partial_cmp(&other.gpu_memory_mb)
+                .unwrap_or(Ordering::Equal),
+            SortKey::DiskIo => (self.io_read_bps + self.io_write_bps)
+                .partial_cmp(&(other.io_read_bps + other.io_write_bps))
+                .unwrap_or(Ordering::Equal),
+            // Unknown start times sort as the oldest
+            SortKey::Started => self.start_time.cmp(&other.start_time),
+            SortKey::Handles => self.handle_count.cmp(&other.handle_count),
+            SortKey::GdiObjects => self.gdi_objects.cmp(&other.gdi_objects),
+            SortKey::UserObjects => self.user_objects.cmp(&other.user_objects),
+            // Processes outside any job sort after every job in ascending order
+            SortKey::Job => match (self.job_id, other.job_id) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+        }
+    }
+
+    /// Name, then PID, so equal rows don't swap places between polls.
+    fn tiebreak(&self, other: &Self) -> Ordering {
+        natural_cmp(&self.name, &other.name).then_with(|| self.pid.cmp(&other.pid))
+    }
+
+    fn hash_into(&self, hasher: &mut DefaultHasher) {
+        self.pid.hash(hasher);
+        self.name.hash(hasher);
+    }
+}
+
+/// A process that exited, kept in the list for a while with its last known metrics.
+#[derive(Debug, Clone)]
+pub struct Tombstone {
+    pub process: ProcessInfo,
+    pub exited_at: std::time::Instant,
+    /// Local time of the poll that first missed the process, as HH:MM:SS.
+    pub clock: String,
+}
+
 pub struct LockerState {
-    pub processes: Vec<ProcessInfo>,
-    pub list_state: ListState,
-    pub active_filter: Option<String>,
-    pub selected_pid: Option<u32>,
-    pub last_navigation: Instant,
-    pub sort_key: SortKey,
-    pub sort_order: SortOrder,
+    pub table: TableState<ProcessInfo, SortKey>,
     pub tree_mode: bool,
     pub tree_nodes: Vec<TreeNode>,
     pub expanded_pids: std::collections::HashSet<u32>,
-    last_data_hash: u64,
-    is_initial_load: bool,
+    /// PIDs with a kill or dump in flight.
+    pub pending_kills: std::collections::HashSet<u32>,
+    /// Show the handle, GDI and USER object columns.
+    pub show_object_columns: bool,
+    pub object_thresholds: ObjectThresholds,
+    /// Show command lines instead of image paths in the path column.
+    pub show_command_line: bool,
+    /// Command lines read so far by PID; None where the process couldn't be read.
+    pub command_lines: std::collections::HashMap<u32, Option<String>>,
+    /// A background command line fetch is running.
+    pub fetching_command_lines: bool,
+    /// Exited processes still listed, by PID.
+    pub tombstones: std::collections::HashMap<u32, Tombstone>,
+    /// Group processes by job object and prefix them with their job number.
+    pub show_jobs: bool,
+    /// Session-stable job numbers by job object address.
+    pub job_numbers: std::collections::HashMap<usize, u32>,
+    /// Job number of each PID from the last scan.
+    pub job_of: std::collections::HashMap<u32, u32>,
+    /// A background job scan is running.
+    pub scanning_jobs: bool,
+    pub jobs_scanned_at: Option<std::time::Instant>,
+    /// Show the top CPU and memory consumers above the list, whatever the sort and filter.
+    pub show_top: bool,
+    /// Columns the path column is scrolled right by, to read the end of long paths.
+    pub path_scroll: usize,
+    /// Show CPU as a percentage of one core rather than of all of them, so a process
+    /// saturating a single core reads 100% instead of 100 divided by the core count.
+    pub cpu_per_core: bool,
+    /// Peak and average CPU and memory of each process since Aperture started.
+    pub usage: crate::state::usage::SessionUsage,
 }
 
 impl LockerState {
-    // Short debounce for navigation only (50ms) - allows real-time feel while preventing jitter
-    const NAVIGATION_DEBOUNCE: Duration = Duration::from_millis(50);
+    /// How long an exited process stays listed.
+    pub const TOMBSTONE_TTL: std::time::Duration = std::time::Duration::from_secs(30);
+    /// How often job membership is rescanned; walking the system handle table is costly.
+    pub const JOB_SCAN_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+    /// Columns one `h`/`l` press scrolls the path column by.
+    pub const PATH_SCROLL_STEP: usize = 8;
 
     pub fn new() -> Self {
         Self {
-            processes: Vec::new(),
-            list_state: ListState::default(),
-            active_filter: None,
-            selected_pid: None,
-            last_navigation: Instant::now(),
-            sort_key: SortKey::Cpu,
-            sort_order: SortOrder::Descending,
+            table: TableState::new(SortKey::Cpu, SortOrder::Descending),
             tree_mode: false,
             tree_nodes: Vec::new(),
             expanded_pids: std::collections::HashSet::new(),
-            last_data_hash: 0,
-            is_initial_load: true,
+            pending_kills: std::collections::HashSet::new(),
+            show_object_columns: false,
+            object_thresholds: ObjectThresholds::default(),
+            tombstones: std::collections::HashMap::new(),
+            show_command_line: false,
+            command_lines: std::collections::HashMap::new(),
+            fetching_command_lines: false,
+            show_jobs: false,
+            job_numbers: std::collections::HashMap::new(),
+            job_of: std::collections::HashMap::new(),
+            scanning_jobs: false,
+            jobs_scanned_at: None,
+            show_top: false,
+            path_scroll: 0,
+            cpu_per_core: false,
+            usage: crate::state::usage::SessionUsage::default(),
         }
     }
 
+    pub fn toggle_cpu_per_core(&mut self) {
+        self.cpu_per_core = !self.cpu_per_core;
+    }
+
+    /// A CPU usage measured as a percentage of all cores, in the mode the tab shows CPU in.
+    pub fn cpu_display(&self, percent: f32) -> f32 {
+        if self.cpu_per_core {
+            percent * crate::sys::process::get_num_cpus() as f32
+        } else {
+            percent
+        }
+    }
+
+    pub fn toggle_top(&mut self) {
+        self.show_top = !self.show_top;
+    }
+
+    /// The `n` running processes using the most CPU and the `n` with the largest working
+    /// sets, ignoring the filter, preset and tree. The idle process is left out.
+    pub fn top_offenders(&self, n: usize) -> (Vec<&ProcessInfo>, Vec<&ProcessInfo>) {
+        let mut processes: Vec<&ProcessInfo> =
+            self.live_processes().filter(|p| p.pid != 0).collect();
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        let cpu = processes.iter().take(n).copied().collect();
+        processes.sort_by(|a, b| b.memory_mb.total_cmp(&a.memory_mb));
+        let memory = processes.into_iter().take(n).collect();
+        (cpu, memory)
+    }
+
+    pub fn toggle_object_columns(&mut self) {
+        self.show_object_columns = !self.show_object_columns;
+    }
+
+    pub fn toggle_command_line(&mut self) {
+        self.show_command_line = !self.show_command_line;
+        self.path_scroll = 0;
+    }
+
+    /// The path or command line the path column shows for `p`.
+    pub fn path_text<'a>(&self, p: &'a ProcessInfo) -> &'a str {
+        // Fall back to the path until the command line has been read, or if it can't be
+        match (&p.command_line, self.show_command_line) {
+            (Some(command_line), true) => command_line.as_str(),
+            _ => p.path.as_deref().unwrap_or("-"),
+        }
+    }
+
+    /// Scrolls the path column by a step, never so far that the longest path scrolls out
+    /// of view.
+    pub fn scroll_path(&mut self, right: bool) {
+        if right {
+            let longest = self
+                .live_processes()
+                .map(|p| display_width(self.path_text(p)))
+                .max()
+                .unwrap_or(0);
+            let limit = longest.saturating_sub(Self::PATH_SCROLL_STEP);
+            self.path_scroll = (self.path_scroll + Self::PATH_SCROLL_STEP).min(limit);
+        } else {
+            self.path_scroll = self.path_scroll.saturating_sub(Self::PATH_SCROLL_STEP);
+        }
+    }
+
+    /// PIDs whose command line hasn't been read yet. Forgets PIDs that are gone, so a
+    /// reused PID gets read again.
+    pub fn command_lines_to_fetch(&mut self) -> Vec<u32> {
+        let alive: std::collections::HashSet<u32> =
+            self.live_processes().map(|p| p.pid).collect();
+        self.command_lines.retain(|pid, _| alive.contains(pid));
+        alive
+            .into_iter()
+            .filter(|pid| !self.command_lines.contains_key(pid))
+            .collect()
+    }
+
+    /// Stores fetched command lines and copies them onto the rows.
+    pub fn set_command_lines(&mut self, command_lines: Vec<(u32, Option<String>)>) {
+        self.fetching_command_lines = false;
+        self.command_lines.extend(command_lines);
+        for row in &mut self.table.rows {
+            if let Some(command_line) = self.command_lines.get(&row.pid) {
+                row.command_line = command_line.clone();
+            }
+        }
+        if self.tree_mode {
+            self.build_tree("");
+        }
+    }
+
+    /// Shows or hides job numbers, sorting by job while they are shown so each job's
+    /// processes sit together.
+    pub fn toggle_jobs(&mut self) {
+        self.show_jobs = !self.show_jobs;
+        if self.show_jobs {
+            self.table.sort_key = SortKey::Job;
+            self.table.sort_order = SortOrder::Ascending;
+        } else if self.table.sort_key == SortKey::Job {
+            self.table.sort_key = SortKey::Cpu;
+            self.table.sort_order = SortOrder::Descending;
+        }
+        self.sort_processes();
+        self.update_selection();
+    }
+
+    /// Whether a background job scan should start now.
+    pub fn jobs_due(&self) -> bool {
+        self.show_jobs
+            && !self.scanning_jobs
+            && self
+                .jobs_scanned_at
+                .is_none_or(|at| at.elapsed() >= Self::JOB_SCAN_INTERVAL)
+    }
+
+    /// Stores a job scan and copies job numbers onto the rows. A process in nested jobs
+    /// is numbered by the innermost one, the job with the fewest processes.
+    pub fn set_jobs(&mut self, jobs: Vec<crate::sys::job::JobInfo>) {
+        self.scanning_jobs = false;
+        self.jobs_scanned_at = Some(std::time::Instant::now());
+
+        let mut jobs = jobs;
+        jobs.sort_by_key(|job| std::cmp::Reverse(job.pids.len()));
+        self.job_of.clear();
+        for job in &jobs {
+            let next = self.job_numbers.len() as u32 + 1;
+            let number = *self.job_numbers.entry(job.object).or_insert(next);
+            // Smaller jobs come later and overwrite the enclosing ones
+            for &pid in &job.pids {
+                self.job_of.insert(pid, number);
+            }
+        }
+
+        for row in &mut self.table.rows {
+            row.job_id = self.job_of.get(&row.pid).copied();
+        }
+        self.sort_processes();
+    }
+
+    /// Ends a failed job scan without touching the last results.
+    pub fn job_scan_failed(&mut self) {
+        self.scanning_jobs = false;
+        self.jobs_scanned_at = Some(std::time::Instant::now());
+    }
+
     pub fn toggle_tree_mode(&mut self) {
         self.tree_mode = !self.tree_mode;
         if self.tree_mode {
             self.build_tree("");
         }
-        self.update_selection_from_pid();
+        self.update_selection();
     }
 
     pub fn toggle_expand(&mut self) {
@@ -110,7 +561,7 @@ impl LockerState {
             return;
         }
 
-        if let Some(idx) = self.list_state.selected() {
+        if let Some(idx) = self.table.list_state.selected() {
             if let Some(node) = self.tree_nodes.get(idx) {
                 let pid = node.process.pid;
                 if node.has_children {
@@ -123,7 +574,7 @@ impl LockerState {
                     // Try to restore selection
                     if let Some(new_idx) = self.tree_nodes.iter().position(|n| n.process.pid == pid)
                     {
-                        self.list_state.select(Some(new_idx));
+                        self.table.list_state.select(Some(new_idx));
                     }
                 }
             }
@@ -135,21 +586,27 @@ impl LockerState {
 
         // Determine which processes match the filter
         let matching_pids: std::collections::HashSet<u32> =
-            if search_query.is_empty() && self.active_filter.is_none() {
-                // No filter - include all processes
-                self.processes.iter().map(|p| p.pid).collect()
+            if search_query.is_empty() && self.table.active_filter.is_none() {
+                // No text filter - include everything the preset allows
+                self.table.rows
+                    .iter()
+                    .filter(|p| self.table.matches_preset(p))
+                    .map(|p| p.pid)
+                    .collect()
             } else {
                 // Get the effective filter query
                 let query = if !search_query.is_empty() {
                     search_query.to_lowercase()
                 } else {
-                    self.active_filter.clone().unwrap_or_default()
+                    self.table.active_filter.clone().unwrap_or_default()
                 };
 
                 // Find processes that match the filter
-                self.processes
+                self.table.rows
                     .iter()
-                    .filter(|p| self.matches_filter(p, &query))
+                    .filter(|p| {
+                        self.table.matches_preset(p) && self.table.filter_score(p, &query).is_some()
+                    })
                     .map(|p| p.pid)
                     .collect()
             };
@@ -157,7 +614,7 @@ impl LockerState {
         // Build parent -> children mapping
         let mut children_map: std::collections::HashMap<u32, Vec<usize>> =
             std::collections::HashMap::new();
-        for (idx, process) in self.processes.iter().enumerate() {
+        for (idx, process) in self.table.rows.iter().enumerate() {
             children_map
                 .entry(process.parent_pid)
                 .or_default()
@@ -167,7 +624,8 @@ impl LockerState {
         // Build set of PIDs to include (matching + their ancestors)
         let mut include_pids: std::collections::HashSet<u32> = std::collections::HashSet::new();
         let pid_to_idx: std::collections::HashMap<u32, usize> = self
-            .processes
+            .table
+            .rows
             .iter()
             .enumerate()
             .map(|(idx, p)| (p.pid, idx))
@@ -181,7 +639,7 @@ impl LockerState {
 
                 // Find parent
                 if let Some(&idx) = pid_to_idx.get(&current_pid) {
-                    let parent_pid = self.processes[idx].parent_pid;
+                    let parent_pid = self.table.rows[idx].parent_pid;
                     if parent_pid == 0 || !pid_to_idx.contains_key(&parent_pid) {
                         break;
                     }
@@ -193,10 +651,10 @@ impl LockerState {
         }
 
         // Find root processes (parent_pid == 0 or parent not in our list)
-        let pids: std::collections::HashSet<u32> = self.processes.iter().map(|p| p.pid).collect();
+        let pids: std::collections::HashSet<u32> = self.table.rows.iter().map(|p| p.pid).collect();
         let mut roots: Vec<usize> = Vec::new();
 
-        for (idx, process) in self.processes.iter().enumerate() {
+        for (idx, process) in self.table.rows.iter().enumerate() {
             if (process.parent_pid == 0 || !pids.contains(&process.parent_pid))
                 && include_pids.contains(&process.pid)
             {
@@ -206,9 +664,9 @@ impl LockerState {
 
         // Sort roots by current sort key
         roots.sort_by(|&a_idx, &b_idx| {
-            let a = &self.processes[a_idx];
-            let b = &self.processes[b_idx];
-            self.compare_processes(a, b)
+            let a = &self.table.rows[a_idx];
+            let b = &self.table.rows[b_idx];
+            self.table.compare(a, b)
         });
 
         // Build tree recursively
@@ -224,14 +682,14 @@ impl LockerState {
         children_map: &std::collections::HashMap<u32, Vec<usize>>,
         include_pids: &std::collections::HashSet<u32>,
     ) {
-        let process = self.processes[process_idx].clone();
+        let process = self.table.rows[process_idx].clone();
         let pid = process.pid;
         let all_children = children_map.get(&pid).cloned().unwrap_or_default();
 
         // Filter children to only include those in include_pids
         let children: Vec<usize> = all_children
             .into_iter()
-            .filter(|&idx| include_pids.contains(&self.processes[idx].pid))
+            .filter(|&idx| include_pids.contains(&self.table.rows[idx].pid))
             .collect();
 
         self.tree_nodes.push(TreeNode {
@@ -245,9 +703,9 @@ impl LockerState {
             // Sort children
             let mut sorted_children = children;
             sorted_children.sort_by(|&a_idx, &b_idx| {
-                let a = &self.processes[a_idx];
-                let b = &self.processes[b_idx];
-                self.compare_processes(a, b)
+                let a = &self.table.rows[a_idx];
+                let b = &self.table.rows[b_idx];
+                self.table.compare(a, b)
             });
 
             for &child_idx in &sorted_children {
@@ -256,171 +714,78 @@ impl LockerState {
         }
     }
 
-    fn compare_processes(&self, a: &ProcessInfo, b: &ProcessInfo) -> std::cmp::Ordering {
-        let cmp = match self.sort_key {
-            SortKey::Name => a.name.cmp(&b.name),
-            SortKey::Pid => a.pid.cmp(&b.pid),
-            SortKey::Cpu => {
-                let a_val = if a.cpu_usage > 0.0 {
-                    a.cpu_usage
-                } else {
-                    a.last_cpu_usage
-                };
-                let b_val = if b.cpu_usage > 0.0 {
-                    b.cpu_usage
-                } else {
-                    b.last_cpu_usage
-                };
-                a_val
-                    .partial_cmp(&b_val)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
-            SortKey::Memory => {
-                let a_val = if a.memory_mb > 0.0 {
-                    a.memory_mb
-                } else {
-                    a.last_memory_mb
-                };
-                let b_val = if b.memory_mb > 0.0 {
-                    b.memory_mb
-                } else {
-                    b.last_memory_mb
-                };
-                a_val
-                    .partial_cmp(&b_val)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }
-        };
-
-        if self.sort_order == SortOrder::Descending {
-            cmp.reverse()
-        } else {
-            cmp
-        }
+    pub fn set_filter(&mut self, query: String) {
+        // Don't mark navigation for filter changes - they should be instant
+        self.table.set_filter(query);
+        self.update_selection();
     }
 
-    fn compute_data_hash(&self, processes: &[ProcessInfo]) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-
-        let mut hasher = DefaultHasher::new();
-        processes.len().hash(&mut hasher);
-        for p in processes {
-            p.pid.hash(&mut hasher);
-            p.name.hash(&mut hasher);
-        }
-        hasher.finish()
+    pub fn clear_filter(&mut self) {
+        // Don't mark navigation for filter changes - they should be instant
+        self.table.active_filter = None;
+        self.update_selection();
     }
 
-    pub fn should_ignore_update(&self) -> bool {
-        // Always allow initial load
-        if self.is_initial_load {
-            return false;
+    pub fn view(&self) -> LockerView {
+        LockerView {
+            filter: self.table.active_filter.clone(),
+            sort_key: self.table.sort_key,
+            sort_order: self.table.sort_order,
+            tree_mode: self.tree_mode,
+            show_object_columns: self.show_object_columns,
+            show_command_line: self.show_command_line,
+            show_jobs: self.show_jobs,
+            show_top: self.show_top,
+            cpu_per_core: self.cpu_per_core,
         }
-        // Only debounce actual navigation (not filter operations)
-        self.last_navigation.elapsed() < Self::NAVIGATION_DEBOUNCE
-    }
-
-    fn mark_navigation(&mut self) {
-        self.last_navigation = Instant::now();
     }
 
-    pub fn set_filter(&mut self, query: String) {
-        // Don't mark navigation for filter changes - they should be instant
-        if query.is_empty() {
-            self.active_filter = None;
+    pub fn apply_view(&mut self, view: &LockerView) {
+        self.table.active_filter = view.filter.clone();
+        self.table.sort_key = view.sort_key;
+        self.table.sort_order = view.sort_order;
+        self.show_object_columns = view.show_object_columns;
+        self.show_command_line = view.show_command_line;
+        self.show_jobs = view.show_jobs;
+        self.show_top = view.show_top;
+        self.cpu_per_core = view.cpu_per_core;
+        self.sort_processes();
+        if self.tree_mode != view.tree_mode {
+            self.toggle_tree_mode();
         } else {
-            self.active_filter = Some(query.to_lowercase());
+            self.update_selection();
         }
-
-        self.update_selection_from_pid();
-    }
-
-    pub fn clear_filter(&mut self) {
-        // Don't mark navigation for filter changes - they should be instant
-        self.active_filter = None;
-        self.update_selection_from_pid();
     }
 
     pub fn cycle_sort_key(&mut self) {
-        self.sort_key = self.sort_key.next();
+        let mut key = self.table.sort_key.next();
+        while (key.is_object_column() && !self.show_object_columns)
+            || (key == SortKey::Job && !self.show_jobs)
+        {
+            key = key.next();
+        }
+        self.table.sort_key = key;
         self.sort_processes();
-        self.update_selection_from_pid();
+        self.update_selection();
     }
 
     pub fn toggle_sort_order(&mut self) {
-        self.sort_order = self.sort_order.toggle();
+        self.table.sort_order = self.table.sort_order.toggle();
         self.sort_processes();
-        self.update_selection_from_pid();
+        self.update_selection();
     }
 
-    pub fn sort_processes(&mut self) {
-        match self.sort_key {
-            SortKey::Name => {
-                self.processes.sort_by(|a, b| {
-                    let cmp = a.name.cmp(&b.name);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Pid => {
-                self.processes.sort_by(|a, b| {
-                    let cmp = a.pid.cmp(&b.pid);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Cpu => {
-                self.processes.sort_by(|a, b| {
-                    let a_val = if a.cpu_usage > 0.0 {
-                        a.cpu_usage
-                    } else {
-                        a.last_cpu_usage
-                    };
-                    let b_val = if b.cpu_usage > 0.0 {
-                        b.cpu_usage
-                    } else {
-                        b.last_cpu_usage
-                    };
-                    let cmp = a_val
-                        .partial_cmp(&b_val)
-                        .unwrap_or(std::cmp::Ordering::Equal);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Memory => {
-                self.processes.sort_by(|a, b| {
-                    let a_val = if a.memory_mb > 0.0 {
-                        a.memory_mb
-                    } else {
-                        a.last_memory_mb
-                    };
-                    let b_val = if b.memory_mb > 0.0 {
-                        b.memory_mb
-                    } else {
-                        b.last_memory_mb
-                    };
-                    let cmp = a_val
-                        .partial_cmp(&b_val)
-                        .unwrap_or(std::cmp::Ordering::Equal);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
+    /// Sorts by `key`, or flips the order when already sorted by it.
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.table.sort_by(key);
+        if self.tree_mode {
+            self.build_tree("");
         }
+        self.update_selection();
+    }
+
+    pub fn sort_processes(&mut self) {
+        self.table.sort();
 
         // Rebuild tree if in tree mode
         if self.tree_mode {
@@ -428,294 +793,327 @@ impl LockerState {
         }
     }
 
-    fn update_selection_from_pid(&mut self) {
-        if let Some(pid) = self.selected_pid {
-            let filtered = self.get_filtered_indices("");
-            if let Some(new_idx) = filtered
-                .iter()
-                .position(|&i| self.processes.get(i).map(|p| p.pid == pid).unwrap_or(false))
-            {
-                self.list_state.select(Some(new_idx));
-            } else if !filtered.is_empty() {
-                self.list_state.select(Some(0));
-                self.selected_pid = filtered
-                    .first()
-                    .and_then(|&i| self.processes.get(i))
-                    .map(|p| p.pid);
-            } else {
-                self.list_state.select(None);
-                self.selected_pid = None;
-            }
-        } else if !self.processes.is_empty() {
-            self.list_state.select(Some(0));
-            self.selected_pid = self.processes.first().map(|p| p.pid);
-        }
+    fn update_selection(&mut self) {
+        let shown = self.shown_ids("");
+        self.table.restore_selection(&shown);
     }
 
-    fn get_filter(&self, search_query: &str) -> Option<String> {
-        if !search_query.is_empty() {
-            Some(search_query.to_lowercase())
+    /// PIDs of the rows on screen, in order: the tree nodes in tree mode, else the
+    /// filtered list.
+    fn shown_ids(&self, search_query: &str) -> Vec<u32> {
+        if self.tree_mode {
+            self.tree_nodes.iter().map(|n| n.process.pid).collect()
         } else {
-            self.active_filter.clone()
+            self.table.ids(&self.get_filtered_indices(search_query))
         }
     }
 
-    fn matches_filter(&self, process: &ProcessInfo, query: &str) -> bool {
-        process.name.to_lowercase().contains(query)
-            || process
-                .path
-                .as_ref()
-                .map(|path| path.to_lowercase().contains(query))
-                .unwrap_or(false)
-            || process.pid.to_string().contains(query)
+    /// Char positions in `text` matched by the current filter, for highlighting.
+    pub fn match_positions(&self, text: &str, search_query: &str) -> Vec<usize> {
+        self.table.match_positions(text, search_query)
     }
 
-    pub fn get_filtered_indices(&self, search_query: &str) -> Vec<usize> {
-        match self.get_filter(search_query) {
-            None => (0..self.processes.len()).collect(),
-            Some(query) => self
-                .processes
-                .iter()
-                .enumerate()
-                .filter(|(_, p)| self.matches_filter(p, &query))
-                .map(|(i, _)| i)
-                .collect(),
+    pub fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        self.table.preset = preset;
+        if self.tree_mode {
+            self.build_tree("");
         }
+        self.update_selection();
+    }
+
+    pub fn get_filtered_indices(&self, search_query: &str) -> Vec<usize> {
+        self.table.filtered_indices(search_query, |_| true)
     }
 
     pub fn filtered_processes(&self, search_query: &str) -> Vec<(usize, ProcessInfo)> {
-        match self.get_filter(search_query) {
-            None => self
-                .processes
-                .iter()
-                .enumerate()
-                .map(|(i, p)| (i, p.clone()))
-                .collect(),
-            Some(query) => self
-                .processes
-                .iter()
-                .enumerate()
-                .filter(|(_, p)| self.matches_filter(p, &query))
-                .map(|(i, p)| (i, p.clone()))
-                .collect(),
-        }
+        self.get_filtered_indices(search_query)
+            .into_iter()
+            .map(|i| (i, self.table.rows[i].clone()))
+            .collect()
     }
 
     pub fn update_processes(&mut self, processes: Vec<ProcessInfo>) {
-        // Check if data actually changed
-        let new_hash = self.compute_data_hash(&processes);
-
-        if new_hash == self.last_data_hash {
-            // Data hasn't changed, skip update entirely
-            return;
-        }
-        self.last_data_hash = new_hash;
-
-        // Don't update during active navigation (but always allow initial load)
-        if self.should_ignore_update() {
+        // Skip unchanged data and updates during active navigation
+        if !self.table.accept_update(&processes) {
             return;
         }
 
         // Preserve cached metric values from existing processes to prevent "-" display
         // during the brief window before metrics are updated
-        let cached_values: std::collections::HashMap<u32, (f32, f32, f64)> = self
-            .processes
-            .iter()
-            .map(|p| (p.pid, (p.cpu_usage, p.last_cpu_usage, p.last_memory_mb)))
-            .collect();
+        let cached_values: std::collections::HashMap<u32, &ProcessInfo> =
+            self.table.rows.iter().map(|p| (p.pid, p)).collect();
 
         // Copy cached values to new processes that still exist
         let mut processes = processes;
         for process in &mut processes {
-            if let Some((cpu, last_cpu, mem)) = cached_values.get(&process.pid) {
-                process.cpu_usage = *cpu;
-                process.last_cpu_usage = *last_cpu;
-                process.last_memory_mb = *mem;
+            if let Some(cached) = cached_values.get(&process.pid) {
+                process.cpu_usage = cached.cpu_usage;
+                process.last_cpu_usage = cached.last_cpu_usage;
+                process.last_memory_mb = cached.last_memory_mb;
+                process.gpu_usage = cached.gpu_usage;
+                process.gpu_memory_mb = cached.gpu_memory_mb;
+                process.io_read_bps = cached.io_read_bps;
+                process.io_write_bps = cached.io_write_bps;
+                process.private_mb = cached.private_mb;
+                process.start_time = cached.start_time;
+                process.handle_count = cached.handle_count;
+                process.gdi_objects = cached.gdi_objects;
+                process.user_objects = cached.user_objects;
             }
+            if let Some(command_line) = self.command_lines.get(&process.pid) {
+                process.command_line = command_line.clone();
+            }
+            process.job_id = self.job_of.get(&process.pid).copied();
         }
+        tag_containers(&mut processes);
 
-        self.processes = processes;
+        self.bury_exited(&mut processes);
+        self.table.carry_frozen(&mut processes);
+        self.table.rows = processes;
+        // Also rebuilds the tree in tree mode
         self.sort_processes();
 
-        // Rebuild tree if in tree mode
-        if self.tree_mode {
-            self.build_tree("");
-        }
-
         // Note: Don't update selection during background updates to prevent cursor jumps
         // Selection is only updated on user-initiated actions (sort change, navigation, etc.)
 
         // Initialize selection on first load (when is_initial_load is still true)
-        if self.is_initial_load && !self.processes.is_empty() {
-            self.update_selection_from_pid();
+        if self.table.is_initial_load() && !self.table.rows.is_empty() {
+            self.update_selection();
         }
 
         // Mark initial load as complete after first successful update
-        self.is_initial_load = false;
+        self.table.finish_update();
     }
 
     pub fn select_next(&mut self, search_query: &str) {
-        self.mark_navigation();
-
-        if self.tree_mode {
-            if self.tree_nodes.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let new_idx = (i + 1) % self.tree_nodes.len();
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = self.tree_nodes.get(new_idx).map(|n| n.process.pid);
-        } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if filtered.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let new_idx = (i + 1) % filtered.len();
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = filtered
-                .get(new_idx)
-                .and_then(|&idx| self.processes.get(idx))
-                .map(|p| p.pid);
-        }
+        let shown = self.shown_ids(search_query);
+        self.table.select_next(&shown);
     }
 
     pub fn select_prev(&mut self, search_query: &str) {
-        self.mark_navigation();
-
-        if self.tree_mode {
-            if self.tree_nodes.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let new_idx = (i + self.tree_nodes.len() - 1) % self.tree_nodes.len();
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = self.tree_nodes.get(new_idx).map(|n| n.process.pid);
-        } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if filtered.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let new_idx = (i + filtered.len() - 1) % filtered.len();
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = filtered
-                .get(new_idx)
-                .and_then(|&idx| self.processes.get(idx))
-                .map(|p| p.pid);
-        }
+        let shown = self.shown_ids(search_query);
+        self.table.select_prev(&shown);
     }
 
     pub fn select_page_up(&mut self, search_query: &str) {
-        self.mark_navigation();
-
-        if self.tree_mode {
-            if self.tree_nodes.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let page_size = 10;
-            let new_idx = i.saturating_sub(page_size);
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = self.tree_nodes.get(new_idx).map(|n| n.process.pid);
-        } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if filtered.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let page_size = 10;
-            let new_idx = i.saturating_sub(page_size);
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = filtered
-                .get(new_idx)
-                .and_then(|&idx| self.processes.get(idx))
-                .map(|p| p.pid);
-        }
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_up(&shown);
     }
 
     pub fn select_page_down(&mut self, search_query: &str) {
-        self.mark_navigation();
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_down(&shown);
+    }
 
-        if self.tree_mode {
-            if self.tree_nodes.is_empty() {
-                return;
-            }
-            let i = self.list_state.selected().unwrap_or(0);
-            let page_size = 10;
-            let new_idx = std::cmp::min(i + page_size, self.tree_nodes.len().saturating_sub(1));
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = self.tree_nodes.get(new_idx).map(|n| n.process.pid);
+    pub fn select_screen_up(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_up(&shown);
+    }
+
+    pub fn select_screen_down(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_down(&shown);
+    }
+
+    pub fn move_up(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_up(&shown, rows);
+    }
+
+    pub fn move_down(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_down(&shown, rows);
+    }
+
+    pub fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        let (shown, matches): (Vec<u32>, Vec<bool>) = if self.tree_mode {
+            self.tree_nodes
+                .iter()
+                .map(|n| (n.process.pid, self.table.row_matches(&n.process, pattern)))
+                .unzip()
         } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if filtered.is_empty() {
-                return;
+            self.get_filtered_indices(search_query)
+                .into_iter()
+                .map(|i| {
+                    let row = &self.table.rows[i];
+                    (row.id(), self.table.row_matches(row, pattern))
+                })
+                .unzip()
+        };
+        self.table.find(&shown, &matches, forward, include_current)
+    }
+
+    /// Turns processes missing from a fresh poll into tombstones and appends the tombstones
+    /// still within their TTL to it. Frozen processes are left to `carry_frozen`.
+    fn bury_exited(&mut self, processes: &mut Vec<ProcessInfo>) {
+        let alive: std::collections::HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+        let clock = crate::sys::system::local_clock();
+        for old in &self.table.rows {
+            if !alive.contains(&old.pid)
+                && !self.tombstones.contains_key(&old.pid)
+                && !self.table.is_frozen(&old.pid)
+            {
+                self.tombstones.insert(
+                    old.pid,
+                    Tombstone {
+                        process: old.clone(),
+                        exited_at: std::time::Instant::now(),
+                        clock: clock.clone(),
+                    },
+                );
             }
-            let i = self.list_state.selected().unwrap_or(0);
-            let page_size = 10;
-            let new_idx = std::cmp::min(i + page_size, filtered.len().saturating_sub(1));
-            self.list_state.select(Some(new_idx));
-            self.selected_pid = filtered
-                .get(new_idx)
-                .and_then(|&idx| self.processes.get(idx))
-                .map(|p| p.pid);
         }
+        // A reused PID belongs to the new process
+        self.tombstones.retain(|pid, tombstone| {
+            !alive.contains(pid) && tombstone.exited_at.elapsed() < Self::TOMBSTONE_TTL
+        });
+        processes.extend(self.tombstones.values().map(|t| t.process.clone()));
     }
 
-    pub fn select_first(&mut self, search_query: &str) {
-        self.mark_navigation();
+    /// Adds the metrics just read to the session usage. Listed processes keep theirs, so a
+    /// tombstone still shows what the process peaked at.
+    pub fn record_usage(&mut self) {
+        let clock = crate::sys::system::local_clock();
+        let live = self
+            .table
+            .live_rows()
+            .filter(|p| !self.tombstones.contains_key(&p.pid));
+        self.usage.record(live, &clock);
+        let listed: std::collections::HashSet<u32> =
+            self.table.rows.iter().map(|p| p.pid).collect();
+        self.usage.retain(|pid| listed.contains(&pid));
+    }
 
-        if self.tree_mode {
-            if !self.tree_nodes.is_empty() {
-                self.list_state.select(Some(0));
-                self.selected_pid = self.tree_nodes.first().map(|n| n.process.pid);
-            }
-        } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if !filtered.is_empty() {
-                self.list_state.select(Some(0));
-                self.selected_pid = filtered
-                    .first()
-                    .and_then(|&idx| self.processes.get(idx))
-                    .map(|p| p.pid);
+    /// Processes that are still running, leaving out tombstones and vanished frozen rows.
+    pub fn live_processes(&self) -> impl Iterator<Item = &ProcessInfo> {
+        self.table
+            .live_rows()
+            .filter(|p| !self.tombstones.contains_key(&p.pid))
+    }
+
+    /// Drops expired tombstones and puts back the last known values of exited processes,
+    /// which a metrics pass just cleared. Called after every metrics update, since polls
+    /// with no changes never reach `update_processes`.
+    pub fn refresh_tombstones(&mut self) {
+        let expired: Vec<u32> = self
+            .tombstones
+            .iter()
+            .filter(|(_, t)| t.exited_at.elapsed() >= Self::TOMBSTONE_TTL)
+            .map(|(&pid, _)| pid)
+            .collect();
+        for pid in &expired {
+            self.tombstones.remove(pid);
+        }
+        let frozen = &self.table.frozen;
+        self.table
+            .rows
+            .retain(|p| !expired.contains(&p.pid) || frozen.iter().any(|f| f.pid == p.pid));
+
+        for row in &mut self.table.rows {
+            if let Some(tombstone) = self.tombstones.get(&row.pid) {
+                *row = tombstone.process.clone();
+            } else if self.table.vanished.contains(&row.pid)
+                && let Some(frozen) = self.table.frozen.iter().find(|f| f.pid == row.pid)
+            {
+                *row = frozen.clone();
             }
         }
+
+        if !expired.is_empty() {
+            self.sort_processes();
+            self.update_selection();
+        }
+    }
+
+    /// Freezes or unfreezes the selected process. Returns its name and new state.
+    pub fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        let process = self.get_selected_process(search_query)?.clone();
+        let frozen = self.table.toggle_freeze(&process);
+        if !frozen {
+            // An unfrozen vanished process left the list
+            self.sort_processes();
+            self.update_selection();
+        }
+        Some((process.name, frozen))
+    }
+
+    pub fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        let shown = self.shown_ids(search_query);
+        self.table.jump_to_mark(mark, &shown)
+    }
+
+    pub fn select_first(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_first(&shown);
     }
 
     pub fn select_last(&mut self, search_query: &str) {
-        self.mark_navigation();
+        let shown = self.shown_ids(search_query);
+        self.table.select_last(&shown);
+    }
+
+    /// Selects the process with `pid`, expanding its ancestors in tree mode and dropping the
+    /// filter and preset if they hide it. Returns false when the process is not in the list.
+    pub fn focus_pid(&mut self, pid: u32) -> bool {
+        if !self.table.rows.iter().any(|p| p.pid == pid) {
+            return false;
+        }
 
+        let visible = self
+            .get_filtered_indices("")
+            .iter()
+            .any(|&i| self.table.rows[i].pid == pid);
+        if !visible {
+            self.table.active_filter = None;
+            self.table.preset = None;
+        }
+
+        self.table.selected = Some(pid);
         if self.tree_mode {
-            if !self.tree_nodes.is_empty() {
-                let last_idx = self.tree_nodes.len() - 1;
-                self.list_state.select(Some(last_idx));
-                self.selected_pid = self.tree_nodes.get(last_idx).map(|n| n.process.pid);
-            }
-        } else {
-            let filtered = self.get_filtered_indices(search_query);
-            if !filtered.is_empty() {
-                let last_idx = filtered.len() - 1;
-                self.list_state.select(Some(last_idx));
-                self.selected_pid = filtered
-                    .get(last_idx)
-                    .and_then(|&idx| self.processes.get(idx))
-                    .map(|p| p.pid);
+            let parents: std::collections::HashMap<u32, u32> = self
+                .table
+                .rows
+                .iter()
+                .map(|p| (p.pid, p.parent_pid))
+                .collect();
+            let mut current = pid;
+            // Bounded walk: stale parent PIDs can form cycles
+            for _ in 0..parents.len() {
+                match parents.get(&current) {
+                    Some(&parent) if parent != current && parents.contains_key(&parent) => {
+                        self.expanded_pids.insert(parent);
+                        current = parent;
+                    }
+                    _ => break,
+                }
             }
+            self.build_tree("");
         }
+        self.update_selection();
+        true
+    }
+
+    /// Aperture's own row, for showing what monitoring costs.
+    pub fn own_process(&self) -> Option<&ProcessInfo> {
+        let pid = std::process::id();
+        self.table.rows.iter().find(|p| p.pid == pid)
     }
 
     pub fn get_selected_process(&self, search_query: &str) -> Option<&ProcessInfo> {
         if self.tree_mode {
-            self.list_state
+            self.table
+                .list_state
                 .selected()
                 .and_then(|idx| self.tree_nodes.get(idx))
                 .map(|n| &n.process)
         } else {
-            let filtered = self.get_filtered_indices(search_query);
-            self.list_state
-                .selected()
-                .and_then(|idx| filtered.get(idx))
-                .and_then(|&original_idx| self.processes.get(original_idx))
+            self.table.selected_row(&self.get_filtered_indices(search_query))
         }
     }
 }
@@ -0,0 +1,125 @@
+/// Where a search query matched inside a piece of text. `positions` are char indices
+/// into the text, used to highlight the match in the rendered rows.
+#[derive(Debug, Clone, Default)]
+pub struct TextMatch {
+    pub score: i32,
+    pub positions: Vec<usize>,
+}
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_CONSECUTIVE: i32 = 8;
+const BONUS_BOUNDARY: i32 = 10;
+const BONUS_CAMEL: i32 = 8;
+const PENALTY_GAP: i32 = 1;
+
+/// Matches `query` against `text`, case-insensitively. In fuzzy mode the query only has to
+/// appear as a subsequence; otherwise it must appear as a contiguous substring.
+pub fn find(query: &str, text: &str, fuzzy: bool) -> Option<TextMatch> {
+    if fuzzy {
+        fuzzy_match(query, text)
+    } else {
+        substring_match(query, text)
+    }
+}
+
+fn lower(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+fn substring_match(query: &str, text: &str) -> Option<TextMatch> {
+    let pattern: Vec<char> = query.chars().map(lower).collect();
+    let chars: Vec<char> = text.chars().map(lower).collect();
+    if pattern.is_empty() {
+        return Some(TextMatch::default());
+    }
+
+    let start = chars.windows(pattern.len()).position(|w| w == pattern.as_slice())?;
+    Some(TextMatch {
+        score: 0,
+        positions: (start..start + pattern.len()).collect(),
+    })
+}
+
+/// fzf-style subsequence match. A forward scan finds where the earliest full match ends,
+/// then a backward scan from there picks the tightest run of positions, which is scored
+/// with bonuses for consecutive characters and word starts and a penalty for gaps.
+fn fuzzy_match(query: &str, text: &str) -> Option<TextMatch> {
+    let pattern: Vec<char> = query.chars().map(lower).collect();
+    let original: Vec<char> = text.chars().collect();
+    let chars: Vec<char> = original.iter().copied().map(lower).collect();
+    if pattern.is_empty() {
+        return Some(TextMatch::default());
+    }
+
+    let mut next = 0;
+    let mut end = None;
+    for (i, &c) in chars.iter().enumerate() {
+        if c == pattern[next] {
+            next += 1;
+            if next == pattern.len() {
+                end = Some(i);
+                break;
+            }
+        }
+    }
+    let end = end?;
+
+    let mut positions = Vec::with_capacity(pattern.len());
+    let mut remaining = pattern.len();
+    for i in (0..=end).rev() {
+        if chars[i] == pattern[remaining - 1] {
+            positions.push(i);
+            remaining -= 1;
+            if remaining == 0 {
+                break;
+            }
+        }
+    }
+    positions.reverse();
+
+    let mut score = 0;
+    let mut previous: Option<usize> = None;
+    for &pos in &positions {
+        score += SCORE_MATCH;
+        if pos == 0 || !original[pos - 1].is_alphanumeric() {
+            score += BONUS_BOUNDARY;
+        } else if original[pos - 1].is_lowercase() && original[pos].is_uppercase() {
+            score += BONUS_CAMEL;
+        }
+        match previous {
+            Some(prev) if pos == prev + 1 => score += BONUS_CONSECUTIVE,
+            Some(prev) => score -= PENALTY_GAP * (pos - prev - 1) as i32,
+            None => {}
+        }
+        previous = Some(pos);
+    }
+
+    Some(TextMatch { score, positions })
+}
+
+/// Matches `text` against `pattern` with `*` for any run of characters and `?` for one,
+/// ignoring case.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Where the last `*` was and the text position it currently stands for
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the `*` take one more character and retry
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
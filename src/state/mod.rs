@@ -1,3 +1,16 @@
 pub mod locker;
 pub mod controller;
 pub mod nexus;
+pub mod perf;
+pub mod errors;
+pub mod notifications;
+pub mod presets;
+pub mod colors;
+pub mod sort;
+pub mod matcher;
+pub mod table;
+pub mod timings;
+pub mod lock_watch;
+pub mod badges;
+pub mod usage;
+pub mod port_watch;
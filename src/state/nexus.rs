@@ -1,14 +1,25 @@
-use std::time::{Duration, Instant};
-
-use ratatui::widgets::ListState;
-
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::time::Instant;
+
+use crate::error::Result;
+use crate::state::presets::FilterPreset;
+use crate::state::sort::natural_cmp;
+use crate::state::table::{TableRow, TableState};
+use crate::sys::firewall::{FirewallCheck, Listener};
 use crate::sys::network::ConnectionInfo;
+use crate::sys::system::InterfaceSample;
+
+pub use crate::state::table::SortOrder;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum SortKey {
     State,
     Pid,
     Protocol,
+    LocalAddr,
+    RemoteAddr,
     ProcessName,
 }
 
@@ -17,7 +28,9 @@ impl SortKey {
         match self {
             SortKey::State => SortKey::Pid,
             SortKey::Pid => SortKey::Protocol,
-            SortKey::Protocol => SortKey::ProcessName,
+            SortKey::Protocol => SortKey::LocalAddr,
+            SortKey::LocalAddr => SortKey::RemoteAddr,
+            SortKey::RemoteAddr => SortKey::ProcessName,
             SortKey::ProcessName => SortKey::State,
         }
     }
@@ -27,29 +40,22 @@ impl SortKey {
             SortKey::State => "State",
             SortKey::Pid => "PID",
             SortKey::Protocol => "Proto",
+            SortKey::LocalAddr => "Local",
+            SortKey::RemoteAddr => "Remote",
             SortKey::ProcessName => "Process",
         }
     }
-}
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum SortOrder {
-    Ascending,
-    Descending,
-}
 
-impl SortOrder {
-    pub fn toggle(&self) -> Self {
-        match self {
-            SortOrder::Ascending => SortOrder::Descending,
-            SortOrder::Descending => SortOrder::Ascending,
-        }
-    }
-
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            SortOrder::Ascending => "▲",
-            SortOrder::Descending => "▼",
+    /// Sort key for the Nth header column (1-based), as picked with Alt+1..9.
+    pub fn for_column(column: usize) -> Option<Self> {
+        match column {
+            1 => Some(SortKey::Pid),
+            2 => Some(SortKey::Protocol),
+            3 => Some(SortKey::LocalAddr),
+            4 => Some(SortKey::RemoteAddr),
+            5 => Some(SortKey::State),
+            6 => Some(SortKey::ProcessName),
+            _ => None,
         }
     }
 }
@@ -73,385 +79,662 @@ fn state_priority(state: &str) -> u8 {
     }
 }
 
-pub struct NexusState {
-    pub connections: Vec<ConnectionInfo>,
-    pub list_state: ListState,
-    pub active_filter: Option<String>,
-    pub selected_connection_key: Option<(u32, String, u16, String, u16)>,
-    pub last_navigation: Instant,
+/// Which protocols the Nexus list shows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ProtocolFilter {
+    #[default]
+    All,
+    Tcp,
+    Udp,
+}
+
+impl ProtocolFilter {
+    fn allows(&self, protocol: &str) -> bool {
+        match self {
+            ProtocolFilter::All => true,
+            ProtocolFilter::Tcp => protocol.starts_with("TCP"),
+            ProtocolFilter::Udp => protocol.starts_with("UDP"),
+        }
+    }
+}
+
+/// UDP sockets are connectionless, so the table reports a placeholder wildcard remote.
+pub fn has_remote(conn: &ConnectionInfo) -> bool {
+    !conn.protocol.starts_with("UDP")
+}
+
+/// Listening TCP sockets and bound UDP endpoints, the ones that accept inbound traffic.
+pub fn is_listener(conn: &ConnectionInfo) -> bool {
+    conn.state == "LISTENING" || !has_remote(conn)
+}
+
+/// Loopback sockets and ones without a remote peer (listeners, UDP, unconnected), which
+/// crowd out external connections.
+pub fn is_local_noise(conn: &ConnectionInfo) -> bool {
+    let is_loopback =
+        |addr: &str| addr.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback());
+    !has_remote(conn)
+        || is_loopback(&conn.local_addr)
+        || matches!(remote_scope(&conn.remote_addr), "loopback" | "none")
+}
+
+/// States that indicate a socket the owning process has not cleaned up.
+/// Large numbers of these per process usually point at a socket leak.
+pub fn is_lingering_state(state: &str) -> bool {
+    matches!(state, "TIME_WAIT" | "CLOSE_WAIT")
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LingeringCounts {
+    pub time_wait: usize,
+    pub close_wait: usize,
+}
+
+/// Lingering sockets grouped the way they pile up. Windows reports TIME_WAIT sockets with
+/// owning PID 0, as no process holds them anymore, so those are counted per local port.
+/// CLOSE_WAIT sockets are still held by the process that didn't close them.
+#[derive(Debug, Clone, Default)]
+pub struct Lingering {
+    pub time_wait_by_port: std::collections::HashMap<u16, usize>,
+    pub close_wait_by_pid: std::collections::HashMap<u32, usize>,
+}
+
+impl Lingering {
+    /// Size of the pile-up `conn` is part of, if it is lingering in one.
+    pub fn pileup(&self, conn: &ConnectionInfo) -> Option<usize> {
+        match conn.state.as_str() {
+            "TIME_WAIT" => self.time_wait_by_port.get(&conn.local_port).copied(),
+            "CLOSE_WAIT" => self.close_wait_by_pid.get(&conn.pid).copied(),
+            _ => None,
+        }
+        .filter(|&count| count >= NexusState::PILEUP_THRESHOLD)
+    }
+}
+
+/// A process's network footprint, joined from the Nexus connection list.
+#[derive(Debug, Clone, Default)]
+pub struct PortFootprint {
+    /// Listening TCP and bound UDP endpoints as (protocol, address, port), sorted by port.
+    pub listening: Vec<(String, String, u16)>,
+    pub established: usize,
+    pub lingering: usize,
+}
+
+/// Throughput of one network interface over the last refresh.
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceRate {
+    pub alias: String,
+    pub rx_bps: f64,
+    pub tx_bps: f64,
+    /// Link speeds in bits per second; 0 when the driver doesn't report one.
+    pub receive_link_bps: u64,
+    pub transmit_link_bps: u64,
+}
+
+impl InterfaceRate {
+    /// Busier direction as a percentage of its link speed.
+    pub fn utilization(&self) -> Option<f64> {
+        let percent = |bytes_per_sec: f64, link_bps: u64| {
+            (link_bps > 0).then(|| bytes_per_sec * 8.0 / link_bps as f64 * 100.0)
+        };
+        match (
+            percent(self.rx_bps, self.receive_link_bps),
+            percent(self.tx_bps, self.transmit_link_bps),
+        ) {
+            (Some(rx), Some(tx)) => Some(rx.max(tx)),
+            (rx, tx) => rx.or(tx),
+        }
+    }
+}
+
+/// The user-facing view settings of the Nexus tab, as stored in a profile.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct NexusView {
+    pub filter: Option<String>,
     pub sort_key: SortKey,
     pub sort_order: SortOrder,
-    last_data_hash: u64,
-    is_initial_load: bool,
+    #[serde(default)]
+    pub lingering_only: bool,
+    #[serde(default)]
+    pub protocol_filter: ProtocolFilter,
+    #[serde(default)]
+    pub hide_local: bool,
+}
+
+/// Identifies a socket across refreshes: owning PID and both endpoints.
+pub type ConnectionKey = (u32, String, u16, String, u16);
+
+impl TableRow<SortKey> for ConnectionInfo {
+    type Id = ConnectionKey;
+
+    fn id(&self) -> ConnectionKey {
+        (
+            self.pid,
+            self.local_addr.clone(),
+            self.local_port,
+            self.remote_addr.clone(),
+            self.remote_port,
+        )
+    }
+
+    fn search_fields(&self) -> Vec<String> {
+        vec![
+            self.process_name.clone().unwrap_or_default(),
+            self.service_name.clone().unwrap_or_default(),
+            self.local_addr.clone(),
+            self.remote_addr.clone(),
+            self.pid.to_string(),
+            self.local_port.to_string(),
+        ]
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        match name {
+            "state" => Some(self.state.clone()),
+            "proto" => Some(self.protocol.clone()),
+            "remote" => Some(format!("{} {}", remote_scope(&self.remote_addr), self.remote_addr)),
+            "local" => Some(self.local_addr.clone()),
+            "port" => Some(self.local_port.to_string()),
+            "rport" => Some(self.remote_port.to_string()),
+            "process" => self.process_name.clone(),
+            "service" => self.service_name.clone(),
+            "pid" => Some(self.pid.to_string()),
+            _ => None,
+        }
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        let mut values = vec![
+            ("pid", self.pid.to_string()),
+            ("proto", self.protocol.clone()),
+            ("state", self.state.clone()),
+            ("local", self.local_addr.clone()),
+            ("port", self.local_port.to_string()),
+        ];
+        if remote_scope(&self.remote_addr) != "none" {
+            values.push(("remote", self.remote_addr.clone()));
+            values.push(("rport", self.remote_port.to_string()));
+        }
+        if let Some(name) = &self.process_name {
+            values.push(("name", name.clone()));
+        }
+        if let Some(service) = &self.service_name {
+            values.push(("service", service.clone()));
+        }
+        values
+    }
+
+    fn compare(&self, other: &Self, key: SortKey) -> Ordering {
+        match key {
+            SortKey::State => state_priority(&self.state).cmp(&state_priority(&other.state)),
+            SortKey::Pid => self.pid.cmp(&other.pid),
+            SortKey::Protocol => natural_cmp(&self.protocol, &other.protocol),
+            SortKey::LocalAddr => natural_cmp(&self.local_addr, &other.local_addr)
+                .then(self.local_port.cmp(&other.local_port)),
+            SortKey::RemoteAddr => natural_cmp(&self.remote_addr, &other.remote_addr)
+                .then(self.remote_port.cmp(&other.remote_port)),
+            SortKey::ProcessName => natural_cmp(
+                self.process_name.as_deref().unwrap_or(""),
+                other.process_name.as_deref().unwrap_or(""),
+            ),
+        }
+    }
+
+    /// Process name, PID and endpoints, so rows with equal keys (e.g. all "ESTABLISHED")
+    /// keep a fixed order between polls.
+    fn tiebreak(&self, other: &Self) -> Ordering {
+        natural_cmp(
+            self.process_name.as_deref().unwrap_or(""),
+            other.process_name.as_deref().unwrap_or(""),
+        )
+        .then_with(|| self.pid.cmp(&other.pid))
+        .then_with(|| self.protocol.cmp(&other.protocol))
+        .then_with(|| natural_cmp(&self.local_addr, &other.local_addr))
+        .then_with(|| self.local_port.cmp(&other.local_port))
+        .then_with(|| natural_cmp(&self.remote_addr, &other.remote_addr))
+        .then_with(|| self.remote_port.cmp(&other.remote_port))
+    }
+
+    fn hash_into(&self, hasher: &mut DefaultHasher) {
+        self.pid.hash(hasher);
+        self.local_addr.hash(hasher);
+        self.local_port.hash(hasher);
+        self.state.hash(hasher);
+    }
+}
+
+pub struct NexusState {
+    pub table: TableState<ConnectionInfo, SortKey>,
+    pub lingering_only: bool,
+    pub protocol_filter: ProtocolFilter,
+    /// Hides loopback sockets and sockets without a remote peer.
+    pub hide_local: bool,
+    /// Reverse DNS names by remote IP. None while the lookup runs or when there is no name.
+    pub host_names: std::collections::HashMap<String, Option<String>>,
+    /// Packet capture started with `C`, until it is stopped.
+    pub capture: Option<crate::sys::capture::Capture>,
+    /// Per-interface throughput shown above the connection list.
+    pub interfaces: Vec<InterfaceRate>,
+    last_interfaces: Option<(Vec<InterfaceSample>, Instant)>,
+    /// Firewall verdicts of listeners by (protocol, port, PID).
+    pub firewall: std::collections::HashMap<(String, u16, u32), FirewallCheck>,
+    pub checking_firewall: bool,
+    pub firewall_checked_at: Option<Instant>,
 }
 
 impl NexusState {
-    const NAVIGATION_DEBOUNCE: Duration = Duration::from_millis(50);
+    // Lingering sockets per local port (TIME_WAIT) or process (CLOSE_WAIT) before they are
+    // flagged as a pile-up
+    pub const PILEUP_THRESHOLD: usize = 20;
+    // Firewall rules rarely change, and reading them all takes a while
+    pub const FIREWALL_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
 
     pub fn new() -> Self {
         Self {
-            connections: Vec::new(),
-            list_state: ListState::default(),
-            active_filter: None,
-            selected_connection_key: None,
-            last_navigation: Instant::now(),
-            sort_key: SortKey::State,
-            sort_order: SortOrder::Ascending,
-            last_data_hash: 0,
-            is_initial_load: true,
+            table: TableState::new(SortKey::State, SortOrder::Ascending),
+            lingering_only: false,
+            hide_local: false,
+            protocol_filter: ProtocolFilter::All,
+            host_names: std::collections::HashMap::new(),
+            capture: None,
+            interfaces: Vec::new(),
+            last_interfaces: None,
+            firewall: std::collections::HashMap::new(),
+            checking_firewall: false,
+            firewall_checked_at: None,
         }
     }
 
-    fn compute_data_hash(&self, connections: &[ConnectionInfo]) -> u64 {
-        use std::collections::hash_map::DefaultHasher;
-        use std::hash::{Hash, Hasher};
-        let mut hasher = DefaultHasher::new();
-        connections.len().hash(&mut hasher);
-        for c in connections {
-            c.pid.hash(&mut hasher);
-            c.local_addr.hash(&mut hasher);
-            c.local_port.hash(&mut hasher);
+    fn firewall_key(conn: &ConnectionInfo) -> (String, u16, u32) {
+        (conn.protocol.clone(), conn.local_port, conn.pid)
+    }
+
+    /// Firewall verdict of a listening row, once checked.
+    pub fn firewall_check(&self, conn: &ConnectionInfo) -> Option<&FirewallCheck> {
+        if !is_listener(conn) {
+            return None;
         }
-        hasher.finish()
+        self.firewall.get(&Self::firewall_key(conn))
     }
 
-    pub fn should_ignore_update(&self) -> bool {
-        if self.is_initial_load {
-            return false;
+    /// Listening ports by protocol, port and PID.
+    pub fn listeners(&self) -> std::collections::HashSet<(String, u16, u32)> {
+        self.table
+            .rows
+            .iter()
+            .filter(|c| is_listener(c))
+            .map(Self::firewall_key)
+            .collect()
+    }
+
+    /// Listeners to check when a firewall check is due: periodically, and soon after a
+    /// new listener shows up.
+    pub fn firewall_due(&self) -> Option<Vec<Listener>> {
+        if self.checking_firewall {
+            return None;
+        }
+        let unchecked = self
+            .table
+            .rows
+            .iter()
+            .any(|c| is_listener(c) && !self.firewall.contains_key(&Self::firewall_key(c)));
+        let due = match self.firewall_checked_at {
+            None => true,
+            Some(at) if unchecked => at.elapsed() >= std::time::Duration::from_secs(2),
+            Some(at) => at.elapsed() >= Self::FIREWALL_CHECK_INTERVAL,
+        };
+        if !due || self.table.rows.is_empty() {
+            return None;
         }
-        self.last_navigation.elapsed() < Self::NAVIGATION_DEBOUNCE
+        let mut seen = std::collections::HashSet::new();
+        Some(
+            self.table
+                .rows
+                .iter()
+                .filter(|c| is_listener(c) && seen.insert(Self::firewall_key(c)))
+                .map(|c| Listener {
+                    protocol: c.protocol.clone(),
+                    port: c.local_port,
+                    pid: c.pid,
+                    service: c.service_name.clone(),
+                })
+                .collect(),
+        )
     }
 
-    fn mark_navigation(&mut self) {
-        self.last_navigation = Instant::now();
+    /// Replaces the verdicts with a finished check.
+    pub fn set_firewall(&mut self, checks: Vec<(Listener, FirewallCheck)>) {
+        self.checking_firewall = false;
+        self.firewall_checked_at = Some(Instant::now());
+        self.firewall = checks
+            .into_iter()
+            .map(|(l, check)| ((l.protocol, l.port, l.pid), check))
+            .collect();
+    }
+
+    /// Ends a failed check, keeping the last verdicts.
+    pub fn firewall_check_failed(&mut self) {
+        self.checking_firewall = false;
+        self.firewall_checked_at = Some(Instant::now());
+    }
+
+    /// Turns interface counters into rates against the previous sample. Interfaces that
+    /// just came up show no traffic until the next one.
+    pub fn update_interfaces(&mut self, samples: Vec<InterfaceSample>) {
+        let now = Instant::now();
+        let previous = self.last_interfaces.as_ref();
+        self.interfaces = samples
+            .iter()
+            .map(|sample| {
+                let mut rate = InterfaceRate {
+                    alias: sample.alias.clone(),
+                    receive_link_bps: sample.receive_link_bps,
+                    transmit_link_bps: sample.transmit_link_bps,
+                    ..Default::default()
+                };
+                if let Some((prev_samples, prev_instant)) = previous
+                    && let Some(prev) = prev_samples.iter().find(|p| p.index == sample.index)
+                {
+                    let elapsed = now.duration_since(*prev_instant).as_secs_f64();
+                    if elapsed > 0.0 {
+                        rate.rx_bps =
+                            sample.in_octets.saturating_sub(prev.in_octets) as f64 / elapsed;
+                        rate.tx_bps =
+                            sample.out_octets.saturating_sub(prev.out_octets) as f64 / elapsed;
+                    }
+                }
+                rate
+            })
+            .collect();
+        self.last_interfaces = Some((samples, now));
     }
 
     pub fn set_filter(&mut self, query: String) {
         // Filter changes are instant - no debounce
-        if query.is_empty() {
-            self.active_filter = None;
-        } else {
-            self.active_filter = Some(query.to_lowercase());
-        }
-
-        self.update_selection_from_key();
+        self.table.set_filter(query);
+        self.update_selection();
     }
 
     pub fn clear_filter(&mut self) {
         // Filter changes are instant - no debounce
-        self.active_filter = None;
-        self.update_selection_from_key();
+        self.table.active_filter = None;
+        self.update_selection();
     }
 
-    pub fn cycle_sort_key(&mut self) {
-        self.sort_key = self.sort_key.next();
-        self.sort_connections();
-        self.update_selection_from_key();
+    pub fn toggle_lingering_only(&mut self) {
+        self.lingering_only = !self.lingering_only;
+        self.update_selection();
     }
 
-    pub fn toggle_sort_order(&mut self) {
-        self.sort_order = self.sort_order.toggle();
-        self.sort_connections();
-        self.update_selection_from_key();
-    }
-
-    fn sort_connections(&mut self) {
-        match self.sort_key {
-            SortKey::State => {
-                self.connections.sort_by(|a, b| {
-                    let a_priority = state_priority(&a.state);
-                    let b_priority = state_priority(&b.state);
-                    let cmp = a_priority.cmp(&b_priority);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Pid => {
-                self.connections.sort_by(|a, b| {
-                    let cmp = a.pid.cmp(&b.pid);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::Protocol => {
-                self.connections.sort_by(|a, b| {
-                    let cmp = a.protocol.cmp(&b.protocol);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
-            }
-            SortKey::ProcessName => {
-                self.connections.sort_by(|a, b| {
-                    let a_name = a.process_name.as_deref().unwrap_or("");
-                    let b_name = b.process_name.as_deref().unwrap_or("");
-                    let cmp = a_name.cmp(b_name);
-                    if self.sort_order == SortOrder::Descending {
-                        cmp.reverse()
-                    } else {
-                        cmp
-                    }
-                });
+    pub fn toggle_hide_local(&mut self) {
+        self.hide_local = !self.hide_local;
+        self.update_selection();
+    }
+
+    /// Shows only `protocol`, or both protocols again if it was already the only one shown.
+    pub fn toggle_protocol_filter(&mut self, protocol: ProtocolFilter) {
+        self.protocol_filter = if self.protocol_filter == protocol {
+            ProtocolFilter::All
+        } else {
+            protocol
+        };
+        self.update_selection();
+    }
+
+    /// TIME_WAIT counts per local port and CLOSE_WAIT counts per owning PID.
+    pub fn lingering(&self) -> Lingering {
+        let mut lingering = Lingering::default();
+        for conn in &self.table.rows {
+            let count = match conn.state.as_str() {
+                "TIME_WAIT" => lingering.time_wait_by_port.entry(conn.local_port).or_default(),
+                "CLOSE_WAIT" => lingering.close_wait_by_pid.entry(conn.pid).or_default(),
+                _ => continue,
+            };
+            *count += 1;
+        }
+        lingering
+    }
+
+    /// Listening ports and connection counts owned by `pid`.
+    pub fn footprint(&self, pid: u32) -> PortFootprint {
+        let mut footprint = PortFootprint::default();
+        for conn in self.table.rows.iter().filter(|c| c.pid == pid) {
+            if is_listener(conn) {
+                footprint.listening.push((
+                    conn.protocol.clone(),
+                    conn.local_addr.clone(),
+                    conn.local_port,
+                ));
+            } else if conn.state == "ESTABLISHED" {
+                footprint.established += 1;
+            } else if is_lingering_state(&conn.state) {
+                footprint.lingering += 1;
             }
         }
+        footprint
+            .listening
+            .sort_by(|a, b| a.2.cmp(&b.2).then_with(|| a.0.cmp(&b.0)));
+        footprint
     }
 
-    fn update_selection_from_key(&mut self) {
-        if let Some((pid, ref local_addr, local_port, ref remote_addr, remote_port)) =
-            self.selected_connection_key
-        {
-            let filtered = self.get_filtered_indices("");
-            if let Some(new_idx) = filtered.iter().position(|&i| {
-                self.connections
-                    .get(i)
-                    .map(|c| {
-                        c.pid == pid
-                            && c.local_addr == *local_addr
-                            && c.local_port == local_port
-                            && c.remote_addr == *remote_addr
-                            && c.remote_port == remote_port
-                    })
-                    .unwrap_or(false)
-            }) {
-                self.list_state.select(Some(new_idx));
-            } else if !filtered.is_empty() {
-                self.list_state.select(Some(0));
-                self.selected_connection_key = filtered.first().and_then(|&i| {
-                    self.connections.get(i).map(|c| {
-                        (
-                            c.pid,
-                            c.local_addr.clone(),
-                            c.local_port,
-                            c.remote_addr.clone(),
-                            c.remote_port,
-                        )
-                    })
-                });
-            } else {
-                self.list_state.select(None);
-                self.selected_connection_key = None;
+    pub fn lingering_totals(&self) -> LingeringCounts {
+        let mut totals = LingeringCounts::default();
+        for conn in &self.table.rows {
+            match conn.state.as_str() {
+                "TIME_WAIT" => totals.time_wait += 1,
+                "CLOSE_WAIT" => totals.close_wait += 1,
+                _ => {}
             }
-        } else if !self.connections.is_empty() {
-            self.list_state.select(Some(0));
-            self.selected_connection_key = self.connections.first().map(|c| {
-                (
-                    c.pid,
-                    c.local_addr.clone(),
-                    c.local_port,
-                    c.remote_addr.clone(),
-                    c.remote_port,
-                )
-            });
         }
+        totals
     }
 
-    fn get_filter(&self, search_query: &str) -> Option<String> {
-        if !search_query.is_empty() {
-            Some(search_query.to_lowercase())
-        } else {
-            self.active_filter.clone()
+    pub fn view(&self) -> NexusView {
+        NexusView {
+            filter: self.table.active_filter.clone(),
+            sort_key: self.table.sort_key,
+            sort_order: self.table.sort_order,
+            lingering_only: self.lingering_only,
+            protocol_filter: self.protocol_filter,
+            hide_local: self.hide_local,
         }
     }
 
-    fn matches_filter(&self, conn: &ConnectionInfo, query: &str) -> bool {
-        conn.process_name
-            .as_ref()
-            .map(|n| n.to_lowercase().contains(query))
-            .unwrap_or(false)
-            || conn.local_addr.to_lowercase().contains(query)
-            || conn.remote_addr.to_lowercase().contains(query)
-            || conn.pid.to_string().contains(query)
-            || conn.local_port.to_string().contains(query)
+    pub fn apply_view(&mut self, view: &NexusView) {
+        self.table.active_filter = view.filter.clone();
+        self.table.sort_key = view.sort_key;
+        self.table.sort_order = view.sort_order;
+        self.lingering_only = view.lingering_only;
+        self.protocol_filter = view.protocol_filter;
+        self.hide_local = view.hide_local;
+        self.table.sort();
+        self.update_selection();
+    }
+
+    pub fn cycle_sort_key(&mut self) {
+        self.table.sort_key = self.table.sort_key.next();
+        self.table.sort();
+        self.update_selection();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.table.toggle_sort_order();
+        self.update_selection();
+    }
+
+    /// Sorts by `key`, or flips the order when already sorted by it.
+    pub fn sort_by(&mut self, key: SortKey) {
+        self.table.sort_by(key);
+        self.update_selection();
+    }
+
+    fn update_selection(&mut self) {
+        let shown = self.shown_ids("");
+        self.table.restore_selection(&shown);
+    }
+
+    fn shown_ids(&self, search_query: &str) -> Vec<ConnectionKey> {
+        self.table.ids(&self.get_filtered_indices(search_query))
+    }
+
+    /// Char positions in `text` matched by the current filter, for highlighting.
+    pub fn match_positions(&self, text: &str, search_query: &str) -> Vec<usize> {
+        self.table.match_positions(text, search_query)
+    }
+
+    /// The lingering-only, protocol and hide-local toggles, applied before the preset and
+    /// filter.
+    fn matches_view(&self, conn: &ConnectionInfo) -> bool {
+        (!self.lingering_only || is_lingering_state(&conn.state))
+            && self.protocol_filter.allows(&conn.protocol)
+            && !(self.hide_local && is_local_noise(conn))
+    }
+
+    pub fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        self.table.preset = preset;
+        self.update_selection();
     }
 
     pub fn get_filtered_indices(&self, search_query: &str) -> Vec<usize> {
-        match self.get_filter(search_query) {
-            None => (0..self.connections.len()).collect(),
-            Some(query) => self
-                .connections
-                .iter()
-                .enumerate()
-                .filter(|(_, c)| self.matches_filter(c, &query))
-                .map(|(i, _)| i)
-                .collect(),
-        }
+        self.table.filtered_indices(search_query, |conn| self.matches_view(conn))
     }
 
     pub fn filtered_connections(&self, search_query: &str) -> Vec<(usize, ConnectionInfo)> {
-        match self.get_filter(search_query) {
-            None => self
-                .connections
-                .iter()
-                .enumerate()
-                .map(|(i, c)| (i, c.clone()))
-                .collect(),
-            Some(query) => self
-                .connections
-                .iter()
-                .enumerate()
-                .filter(|(_, c)| self.matches_filter(c, &query))
-                .map(|(i, c)| (i, c.clone()))
-                .collect(),
-        }
+        self.get_filtered_indices(search_query)
+            .into_iter()
+            .map(|i| (i, self.table.rows[i].clone()))
+            .collect()
     }
 
     pub fn update_connections(&mut self, connections: Vec<ConnectionInfo>) {
-        // Check if data actually changed
-        let new_hash = self.compute_data_hash(&connections);
-
-        if new_hash == self.last_data_hash {
-            // Data hasn't changed, skip update
-            return;
-        }
-        self.last_data_hash = new_hash;
-
-        // Don't update during active navigation (but always allow initial load)
-        if self.should_ignore_update() {
+        // Skip unchanged data and updates during active navigation
+        if !self.table.accept_update(&connections) {
             return;
         }
 
-        self.connections = connections;
-        self.sort_connections();
-        self.update_selection_from_key();
-
-        // Mark initial load as complete
-        self.is_initial_load = false;
+        let mut connections = connections;
+        self.table.carry_frozen(&mut connections);
+        self.table.rows = connections;
+        self.table.sort();
+        self.update_selection();
+        self.table.finish_update();
     }
 
     pub fn select_next(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let new_idx = (i + 1) % filtered.len();
-        self.list_state.select(Some(new_idx));
-        self.selected_connection_key = filtered.get(new_idx).and_then(|&idx| {
-            self.connections.get(idx).map(|c| {
-                (
-                    c.pid,
-                    c.local_addr.clone(),
-                    c.local_port,
-                    c.remote_addr.clone(),
-                    c.remote_port,
-                )
-            })
-        });
+        let shown = self.shown_ids(search_query);
+        self.table.select_next(&shown);
     }
 
     pub fn select_prev(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let new_idx = (i + filtered.len() - 1) % filtered.len();
-        self.list_state.select(Some(new_idx));
-        self.selected_connection_key = filtered.get(new_idx).and_then(|&idx| {
-            self.connections.get(idx).map(|c| {
-                (
-                    c.pid,
-                    c.local_addr.clone(),
-                    c.local_port,
-                    c.remote_addr.clone(),
-                    c.remote_port,
-                )
-            })
-        });
+        let shown = self.shown_ids(search_query);
+        self.table.select_prev(&shown);
     }
 
     pub fn select_page_up(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let page_size = 10;
-        let new_idx = i.saturating_sub(page_size);
-        self.list_state.select(Some(new_idx));
-        self.selected_connection_key = filtered.get(new_idx).and_then(|&idx| {
-            self.connections.get(idx).map(|c| {
-                (
-                    c.pid,
-                    c.local_addr.clone(),
-                    c.local_port,
-                    c.remote_addr.clone(),
-                    c.remote_port,
-                )
-            })
-        });
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_up(&shown);
     }
 
     pub fn select_page_down(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if filtered.is_empty() {
-            return;
-        }
-        let i = self.list_state.selected().unwrap_or(0);
-        let page_size = 10;
-        let new_idx = std::cmp::min(i + page_size, filtered.len().saturating_sub(1));
-        self.list_state.select(Some(new_idx));
-        self.selected_connection_key = filtered.get(new_idx).and_then(|&idx| {
-            self.connections.get(idx).map(|c| {
-                (
-                    c.pid,
-                    c.local_addr.clone(),
-                    c.local_port,
-                    c.remote_addr.clone(),
-                    c.remote_port,
-                )
+        let shown = self.shown_ids(search_query);
+        self.table.select_page_down(&shown);
+    }
+
+    pub fn select_screen_up(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_up(&shown);
+    }
+
+    pub fn select_screen_down(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_screen_down(&shown);
+    }
+
+    pub fn move_up(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_up(&shown, rows);
+    }
+
+    pub fn move_down(&mut self, search_query: &str, rows: usize) {
+        let shown = self.shown_ids(search_query);
+        self.table.move_down(&shown, rows);
+    }
+
+    pub fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        let (shown, matches): (Vec<_>, Vec<bool>) = self
+            .get_filtered_indices(search_query)
+            .into_iter()
+            .map(|i| {
+                let row = &self.table.rows[i];
+                (row.id(), self.table.row_matches(row, pattern))
             })
-        });
+            .unzip();
+        self.table.find(&shown, &matches, forward, include_current)
     }
 
-    pub fn select_first(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if !filtered.is_empty() {
-            self.list_state.select(Some(0));
-            self.selected_connection_key = filtered.first().and_then(|&idx| {
-                self.connections.get(idx).map(|c| {
-                    (
-                        c.pid,
-                        c.local_addr.clone(),
-                        c.local_port,
-                        c.remote_addr.clone(),
-                        c.remote_port,
-                    )
-                })
-            });
+    /// Freezes or unfreezes the selected row. Returns its label and new state.
+    pub fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        let row = self.selected_connection(search_query)?.clone();
+        let frozen = self.table.toggle_freeze(&row);
+        if !frozen {
+            self.update_selection();
         }
+        Some((format!("{}:{} {}", row.local_addr, row.local_port, row.protocol), frozen))
+    }
+
+    pub fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        let shown = self.shown_ids(search_query);
+        self.table.jump_to_mark(mark, &shown)
+    }
+
+    pub fn select_first(&mut self, search_query: &str) {
+        let shown = self.shown_ids(search_query);
+        self.table.select_first(&shown);
     }
 
     pub fn select_last(&mut self, search_query: &str) {
-        self.mark_navigation();
-        let filtered = self.get_filtered_indices(search_query);
-        if !filtered.is_empty() {
-            let last_idx = filtered.len() - 1;
-            self.list_state.select(Some(last_idx));
-            self.selected_connection_key = filtered.get(last_idx).and_then(|&idx| {
-                self.connections.get(idx).map(|c| {
-                    (
-                        c.pid,
-                        c.local_addr.clone(),
-                        c.local_port,
-                        c.remote_addr.clone(),
-                        c.remote_port,
-                    )
-                })
-            });
+        let shown = self.shown_ids(search_query);
+        self.table.select_last(&shown);
+    }
+
+    pub fn selected_connection(&self, search_query: &str) -> Option<&ConnectionInfo> {
+        self.table.selected_row(&self.get_filtered_indices(search_query))
+    }
+}
+
+/// The endpoints a capture of `conn` filters on.
+pub fn capture_target(conn: &ConnectionInfo) -> crate::sys::capture::CaptureTarget {
+    let remote = (has_remote(conn) && conn.remote_port != 0)
+        .then(|| (conn.remote_addr.clone(), conn.remote_port));
+    crate::sys::capture::CaptureTarget {
+        protocol: if conn.protocol.starts_with("UDP") { "UDP" } else { "TCP" },
+        local_addr: conn.local_addr.clone(),
+        local_port: conn.local_port,
+        remote,
+    }
+}
+
+/// Classifies a remote address as "external", "private", "loopback" or "none" (unbound / UDP).
+pub fn remote_scope(addr: &str) -> &'static str {
+    use std::net::IpAddr;
+
+    match addr.parse::<IpAddr>() {
+        Ok(ip) if ip.is_unspecified() => "none",
+        Ok(ip) if ip.is_loopback() => "loopback",
+        Ok(IpAddr::V4(v4)) if v4.is_private() || v4.is_link_local() => "private",
+        // fc00::/7 unique local and fe80::/10 link local
+        Ok(IpAddr::V6(v6))
+            if (v6.segments()[0] & 0xfe00) == 0xfc00 || (v6.segments()[0] & 0xffc0) == 0xfe80 =>
+        {
+            "private"
         }
+        Ok(_) => "external",
+        Err(_) => "none",
     }
 }
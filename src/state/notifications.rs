@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Success => "OK",
+            Severity::Warning => "WARN",
+            Severity::Error => "ERROR",
+        }
+    }
+
+    /// How long a toast stays on screen. Failures linger so they aren't missed.
+    fn lifetime(&self) -> Duration {
+        match self {
+            Severity::Info | Severity::Success => Duration::from_secs(4),
+            Severity::Warning => Duration::from_secs(8),
+            Severity::Error => Duration::from_secs(12),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub severity: Severity,
+    pub message: String,
+    pub created: Instant,
+}
+
+/// Toast messages currently on screen plus a history of everything shown this session, newest first.
+pub struct Notifications {
+    pub active: VecDeque<Notification>,
+    pub history: VecDeque<Notification>,
+}
+
+impl Notifications {
+    const MAX_ACTIVE: usize = 5;
+    const MAX_HISTORY: usize = 200;
+
+    pub fn new() -> Self {
+        Self {
+            active: VecDeque::new(),
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, severity: Severity, message: String) {
+        let notification = Notification {
+            severity,
+            message,
+            created: Instant::now(),
+        };
+
+        if self.history.len() == Self::MAX_HISTORY {
+            self.history.pop_back();
+        }
+        self.history.push_front(notification.clone());
+
+        // Identical toasts already on screen are replaced rather than stacked
+        self.active
+            .retain(|n| n.severity != severity || n.message != notification.message);
+        if self.active.len() == Self::MAX_ACTIVE {
+            self.active.pop_back();
+        }
+        self.active.push_front(notification);
+    }
+
+    /// Drops toasts whose lifetime has passed.
+    pub fn expire(&mut self) {
+        self.active
+            .retain(|n| n.created.elapsed() < n.severity.lifetime());
+    }
+
+    pub fn dismiss(&mut self) {
+        self.active.clear();
+    }
+
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+}
@@ -0,0 +1,85 @@
+use std::collections::VecDeque;
+use std::time::Instant;
+
+use crate::sys::system::SystemSample;
+
+pub struct PerfState {
+    pub cpu_percent: f64,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub net_rx_bps: f64,
+    pub net_tx_bps: f64,
+    pub cpu_history: VecDeque<u64>,
+    pub memory_history: VecDeque<u64>,
+    pub net_rx_history: VecDeque<u64>,
+    pub net_tx_history: VecDeque<u64>,
+    last_sample: Option<(SystemSample, Instant)>,
+}
+
+impl PerfState {
+    // One sample per metrics tick (1s), so this keeps about two minutes of history
+    const HISTORY_LEN: usize = 120;
+
+    pub fn new() -> Self {
+        Self {
+            cpu_percent: 0.0,
+            memory_used: 0,
+            memory_total: 0,
+            net_rx_bps: 0.0,
+            net_tx_bps: 0.0,
+            cpu_history: VecDeque::with_capacity(Self::HISTORY_LEN),
+            memory_history: VecDeque::with_capacity(Self::HISTORY_LEN),
+            net_rx_history: VecDeque::with_capacity(Self::HISTORY_LEN),
+            net_tx_history: VecDeque::with_capacity(Self::HISTORY_LEN),
+            last_sample: None,
+        }
+    }
+
+    pub fn memory_percent(&self) -> f64 {
+        if self.memory_total == 0 {
+            0.0
+        } else {
+            self.memory_used as f64 / self.memory_total as f64 * 100.0
+        }
+    }
+
+    fn push(history: &mut VecDeque<u64>, value: u64) {
+        if history.len() == Self::HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(value);
+    }
+
+    pub fn update(&mut self, sample: SystemSample) {
+        let now = Instant::now();
+        self.memory_used = sample.memory_used;
+        self.memory_total = sample.memory_total;
+
+        if let Some((prev, prev_instant)) = self.last_sample {
+            let idle = sample.idle_time.saturating_sub(prev.idle_time);
+            // Kernel time already includes idle time
+            let total = sample.kernel_time.saturating_sub(prev.kernel_time)
+                + sample.user_time.saturating_sub(prev.user_time);
+            if total > 0 {
+                self.cpu_percent =
+                    ((total.saturating_sub(idle)) as f64 / total as f64 * 100.0).clamp(0.0, 100.0);
+            }
+
+            let elapsed = now.duration_since(prev_instant).as_secs_f64();
+            if elapsed > 0.0 {
+                self.net_rx_bps =
+                    sample.net_in_octets.saturating_sub(prev.net_in_octets) as f64 / elapsed;
+                self.net_tx_bps =
+                    sample.net_out_octets.saturating_sub(prev.net_out_octets) as f64 / elapsed;
+            }
+
+            Self::push(&mut self.cpu_history, self.cpu_percent.round() as u64);
+            Self::push(&mut self.net_rx_history, self.net_rx_bps as u64);
+            Self::push(&mut self.net_tx_history, self.net_tx_bps as u64);
+        }
+        let memory_percent = self.memory_percent().round() as u64;
+        Self::push(&mut self.memory_history, memory_percent);
+
+        self.last_sample = Some((sample, now));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::config::PortWatch;
+use crate::sys::network::ConnectionInfo;
+
+/// A watched port and whether it was up at the last Nexus poll.
+#[derive(Debug, Clone)]
+pub struct WatchStatus {
+    pub watch: PortWatch,
+    /// What's wrong and since when (local time), while the watch fails.
+    pub down: Option<(String, String)>,
+}
+
+/// The configured port watches, checked against the listeners on every Nexus poll.
+#[derive(Debug, Default)]
+pub struct PortWatches {
+    pub statuses: Vec<WatchStatus>,
+}
+
+impl PortWatches {
+    /// Follows the configured watches, keeping what's known about ones that stay.
+    pub fn sync(&mut self, watches: &[PortWatch]) {
+        let mut old = std::mem::take(&mut self.statuses);
+        self.statuses = watches
+            .iter()
+            .map(|watch| match old.iter().position(|s| s.watch == *watch) {
+                Some(index) => old.swap_remove(index),
+                None => WatchStatus {
+                    watch: watch.clone(),
+                    down: None,
+                },
+            })
+            .collect();
+    }
+
+    /// Checks every watch against `connections`. Returns the watches that went down and
+    /// the ones that came back, as messages to show.
+    pub fn check(
+        &mut self,
+        connections: &[&ConnectionInfo],
+        clock: &str,
+    ) -> (Vec<String>, Vec<String>) {
+        let (mut down, mut up) = (Vec::new(), Vec::new());
+        for status in &mut self.statuses {
+            match problem(&status.watch, connections) {
+                Some(problem) => {
+                    if status.down.is_none() {
+                        down.push(format!("{}: {}", status.watch.label(), problem));
+                    }
+                    // Keep the time it went down, but say what's wrong now
+                    let since = status.down.take().map_or(clock.to_string(), |(_, since)| since);
+                    status.down = Some((problem, since));
+                }
+                None => {
+                    if status.down.take().is_some() {
+                        up.push(format!("{} is listening again", status.watch.label()));
+                    }
+                }
+            }
+        }
+        (down, up)
+    }
+
+    /// Watches that failed the last check.
+    pub fn failing(&self) -> impl Iterator<Item = &WatchStatus> {
+        self.statuses.iter().filter(|status| status.down.is_some())
+    }
+}
+
+/// Why `watch` isn't met by `connections`, or None if it is.
+fn problem(watch: &PortWatch, connections: &[&ConnectionInfo]) -> Option<String> {
+    if connections.iter().any(|c| watch.accepts(c)) {
+        return None;
+    }
+    let holder = connections
+        .iter()
+        .find(|c| c.state == "LISTENING" && c.local_port == watch.port);
+    Some(match holder {
+        Some(c) => format!(
+            "listening by {} (PID {}) instead",
+            c.process_name.as_deref().unwrap_or("?"),
+            c.pid
+        ),
+        None => "not listening".to_string(),
+    })
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+/// A quick filter bound to a number key. `query` is a list of `field:value` terms that
+/// must all match, e.g. `start:auto status:stopped`. Values match as case-insensitive
+/// substrings; `=` requires an exact match, `>N` / `<N` compare numerically and a
+/// leading `!` negates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    pub name: String,
+    pub query: String,
+}
+
+impl FilterPreset {
+    fn new(name: &str, query: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    /// Ad-hoc preset narrowing a tab to one process, used by the cross-tab jumps.
+    pub fn pid(pid: u32) -> Self {
+        Self {
+            name: format!("PID {}", pid),
+            query: format!("pid:={}", pid),
+        }
+    }
+}
+
+pub fn default_locker_presets() -> Vec<FilterPreset> {
+    vec![
+        FilterPreset::new("Busy", "cpu:>1"),
+        FilterPreset::new("Over 500 MB", "mem:>500"),
+        FilterPreset::new("Disk active", "io:>0"),
+        FilterPreset::new("Started < 5 min", "uptime:<300"),
+        FilterPreset::new("WSL / containers", "container:!="),
+    ]
+}
+
+pub fn default_controller_presets() -> Vec<FilterPreset> {
+    vec![
+        FilterPreset::new("Running", "status:running"),
+        FilterPreset::new("Stopped", "status:stopped"),
+        FilterPreset::new("Auto but stopped", "start:auto status:stopped"),
+    ]
+}
+
+pub fn default_nexus_presets() -> Vec<FilterPreset> {
+    vec![
+        FilterPreset::new("Listening", "state:listen"),
+        FilterPreset::new("Established", "state:established"),
+        FilterPreset::new("External", "remote:external"),
+    ]
+}
+
+/// Evaluates a preset query against one row. `field` maps a field name to the row's value,
+/// returning None for fields the tab doesn't have (such terms never match).
+pub fn matches(query: &str, field: impl Fn(&str) -> Option<String>) -> bool {
+    query.split_whitespace().all(|term| {
+        let Some((key, pattern)) = term.split_once(':') else {
+            return false;
+        };
+        field(&key.to_lowercase()).is_some_and(|value| value_matches(&value, pattern))
+    })
+}
+
+fn value_matches(value: &str, pattern: &str) -> bool {
+    if let Some(rest) = pattern.strip_prefix('!') {
+        return !value_matches(value, rest);
+    }
+
+    let numbers = |rest: &str| -> Option<(f64, f64)> {
+        Some((value.parse().ok()?, rest.parse().ok()?))
+    };
+    if let Some(rest) = pattern.strip_prefix('>') {
+        return numbers(rest).is_some_and(|(v, limit)| v > limit);
+    }
+    if let Some(rest) = pattern.strip_prefix('<') {
+        return numbers(rest).is_some_and(|(v, limit)| v < limit);
+    }
+    if let Some(rest) = pattern.strip_prefix('=') {
+        return value.eq_ignore_ascii_case(rest);
+    }
+
+    value.to_lowercase().contains(&pattern.to_lowercase())
+}
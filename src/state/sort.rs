@@ -0,0 +1,50 @@
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Orders strings the way a person would: case-insensitive, with runs of digits compared
+/// by value so "svc2" comes before "svc10". Strings that only differ in case or leading
+/// zeros fall back to a byte comparison so the ordering stays total.
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut left = a.chars().peekable();
+    let mut right = b.chars().peekable();
+
+    loop {
+        let (l, r) = match (left.peek(), right.peek()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(&l), Some(&r)) => (l, r),
+        };
+
+        let ordering = if l.is_ascii_digit() && r.is_ascii_digit() {
+            compare_numbers(&digit_run(&mut left), &digit_run(&mut right))
+        } else {
+            left.next();
+            right.next();
+            l.to_lowercase().cmp(r.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn digit_run(chars: &mut Peekable<Chars>) -> String {
+    let mut run = String::new();
+    while let Some(&c) = chars.peek() {
+        if !c.is_ascii_digit() {
+            break;
+        }
+        run.push(c);
+        chars.next();
+    }
+    run
+}
+
+/// Compares digit runs by value without parsing, so arbitrarily long numbers work.
+fn compare_numbers(a: &str, b: &str) -> Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
@@ -0,0 +1,536 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::time::{Duration, Instant};
+
+use ratatui::widgets::ListState;
+
+use crate::error::Result;
+use crate::state::colors::RowColors;
+use crate::state::matcher;
+use crate::state::presets::{self, FilterPreset};
+use crate::ui::text::pad;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl SortOrder {
+    pub fn toggle(&self) -> Self {
+        match self {
+            SortOrder::Ascending => SortOrder::Descending,
+            SortOrder::Descending => SortOrder::Ascending,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortOrder::Ascending => "▲",
+            SortOrder::Descending => "▼",
+        }
+    }
+}
+
+/// Where a list's cursor and scroll were, as kept between sessions.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TablePosition<Id> {
+    pub selected: Option<Id>,
+    pub offset: usize,
+}
+
+/// A row a `TableState` can list. `K` is the tab's sort key.
+pub trait TableRow<K> {
+    /// Identifies the row across refreshes, so the cursor stays on it when rows move.
+    type Id: Clone + PartialEq + Eq + std::hash::Hash;
+
+    fn id(&self) -> Self::Id;
+
+    /// Texts the search filter matches against.
+    fn search_fields(&self) -> Vec<String>;
+
+    /// Value of a preset query field, or None if the row has no such field.
+    fn field(&self, name: &str) -> Option<String>;
+
+    /// The row's values by name for external tool placeholders and scripts, e.g.
+    /// ("pid", "1234"). Unlike `field`, these are plain values meant to be passed on.
+    fn values(&self) -> Vec<(&'static str, String)>;
+
+    /// Compares by `key` alone; the table applies the sort order, then `tiebreak`.
+    fn compare(&self, other: &Self, key: K) -> Ordering;
+
+    /// Fixed fallback order so rows with equal keys don't swap places between polls.
+    fn tiebreak(&self, other: &Self) -> Ordering;
+
+    /// Feeds the fields whose change should replace the rows into `hasher`.
+    fn hash_into(&self, hasher: &mut DefaultHasher);
+}
+
+/// Rows plus the selection, filter, preset, sort and update debounce shared by the list
+/// tabs. Selection is keyed by row id and methods that move it take the ids of the rows
+/// currently shown, in display order, so a tab can add its own filters or layout (such
+/// as the Locker tree) on top.
+pub struct TableState<T: TableRow<K>, K> {
+    pub rows: Vec<T>,
+    pub list_state: ListState,
+    pub selected: Option<T::Id>,
+    pub active_filter: Option<String>,
+    /// Quick filter applied on top of the text filter.
+    pub preset: Option<FilterPreset>,
+    /// Match the text filter as a subsequence, ranking the best matches first.
+    pub fuzzy: bool,
+    /// Row coloring rules from the config.
+    pub colors: RowColors,
+    pub sort_key: K,
+    pub sort_order: SortOrder,
+    /// Rows the list showed on the last draw, set by the renderer for paging.
+    pub viewport_rows: usize,
+    /// Rows Ctrl+D/U move by. None pages by half the viewport.
+    pub page_step: Option<usize>,
+    /// Rows bookmarked with `m{a-z}`, jumped back to with `'{a-z}`.
+    pub marks: HashMap<char, T::Id>,
+    /// Rows frozen with `F`, as of their last poll, so they stay listed after they vanish.
+    pub frozen: Vec<T>,
+    /// Frozen rows the last poll no longer reported, listed from their snapshot.
+    pub vanished: Vec<T::Id>,
+    /// Script column names and widths, in the order of each row's `script_values`.
+    pub script_columns: Vec<(String, usize)>,
+    /// Script column values per row, computed after each refresh.
+    pub script_values: HashMap<T::Id, Vec<String>>,
+    last_navigation: Instant,
+    last_data_hash: u64,
+    is_initial_load: bool,
+}
+
+impl<T: TableRow<K>, K: Copy + PartialEq> TableState<T, K> {
+    // Short debounce for navigation only (50ms) - allows real-time feel while preventing jitter
+    const NAVIGATION_DEBOUNCE: Duration = Duration::from_millis(50);
+
+    pub fn new(sort_key: K, sort_order: SortOrder) -> Self {
+        Self {
+            rows: Vec::new(),
+            list_state: ListState::default(),
+            selected: None,
+            active_filter: None,
+            preset: None,
+            fuzzy: false,
+            colors: RowColors::default(),
+            sort_key,
+            sort_order,
+            // Until the first draw reports the real height
+            viewport_rows: 20,
+            page_step: None,
+            marks: HashMap::new(),
+            frozen: Vec::new(),
+            vanished: Vec::new(),
+            script_columns: Vec::new(),
+            script_values: HashMap::new(),
+            last_navigation: Instant::now(),
+            last_data_hash: 0,
+            is_initial_load: true,
+        }
+    }
+
+    pub fn is_initial_load(&self) -> bool {
+        self.is_initial_load
+    }
+
+    pub fn should_ignore_update(&self) -> bool {
+        // Always allow initial load
+        if self.is_initial_load {
+            return false;
+        }
+        // Only debounce actual navigation (not filter operations)
+        self.last_navigation.elapsed() < Self::NAVIGATION_DEBOUNCE
+    }
+
+    fn mark_navigation(&mut self) {
+        self.last_navigation = Instant::now();
+    }
+
+    /// Whether a fresh poll should replace the rows: skipped when nothing changed or while
+    /// the user is navigating. Call `finish_update` after replacing them.
+    pub fn accept_update(&mut self, rows: &[T]) -> bool {
+        let mut hasher = DefaultHasher::new();
+        hasher.write_usize(rows.len());
+        for row in rows {
+            row.hash_into(&mut hasher);
+        }
+        let new_hash = hasher.finish();
+
+        if new_hash == self.last_data_hash {
+            return false;
+        }
+        self.last_data_hash = new_hash;
+        !self.should_ignore_update()
+    }
+
+    pub fn finish_update(&mut self) {
+        self.is_initial_load = false;
+    }
+
+    pub fn set_filter(&mut self, query: String) {
+        if query.is_empty() {
+            self.active_filter = None;
+        } else {
+            self.active_filter = Some(query.to_lowercase());
+        }
+    }
+
+    /// The search box while typing, otherwise the applied filter.
+    pub fn filter(&self, search_query: &str) -> Option<String> {
+        if !search_query.is_empty() {
+            Some(search_query.to_lowercase())
+        } else {
+            self.active_filter.clone()
+        }
+    }
+
+    pub fn filter_score(&self, row: &T, query: &str) -> Option<i32> {
+        row.search_fields()
+            .iter()
+            .filter_map(|text| matcher::find(query, text, self.fuzzy))
+            .map(|m| m.score)
+            .max()
+    }
+
+    /// Char positions in `text` matched by the current filter, for highlighting.
+    pub fn match_positions(&self, text: &str, search_query: &str) -> Vec<usize> {
+        self.filter(search_query)
+            .and_then(|query| matcher::find(&query, text, self.fuzzy))
+            .map(|m| m.positions)
+            .unwrap_or_default()
+    }
+
+    pub fn matches_preset(&self, row: &T) -> bool {
+        match &self.preset {
+            Some(preset) => presets::matches(&preset.query, |field| self.field(row, field)),
+            None => true,
+        }
+    }
+
+    /// Indices of rows passing `keep`, the preset and the filter. Fuzzy matches rank best
+    /// first; the stable sort keeps column order among equal scores.
+    pub fn filtered_indices(&self, search_query: &str, keep: impl Fn(&T) -> bool) -> Vec<usize> {
+        let query = self.filter(search_query);
+        let mut matches: Vec<(usize, i32)> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| keep(row) && self.matches_preset(row))
+            .filter_map(|(i, row)| match &query {
+                None => Some((i, 0)),
+                Some(query) => self.filter_score(row, query).map(|score| (i, score)),
+            })
+            .collect();
+        matches.sort_by_key(|&(_, score)| std::cmp::Reverse(score));
+        matches.into_iter().map(|(i, _)| i).collect()
+    }
+
+    pub fn ids(&self, indices: &[usize]) -> Vec<T::Id> {
+        indices.iter().map(|&i| self.rows[i].id()).collect()
+    }
+
+    pub fn compare(&self, a: &T, b: &T) -> Ordering {
+        let cmp = a.compare(b, self.sort_key);
+        let cmp = if self.sort_order == SortOrder::Descending {
+            cmp.reverse()
+        } else {
+            cmp
+        };
+        cmp.then_with(|| a.tiebreak(b))
+    }
+
+    pub fn sort(&mut self) {
+        let mut rows = std::mem::take(&mut self.rows);
+        rows.sort_by(|a, b| self.compare(a, b));
+        self.rows = rows;
+    }
+
+    /// Sorts by `key`, or flips the order when already sorted by it.
+    pub fn sort_by(&mut self, key: K) {
+        if self.sort_key == key {
+            self.sort_order = self.sort_order.toggle();
+        } else {
+            self.sort_key = key;
+        }
+        self.sort();
+    }
+
+    pub fn toggle_sort_order(&mut self) {
+        self.sort_order = self.sort_order.toggle();
+        self.sort();
+    }
+
+    pub fn position(&self) -> TablePosition<T::Id> {
+        TablePosition {
+            selected: self.selected.clone(),
+            offset: self.list_state.offset(),
+        }
+    }
+
+    /// Scrolls back to `position` and selects its row. The cursor lands on the row with
+    /// the tab's next `restore_selection`, if the row still exists.
+    pub fn set_position(&mut self, position: TablePosition<T::Id>) {
+        self.selected = position.selected;
+        *self.list_state.offset_mut() = position.offset;
+    }
+
+    /// Puts the cursor back on the selected row after the shown rows changed, falling back
+    /// to the first row when it is gone.
+    pub fn restore_selection(&mut self, shown: &[T::Id]) {
+        if let Some(selected) = &self.selected
+            && let Some(idx) = shown.iter().position(|id| id == selected)
+        {
+            self.list_state.select(Some(idx));
+            return;
+        }
+        self.list_state.select(if shown.is_empty() { None } else { Some(0) });
+        self.selected = shown.first().cloned();
+    }
+
+    fn select_index(&mut self, shown: &[T::Id], idx: usize) {
+        self.list_state.select(Some(idx));
+        self.selected = shown.get(idx).cloned();
+    }
+
+    pub fn select_next(&mut self, shown: &[T::Id]) {
+        self.mark_navigation();
+        if shown.is_empty() {
+            return;
+        }
+        let i = self.list_state.selected().unwrap_or(0);
+        self.select_index(shown, (i + 1) % shown.len());
+    }
+
+    pub fn select_prev(&mut self, shown: &[T::Id]) {
+        self.mark_navigation();
+        if shown.is_empty() {
+            return;
+        }
+        let i = self.list_state.selected().unwrap_or(0);
+        self.select_index(shown, (i + shown.len() - 1) % shown.len());
+    }
+
+    fn half_page(&self) -> usize {
+        self.page_step.unwrap_or(self.viewport_rows / 2).max(1)
+    }
+
+    fn full_page(&self) -> usize {
+        self.viewport_rows.max(1)
+    }
+
+    /// Moves up `rows` rows, stopping at the first. Used for counts like `15k`.
+    pub fn move_up(&mut self, shown: &[T::Id], rows: usize) {
+        self.mark_navigation();
+        if shown.is_empty() {
+            return;
+        }
+        let i = self.list_state.selected().unwrap_or(0);
+        self.select_index(shown, i.saturating_sub(rows));
+    }
+
+    /// Moves down `rows` rows, stopping at the last. Used for counts like `15j`.
+    pub fn move_down(&mut self, shown: &[T::Id], rows: usize) {
+        self.mark_navigation();
+        if shown.is_empty() {
+            return;
+        }
+        let i = self.list_state.selected().unwrap_or(0);
+        self.select_index(shown, (i + rows).min(shown.len() - 1));
+    }
+
+    /// Ctrl+U: up by the configured step or half the viewport.
+    pub fn select_page_up(&mut self, shown: &[T::Id]) {
+        self.move_up(shown, self.half_page());
+    }
+
+    /// Ctrl+D: down by the configured step or half the viewport.
+    pub fn select_page_down(&mut self, shown: &[T::Id]) {
+        self.move_down(shown, self.half_page());
+    }
+
+    /// PageUp: up by a full viewport.
+    pub fn select_screen_up(&mut self, shown: &[T::Id]) {
+        self.move_up(shown, self.full_page());
+    }
+
+    /// PageDown: down by a full viewport.
+    pub fn select_screen_down(&mut self, shown: &[T::Id]) {
+        self.move_down(shown, self.full_page());
+    }
+
+    pub fn select_first(&mut self, shown: &[T::Id]) {
+        self.mark_navigation();
+        if !shown.is_empty() {
+            self.select_index(shown, 0);
+        }
+    }
+
+    pub fn select_last(&mut self, shown: &[T::Id]) {
+        self.mark_navigation();
+        if !shown.is_empty() {
+            self.select_index(shown, shown.len() - 1);
+        }
+    }
+
+    /// Whether `row` matches a find pattern, the same way the filter would match it.
+    pub fn row_matches(&self, row: &T, pattern: &str) -> bool {
+        self.filter_score(row, &pattern.to_lowercase()).is_some()
+    }
+
+    /// Moves to the next shown row whose `matches` entry is set, or the previous one when
+    /// `forward` is false, wrapping around. With `include_current` the cursor stays put if
+    /// its row matches, so typing a find pattern doesn't skip past a match already selected.
+    pub fn find(
+        &mut self,
+        shown: &[T::Id],
+        matches: &[bool],
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        if shown.is_empty() {
+            return false;
+        }
+        let len = shown.len();
+        let current = self.list_state.selected().unwrap_or(0).min(len - 1);
+        let start = if include_current { 0 } else { 1 };
+        let found = (start..=len)
+            .map(|step| {
+                if forward {
+                    (current + step) % len
+                } else {
+                    (current + len - step % len) % len
+                }
+            })
+            .find(|&idx| matches.get(idx).copied().unwrap_or(false));
+        match found {
+            Some(idx) => {
+                self.mark_navigation();
+                self.select_index(shown, idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Style of the first color rule matching `row`, if any.
+    pub fn row_style(&self, row: &T) -> Option<ratatui::style::Style> {
+        self.colors.style(|field| self.field(row, field))
+    }
+
+    /// A preset query field of `row`, falling back to the script columns by name.
+    fn field(&self, row: &T, name: &str) -> Option<String> {
+        row.field(name).or_else(|| {
+            let column = self
+                .script_columns
+                .iter()
+                .position(|(column, _)| column.eq_ignore_ascii_case(name))?;
+            self.script_values.get(&row.id())?.get(column).cloned()
+        })
+    }
+
+    /// The shown script columns of the row with `id`, each led by a space. Columns of
+    /// width 0 only exist for filtering and aren't shown.
+    pub fn script_cells(&self, id: &T::Id) -> String {
+        let values = self.script_values.get(id);
+        self.script_columns
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, width))| *width > 0)
+            .map(|(i, (_, width))| {
+                let value = values.and_then(|values| values.get(i)).map_or("", String::as_str);
+                format!(" {}", pad(value, *width))
+            })
+            .collect()
+    }
+
+    /// Headers of the shown script columns, padded to their widths.
+    pub fn script_headers(&self) -> Vec<String> {
+        self.script_columns
+            .iter()
+            .filter(|(_, width)| *width > 0)
+            .map(|(name, width)| pad(name, *width))
+            .collect()
+    }
+
+    pub fn is_frozen(&self, id: &T::Id) -> bool {
+        self.frozen.iter().any(|row| row.id() == *id)
+    }
+
+    pub fn is_vanished(&self, id: &T::Id) -> bool {
+        self.vanished.contains(id)
+    }
+
+    /// Rows the last poll reported, leaving out vanished frozen rows.
+    pub fn live_rows(&self) -> impl Iterator<Item = &T> {
+        self.rows.iter().filter(|row| !self.is_vanished(&row.id()))
+    }
+
+    /// Freezes `row`, or unfreezes it if it already is. Returns whether it is now frozen.
+    /// Unfreezing a row that has vanished drops it from the list right away.
+    pub fn toggle_freeze(&mut self, row: &T) -> bool
+    where
+        T: Clone,
+    {
+        let id = row.id();
+        let Some(pos) = self.frozen.iter().position(|r| r.id() == id) else {
+            self.frozen.push(row.clone());
+            return true;
+        };
+        self.frozen.remove(pos);
+        if let Some(pos) = self.vanished.iter().position(|v| *v == id) {
+            self.vanished.remove(pos);
+            self.rows.retain(|r| r.id() != id);
+        }
+        false
+    }
+
+    /// Call with a fresh poll before it replaces `rows`: refreshes the snapshots of frozen
+    /// rows that are still there and appends the ones that are gone, so they stay listed.
+    pub fn carry_frozen(&mut self, rows: &mut Vec<T>)
+    where
+        T: Clone,
+    {
+        self.vanished.clear();
+        for frozen in &mut self.frozen {
+            let id = frozen.id();
+            match rows.iter().find(|row| row.id() == id) {
+                Some(row) => *frozen = row.clone(),
+                None => {
+                    rows.push(frozen.clone());
+                    self.vanished.push(id);
+                }
+            }
+        }
+    }
+
+    /// Bookmarks the selected row under `mark`.
+    pub fn set_mark(&mut self, mark: char) -> Result<()> {
+        let id = self.selected.clone().ok_or("Nothing selected to mark")?;
+        self.marks.insert(mark, id);
+        Ok(())
+    }
+
+    pub fn jump_to_mark(&mut self, mark: char, shown: &[T::Id]) -> Result<()> {
+        let id = self
+            .marks
+            .get(&mark)
+            .ok_or_else(|| format!("Mark '{}' is not set", mark))?;
+        let idx = shown
+            .iter()
+            .position(|shown_id| shown_id == id)
+            .ok_or_else(|| format!("Row marked '{}' is gone or filtered out", mark))?;
+        self.mark_navigation();
+        self.select_index(shown, idx);
+        Ok(())
+    }
+
+    /// The row under the cursor, given the indices of the rows shown.
+    pub fn selected_row(&self, indices: &[usize]) -> Option<&T> {
+        let idx = self.list_state.selected()?;
+        self.rows.get(*indices.get(idx)?)
+    }
+}
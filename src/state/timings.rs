@@ -0,0 +1,78 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// How long one collector took on its recent runs.
+pub struct CollectorTiming {
+    pub name: &'static str,
+    pub last: Duration,
+    pub max: Duration,
+    pub runs: u64,
+    pub last_run: Instant,
+    /// Recent run times, newest last.
+    pub history: VecDeque<Duration>,
+}
+
+impl CollectorTiming {
+    /// Mean of the runs in `history`.
+    pub fn average(&self) -> Duration {
+        match self.history.len() {
+            0 => Duration::ZERO,
+            n => self.history.iter().sum::<Duration>() / n as u32,
+        }
+    }
+}
+
+/// Run times of the collectors behind the tabs and metrics, so a slow poll on a big
+/// server can be pinned on the API that causes it. Listed in the order they first ran.
+pub struct Timings {
+    pub collectors: Vec<CollectorTiming>,
+}
+
+impl Timings {
+    pub const HISTORY: usize = 60;
+
+    pub fn new() -> Self {
+        Self {
+            collectors: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, name: &'static str, elapsed: Duration) {
+        let index = match self.collectors.iter().position(|c| c.name == name) {
+            Some(index) => index,
+            None => {
+                self.collectors.push(CollectorTiming {
+                    name,
+                    last: Duration::ZERO,
+                    max: Duration::ZERO,
+                    runs: 0,
+                    last_run: Instant::now(),
+                    history: VecDeque::with_capacity(Self::HISTORY),
+                });
+                self.collectors.len() - 1
+            }
+        };
+        let collector = &mut self.collectors[index];
+        collector.last = elapsed;
+        collector.max = collector.max.max(elapsed);
+        collector.runs += 1;
+        collector.last_run = Instant::now();
+        if collector.history.len() == Self::HISTORY {
+            collector.history.pop_front();
+        }
+        collector.history.push_back(elapsed);
+    }
+
+    /// Runs `collect` and records how long it took under `name`.
+    pub fn time<T>(&mut self, name: &'static str, collect: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = collect();
+        self.record(name, started.elapsed());
+        result
+    }
+
+    /// Forgets all runs, e.g. to measure from a known point.
+    pub fn clear(&mut self) {
+        self.collectors.clear();
+    }
+}
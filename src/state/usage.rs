@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+
+use crate::sys::process::ProcessInfo;
+
+/// Peak and average CPU and working set of one process, over every metrics sample taken
+/// since Aperture first saw it.
+#[derive(Debug, Clone)]
+pub struct UsageStats {
+    /// Start time of the process sampled, to tell a reused PID from the same process.
+    start_time: Option<u64>,
+    pub samples: u64,
+    /// Local time of the first sample.
+    pub since: String,
+    /// Percent of all cores.
+    pub peak_cpu: f32,
+    pub peak_cpu_at: String,
+    cpu_total: f64,
+    pub peak_memory_mb: f64,
+    pub peak_memory_at: String,
+    memory_total: f64,
+}
+
+impl UsageStats {
+    fn new(process: &ProcessInfo, clock: &str) -> Self {
+        Self {
+            start_time: process.start_time,
+            samples: 0,
+            since: clock.to_string(),
+            peak_cpu: 0.0,
+            peak_cpu_at: clock.to_string(),
+            cpu_total: 0.0,
+            peak_memory_mb: 0.0,
+            peak_memory_at: clock.to_string(),
+            memory_total: 0.0,
+        }
+    }
+
+    fn record(&mut self, process: &ProcessInfo, clock: &str) {
+        self.start_time = self.start_time.or(process.start_time);
+        self.samples += 1;
+        self.cpu_total += process.cpu_usage as f64;
+        self.memory_total += process.memory_mb;
+        if process.cpu_usage > self.peak_cpu {
+            self.peak_cpu = process.cpu_usage;
+            self.peak_cpu_at = clock.to_string();
+        }
+        if process.memory_mb > self.peak_memory_mb {
+            self.peak_memory_mb = process.memory_mb;
+            self.peak_memory_at = clock.to_string();
+        }
+    }
+
+    /// Average CPU, as a percent of all cores.
+    pub fn average_cpu(&self) -> f32 {
+        (self.cpu_total / self.samples.max(1) as f64) as f32
+    }
+
+    pub fn average_memory_mb(&self) -> f64 {
+        self.memory_total / self.samples.max(1) as f64
+    }
+}
+
+/// Session-long usage of every running process, sampled with the process metrics so
+/// spikes that come and go between looks at the list are still on record.
+#[derive(Debug, Default)]
+pub struct SessionUsage {
+    by_pid: HashMap<u32, UsageStats>,
+}
+
+impl SessionUsage {
+    /// Adds one metrics sample of the running processes.
+    pub fn record<'a>(&mut self, processes: impl Iterator<Item = &'a ProcessInfo>, clock: &str) {
+        for process in processes {
+            // Without a working set the process couldn't be read, so there's nothing to add
+            if process.memory_mb <= 0.0 {
+                continue;
+            }
+            let stats = self
+                .by_pid
+                .entry(process.pid)
+                .or_insert_with(|| UsageStats::new(process, clock));
+            if stats.start_time.is_some()
+                && process.start_time.is_some()
+                && stats.start_time != process.start_time
+            {
+                // A reused PID starts over
+                *stats = UsageStats::new(process, clock);
+            }
+            stats.record(process, clock);
+        }
+    }
+
+    /// Forgets processes `keep` turns down.
+    pub fn retain(&mut self, keep: impl Fn(u32) -> bool) {
+        self.by_pid.retain(|pid, _| keep(*pid));
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&UsageStats> {
+        self.by_pid.get(&pid)
+    }
+}
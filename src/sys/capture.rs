@@ -0,0 +1,252 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Instant;
+
+use crate::error::{Context, Result};
+
+// Name of the pktmon filter Aperture adds
+const FILTER_NAME: &str = "Aperture";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureTool {
+    /// Packet Monitor, built into Windows 10 2004 and later. Filters on the full 5-tuple
+    /// and converts its trace to pcapng for Wireshark.
+    Pktmon,
+    /// `netsh trace`, for older systems. Filters on one address and the protocol only.
+    NetshTrace,
+}
+
+impl CaptureTool {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CaptureTool::Pktmon => "pktmon",
+            CaptureTool::NetshTrace => "netsh trace",
+        }
+    }
+
+    /// pktmon where it is installed, netsh trace otherwise.
+    pub fn detect() -> Self {
+        let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".into());
+        if PathBuf::from(system_root).join("System32").join("PktMon.exe").exists() {
+            CaptureTool::Pktmon
+        } else {
+            CaptureTool::NetshTrace
+        }
+    }
+}
+
+/// The endpoints of the connection to capture.
+#[derive(Debug, Clone)]
+pub struct CaptureTarget {
+    /// "TCP" or "UDP"; the v6 variants are folded in since the addresses carry the family.
+    pub protocol: &'static str,
+    pub local_addr: String,
+    pub local_port: u16,
+    /// None for sockets without a peer, i.e. listeners and UDP.
+    pub remote: Option<(String, u16)>,
+}
+
+impl CaptureTarget {
+    pub fn describe(&self) -> String {
+        match &self.remote {
+            Some((addr, port)) => format!(
+                "{} {}:{} <-> {}:{}",
+                self.protocol, self.local_addr, self.local_port, addr, port
+            ),
+            None => format!("{} {}:{}", self.protocol, self.local_addr, self.local_port),
+        }
+    }
+}
+
+/// Wildcard binds match every local address, so they're left out of the filter.
+fn is_unspecified(addr: &str) -> bool {
+    matches!(addr, "0.0.0.0" | "::" | "*")
+}
+
+/// A capture started by Aperture. Only one runs at a time, as pktmon allows one session.
+#[derive(Debug, Clone)]
+pub struct Capture {
+    pub tool: CaptureTool,
+    pub target: CaptureTarget,
+    /// Trace file the tool writes.
+    pub etl_path: PathBuf,
+    pub started: Instant,
+}
+
+impl Capture {
+    pub fn new(tool: CaptureTool, target: CaptureTarget, etl_path: PathBuf) -> Self {
+        Self {
+            tool,
+            target,
+            etl_path,
+            started: Instant::now(),
+        }
+    }
+
+    /// File to open once the capture stopped: the pcapng pktmon converted the trace to, or
+    /// the raw trace if there is none (netsh, or a failed conversion).
+    pub fn output_path(&self) -> PathBuf {
+        let pcapng = self.etl_path.with_extension("pcapng");
+        if self.tool == CaptureTool::Pktmon && pcapng.exists() {
+            pcapng
+        } else {
+            self.etl_path.clone()
+        }
+    }
+
+    /// Command lines that start the capture, in order.
+    pub fn start_commands(&self) -> Vec<Vec<String>> {
+        let target = &self.target;
+        let etl = self.etl_path.to_string_lossy().to_string();
+        match self.tool {
+            CaptureTool::Pktmon => {
+                let mut filter =
+                    args(&["pktmon", "filter", "add", FILTER_NAME, "-t", target.protocol]);
+                let mut addrs = Vec::new();
+                if !is_unspecified(&target.local_addr) {
+                    addrs.push(target.local_addr.clone());
+                }
+                let mut ports = vec![target.local_port.to_string()];
+                if let Some((addr, port)) = &target.remote {
+                    addrs.push(addr.clone());
+                    ports.push(port.to_string());
+                }
+                // With two addresses and ports pktmon matches traffic between the pair
+                // in either direction
+                if !addrs.is_empty() {
+                    filter.push("-i".to_string());
+                    filter.extend(addrs);
+                }
+                filter.push("-p".to_string());
+                filter.extend(ports);
+                vec![
+                    // pktmon can't remove filters by name, so this clears any left over;
+                    // `start` makes sure no one else's are set
+                    filter_remove(),
+                    filter,
+                    args(&["pktmon", "start", "--capture", "--pkt-size", "0", "--file-name", &etl]),
+                ]
+            }
+            CaptureTool::NetshTrace => {
+                // netsh filters on one address; the peer says more than a local wildcard
+                let addr = match &target.remote {
+                    Some((addr, _)) => addr.clone(),
+                    None => target.local_addr.clone(),
+                };
+                let family = if addr.contains(':') { "IPv6" } else { "IPv4" };
+                let protocol = if target.protocol == "TCP" { 6 } else { 17 };
+                let mut command = args(&["netsh", "trace", "start", "capture=yes"]);
+                if !is_unspecified(&addr) {
+                    command.push(format!("{}.Address={}", family, addr));
+                }
+                command.extend([
+                    format!("Protocol={}", protocol),
+                    format!("tracefile={}", etl),
+                    "report=disabled".to_string(),
+                    "persistent=no".to_string(),
+                    "maxsize=1024".to_string(),
+                    "overwrite=yes".to_string(),
+                ]);
+                vec![command]
+            }
+        }
+    }
+
+    /// Command lines that stop the capture and convert its trace, in order.
+    pub fn stop_commands(&self) -> Vec<Vec<String>> {
+        match self.tool {
+            CaptureTool::Pktmon => {
+                let etl = self.etl_path.to_string_lossy().to_string();
+                let pcapng = self.etl_path.with_extension("pcapng");
+                vec![
+                    args(&["pktmon", "stop"]),
+                    args(&["pktmon", "etl2pcap", &etl, "--out", &pcapng.to_string_lossy()]),
+                    filter_remove(),
+                ]
+            }
+            CaptureTool::NetshTrace => vec![args(&["netsh", "trace", "stop"])],
+        }
+    }
+}
+
+fn args(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|part| part.to_string()).collect()
+}
+
+/// Clears every pktmon packet filter; it can't remove them by name.
+fn filter_remove() -> Vec<String> {
+    args(&["pktmon", "filter", "remove"])
+}
+
+/// Names of the pktmon packet filters set by anyone but Aperture, from the rows of
+/// `pktmon filter list`, which start with the filter's number and name.
+fn other_pktmon_filters() -> Result<Vec<String>> {
+    let list = output(&args(&["pktmon", "filter", "list"]))?;
+    Ok(list
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            words.next()?.parse::<u32>().ok()?;
+            words.next().map(str::to_string)
+        })
+        .filter(|name| name != FILTER_NAME)
+        .collect())
+}
+
+fn run(command: &[String]) -> Result<()> {
+    output(command).map(|_| ())
+}
+
+/// Runs `command` and returns what it printed, failing when it exits with an error.
+fn output(command: &[String]) -> Result<String> {
+    let (program, rest) = command.split_first().ok_or("Empty capture command")?;
+    let output = Command::new(program)
+        .args(rest)
+        .output()
+        .context(format!("Failed to run {}", program))?;
+    if !output.status.success() {
+        // Both tools report errors on stdout
+        let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        return Err(format!("`{}` failed: {}", command.join(" "), text.trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Starts `capture`. Needs admin. A pktmon capture is refused while filters Aperture
+/// didn't add are set, as setting up its own clears them.
+pub fn start(capture: &Capture) -> Result<()> {
+    if capture.tool == CaptureTool::Pktmon {
+        let others = other_pktmon_filters()?;
+        if !others.is_empty() {
+            return Err(format!(
+                "pktmon has packet filters set ({}); remove them with `pktmon filter remove` \
+                 before capturing here, or capture with pktmon directly",
+                others.join(", ")
+            )
+            .into());
+        }
+    }
+    for command in capture.start_commands() {
+        run(&command)?;
+    }
+    Ok(())
+}
+
+/// Stops `capture` and converts its trace. A failed pcapng conversion leaves the .etl,
+/// which Microsoft Network Monitor and `pktmon etl2pcap` can still read.
+pub fn stop(capture: &Capture) -> Result<()> {
+    let commands = capture.stop_commands();
+    let (stop, cleanup) = commands.split_first().ok_or("Empty capture command")?;
+    run(stop)?;
+    for command in cleanup {
+        // Filters someone added during the capture stay, and Aperture's with them
+        if *command == filter_remove()
+            && !other_pktmon_filters().is_ok_and(|others| others.is_empty())
+        {
+            continue;
+        }
+        let _ = run(command);
+    }
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use windows::core::{s, w, PCWSTR, PWSTR};
+use windows::Win32::NetworkManagement::Dns::{
+    DnsFree, DnsQuery_W, DnsFreeFlat, DnsFreeRecordList, DNS_QUERY_NO_WIRE_QUERY,
+    DNS_QUERY_STANDARD, DNS_RECORDA, DNS_RECORDW, DNS_TYPE, DNS_TYPE_A, DNS_TYPE_AAAA,
+    DNS_TYPE_CNAME, DNS_TYPE_PTR,
+};
+use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+use crate::error::{Context, Result};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DnsCacheEntry {
+    pub name: String,
+    pub record_type: String,
+    pub data: Vec<String>,
+    pub ttl: Option<u32>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct HostsEntry {
+    pub address: String,
+    pub hostname: String,
+}
+
+// Layout of the undocumented DNS_CACHE_ENTRY returned by DnsGetCacheDataTable
+#[repr(C)]
+struct RawCacheEntry {
+    next: *mut RawCacheEntry,
+    name: PWSTR,
+    record_type: u16,
+    data_length: u16,
+    flags: u32,
+}
+
+type DnsGetCacheDataTableFn = unsafe extern "system" fn(*mut *mut RawCacheEntry) -> i32;
+type DnsFlushResolverCacheFn = unsafe extern "system" fn() -> i32;
+
+fn record_type_to_string(record_type: u16) -> String {
+    match record_type {
+        1 => "A".to_string(),
+        2 => "NS".to_string(),
+        5 => "CNAME".to_string(),
+        6 => "SOA".to_string(),
+        12 => "PTR".to_string(),
+        15 => "MX".to_string(),
+        16 => "TXT".to_string(),
+        28 => "AAAA".to_string(),
+        33 => "SRV".to_string(),
+        65 => "HTTPS".to_string(),
+        _ => format!("TYPE{}", record_type),
+    }
+}
+
+/// Reads the cached answer for a name without sending a query on the wire.
+unsafe fn query_cached_records(name: &str, record_type: u16) -> (Vec<String>, Option<u32>) {
+    unsafe {
+        let wide_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut results: *mut DNS_RECORDA = std::ptr::null_mut();
+
+        let status = DnsQuery_W(
+            PCWSTR(wide_name.as_ptr()),
+            DNS_TYPE(record_type),
+            DNS_QUERY_NO_WIRE_QUERY,
+            None,
+            &mut results,
+            None,
+        );
+        if status.0 != 0 || results.is_null() {
+            return (Vec::new(), None);
+        }
+
+        let mut data = Vec::new();
+        let mut ttl = None;
+        let mut record = results as *const DNS_RECORDW;
+        while !record.is_null() {
+            let r = &*record;
+            ttl.get_or_insert(r.dwTtl);
+            if r.wType == DNS_TYPE_A.0 {
+                data.push(Ipv4Addr::from(r.Data.A.IpAddress.to_ne_bytes()).to_string());
+            } else if r.wType == DNS_TYPE_AAAA.0 {
+                data.push(Ipv6Addr::from(r.Data.AAAA.Ip6Address.IP6Byte).to_string());
+            } else if r.wType == DNS_TYPE_CNAME.0 {
+                data.push(r.Data.CNAME.pNameHost.to_string().unwrap_or_default());
+            }
+            record = r.pNext;
+        }
+
+        DnsFree(Some(results as *const _), DnsFreeRecordList);
+        (data, ttl)
+    }
+}
+
+/// Lists the Windows DNS client (resolver) cache.
+/// DnsGetCacheDataTable is exported by dnsapi.dll but not documented, so it is
+/// resolved at runtime; record data comes from a cache-only DnsQuery_W per entry.
+pub fn enumerate_dns_cache() -> Result<Vec<DnsCacheEntry>> {
+    let mut entries = Vec::new();
+
+    unsafe {
+        let module = LoadLibraryW(w!("dnsapi.dll")).context("LoadLibraryW(dnsapi.dll)")?;
+        let proc = GetProcAddress(module, s!("DnsGetCacheDataTable"))
+            .ok_or("DnsGetCacheDataTable is not available")?;
+        let get_cache_table: DnsGetCacheDataTableFn = std::mem::transmute(proc);
+
+        let mut head: *mut RawCacheEntry = std::ptr::null_mut();
+        if get_cache_table(&mut head) == 0 {
+            // An empty cache also reports failure
+            return Ok(entries);
+        }
+
+        let mut entry = head;
+        while !entry.is_null() {
+            let next = (*entry).next;
+            let name = (*entry).name.to_string().unwrap_or_default();
+            let record_type = (*entry).record_type;
+
+            let (data, ttl) = query_cached_records(&name, record_type);
+            entries.push(DnsCacheEntry {
+                name,
+                record_type: record_type_to_string(record_type),
+                data,
+                ttl,
+            });
+
+            DnsFree(Some((*entry).name.0 as *const _), DnsFreeFlat);
+            DnsFree(Some(entry as *const _), DnsFreeFlat);
+            entry = next;
+        }
+    }
+
+    entries.sort_by(|a, b| a.name.cmp(&b.name).then(a.record_type.cmp(&b.record_type)));
+    Ok(entries)
+}
+
+/// Reverse-resolves an IP address through a PTR query. Returns None when the address
+/// has no PTR record or the query fails. This goes to the network, so call it off the
+/// UI thread.
+pub fn reverse_lookup(ip: &str) -> Option<String> {
+    let name = match ip.parse::<IpAddr>().ok()? {
+        IpAddr::V4(v4) => {
+            let [a, b, c, d] = v4.octets();
+            format!("{}.{}.{}.{}.in-addr.arpa", d, c, b, a)
+        }
+        IpAddr::V6(v6) => {
+            let mut name = String::new();
+            for byte in v6.octets().iter().rev() {
+                name.push_str(&format!("{:x}.{:x}.", byte & 0xf, byte >> 4));
+            }
+            name.push_str("ip6.arpa");
+            name
+        }
+    };
+
+    unsafe {
+        let wide_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut results: *mut DNS_RECORDA = std::ptr::null_mut();
+
+        let status = DnsQuery_W(
+            PCWSTR(wide_name.as_ptr()),
+            DNS_TYPE_PTR,
+            DNS_QUERY_STANDARD,
+            None,
+            &mut results,
+            None,
+        );
+        if status.0 != 0 || results.is_null() {
+            return None;
+        }
+
+        let mut host = None;
+        let mut record = results as *const DNS_RECORDW;
+        while !record.is_null() {
+            let r = &*record;
+            if r.wType == DNS_TYPE_PTR.0 {
+                host = r.Data.PTR.pNameHost.to_string().ok();
+                break;
+            }
+            record = r.pNext;
+        }
+
+        DnsFree(Some(results as *const _), DnsFreeRecordList);
+        host
+    }
+}
+
+/// Clears the resolver cache, same as `ipconfig /flushdns`.
+pub fn flush_dns_cache() -> Result<()> {
+    unsafe {
+        let module = LoadLibraryW(w!("dnsapi.dll")).context("LoadLibraryW(dnsapi.dll)")?;
+        let proc = GetProcAddress(module, s!("DnsFlushResolverCache"))
+            .ok_or("DnsFlushResolverCache is not available")?;
+        let flush: DnsFlushResolverCacheFn = std::mem::transmute(proc);
+
+        if flush() == 0 {
+            return Err("DnsFlushResolverCache failed".into());
+        }
+    }
+    Ok(())
+}
+
+/// Parses the hosts file into address/hostname pairs, one entry per hostname.
+pub fn read_hosts_file() -> Result<Vec<HostsEntry>> {
+    let system_root = std::env::var("SystemRoot").unwrap_or_else(|_| "C:\\Windows".to_string());
+    let path = std::path::Path::new(&system_root)
+        .join("System32")
+        .join("drivers")
+        .join("etc")
+        .join("hosts");
+
+    let contents = std::fs::read_to_string(&path).context(path.display().to_string())?;
+    let mut entries = Vec::new();
+
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        let mut fields = line.split_whitespace();
+        if let Some(address) = fields.next() {
+            for hostname in fields {
+                entries.push(HostsEntry {
+                    address: address.to_string(),
+                    hostname: hostname.to_lowercase(),
+                });
+            }
+        }
+    }
+
+    Ok(entries)
+}
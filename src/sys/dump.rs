@@ -0,0 +1,82 @@
+use std::fs::File;
+use std::os::windows::io::AsRawHandle;
+use std::path::Path;
+
+use windows::Win32::Foundation::{CloseHandle, HANDLE};
+use windows::Win32::System::Diagnostics::Debug::{
+    MiniDumpNormal, MiniDumpWithFullMemory, MiniDumpWithFullMemoryInfo, MiniDumpWithHandleData,
+    MiniDumpWithThreadInfo, MiniDumpWithUnloadedModules, MiniDumpWriteDump, MINIDUMP_TYPE,
+};
+use windows::Win32::System::Threading::{
+    OpenProcess, PROCESS_DUP_HANDLE, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+};
+
+use crate::error::{Context, Result};
+
+/// How much of a process a dump captures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpType {
+    /// Thread stacks, modules and handles - small, but enough for a stack trace.
+    Mini,
+    /// The whole address space as well, like Task Manager's "Create dump file".
+    #[default]
+    Full,
+}
+
+impl DumpType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DumpType::Mini => "mini",
+            DumpType::Full => "full",
+        }
+    }
+
+    fn flags(&self) -> MINIDUMP_TYPE {
+        let common = MiniDumpWithHandleData | MiniDumpWithThreadInfo | MiniDumpWithUnloadedModules;
+        match self {
+            DumpType::Mini => MiniDumpNormal | common,
+            DumpType::Full => MiniDumpWithFullMemory | MiniDumpWithFullMemoryInfo | common,
+        }
+    }
+}
+
+/// Writes a dump of `pid` to `path` with MiniDumpWriteDump. A partly written file is
+/// removed on failure so it can't be mistaken for a usable dump.
+pub fn write_dump(pid: u32, path: &Path, dump_type: DumpType) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).context(format!("Creating {}", dir.display()))?;
+    }
+
+    let process = unsafe {
+        OpenProcess(
+            PROCESS_QUERY_INFORMATION | PROCESS_VM_READ | PROCESS_DUP_HANDLE,
+            false,
+            pid,
+        )
+    }
+    .context(format!("OpenProcess(PID {})", pid))?;
+
+    let result = File::create(path)
+        .context(format!("Creating {}", path.display()))
+        .and_then(|file| unsafe {
+            MiniDumpWriteDump(
+                process,
+                pid,
+                HANDLE(file.as_raw_handle()),
+                dump_type.flags(),
+                None,
+                None,
+                None,
+            )
+            .context(format!("MiniDumpWriteDump(PID {})", pid))
+        });
+    unsafe {
+        let _ = CloseHandle(process);
+    }
+
+    if result.is_err() {
+        let _ = std::fs::remove_file(path);
+    }
+    result
+}
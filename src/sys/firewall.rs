@@ -0,0 +1,286 @@
+use windows::core::Interface;
+use windows::Win32::NetworkManagement::WindowsFirewall::{
+    INetFwPolicy2, INetFwRule, NetFwPolicy2, NET_FW_ACTION_ALLOW, NET_FW_ACTION_BLOCK,
+    NET_FW_IP_PROTOCOL_ANY, NET_FW_PROFILE_TYPE2, NET_FW_RULE_DIR_IN,
+};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+};
+use windows::Win32::System::Ole::IEnumVARIANT;
+
+use crate::error::{Context, Result};
+
+// NET_FW_PROFILE_TYPE2 bits
+const PROFILES: [i32; 3] = [1, 2, 4];
+
+/// An enabled inbound rule, reduced to the fields a listening socket is matched on.
+#[derive(Debug, Clone)]
+struct InboundRule {
+    name: String,
+    allow: bool,
+    profiles: i32,
+    /// IANA protocol number, 256 for any.
+    protocol: i32,
+    local_ports: String,
+    /// Lowercase program path with environment variables expanded; None for any program.
+    application: Option<String>,
+    /// Lowercase service short name; None for any.
+    service: Option<String>,
+}
+
+/// The parts of the Windows Firewall policy that decide inbound traffic, for the
+/// currently active profiles.
+#[derive(Debug, Clone)]
+struct FirewallPolicy {
+    enabled: bool,
+    block_all: bool,
+    default_block: bool,
+    profiles: i32,
+    rules: Vec<InboundRule>,
+}
+
+/// Whether inbound traffic to a listening socket gets through, and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FirewallCheck {
+    pub allowed: bool,
+    /// Matching rule name, or which policy setting decided.
+    pub reason: String,
+}
+
+/// A listening socket to check.
+#[derive(Debug, Clone)]
+pub struct Listener {
+    pub protocol: String,
+    pub port: u16,
+    pub pid: u32,
+    pub service: Option<String>,
+}
+
+/// Replaces `%NAME%` with the environment variable, as rule program paths use them.
+fn expand_env(path: &str) -> String {
+    let mut expanded = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('%') {
+        let Some(len) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + len];
+        expanded.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(value) => expanded.push_str(&value),
+            Err(_) => expanded.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Whether a rule's LocalPorts list covers `port`. Keywords for dynamic ports (RPC,
+/// IPHTTPS, Teredo, ...) aren't resolved and never match.
+fn ports_match(local_ports: &str, port: u16) -> bool {
+    local_ports.split(',').map(str::trim).any(|part| {
+        if part == "*" {
+            return true;
+        }
+        if part.eq_ignore_ascii_case("RPC-EPMap") {
+            return port == 135;
+        }
+        match part.split_once('-') {
+            Some((low, high)) => match (low.parse::<u16>(), high.parse::<u16>()) {
+                (Ok(low), Ok(high)) => (low..=high).contains(&port),
+                _ => false,
+            },
+            None => part.parse::<u16>() == Ok(port),
+        }
+    })
+}
+
+fn protocol_number(protocol: &str) -> i32 {
+    if protocol.starts_with("UDP") { 17 } else { 6 }
+}
+
+impl InboundRule {
+    fn matches(&self, listener: &Listener, path: Option<&str>, profiles: i32) -> bool {
+        if self.profiles & profiles == 0 {
+            return false;
+        }
+        if self.protocol != NET_FW_IP_PROTOCOL_ANY.0
+            && self.protocol != protocol_number(&listener.protocol)
+        {
+            return false;
+        }
+        if !ports_match(&self.local_ports, listener.port) {
+            return false;
+        }
+        if let Some(application) = &self.application
+            && path.is_none_or(|path| !path.eq_ignore_ascii_case(application))
+        {
+            return false;
+        }
+        if let Some(service) = &self.service
+            && listener
+                .service
+                .as_deref()
+                .is_none_or(|name| !name.eq_ignore_ascii_case(service))
+        {
+            return false;
+        }
+        true
+    }
+}
+
+impl FirewallPolicy {
+    /// Decides a listener the way the firewall does: block rules win over allow rules,
+    /// and the default inbound action applies when no rule matches. Local address and
+    /// interface restrictions of rules are ignored.
+    fn check(&self, listener: &Listener, path: Option<&str>) -> FirewallCheck {
+        let decided = |allowed: bool, reason: &str| FirewallCheck {
+            allowed,
+            reason: reason.to_string(),
+        };
+        if !self.enabled {
+            return decided(true, "Firewall off");
+        }
+        if self.block_all {
+            return decided(false, "All inbound traffic blocked");
+        }
+        let matching = || {
+            self.rules
+                .iter()
+                .filter(|rule| rule.matches(listener, path, self.profiles))
+        };
+        if let Some(rule) = matching().find(|rule| !rule.allow) {
+            return decided(false, &rule.name);
+        }
+        if let Some(rule) = matching().find(|rule| rule.allow) {
+            return decided(true, &rule.name);
+        }
+        if self.default_block {
+            decided(false, "No allow rule")
+        } else {
+            decided(true, "Default inbound action allows")
+        }
+    }
+}
+
+fn optional(value: String) -> Option<String> {
+    let value = value.trim().to_string();
+    (!value.is_empty() && value != "*").then_some(value)
+}
+
+unsafe fn read_rule(rule: &INetFwRule) -> Option<InboundRule> {
+    unsafe {
+        if !rule.Enabled().ok()?.as_bool() || rule.Direction().ok()? != NET_FW_RULE_DIR_IN {
+            return None;
+        }
+        let action = rule.Action().ok()?;
+        if action != NET_FW_ACTION_ALLOW && action != NET_FW_ACTION_BLOCK {
+            return None;
+        }
+        Some(InboundRule {
+            name: rule.Name().map(|s| s.to_string()).unwrap_or_default(),
+            allow: action == NET_FW_ACTION_ALLOW,
+            profiles: rule.Profiles().unwrap_or(0),
+            protocol: rule.Protocol().unwrap_or(NET_FW_IP_PROTOCOL_ANY.0),
+            local_ports: rule
+                .LocalPorts()
+                .map(|s| s.to_string())
+                .unwrap_or_else(|_| "*".to_string()),
+            application: rule
+                .ApplicationName()
+                .ok()
+                .and_then(|s| optional(s.to_string()))
+                .map(|path| expand_env(&path).to_lowercase()),
+            service: rule
+                .ServiceName()
+                .ok()
+                .and_then(|s| optional(s.to_string()))
+                .map(|name| name.to_lowercase()),
+        })
+    }
+}
+
+unsafe fn read_policy() -> Result<FirewallPolicy> {
+    unsafe {
+        let policy: INetFwPolicy2 = CoCreateInstance(&NetFwPolicy2, None, CLSCTX_INPROC_SERVER)
+            .context("CoCreateInstance(NetFwPolicy2)")?;
+        let profiles = policy.CurrentProfileTypes().context("CurrentProfileTypes")?;
+
+        // Several profiles are active when adapters sit on different network types; take
+        // the strictest so an indicator never claims more than the firewall allows
+        let mut enabled = false;
+        let mut block_all = false;
+        let mut default_block = false;
+        for profile in PROFILES.iter().filter(|&&p| profiles & p != 0) {
+            let profile = NET_FW_PROFILE_TYPE2(*profile);
+            if !policy.get_FirewallEnabled(profile).is_ok_and(|on| on.as_bool()) {
+                continue;
+            }
+            enabled = true;
+            block_all |= policy.get_BlockAllInboundTraffic(profile).is_ok_and(|b| b.as_bool());
+            default_block |= policy
+                .get_DefaultInboundAction(profile)
+                .is_ok_and(|action| action == NET_FW_ACTION_BLOCK);
+        }
+
+        let mut rules = Vec::new();
+        let enumerator: IEnumVARIANT = policy
+            .Rules()
+            .and_then(|rules| rules._NewEnum())
+            .and_then(|unknown| unknown.cast())
+            .context("INetFwRules::_NewEnum")?;
+        loop {
+            let mut item = [windows::core::VARIANT::default()];
+            let mut fetched = 0u32;
+            if enumerator.Next(&mut item, &mut fetched).is_err() || fetched == 0 {
+                break;
+            }
+            let rule = windows::core::IUnknown::try_from(&item[0])
+                .and_then(|unknown| unknown.cast::<INetFwRule>());
+            if let Ok(rule) = rule
+                && let Some(rule) = read_rule(&rule)
+            {
+                rules.push(rule);
+            }
+        }
+
+        Ok(FirewallPolicy {
+            enabled,
+            block_all,
+            default_block,
+            profiles,
+            rules,
+        })
+    }
+}
+
+/// Reads the active firewall policy through the INetFwPolicy2 COM API. Runs on its own
+/// thread, which is initialized for COM here.
+fn load_policy() -> Result<FirewallPolicy> {
+    unsafe {
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let policy = read_policy();
+        if initialized {
+            CoUninitialize();
+        }
+        policy
+    }
+}
+
+/// Checks each listener against the current policy, resolving owning programs here.
+pub fn check_listeners(listeners: Vec<Listener>) -> Result<Vec<(Listener, FirewallCheck)>> {
+    let policy = load_policy()?;
+    let mut paths = std::collections::HashMap::new();
+    Ok(listeners
+        .into_iter()
+        .map(|listener| {
+            let path = paths
+                .entry(listener.pid)
+                .or_insert_with(|| super::network::get_process_path(listener.pid))
+                .clone();
+            let check = policy.check(&listener, path.as_deref());
+            (listener, check)
+        })
+        .collect())
+}
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use windows::core::{w, PCWSTR};
+use windows::Win32::System::Performance::{
+    PdhAddEnglishCounterW, PdhCollectQueryData, PdhGetFormattedCounterArrayW, PdhOpenQueryW,
+    PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE, PDH_MORE_DATA,
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GpuUsage {
+    pub utilization: f32,
+    pub dedicated_mb: f64,
+}
+
+struct GpuQuery {
+    query: isize,
+    engine_counter: isize,
+    memory_counter: isize,
+}
+
+// None once we know the GPU counters aren't available (no WDDM 2.x driver, PDH disabled, etc.)
+static GPU_QUERY: OnceLock<Mutex<Option<GpuQuery>>> = OnceLock::new();
+
+fn open_query() -> Option<GpuQuery> {
+    unsafe {
+        let mut query = 0isize;
+        if PdhOpenQueryW(PCWSTR::null(), 0, &mut query) != 0 {
+            return None;
+        }
+
+        let mut engine_counter = 0isize;
+        let mut memory_counter = 0isize;
+        if PdhAddEnglishCounterW(
+            query,
+            w!("\\GPU Engine(*)\\Utilization Percentage"),
+            0,
+            &mut engine_counter,
+        ) != 0
+            || PdhAddEnglishCounterW(
+                query,
+                w!("\\GPU Process Memory(*)\\Dedicated Usage"),
+                0,
+                &mut memory_counter,
+            ) != 0
+        {
+            return None;
+        }
+
+        // Rate counters need a baseline sample before the first read
+        let _ = PdhCollectQueryData(query);
+
+        Some(GpuQuery {
+            query,
+            engine_counter,
+            memory_counter,
+        })
+    }
+}
+
+/// Extracts the PID from a GPU counter instance name such as
+/// "pid_1234_luid_0x00000000_0x0000C2A1_phys_0_eng_0_engtype_3D".
+fn parse_instance_pid(instance: &str) -> Option<u32> {
+    instance
+        .strip_prefix("pid_")?
+        .split('_')
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Extracts the engine type ("3D", "VideoDecode", "Copy", ...) from a GPU Engine instance name.
+fn parse_engine_type(instance: &str) -> &str {
+    instance
+        .rsplit_once("engtype_")
+        .map(|(_, t)| t)
+        .unwrap_or("")
+}
+
+unsafe fn read_counter_array(counter: isize) -> Vec<(String, f64)> {
+    unsafe {
+        let mut buffer_size = 0u32;
+        let mut item_count = 0u32;
+        let result =
+            PdhGetFormattedCounterArrayW(counter, PDH_FMT_DOUBLE, &mut buffer_size, &mut item_count, None);
+        if result != PDH_MORE_DATA || buffer_size == 0 {
+            return Vec::new();
+        }
+
+        // The buffer holds the item array followed by the instance name strings
+        let item_size = std::mem::size_of::<PDH_FMT_COUNTERVALUE_ITEM_W>();
+        let mut items: Vec<PDH_FMT_COUNTERVALUE_ITEM_W> =
+            vec![PDH_FMT_COUNTERVALUE_ITEM_W::default(); (buffer_size as usize).div_ceil(item_size)];
+
+        if PdhGetFormattedCounterArrayW(
+            counter,
+            PDH_FMT_DOUBLE,
+            &mut buffer_size,
+            &mut item_count,
+            Some(items.as_mut_ptr()),
+        ) != 0
+        {
+            return Vec::new();
+        }
+
+        items
+            .iter()
+            .take(item_count as usize)
+            .filter(|item| item.FmtValue.CStatus == 0)
+            .map(|item| {
+                let name = item.szName.to_string().unwrap_or_default();
+                (name, item.FmtValue.Anonymous.doubleValue)
+            })
+            .collect()
+    }
+}
+
+/// Samples per-process GPU utilization and dedicated GPU memory from the PDH
+/// "GPU Engine" and "GPU Process Memory" counters.
+/// Utilization follows Task Manager: engines of the same type are summed and the
+/// busiest engine type is reported.
+pub fn sample_gpu_usage() -> HashMap<u32, GpuUsage> {
+    let mut usage: HashMap<u32, GpuUsage> = HashMap::new();
+
+    let query_lock = GPU_QUERY.get_or_init(|| Mutex::new(open_query()));
+    let guard = query_lock.lock().unwrap();
+    let Some(gpu) = guard.as_ref() else {
+        return usage;
+    };
+
+    unsafe {
+        if PdhCollectQueryData(gpu.query) != 0 {
+            return usage;
+        }
+
+        let mut per_engine_type: HashMap<(u32, String), f64> = HashMap::new();
+        for (instance, value) in read_counter_array(gpu.engine_counter) {
+            if let Some(pid) = parse_instance_pid(&instance) {
+                *per_engine_type
+                    .entry((pid, parse_engine_type(&instance).to_string()))
+                    .or_default() += value;
+            }
+        }
+        for ((pid, _), value) in per_engine_type {
+            let entry = usage.entry(pid).or_default();
+            entry.utilization = entry.utilization.max(value.clamp(0.0, 100.0) as f32);
+        }
+
+        for (instance, value) in read_counter_array(gpu.memory_counter) {
+            if let Some(pid) = parse_instance_pid(&instance) {
+                usage.entry(pid).or_default().dedicated_mb += value / (1024.0 * 1024.0);
+            }
+        }
+    }
+
+    usage
+}
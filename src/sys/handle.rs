@@ -1,19 +1,72 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use windows::core::PCWSTR;
+use std::time::{Duration, Instant};
+use windows::core::{PCWSTR, PWSTR};
+use windows::Wdk::Foundation::{NtQueryObject, OBJECT_INFORMATION_CLASS, OBJECT_NAME_INFORMATION};
+use windows::Win32::Foundation::{
+    CloseHandle, DuplicateHandle, DUPLICATE_CLOSE_SOURCE, DUPLICATE_SAME_ACCESS, FILETIME,
+    HANDLE, HMODULE, NO_ERROR,
+};
+use windows::Win32::NetworkManagement::WNet::WNetGetConnectionW;
+use windows::Win32::Storage::FileSystem::{
+    GetDriveTypeW, GetFileType, GetFinalPathNameByHandleW, FILE_NAME_NORMALIZED,
+    FILE_TYPE_DISK,
+};
+use windows::Win32::System::ProcessStatus::{
+    EnumProcessModulesEx, EnumProcesses, GetModuleFileNameExW, LIST_MODULES_ALL,
+};
+use windows::Win32::System::Registry::{
+    RegCloseKey, RegOpenKeyExW, HKEY, HKEY_CLASSES_ROOT, HKEY_CURRENT_CONFIG, HKEY_CURRENT_USER,
+    HKEY_LOCAL_MACHINE, HKEY_USERS, KEY_READ,
+};
 use windows::Win32::System::RestartManager::{
-    RmEndSession, RmGetList, RmRegisterResources, RmStartSession, RM_APP_STATUS,
-    RM_INVALID_PROCESS, RM_PROCESS_INFO,
+    RmEndSession, RmGetList, RmRegisterResources, RmRestart, RmShutdown, RmStartSession,
+    RM_APP_STATUS, RM_INVALID_PROCESS, RM_PROCESS_INFO, RM_UNIQUE_PROCESS,
 };
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetCurrentProcessId, GetProcessTimes, OpenProcess,
+    QueryFullProcessImageNameW, PROCESS_DUP_HANDLE, PROCESS_NAME_FORMAT,
+    PROCESS_QUERY_INFORMATION, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_VM_READ,
+};
+
+use crate::error::{ApertureError, Context, Result};
 
 #[derive(Debug, Clone)]
 pub struct LockingProcess {
     pub pid: u32,
     pub name: String,
+    /// The searched files it locks.
+    pub files: Vec<String>,
+    /// Handles to them inside the process, for closing them there. Empty when Restart
+    /// Manager found it, which doesn't say.
+    pub handles: Vec<HeldHandle>,
+    /// When the process was created, in FILETIME ticks, to tell it from a later process
+    /// on its PID before closing its handles or killing it. None if it couldn't be read.
+    pub started: Option<u64>,
+}
+
+/// A handle to a searched file or key inside another process, with what it referred to
+/// when found. Handle values are reused once closed, so it is only closed while it still
+/// refers to the same object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeldHandle {
+    pub value: usize,
+    /// The kernel's name for the object type, `File` or `Key`.
+    object_type: String,
+    /// The file's final path, or the key's kernel name.
+    name: String,
 }
 
+/// Longest path that works without the `\\?\` prefix.
+const MAX_PATH: usize = 260;
+/// GetDriveTypeW's answer for a network drive.
+const DRIVE_REMOTE: u32 = 4;
+
 /// Canonicalizes a path for Windows Restart Manager.
-/// Converts to absolute path with proper Windows formatting.
+/// Converts to absolute path with proper Windows formatting: a mapped drive becomes the
+/// `\\server\share` it maps, and the `\\?\` prefix canonicalize() adds is dropped when
+/// the path fits without it, as Restart Manager compares paths with what processes
+/// opened and they rarely open the prefixed form.
 fn canonicalize_path(path: &str) -> Option<String> {
     let path_obj = Path::new(path);
 
@@ -24,24 +77,112 @@ fn canonicalize_path(path: &str) -> Option<String> {
         std::env::current_dir().ok()?.join(path_obj)
     };
 
-    // Clean up the path: normalize . and .. components
-    match absolute.canonicalize() {
-        Ok(canon) => {
-            // canonicalize() adds \\?\ prefix on Windows, which is good for long paths
-            Some(canon.to_string_lossy().to_string())
-        }
-        Err(_) => {
-            // If file doesn't exist, still try to get absolute path
-            Some(absolute.to_string_lossy().to_string())
-        }
+    // Clean up the path: normalize . and .. components. If the file doesn't exist,
+    // still go with the absolute path
+    let canonical = absolute.canonicalize().unwrap_or(absolute);
+    let canonical = strip_verbatim_prefix(&canonical.to_string_lossy());
+    Some(resolve_mapped_drive(&canonical).unwrap_or(canonical))
+}
+
+/// `path` without its `\\?\` or `\\?\UNC\` prefix, unless it's too long to go without.
+fn strip_verbatim_prefix(path: &str) -> String {
+    let stripped = if let Some(share) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", share)
+    } else if let Some(local) = path.strip_prefix(r"\\?\") {
+        local.to_string()
+    } else {
+        return path.to_string();
+    };
+    if stripped.len() < MAX_PATH {
+        stripped
+    } else {
+        path.to_string()
+    }
+}
+
+/// `path` on a mapped network drive rewritten onto the share the drive maps, e.g.
+/// `Z:\docs\a.txt` to `\\fileserver\team\docs\a.txt`. None for any other path.
+fn resolve_mapped_drive(path: &str) -> Option<String> {
+    let drive = path.get(..2).filter(|drive| drive.ends_with(':'))?;
+    if !is_remote_drive(drive) {
+        return None;
+    }
+    let wide_drive = wide(drive);
+    let mut remote = [0u16; 1024];
+    let mut length = remote.len() as u32;
+    let result = unsafe {
+        WNetGetConnectionW(
+            PCWSTR(wide_drive.as_ptr()),
+            PWSTR(remote.as_mut_ptr()),
+            &mut length,
+        )
+    };
+    if result != NO_ERROR {
+        return None;
     }
+    let end = remote.iter().position(|&c| c == 0).unwrap_or(remote.len());
+    let share = String::from_utf16_lossy(&remote[..end]);
+    Some(format!("{}{}", share.trim_end_matches('\\'), &path[2..]))
+}
+
+/// Whether drive `drive` (`Z:`) is a network drive.
+fn is_remote_drive(drive: &str) -> bool {
+    let root = wide(&format!("{}\\", drive));
+    unsafe { GetDriveTypeW(PCWSTR(root.as_ptr())) == DRIVE_REMOTE }
+}
+
+/// Whether `path` is on another machine: a UNC path or one on a mapped drive. Restart
+/// Manager only sees the processes of this machine, not those of the file server or its
+/// other clients.
+pub fn is_network_path(path: &str) -> bool {
+    let path = path.trim();
+    if let Some(verbatim) = path.strip_prefix(r"\\?\") {
+        return verbatim.starts_with(r"UNC\");
+    }
+    if path.starts_with(r"\\.\") {
+        return false;
+    }
+    path.starts_with(r"\\")
+        || path.get(..2).is_some_and(|drive| drive.ends_with(':') && is_remote_drive(drive))
+}
+
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Files registered with one Restart Manager session.
+const RM_CHUNK_SIZE: usize = 1000;
+
+/// Which files of a folder a lock scan checks.
+pub struct ScanLimits<'a> {
+    /// Files checked at most; the scan stops there.
+    pub max_files: usize,
+    /// Whether to skip a file or subfolder, by its path.
+    pub exclude: &'a dyn Fn(&Path) -> bool,
+}
+
+impl ScanLimits<'_> {
+    /// Every file, however many.
+    pub const ALL: ScanLimits<'static> = ScanLimits {
+        max_files: usize::MAX,
+        exclude: &|_| false,
+    };
 }
 
 /// Finds processes that are locking the specified files using Windows Restart Manager API.
 /// This is the official, reliable way to detect file locks on Windows Vista and later.
+/// Each process comes with the files it locks. Registry keys among the paths are looked
+/// up with `find_registry_lockers` instead.
 pub fn find_locking_processes(
-    file_paths: &[&str],
-) -> Result<Vec<LockingProcess>, Box<dyn std::error::Error>> {
+    paths: &[&str],
+) -> Result<Vec<LockingProcess>> {
+    let (keys, file_paths): (Vec<&str>, Vec<&str>) =
+        paths.iter().partition(|path| is_registry_path(path));
+    if !keys.is_empty() {
+        let mut lockers = find_registry_lockers(&keys)?;
+        merge_lockers(&mut lockers, find_locking_processes(&file_paths)?);
+        return Ok(lockers);
+    }
     if file_paths.is_empty() {
         return Ok(Vec::new());
     }
@@ -56,6 +197,69 @@ pub fn find_locking_processes(
         return Ok(Vec::new());
     }
 
+    // One session per chunk: a session slows down with every resource registered, and a
+    // big enough list makes RmRegisterResources fail outright
+    let mut locking_processes: Vec<LockingProcess> = Vec::new();
+    let mut files: HashMap<u32, Vec<String>> = HashMap::new();
+    for chunk in canonical_paths.chunks(RM_CHUNK_SIZE) {
+        let lockers = restart_manager_lockers(chunk).map_err(|e| network_path_error(chunk, e))?;
+        attribute_files(chunk, &lockers, &mut files).map_err(|e| network_path_error(chunk, e))?;
+        for locker in lockers {
+            if !locking_processes.iter().any(|known| known.pid == locker.pid) {
+                locking_processes.push(locker);
+            }
+        }
+    }
+    for process in &mut locking_processes {
+        process.files = files.remove(&process.pid).unwrap_or_default();
+    }
+    locking_processes.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(locking_processes)
+}
+
+/// Says which network path a failed Restart Manager query had, as that's the usual
+/// reason: remote file systems that don't support it make registering or listing fail.
+fn network_path_error(paths: &[String], error: ApertureError) -> ApertureError {
+    match paths.iter().find(|path| is_network_path(path)) {
+        Some(path) => ApertureError::Other(format!(
+            "Restart Manager can't check network path {} ({}). Only processes on this \
+             machine can be found; check the open files on the file server instead",
+            path, error
+        )),
+        None => error,
+    }
+}
+
+/// Works out which of `paths` each of `lockers` holds. Restart Manager only reports the
+/// processes of a whole session, so the paths are halved and each half with lockers
+/// asked again, down to single files: a few sessions per locked file rather than one per
+/// file of a big folder.
+fn attribute_files(
+    paths: &[String],
+    lockers: &[LockingProcess],
+    files: &mut HashMap<u32, Vec<String>>,
+) -> Result<()> {
+    if lockers.is_empty() {
+        return Ok(());
+    }
+    if let [path] = paths {
+        // Drop the \\?\ prefix canonicalize() added
+        let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+        for locker in lockers {
+            files.entry(locker.pid).or_default().push(path.to_string());
+        }
+        return Ok(());
+    }
+    let (left, right) = paths.split_at(paths.len() / 2);
+    for half in [left, right] {
+        let lockers = restart_manager_lockers(half)?;
+        attribute_files(half, &lockers, files)?;
+    }
+    Ok(())
+}
+
+/// Running processes holding any of `canonical_paths`, from one Restart Manager session.
+fn restart_manager_lockers(canonical_paths: &[String]) -> Result<Vec<LockingProcess>> {
     unsafe {
         // Start a Restart Manager session
         let mut session_handle: u32 = 0;
@@ -68,7 +272,7 @@ pub fn find_locking_processes(
         );
 
         if result.0 != 0 {
-            return Err(format!("RmStartSession failed with error {}", result.0).into());
+            return Err(ApertureError::win32("RmStartSession", result.0));
         }
 
         // Prepare file paths as wide strings
@@ -93,7 +297,7 @@ pub fn find_locking_processes(
 
         if result.0 != 0 {
             let _ = RmEndSession(session_handle);
-            return Err(format!("RmRegisterResources failed with error {}", result.0).into());
+            return Err(ApertureError::win32("RmRegisterResources", result.0));
         }
 
         // Get the list of processes that are using these resources
@@ -114,7 +318,7 @@ pub fn find_locking_processes(
         if result.0 != 0 && result.0 != 234 {
             // 234 = ERROR_MORE_DATA, expected on first call
             let _ = RmEndSession(session_handle);
-            return Err(format!("RmGetList (first call) failed with error {}", result.0).into());
+            return Err(ApertureError::win32("RmGetList (first call)", result.0));
         }
 
         if proc_info_needed == 0 {
@@ -139,7 +343,7 @@ pub fn find_locking_processes(
 
         if result.0 != 0 {
             let _ = RmEndSession(session_handle);
-            return Err(format!("RmGetList (second call) failed with error {}", result.0).into());
+            return Err(ApertureError::win32("RmGetList (second call)", result.0));
         }
 
         // Collect unique processes
@@ -168,7 +372,13 @@ pub fn find_locking_processes(
                     format!("PID {}", pid)
                 };
 
-                locking_processes.push(LockingProcess { pid, name });
+                locking_processes.push(LockingProcess {
+                    pid,
+                    name,
+                    files: Vec::new(),
+                    handles: Vec::new(),
+                    started: Some(filetime_to_u64(info.Process.ProcessStartTime)),
+                });
             }
         }
 
@@ -182,29 +392,659 @@ pub fn find_locking_processes(
     }
 }
 
-/// Finds processes locking files in a directory.
+/// A Restart Manager session over locking processes, kept open after shutting them down
+/// so they can be restarted once their files are free. Dropping it ends the session.
+#[derive(Debug)]
+pub struct RestartSession {
+    handle: u32,
+    /// Names of the processes it covers, for messages.
+    pub names: Vec<String>,
+}
+
+impl RestartSession {
+    /// Starts a session over the given processes. Restart Manager tells processes apart by
+    /// PID and start time, so a PID reused since the search isn't shut down instead.
+    pub fn open(processes: &[LockingProcess]) -> Result<Self> {
+        let applications = processes
+            .iter()
+            .map(|process| {
+                Ok(RM_UNIQUE_PROCESS {
+                    dwProcessId: process.pid,
+                    ProcessStartTime: process_start_time(process.pid)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        unsafe {
+            let mut handle: u32 = 0;
+            let mut session_key = [0u16; 256];
+            let result = RmStartSession(
+                &mut handle,
+                0,
+                windows::core::PWSTR(session_key.as_mut_ptr()),
+            );
+            if result.0 != 0 {
+                return Err(ApertureError::win32("RmStartSession", result.0));
+            }
+            let session = Self {
+                handle,
+                names: processes.iter().map(|process| process.name.clone()).collect(),
+            };
+            let result = RmRegisterResources(handle, None, Some(&applications), None);
+            if result.0 != 0 {
+                return Err(ApertureError::win32("RmRegisterResources", result.0));
+            }
+            Ok(session)
+        }
+    }
+
+    /// Asks the processes to close the way Windows Update does: a close message for
+    /// windows, a stop for services. Fails without killing anything if one refuses.
+    pub fn shutdown(&self) -> Result<()> {
+        let result = unsafe { RmShutdown(self.handle, 0, None) };
+        if result.0 != 0 {
+            return Err(ApertureError::win32("RmShutdown", result.0));
+        }
+        Ok(())
+    }
+
+    /// Starts again what `shutdown` closed. Only applications registered for restart
+    /// with Windows, and services, come back.
+    pub fn restart(&self) -> Result<()> {
+        let result = unsafe { RmRestart(self.handle, 0, None) };
+        if result.0 != 0 {
+            return Err(ApertureError::win32("RmRestart", result.0));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for RestartSession {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = RmEndSession(self.handle);
+        }
+    }
+}
+
+fn process_start_time(pid: u32) -> Result<FILETIME> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)?;
+        let mut creation_time = FILETIME::default();
+        let (mut exit_time, mut kernel_time, mut user_time) =
+            (FILETIME::default(), FILETIME::default(), FILETIME::default());
+        let result = GetProcessTimes(
+            handle,
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        );
+        let _ = CloseHandle(handle);
+        result?;
+        Ok(creation_time)
+    }
+}
+
+// Not in the Win32 headers the windows crate is generated from
+const OBJECT_NAME_INFORMATION_CLASS: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(1);
+const OBJECT_TYPE_INFORMATION_CLASS: OBJECT_INFORMATION_CLASS = OBJECT_INFORMATION_CLASS(2);
+
+fn filetime_to_u64(ft: FILETIME) -> u64 {
+    ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
+}
+
+/// When the process behind `process` was created, in FILETIME ticks.
+unsafe fn start_time(process: HANDLE) -> Option<u64> {
+    let mut creation_time = FILETIME::default();
+    let (mut exit_time, mut kernel_time, mut user_time) =
+        (FILETIME::default(), FILETIME::default(), FILETIME::default());
+    unsafe {
+        GetProcessTimes(
+            process,
+            &mut creation_time,
+            &mut exit_time,
+            &mut kernel_time,
+            &mut user_time,
+        )
+    }
+    .ok()
+    .map(|()| filetime_to_u64(creation_time))
+}
+
+/// The predefined root key a registry path starts with, and the subkey below it.
+fn registry_root(path: &str) -> Option<(HKEY, &str)> {
+    let (root, subkey) = path.split_once('\\').unwrap_or((path, ""));
+    let root = match root.to_ascii_uppercase().as_str() {
+        "HKLM" | "HKEY_LOCAL_MACHINE" => HKEY_LOCAL_MACHINE,
+        "HKCU" | "HKEY_CURRENT_USER" => HKEY_CURRENT_USER,
+        "HKU" | "HKEY_USERS" => HKEY_USERS,
+        "HKCR" | "HKEY_CLASSES_ROOT" => HKEY_CLASSES_ROOT,
+        "HKCC" | "HKEY_CURRENT_CONFIG" => HKEY_CURRENT_CONFIG,
+        _ => return None,
+    };
+    Some((root, subkey.trim_end_matches('\\')))
+}
+
+/// Whether `path` names a registry key, like `HKLM\SOFTWARE\Vendor`, rather than a file.
+pub fn is_registry_path(path: &str) -> bool {
+    registry_root(path.trim()).is_some()
+}
+
+fn open_key(path: &str) -> Result<HKEY> {
+    let (root, subkey) = registry_root(path.trim())
+        .ok_or_else(|| ApertureError::Other(format!("Not a registry key: {}", path)))?;
+    let wide: Vec<u16> = subkey.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut key = HKEY::default();
+    let result = unsafe { RegOpenKeyExW(root, PCWSTR(wide.as_ptr()), 0, KEY_READ, &mut key) };
+    if result.0 != 0 {
+        return Err(ApertureError::win32(format!("RegOpenKeyExW({})", path), result.0));
+    }
+    Ok(key)
+}
+
+/// Whether the file, folder or registry key `path` names is there.
+pub fn path_exists(path: &str) -> bool {
+    if !is_registry_path(path) {
+        return Path::new(path).exists();
+    }
+    match open_key(path) {
+        Ok(key) => {
+            let _ = unsafe { RegCloseKey(key) };
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// The kernel's name for the object behind `handle`, e.g. `\REGISTRY\MACHINE\SOFTWARE`.
+/// Only safe on handles that can't block the query, such as registry keys: a name query
+/// on a synchronous pipe can hang.
+unsafe fn object_name(handle: HANDLE) -> Option<String> {
+    unsafe { object_string(handle, OBJECT_NAME_INFORMATION_CLASS) }
+}
+
+/// The string NtQueryObject returns for `class` of the object behind `handle`: its name,
+/// or its type name. Both answers start with the UNICODE_STRING read here.
+unsafe fn object_string(handle: HANDLE, class: OBJECT_INFORMATION_CLASS) -> Option<String> {
+    unsafe {
+        let mut buffer: Vec<usize> = vec![0; 512];
+        let mut needed = 0u32;
+        for _ in 0..2 {
+            let status = NtQueryObject(
+                handle,
+                class,
+                Some(buffer.as_mut_ptr() as *mut _),
+                (buffer.len() * std::mem::size_of::<usize>()) as u32,
+                Some(&mut needed),
+            );
+            if status.is_ok() {
+                let info = &*(buffer.as_ptr() as *const OBJECT_NAME_INFORMATION);
+                if info.Name.Length == 0 {
+                    return Some(String::new());
+                }
+                let name =
+                    std::slice::from_raw_parts(info.Name.Buffer.0, info.Name.Length as usize / 2);
+                return Some(String::from_utf16_lossy(name));
+            }
+            // Too small for a deep key - retry once at the size asked for
+            buffer = vec![0; needed as usize / std::mem::size_of::<usize>() + 1];
+        }
+        None
+    }
+}
+
+/// The path of the disk file behind `file`, without the `\\?\` prefix.
+unsafe fn final_path(file: HANDLE) -> Option<String> {
+    let mut buffer = vec![0u16; 1024];
+    let len = unsafe { GetFinalPathNameByHandleW(file, &mut buffer, FILE_NAME_NORMALIZED) }
+        as usize;
+    if len == 0 || len > buffer.len() {
+        return None;
+    }
+    let path = String::from_utf16_lossy(&buffer[..len]);
+    Some(path.strip_prefix(r"\\?\").unwrap_or(&path).to_string())
+}
+
+/// The object type and name of the object behind `handle`, for disk files and registry
+/// keys. Other objects are None, as a name query on a pipe can hang.
+unsafe fn handle_identity(handle: HANDLE) -> Option<(String, String)> {
+    unsafe {
+        let object_type = object_string(handle, OBJECT_TYPE_INFORMATION_CLASS)?;
+        let name = match object_type.as_str() {
+            "Key" => object_name(handle)?,
+            "File" if GetFileType(handle) == FILE_TYPE_DISK => final_path(handle)?,
+            _ => return None,
+        };
+        Some((object_type, name))
+    }
+}
+
+/// The executable name of the process behind `process`, or None if it can't be read.
+unsafe fn image_name(process: HANDLE) -> Option<String> {
+    unsafe {
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        QueryFullProcessImageNameW(
+            process,
+            PROCESS_NAME_FORMAT(0),
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        )
+        .ok()?;
+        let path = String::from_utf16_lossy(&buffer[..len as usize]);
+        Some(path.rsplit('\\').next().unwrap_or(&path).to_string())
+    }
+}
+
+/// Finds processes holding handles to the registry `keys` or to keys below them, which
+/// keep an uninstaller from deleting them. Restart Manager only knows files, so this
+/// walks the system handle table for handles of the key type, learnt from opening the
+/// keys here, and asks the kernel for each one's name. Each process comes with the keys
+/// it holds. Processes that can't be opened (protected ones, other users' without
+/// admin) are missed.
+pub fn find_registry_lockers(keys: &[&str]) -> Result<Vec<LockingProcess>> {
+    // The kernel names to look for, next to the paths as typed, to show matches with
+    let mut targets: Vec<(String, &str)> = Vec::new();
+    let mut probes = Vec::new();
+    for key in keys {
+        let handle = match open_key(key) {
+            Ok(handle) => handle,
+            Err(e) => {
+                for probe in probes {
+                    let _ = unsafe { RegCloseKey(probe) };
+                }
+                return Err(e);
+            }
+        };
+        probes.push(handle);
+        if let Some(name) = unsafe { object_name(HANDLE(handle.0)) } {
+            targets.push((name.to_lowercase(), key.trim().trim_end_matches('\\')));
+        }
+    }
+    let own_pid = unsafe { GetCurrentProcessId() } as usize;
+    let entries = super::job::handle_table();
+    let key_type = entries.as_ref().ok().and_then(|entries| {
+        entries
+            .iter()
+            .find(|e| e.pid == own_pid && probes.iter().any(|p| p.0 as usize == e.handle))
+            .map(|e| e.object_type_index)
+    });
+    for probe in probes {
+        let _ = unsafe { RegCloseKey(probe) };
+    }
+    let entries = entries?;
+    let key_type = key_type.ok_or("Couldn't find the registry key object type")?;
+
+    let lockers = walk_handles(&entries, key_type, |key| {
+        let name = unsafe { object_name(key) }?;
+        let lower = name.to_lowercase();
+        targets.iter().find_map(|(target, shown)| {
+            // The key itself or one below it
+            let rest = lower.strip_prefix(target.as_str())?;
+            if !rest.is_empty() && !rest.starts_with('\\') {
+                return None;
+            }
+            Some(format!("{}{}", shown, &name[name.len() - rest.len()..]))
+        })
+    });
+    Ok(lockers)
+}
+
+/// Goes through the handles of `object_type` other processes hold, each duplicated here
+/// and passed to `matches`, which returns the searched-for path it stands for, if any.
+/// Processes that can't be opened (protected ones, other users' without admin) are
+/// skipped. Each locker comes with the paths and the handles they're held through.
+fn walk_handles(
+    entries: &[super::job::HandleEntry],
+    object_type: u16,
+    mut matches: impl FnMut(HANDLE) -> Option<String>,
+) -> Vec<LockingProcess> {
+    let own_pid = unsafe { GetCurrentProcessId() } as usize;
+    let mut owners: HashMap<usize, Option<HANDLE>> = HashMap::new();
+    let mut lockers: Vec<LockingProcess> = Vec::new();
+    for entry in entries.iter().filter(|e| e.object_type_index == object_type) {
+        if entry.pid == own_pid {
+            continue;
+        }
+        let owner = *owners.entry(entry.pid).or_insert_with(|| unsafe {
+            let access = PROCESS_DUP_HANDLE | PROCESS_QUERY_LIMITED_INFORMATION;
+            OpenProcess(access, false, entry.pid as u32).ok()
+        });
+        let Some(owner) = owner else {
+            continue;
+        };
+        let path = unsafe {
+            let mut copy = HANDLE::default();
+            if DuplicateHandle(
+                owner,
+                HANDLE(entry.handle as *mut _),
+                GetCurrentProcess(),
+                &mut copy,
+                0,
+                false,
+                DUPLICATE_SAME_ACCESS,
+            )
+            .is_err()
+            {
+                continue;
+            }
+            // A handle whose object can't be told apart later isn't offered for closing
+            let found = matches(copy).map(|path| {
+                let held = handle_identity(copy).map(|(object_type, name)| HeldHandle {
+                    value: entry.handle,
+                    object_type,
+                    name,
+                });
+                (path, held)
+            });
+            let _ = CloseHandle(copy);
+            found
+        };
+        if let Some((path, held)) = path {
+            add_locker(&mut lockers, entry.pid as u32, owner, path, held);
+        }
+    }
+    for owner in owners.into_values().flatten() {
+        let _ = unsafe { CloseHandle(owner) };
+    }
+    lockers.sort_by(|a, b| a.name.cmp(&b.name));
+    lockers
+}
+
+/// Adds `path` to the locker entry for `pid`, creating it named after the executable of
+/// `process`, along with the handle it's held through when there is one to close.
+fn add_locker(
+    lockers: &mut Vec<LockingProcess>,
+    pid: u32,
+    process: HANDLE,
+    path: String,
+    handle: Option<HeldHandle>,
+) {
+    let index = match lockers.iter().position(|locker| locker.pid == pid) {
+        Some(index) => index,
+        None => {
+            let name = unsafe { image_name(process) }.unwrap_or_else(|| format!("PID {}", pid));
+            lockers.push(LockingProcess {
+                pid,
+                name,
+                files: Vec::new(),
+                handles: Vec::new(),
+                started: unsafe { start_time(process) },
+            });
+            lockers.len() - 1
+        }
+    };
+    let locker = &mut lockers[index];
+    if !locker.files.contains(&path) {
+        locker.files.push(path);
+    }
+    locker.handles.extend(handle);
+}
+
+/// The drive letter of a volume root like `E:` or `E:\`, searched as a whole volume.
+pub fn volume_letter(path: &str) -> Option<char> {
+    let path = path.trim();
+    let mut chars = path.chars();
+    let letter = chars.next().filter(char::is_ascii_alphabetic)?;
+    match chars.as_str() {
+        ":" | ":\\" => Some(letter.to_ascii_uppercase()),
+        _ => None,
+    }
+}
+
+/// Finds every process keeping the volume of drive `letter` from being ejected: files
+/// and folders open on it, current directories included, and executables and DLLs loaded
+/// from it. Open files come from the system handle table, with the file type learnt from
+/// a file opened here; only disk files are asked for their path, as the query can hang on
+/// a pipe. Open files come with their handles, so they can be closed; loaded modules
+/// can't be.
+pub fn find_volume_lockers(letter: char) -> Result<Vec<LockingProcess>> {
+    use std::os::windows::io::AsRawHandle;
+
+    let prefix = format!("{}:\\", letter.to_ascii_uppercase());
+    let on_volume = |path: &str| {
+        let path = path.strip_prefix(r"\\?\").unwrap_or(path);
+        path.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(&prefix))
+    };
+
+    let probe = std::fs::File::open(std::env::current_exe()?)?;
+    let own_pid = unsafe { GetCurrentProcessId() } as usize;
+    let entries = super::job::handle_table()?;
+    let file_type = entries
+        .iter()
+        .find(|e| e.pid == own_pid && e.handle == probe.as_raw_handle() as usize)
+        .map(|e| e.object_type_index)
+        .ok_or("Couldn't find the file object type")?;
+    drop(probe);
+
+    let mut lockers = walk_handles(&entries, file_type, |file| unsafe {
+        if GetFileType(file) != FILE_TYPE_DISK {
+            return None;
+        }
+        final_path(file).filter(|path| on_volume(path))
+    });
+
+    for (pid, modules) in loaded_modules()? {
+        let modules: Vec<String> = modules.into_iter().filter(|m| on_volume(m)).collect();
+        if modules.is_empty() {
+            continue;
+        }
+        let Ok(process) = (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) })
+        else {
+            continue;
+        };
+        for module in modules {
+            add_locker(&mut lockers, pid, process, format!("{} (loaded)", module), None);
+        }
+        let _ = unsafe { CloseHandle(process) };
+    }
+    lockers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(lockers)
+}
+
+/// Paths of the executables and DLLs loaded in each process that can be read.
+fn loaded_modules() -> Result<Vec<(u32, Vec<String>)>> {
+    let mut pids = vec![0u32; 4096];
+    let mut needed = 0u32;
+    unsafe {
+        EnumProcesses(pids.as_mut_ptr(), (pids.len() * 4) as u32, &mut needed)
+            .context("EnumProcesses")?;
+    }
+    pids.truncate(needed as usize / 4);
+    let own_pid = unsafe { GetCurrentProcessId() };
+    let mut loaded = Vec::new();
+    for pid in pids.into_iter().filter(|&pid| pid != 0 && pid != own_pid) {
+        unsafe {
+            let access = PROCESS_QUERY_INFORMATION | PROCESS_VM_READ;
+            let Ok(process) = OpenProcess(access, false, pid) else {
+                continue;
+            };
+            let mut modules = vec![HMODULE::default(); 1024];
+            let mut needed = 0u32;
+            let listed = EnumProcessModulesEx(
+                process,
+                modules.as_mut_ptr(),
+                (modules.len() * std::mem::size_of::<HMODULE>()) as u32,
+                &mut needed,
+                LIST_MODULES_ALL,
+            );
+            if listed.is_ok() {
+                modules.truncate(needed as usize / std::mem::size_of::<HMODULE>());
+                let paths = modules
+                    .iter()
+                    .filter_map(|&module| {
+                        let mut buffer = [0u16; 1024];
+                        let len = GetModuleFileNameExW(process, module, &mut buffer) as usize;
+                        (len > 0).then(|| String::from_utf16_lossy(&buffer[..len]))
+                    })
+                    .collect();
+                loaded.push((pid, paths));
+            }
+            let _ = CloseHandle(process);
+        }
+    }
+    Ok(loaded)
+}
+
+/// Closes `handles` inside process `pid`, started at `started`, releasing what they hold
+/// without killing it. The process isn't told: its next use of one fails, or hits
+/// whatever reused the value. Nothing is closed if `pid` is now another process, and a
+/// handle that no longer refers to the file or key it did when found is left alone.
+pub fn close_remote_handles(pid: u32, started: Option<u64>, handles: &[HeldHandle]) -> Result<()> {
+    unsafe {
+        let access = PROCESS_DUP_HANDLE | PROCESS_QUERY_LIMITED_INFORMATION;
+        let owner =
+            OpenProcess(access, false, pid).context(format!("OpenProcess(PID {})", pid))?;
+        if started.is_none() || start_time(owner) != started {
+            let _ = CloseHandle(owner);
+            return Err(ApertureError::Other(format!(
+                "PID {} is no longer the process searched - search again",
+                pid
+            )));
+        }
+        let (mut changed, mut failed) = (0, 0);
+        for handle in handles {
+            if !still_refers(owner, handle) {
+                changed += 1;
+                continue;
+            }
+            let closed = DuplicateHandle(
+                owner,
+                HANDLE(handle.value as *mut _),
+                HANDLE::default(),
+                std::ptr::null_mut(),
+                0,
+                false,
+                DUPLICATE_CLOSE_SOURCE,
+            );
+            if closed.is_err() {
+                failed += 1;
+            }
+        }
+        let _ = CloseHandle(owner);
+        if changed + failed > 0 {
+            return Err(ApertureError::Other(format!(
+                "{} of {} handles could not be closed, {} of them changed since the search",
+                changed + failed,
+                handles.len(),
+                changed
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Whether `handle` inside `owner` still refers to the object it did when found.
+unsafe fn still_refers(owner: HANDLE, handle: &HeldHandle) -> bool {
+    unsafe {
+        let mut copy = HANDLE::default();
+        if DuplicateHandle(
+            owner,
+            HANDLE(handle.value as *mut _),
+            GetCurrentProcess(),
+            &mut copy,
+            0,
+            false,
+            DUPLICATE_SAME_ACCESS,
+        )
+        .is_err()
+        {
+            return false;
+        }
+        let identity = handle_identity(copy);
+        let _ = CloseHandle(copy);
+        identity.is_some_and(|(object_type, name)| {
+            object_type == handle.object_type && name == handle.name
+        })
+    }
+}
+
+/// Waits for `paths` to have no lockers left, e.g. while killed processes wind down and
+/// their handles close. Folders are checked down to their last file. Fails naming the
+/// remaining lockers after `timeout`.
+pub fn wait_until_unlocked(paths: &[&str], timeout: Duration) -> Result<()> {
+    let started = Instant::now();
+    loop {
+        let mut lockers = Vec::new();
+        for path in paths {
+            if Path::new(path).is_dir() {
+                lockers.extend(find_locking_processes_in_directory(path, &ScanLimits::ALL)?.0);
+            } else {
+                lockers.extend(find_locking_processes(&[path])?);
+            }
+        }
+        if lockers.is_empty() {
+            return Ok(());
+        }
+        if started.elapsed() >= timeout {
+            let names: Vec<String> = lockers.into_iter().map(|locker| locker.name).collect();
+            return Err(ApertureError::Other(format!("Still locked by {}", names.join(", "))));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+}
+
+/// Deletes a file, or a folder with everything in it. Nothing goes to the Recycle Bin.
+pub fn delete_path(path: &str) -> Result<()> {
+    if Path::new(path).is_dir() {
+        std::fs::remove_dir_all(path).context(format!("Deleting {}", path))
+    } else {
+        std::fs::remove_file(path).context(format!("Deleting {}", path))
+    }
+}
+
+/// Renames `path` to `new_name` in the same folder.
+pub fn rename_path(path: &str, new_name: &str) -> Result<()> {
+    let target = Path::new(path).with_file_name(new_name);
+    if target.exists() {
+        return Err(ApertureError::Other(format!("{} already exists", target.display())));
+    }
+    std::fs::rename(path, &target).context(format!("Renaming {}", path))
+}
+
+/// Files a directory scan goes through, in chunks, with the lockers each chunk found.
+#[derive(Debug)]
+pub struct ScanProgress {
+    pub lockers: Vec<LockingProcess>,
+    /// Files checked so far, out of `total`.
+    pub checked: usize,
+    pub total: usize,
+}
+
+/// Files checked between two progress reports of a directory scan.
+const SCAN_CHUNK_SIZE: usize = 250;
+
+/// Finds processes locking files in a directory and its subfolders, within `limits`.
 /// Returns the list of locking processes and the count of files scanned.
 pub fn find_locking_processes_in_directory(
     directory: &str,
-) -> Result<(Vec<LockingProcess>, usize), Box<dyn std::error::Error>> {
-    use std::fs;
+    limits: &ScanLimits,
+) -> Result<(Vec<LockingProcess>, usize)> {
+    let mut lockers: Vec<LockingProcess> = Vec::new();
+    let mut file_count = 0;
+    scan_directory(directory, limits, |progress| {
+        merge_lockers(&mut lockers, progress.lockers);
+        file_count = progress.total;
+        true
+    })?;
+    Ok((lockers, file_count))
+}
 
+/// Scans a directory like `find_locking_processes_in_directory`, but reports each chunk
+/// of files as it's checked, first with none checked once the files are listed. Stops
+/// early when `report` returns false.
+pub fn scan_directory(
+    directory: &str,
+    limits: &ScanLimits,
+    mut report: impl FnMut(ScanProgress) -> bool,
+) -> Result<()> {
     let path = Path::new(directory);
 
     // Collect all files to check
     let mut all_files: Vec<String> = Vec::new();
 
     if path.is_dir() {
-        // Read all entries in the directory
-        if let Ok(entries) = fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let entry_path = entry.path();
-                if entry_path.is_file()
-                    && let Some(path_str) = entry_path.to_str() {
-                        all_files.push(path_str.to_string());
-                    }
-            }
-        }
+        collect_files(path, limits, &mut all_files);
     } else if path.is_file() {
         // Single file
         if let Some(path_str) = path.to_str() {
@@ -212,17 +1052,73 @@ pub fn find_locking_processes_in_directory(
         }
     }
 
-    let file_count = all_files.len();
-
-    if all_files.is_empty() {
-        return Ok((Vec::new(), 0));
+    let total = all_files.len();
+    let listed = ScanProgress {
+        lockers: Vec::new(),
+        checked: 0,
+        total,
+    };
+    if !report(listed) {
+        return Ok(());
     }
+    let mut checked = 0;
+    for chunk in all_files.chunks(SCAN_CHUNK_SIZE) {
+        let file_refs: Vec<&str> = chunk.iter().map(|s| s.as_str()).collect();
+        let lockers = find_locking_processes(&file_refs)?;
+        checked += chunk.len();
+        if !report(ScanProgress {
+            lockers,
+            checked,
+            total,
+        }) {
+            break;
+        }
+    }
+    Ok(())
+}
 
-    // Convert to slice of string references
-    let file_refs: Vec<&str> = all_files.iter().map(|s| s.as_str()).collect();
-
-    // Find locking processes
-    let locking_processes = find_locking_processes(&file_refs)?;
+/// Adds `found` to `lockers`, joining the files and handles of a process found again.
+pub fn merge_lockers(lockers: &mut Vec<LockingProcess>, found: Vec<LockingProcess>) {
+    for process in found {
+        match lockers.iter_mut().find(|locker| locker.pid == process.pid) {
+            Some(locker) => {
+                for file in process.files {
+                    if !locker.files.contains(&file) {
+                        locker.files.push(file);
+                    }
+                }
+                locker.handles.extend(process.handles);
+            }
+            None => lockers.push(process),
+        }
+    }
+    lockers.sort_by(|a, b| a.name.cmp(&b.name));
+}
 
-    Ok((locking_processes, file_count))
+/// Adds the files under `directory` to `files`, depth first, leaving out what `limits`
+/// excludes and stopping at its maximum. Unreadable folders are skipped.
+fn collect_files(directory: &Path, limits: &ScanLimits, files: &mut Vec<String>) {
+    let Ok(entries) = std::fs::read_dir(directory) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if files.len() >= limits.max_files {
+            return;
+        }
+        let entry_path = entry.path();
+        if (limits.exclude)(&entry_path) {
+            continue;
+        }
+        // Not through junctions and symlinks, which can loop back up
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            collect_files(&entry_path, limits, files);
+        } else if file_type.is_file()
+            && let Some(path_str) = entry_path.to_str()
+        {
+            files.push(path_str.to_string());
+        }
+    }
 }
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+use std::mem;
+
+use windows::core::PCWSTR;
+use windows::Wdk::System::SystemInformation::{NtQuerySystemInformation, SYSTEM_INFORMATION_CLASS};
+use windows::Win32::Foundation::{
+    CloseHandle, DuplicateHandle, DUPLICATE_HANDLE_OPTIONS, HANDLE, STATUS_INFO_LENGTH_MISMATCH,
+};
+use windows::Win32::System::JobObjects::{
+    CreateJobObjectW, JobObjectBasicProcessIdList, QueryInformationJobObject,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, GetCurrentProcessId, OpenProcess, PROCESS_DUP_HANDLE,
+};
+
+use crate::error::{Context, Result};
+
+// Not in the Win32 headers the windows crate is generated from
+const SYSTEM_EXTENDED_HANDLE_INFORMATION: SYSTEM_INFORMATION_CLASS = SYSTEM_INFORMATION_CLASS(64);
+const JOB_OBJECT_QUERY: u32 = 0x0004;
+// Most PIDs read from one job; larger jobs are cut off
+const MAX_JOB_PIDS: usize = 4096;
+
+/// SYSTEM_HANDLE_TABLE_ENTRY_INFO_EX from ntdll.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub(super) struct HandleEntry {
+    pub(super) object: usize,
+    pub(super) pid: usize,
+    pub(super) handle: usize,
+    granted_access: u32,
+    creator_back_trace_index: u16,
+    pub(super) object_type_index: u16,
+    attributes: u32,
+    reserved: u32,
+}
+
+/// A job object and the processes in it.
+#[derive(Debug, Clone)]
+pub struct JobInfo {
+    /// Kernel address of the job object. Only good for telling jobs apart.
+    pub object: usize,
+    pub pids: Vec<u32>,
+}
+
+/// Every handle open in the system, from NtQuerySystemInformation.
+pub(super) fn handle_table() -> Result<Vec<HandleEntry>> {
+    let mut buffer: Vec<usize> = vec![0; 1 << 18];
+    loop {
+        let mut needed = 0u32;
+        let status = unsafe {
+            NtQuerySystemInformation(
+                SYSTEM_EXTENDED_HANDLE_INFORMATION,
+                buffer.as_mut_ptr() as *mut _,
+                (buffer.len() * mem::size_of::<usize>()) as u32,
+                &mut needed,
+            )
+        };
+        if status == STATUS_INFO_LENGTH_MISMATCH {
+            // Handles come and go between calls, so leave some slack
+            let words = needed as usize / mem::size_of::<usize>();
+            buffer = vec![0; (words + words / 4).max(buffer.len() * 2)];
+            continue;
+        }
+        status.ok().context("NtQuerySystemInformation(SystemExtendedHandleInformation)")?;
+
+        // Header is NumberOfHandles and a reserved word, then the entries
+        let count = buffer[0];
+        let entries = unsafe {
+            std::slice::from_raw_parts(buffer.as_ptr().add(2) as *const HandleEntry, count)
+        };
+        return Ok(entries.to_vec());
+    }
+}
+
+/// PIDs in a job, from a handle opened with JOB_OBJECT_QUERY.
+unsafe fn job_pids(job: HANDLE) -> Option<Vec<u32>> {
+    unsafe {
+        // Two u32 counts, then the ULONG_PTR list at offset 8 on both x86 and x64
+        let mut buffer: Vec<usize> = vec![0; 2 + MAX_JOB_PIDS];
+        QueryInformationJobObject(
+            job,
+            JobObjectBasicProcessIdList,
+            buffer.as_mut_ptr() as *mut _,
+            (buffer.len() * mem::size_of::<usize>()) as u32,
+            None,
+        )
+        .ok()?;
+        let header = buffer.as_ptr() as *const u32;
+        let listed = *header.add(1) as usize;
+        let ids = (buffer.as_ptr() as *const u8).add(8) as *const usize;
+        Some(
+            (0..listed.min(MAX_JOB_PIDS))
+                .map(|i| *ids.add(i) as u32)
+                .collect(),
+        )
+    }
+}
+
+/// Opens every job object some process holds a handle to, duplicated into this process
+/// with `access`. Job objects have no enumeration API, so this walks the system handle
+/// table for handles of the job type, which is learnt from a job created here. Jobs whose
+/// owner can't be opened (protected processes, other users without admin) are missed.
+/// The caller closes the returned handles.
+fn open_jobs(access: u32) -> Result<Vec<(JobInfo, HANDLE)>> {
+    unsafe {
+        let probe = CreateJobObjectW(None, PCWSTR::null()).context("CreateJobObjectW")?;
+        let own_pid = GetCurrentProcessId() as usize;
+        let entries = handle_table();
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(e) => {
+                let _ = CloseHandle(probe);
+                return Err(e);
+            }
+        };
+        let job_type = entries
+            .iter()
+            .find(|e| e.pid == own_pid && e.handle == probe.0 as usize)
+            .map(|e| e.object_type_index);
+        let _ = CloseHandle(probe);
+        let job_type = job_type.ok_or("Couldn't find the job object type")?;
+
+        let mut owners: HashMap<usize, Option<HANDLE>> = HashMap::new();
+        let mut seen = std::collections::HashSet::new();
+        let mut jobs = Vec::new();
+        for entry in entries.iter().filter(|e| e.object_type_index == job_type) {
+            if entry.pid == own_pid || seen.contains(&entry.object) {
+                continue;
+            }
+            let owner = *owners.entry(entry.pid).or_insert_with(|| {
+                OpenProcess(PROCESS_DUP_HANDLE, false, entry.pid as u32).ok()
+            });
+            let Some(owner) = owner else {
+                continue;
+            };
+
+            let mut job = HANDLE::default();
+            if DuplicateHandle(
+                owner,
+                HANDLE(entry.handle as *mut _),
+                GetCurrentProcess(),
+                &mut job,
+                access | JOB_OBJECT_QUERY,
+                false,
+                DUPLICATE_HANDLE_OPTIONS(0),
+            )
+            .is_err()
+            {
+                continue;
+            }
+            match job_pids(job) {
+                Some(pids) if !pids.is_empty() => {
+                    seen.insert(entry.object);
+                    jobs.push((
+                        JobInfo {
+                            object: entry.object,
+                            pids,
+                        },
+                        job,
+                    ));
+                }
+                _ => {
+                    let _ = CloseHandle(job);
+                }
+            }
+        }
+
+        for owner in owners.into_values().flatten() {
+            let _ = CloseHandle(owner);
+        }
+        Ok(jobs)
+    }
+}
+
+/// Lists the job objects that have processes in them.
+pub fn enumerate_jobs() -> Result<Vec<JobInfo>> {
+    let jobs = open_jobs(0)?;
+    Ok(jobs
+        .into_iter()
+        .map(|(info, handle)| {
+            let _ = unsafe { CloseHandle(handle) };
+            info
+        })
+        .collect())
+}
+
+/// The processes in the innermost job `pid` belongs to, the ones a job kill ends.
+pub fn job_members(pid: u32) -> Result<Vec<u32>> {
+    enumerate_jobs()?
+        .into_iter()
+        .filter(|info| info.pids.contains(&pid))
+        .min_by_key(|info| info.pids.len())
+        .map(|info| info.pids)
+        .ok_or_else(|| format!("PID {} isn't in a job Aperture can open", pid).into())
+}
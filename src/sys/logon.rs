@@ -0,0 +1,156 @@
+use windows::core::PWSTR;
+use windows::Win32::System::RemoteDesktop::{
+    ProcessIdToSessionId, WTSActive, WTSClientName, WTSConnectQuery, WTSConnected,
+    WTSDisconnectSession, WTSDisconnected, WTSDown, WTSEnumerateSessionsW, WTSFreeMemory,
+    WTSIdle, WTSInit, WTSListen, WTSLogoffSession, WTSQuerySessionInformationW, WTSReset,
+    WTSSessionInfo, WTSShadow, WTSINFOW, WTS_CONNECTSTATE_CLASS, WTS_CURRENT_SERVER_HANDLE,
+    WTS_INFO_CLASS, WTS_SESSION_INFOW,
+};
+use windows::Win32::System::Threading::GetCurrentProcessId;
+
+use super::network::filetime_to_unix;
+use crate::error::{Context, Result};
+
+/// A Remote Desktop Services session: the console, an RDP connection, or session 0 where
+/// services run.
+#[derive(Debug, Clone)]
+pub struct LogonSession {
+    pub id: u32,
+    /// Window station, e.g. "Console" or "RDP-Tcp#3". Empty for a disconnected session.
+    pub station: String,
+    /// DOMAIN\user, empty when nobody is logged on.
+    pub user: String,
+    pub state: &'static str,
+    /// Machine an RDP session connects from.
+    pub client: String,
+    /// When the user logged on, in Unix seconds.
+    pub logon_time: Option<u64>,
+    /// Seconds since the last keyboard or mouse input, where the session reports it.
+    pub idle_secs: Option<u64>,
+    /// PIDs and names of the processes running in it. Filled in by the caller.
+    pub processes: Vec<(u32, String)>,
+}
+
+const STATES: [(WTS_CONNECTSTATE_CLASS, &str); 10] = [
+    (WTSActive, "Active"),
+    (WTSConnected, "Connected"),
+    (WTSConnectQuery, "Connecting"),
+    (WTSShadow, "Shadowing"),
+    (WTSDisconnected, "Disconnected"),
+    (WTSIdle, "Idle"),
+    (WTSListen, "Listening"),
+    (WTSReset, "Resetting"),
+    (WTSDown, "Down"),
+    (WTSInit, "Initializing"),
+];
+
+fn state_name(state: WTS_CONNECTSTATE_CLASS) -> &'static str {
+    STATES
+        .iter()
+        .find(|(known, _)| *known == state)
+        .map_or("Unknown", |(_, name)| name)
+}
+
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Calls `read` with a buffer WTSQuerySessionInformationW filled in, then frees it.
+fn query<T>(id: u32, class: WTS_INFO_CLASS, read: impl FnOnce(PWSTR, u32) -> T) -> Result<T> {
+    let mut buffer = PWSTR::null();
+    let mut bytes = 0u32;
+    unsafe {
+        WTSQuerySessionInformationW(WTS_CURRENT_SERVER_HANDLE, id, class, &mut buffer, &mut bytes)
+            .context("WTSQuerySessionInformationW")?;
+        let value = read(buffer, bytes);
+        WTSFreeMemory(buffer.0 as *mut _);
+        Ok(value)
+    }
+}
+
+/// Lists the sessions on this machine, listener sessions (RDP-Tcp waiting for
+/// connections) left out. Sessions whose details can't be read are still listed.
+pub fn enumerate_sessions() -> Result<Vec<LogonSession>> {
+    let mut infos: *mut WTS_SESSION_INFOW = std::ptr::null_mut();
+    let mut count = 0u32;
+    let entries: Vec<(u32, String, WTS_CONNECTSTATE_CLASS)> = unsafe {
+        WTSEnumerateSessionsW(WTS_CURRENT_SERVER_HANDLE, 0, 1, &mut infos, &mut count)
+            .context("WTSEnumerateSessionsW")?;
+        let entries = std::slice::from_raw_parts(infos, count as usize)
+            .iter()
+            .map(|info| {
+                let station = info.pWinStationName.to_string().unwrap_or_default();
+                (info.SessionId, station, info.State)
+            })
+            .collect();
+        WTSFreeMemory(infos as *mut _);
+        entries
+    };
+
+    let mut sessions = Vec::new();
+    for (id, station, state) in entries {
+        if state == WTSListen {
+            continue;
+        }
+        let mut session = LogonSession {
+            id,
+            station,
+            user: String::new(),
+            state: state_name(state),
+            client: String::new(),
+            logon_time: None,
+            idle_secs: None,
+            processes: Vec::new(),
+        };
+        let info = query(id, WTSSessionInfo, |buffer, bytes| {
+            (bytes as usize >= std::mem::size_of::<WTSINFOW>())
+                .then(|| unsafe { *(buffer.0 as *const WTSINFOW) })
+        });
+        if let Ok(Some(info)) = info {
+            let (domain, user) = (wide_to_string(&info.Domain), wide_to_string(&info.UserName));
+            session.user = match (domain.is_empty(), user.is_empty()) {
+                (_, true) => String::new(),
+                (true, false) => user,
+                (false, false) => format!("{}\\{}", domain, user),
+            };
+            session.logon_time = filetime_to_unix(info.LogonTime);
+            // FILETIMEs count 100ns intervals
+            if info.LastInputTime > 0 && info.CurrentTime >= info.LastInputTime {
+                let idle = (info.CurrentTime - info.LastInputTime) / 10_000_000;
+                session.idle_secs = Some(idle as u64);
+            }
+        }
+        session.client = query(id, WTSClientName, |buffer, _| unsafe {
+            buffer.to_string().unwrap_or_default()
+        })
+        .unwrap_or_default();
+        sessions.push(session);
+    }
+    Ok(sessions)
+}
+
+/// The session `pid` runs in.
+pub fn process_session(pid: u32) -> Option<u32> {
+    let mut session = 0u32;
+    unsafe { ProcessIdToSessionId(pid, &mut session).ok()? };
+    Some(session)
+}
+
+/// The session Aperture runs in.
+pub fn current_session() -> Option<u32> {
+    process_session(unsafe { GetCurrentProcessId() })
+}
+
+/// Disconnects session `id`, leaving its user logged on and its programs running, as
+/// closing the Remote Desktop window does.
+pub fn disconnect_session(id: u32) -> Result<()> {
+    unsafe { WTSDisconnectSession(WTS_CURRENT_SERVER_HANDLE, id, false) }
+        .context(format!("WTSDisconnectSession({})", id))
+}
+
+/// Logs session `id` off, closing its programs; unsaved work is lost.
+pub fn log_off_session(id: u32) -> Result<()> {
+    unsafe { WTSLogoffSession(WTS_CURRENT_SERVER_HANDLE, id, false) }
+        .context(format!("WTSLogoffSession({})", id))
+}
@@ -1,4 +1,16 @@
+pub mod capture;
+pub mod dns;
+pub mod dump;
+pub mod firewall;
+pub mod gpu;
 pub mod handle;
+pub mod job;
+pub mod logon;
 pub mod network;
+pub mod pipe;
 pub mod process;
 pub mod service;
+pub mod shell;
+pub mod system;
+pub mod thread;
+pub mod window;
@@ -1,16 +1,23 @@
+use std::collections::{HashMap, HashSet};
 use std::net::{Ipv4Addr, Ipv6Addr};
-use windows::core::PWSTR;
-use windows::Win32::Foundation::CloseHandle;
+use std::sync::{Mutex, OnceLock};
+use windows::core::{s, w, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, LocalFree, HLOCAL};
 use windows::Win32::NetworkManagement::IpHelper::{
-    GetExtendedTcpTable, GetExtendedUdpTable, MIB_TCP6TABLE_OWNER_PID, MIB_TCPTABLE_OWNER_PID,
-    MIB_UDP6TABLE_OWNER_PID, MIB_UDPTABLE_OWNER_PID, TCP_TABLE_OWNER_PID_ALL, UDP_TABLE_OWNER_PID,
+    GetExtendedTcpTable, GetExtendedUdpTable, SetTcpEntry, MIB_TCP6ROW_OWNER_MODULE,
+    MIB_TCPROW_LH, MIB_TCPROW_LH_0, MIB_TCPROW_OWNER_MODULE, MIB_TCP_STATE_DELETE_TCB,
+    MIB_UDP6ROW_OWNER_MODULE, MIB_UDPROW_OWNER_MODULE, TCP_TABLE_OWNER_MODULE_ALL,
+    UDP_TABLE_OWNER_MODULE,
 };
 use windows::Win32::Networking::WinSock::{ntohl, ntohs};
+use windows::Win32::System::LibraryLoader::{GetModuleHandleW, GetProcAddress, LoadLibraryW};
 use windows::Win32::System::Threading::{
     OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION,
 };
 
-#[derive(Debug, Clone, serde::Serialize)]
+use crate::error::{ApertureError, Result};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ConnectionInfo {
     pub protocol: String,
     pub local_addr: String,
@@ -20,6 +27,9 @@ pub struct ConnectionInfo {
     pub state: String,
     pub pid: u32,
     pub process_name: Option<String>,
+    /// Service hosted in svchost.exe that owns the socket, resolved from its service tag.
+    #[serde(default)]
+    pub service_name: Option<String>,
 }
 
 fn tcp_state_to_string(state: u32) -> String {
@@ -50,13 +60,17 @@ fn ipv6_to_string(ip: &[u8; 16]) -> String {
 }
 
 fn get_process_name(pid: u32) -> Option<String> {
+    get_process_path(pid).and_then(|path| path.rsplit('\\').next().map(|s| s.to_string()))
+}
+
+pub(crate) fn get_process_path(pid: u32) -> Option<String> {
     unsafe {
         let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
 
         let mut path_buffer = [0u16; 260];
         let mut path_len = path_buffer.len() as u32;
 
-        let name = if QueryFullProcessImageNameW(
+        let path = if QueryFullProcessImageNameW(
             handle,
             PROCESS_NAME_FORMAT(0),
             PWSTR(path_buffer.as_mut_ptr()),
@@ -64,184 +78,360 @@ fn get_process_name(pid: u32) -> Option<String> {
         )
         .is_ok()
         {
-            let path = String::from_utf16_lossy(&path_buffer[..path_len as usize]);
-            path.rsplit('\\').next().map(|s| s.to_string())
+            Some(String::from_utf16_lossy(&path_buffer[..path_len as usize]))
         } else {
             None
         };
 
         let _ = CloseHandle(handle);
-        name
+        path
     }
 }
 
-pub fn enumerate_connections() -> Result<Vec<ConnectionInfo>, Box<dyn std::error::Error>> {
+pub fn enumerate_connections() -> Result<Vec<ConnectionInfo>> {
     let mut connections = Vec::new();
-
-    unsafe {
-        let mut size = 0u32;
-        let _ = GetExtendedTcpTable(None, &mut size, false, 2, TCP_TABLE_OWNER_PID_ALL, 0);
-
-        let mut buffer = vec![0u8; size as usize];
-
-        let result = GetExtendedTcpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            2,
-            TCP_TABLE_OWNER_PID_ALL,
-            0,
-        );
-
-        if result == 0 {
-            let table = buffer.as_ptr() as *const MIB_TCPTABLE_OWNER_PID;
-            let num_entries = (*table).dwNumEntries;
-            let rows = (*table).table.as_ptr();
-
-            for i in 0..num_entries {
-                let row = &*rows.add(i as usize);
-
-                let local_addr = ip_to_string(ntohl(row.dwLocalAddr));
-                let local_port = ntohs(row.dwLocalPort as u16);
-                let remote_addr = ip_to_string(ntohl(row.dwRemoteAddr));
-                let remote_port = ntohs(row.dwRemotePort as u16);
-                let pid = row.dwOwningPid;
-
+    // Service names per (pid, tag), kept across polls so each svchost service is only
+    // looked up once while its sockets stay open
+    let mut services = SERVICE_TAGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut seen = HashSet::new();
+    let mut service_name = |pid: u32, module_info: u64| -> Option<String> {
+        let tag = module_info as u32;
+        if tag == 0 {
+            return None;
+        }
+        seen.insert((pid, tag));
+        services
+            .entry((pid, tag))
+            .or_insert_with(|| service_name_from_tag(pid, tag))
+            .clone()
+    };
+
+    // The owner-module tables are used over the owner-PID ones because they carry the
+    // service tag of sockets opened by services hosted in svchost.exe
+    if let Ok(buffer) = owner_module_table(true, 2) {
+        let rows = unsafe { table_rows::<MIB_TCPROW_OWNER_MODULE>(&buffer) };
+        for row in rows {
+            let pid = row.dwOwningPid;
+            unsafe {
                 connections.push(ConnectionInfo {
                     protocol: "TCP".to_string(),
-                    local_addr,
-                    local_port,
-                    remote_addr,
-                    remote_port,
+                    local_addr: ip_to_string(ntohl(row.dwLocalAddr)),
+                    local_port: ntohs(row.dwLocalPort as u16),
+                    remote_addr: ip_to_string(ntohl(row.dwRemoteAddr)),
+                    remote_port: ntohs(row.dwRemotePort as u16),
                     state: tcp_state_to_string(row.dwState),
                     pid,
                     process_name: get_process_name(pid),
+                    service_name: service_name(pid, row.OwningModuleInfo[0]),
                 });
             }
         }
+    }
 
-        let mut size = 0u32;
-        let _ = GetExtendedUdpTable(None, &mut size, false, 2, UDP_TABLE_OWNER_PID, 0);
-
-        let mut buffer = vec![0u8; size as usize];
-
-        let result = GetExtendedUdpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            2,
-            UDP_TABLE_OWNER_PID,
-            0,
-        );
-
-        if result == 0 {
-            let table = buffer.as_ptr() as *const MIB_UDPTABLE_OWNER_PID;
-            let num_entries = (*table).dwNumEntries;
-            let rows = (*table).table.as_ptr();
-
-            for i in 0..num_entries {
-                let row = &*rows.add(i as usize);
-
-                let local_addr = ip_to_string(ntohl(row.dwLocalAddr));
-                let local_port = ntohs(row.dwLocalPort as u16);
-                let pid = row.dwOwningPid;
-
+    if let Ok(buffer) = owner_module_table(false, 2) {
+        let rows = unsafe { table_rows::<MIB_UDPROW_OWNER_MODULE>(&buffer) };
+        for row in rows {
+            let pid = row.dwOwningPid;
+            unsafe {
                 connections.push(ConnectionInfo {
                     protocol: "UDP".to_string(),
-                    local_addr,
-                    local_port,
+                    local_addr: ip_to_string(ntohl(row.dwLocalAddr)),
+                    local_port: ntohs(row.dwLocalPort as u16),
                     remote_addr: "0.0.0.0".to_string(),
                     remote_port: 0,
                     state: "N/A".to_string(),
                     pid,
                     process_name: get_process_name(pid),
+                    service_name: service_name(pid, row.OwningModuleInfo[0]),
                 });
             }
         }
+    }
 
-        // IPv6 TCP connections
-        let mut size = 0u32;
-        let _ = GetExtendedTcpTable(None, &mut size, false, 23, TCP_TABLE_OWNER_PID_ALL, 0);
-
-        let mut buffer = vec![0u8; size as usize];
-
-        let result = GetExtendedTcpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            23,
-            TCP_TABLE_OWNER_PID_ALL,
-            0,
-        );
-
-        if result == 0 {
-            let table = buffer.as_ptr() as *const MIB_TCP6TABLE_OWNER_PID;
-            let num_entries = (*table).dwNumEntries;
-            let rows = (*table).table.as_ptr();
-
-            for i in 0..num_entries {
-                let row = &*rows.add(i as usize);
-
-                let local_addr = ipv6_to_string(&row.ucLocalAddr);
-                let local_port = ntohs(row.dwLocalPort as u16);
-                let remote_addr = ipv6_to_string(&row.ucRemoteAddr);
-                let remote_port = ntohs(row.dwRemotePort as u16);
-                let pid = row.dwOwningPid;
-
+    // IPv6 TCP connections
+    if let Ok(buffer) = owner_module_table(true, 23) {
+        let rows = unsafe { table_rows::<MIB_TCP6ROW_OWNER_MODULE>(&buffer) };
+        for row in rows {
+            let pid = row.dwOwningPid;
+            unsafe {
                 connections.push(ConnectionInfo {
                     protocol: "TCP6".to_string(),
-                    local_addr,
-                    local_port,
-                    remote_addr,
-                    remote_port,
+                    local_addr: ipv6_to_string(&row.ucLocalAddr),
+                    local_port: ntohs(row.dwLocalPort as u16),
+                    remote_addr: ipv6_to_string(&row.ucRemoteAddr),
+                    remote_port: ntohs(row.dwRemotePort as u16),
                     state: tcp_state_to_string(row.dwState),
                     pid,
                     process_name: get_process_name(pid),
+                    service_name: service_name(pid, row.OwningModuleInfo[0]),
                 });
             }
         }
+    }
 
-        // IPv6 UDP connections
-        let mut size = 0u32;
-        let _ = GetExtendedUdpTable(None, &mut size, false, 23, UDP_TABLE_OWNER_PID, 0);
-
-        let mut buffer = vec![0u8; size as usize];
-
-        let result = GetExtendedUdpTable(
-            Some(buffer.as_mut_ptr() as *mut _),
-            &mut size,
-            false,
-            23,
-            UDP_TABLE_OWNER_PID,
-            0,
-        );
-
-        if result == 0 {
-            let table = buffer.as_ptr() as *const MIB_UDP6TABLE_OWNER_PID;
-            let num_entries = (*table).dwNumEntries;
-            let rows = (*table).table.as_ptr();
-
-            for i in 0..num_entries {
-                let row = &*rows.add(i as usize);
-
-                let local_addr = ipv6_to_string(&row.ucLocalAddr);
-                let local_port = ntohs(row.dwLocalPort as u16);
-                let pid = row.dwOwningPid;
-
+    // IPv6 UDP connections
+    if let Ok(buffer) = owner_module_table(false, 23) {
+        let rows = unsafe { table_rows::<MIB_UDP6ROW_OWNER_MODULE>(&buffer) };
+        for row in rows {
+            let pid = row.dwOwningPid;
+            unsafe {
                 connections.push(ConnectionInfo {
                     protocol: "UDP6".to_string(),
-                    local_addr,
-                    local_port,
+                    local_addr: ipv6_to_string(&row.ucLocalAddr),
+                    local_port: ntohs(row.dwLocalPort as u16),
                     remote_addr: "::".to_string(),
                     remote_port: 0,
                     state: "N/A".to_string(),
                     pid,
                     process_name: get_process_name(pid),
+                    service_name: service_name(pid, row.OwningModuleInfo[0]),
                 });
             }
         }
+    }
+
+    // A tag whose sockets are gone may come back with a reused PID, so it is looked up again
+    services.retain(|key, _| seen.contains(key));
+    drop(services);
+
+    connections.sort_by(|a, b| a.pid.cmp(&b.pid));
+    Ok(connections)
+}
+
+// Layout of the undocumented SC_SERVICE_TAG_QUERY used by I_QueryTagInformation
+#[repr(C)]
+struct ServiceTagQuery {
+    process_id: u32,
+    service_tag: u32,
+    reserved: u32,
+    buffer: *mut u16,
+}
+
+// SC_SERVICE_TAG_QUERY_TYPE::ServiceNameFromTagInformation
+const SERVICE_NAME_FROM_TAG: u32 = 1;
+
+type QueryTagInformationFn =
+    unsafe extern "system" fn(*const u16, u32, *mut ServiceTagQuery) -> u32;
+
+static QUERY_TAG: OnceLock<Option<QueryTagInformationFn>> = OnceLock::new();
+
+// Service name (or None if the lookup failed) per (pid, tag)
+type ServiceTags = HashMap<(u32, u32), Option<String>>;
+
+static SERVICE_TAGS: OnceLock<Mutex<ServiceTags>> = OnceLock::new();
 
-        connections.sort_by(|a, b| a.pid.cmp(&b.pid));
-        Ok(connections)
+/// I_QueryTagInformation is exported by advapi32.dll but not documented, so it is resolved
+/// at runtime, once. advapi32 is normally loaded already; if it isn't, the one reference
+/// taken here is kept for the rest of the run, which the cached pointer needs anyway.
+fn query_tag_information() -> Option<QueryTagInformationFn> {
+    *QUERY_TAG.get_or_init(|| unsafe {
+        let module = GetModuleHandleW(w!("advapi32.dll"))
+            .or_else(|_| LoadLibraryW(w!("advapi32.dll")))
+            .ok()?;
+        let proc = GetProcAddress(module, s!("I_QueryTagInformation"))?;
+        let query_tag: QueryTagInformationFn = std::mem::transmute(proc);
+        Some(query_tag)
+    })
+}
+
+/// Resolves the service tag of a socket owned by a shared svchost.exe to the service's name.
+pub fn service_name_from_tag(pid: u32, tag: u32) -> Option<String> {
+    let query_tag = query_tag_information()?;
+    unsafe {
+        let mut query = ServiceTagQuery {
+            process_id: pid,
+            service_tag: tag,
+            reserved: 0,
+            buffer: std::ptr::null_mut(),
+        };
+        if query_tag(std::ptr::null(), SERVICE_NAME_FROM_TAG, &mut query) != 0
+            || query.buffer.is_null()
+        {
+            return None;
+        }
+
+        let name = PWSTR(query.buffer).to_string().ok();
+        // The name is allocated by the service control manager with LocalAlloc
+        let _ = LocalFree(HLOCAL(query.buffer as *mut _));
+        name
+    }
+}
+
+/// Returns every socket bound to the given local port across TCP/UDP and IPv4/IPv6,
+/// listeners first.
+pub fn find_port_owners(port: u16) -> Result<Vec<ConnectionInfo>> {
+    let mut owners: Vec<ConnectionInfo> = enumerate_connections()?
+        .into_iter()
+        .filter(|c| c.local_port == port)
+        .collect();
+    owners.sort_by_key(|c| (c.state != "LISTENING", c.pid));
+    Ok(owners)
+}
+
+/// Tears down an IPv4 TCP connection by setting its TCB to DELETE_TCB.
+/// Windows only supports this for IPv4 connections, and listening sockets
+/// can only be released by their owning process.
+pub fn close_tcp_connection(conn: &ConnectionInfo) -> Result<()> {
+    if conn.protocol != "TCP" {
+        return Err("Only IPv4 TCP connections can be closed".into());
+    }
+    if conn.state == "LISTENING" {
+        return Err("Listening sockets can't be closed - kill the owning process instead".into());
+    }
+
+    let local_addr: Ipv4Addr = conn
+        .local_addr
+        .parse()
+        .map_err(|_| format!("Invalid local address {}", conn.local_addr))?;
+    let remote_addr: Ipv4Addr = conn
+        .remote_addr
+        .parse()
+        .map_err(|_| format!("Invalid remote address {}", conn.remote_addr))?;
+
+    // Addresses and ports are stored in network byte order
+    let row = MIB_TCPROW_LH {
+        Anonymous: MIB_TCPROW_LH_0 {
+            State: MIB_TCP_STATE_DELETE_TCB,
+        },
+        dwLocalAddr: u32::from_ne_bytes(local_addr.octets()),
+        dwLocalPort: conn.local_port.to_be() as u32,
+        dwRemoteAddr: u32::from_ne_bytes(remote_addr.octets()),
+        dwRemotePort: conn.remote_port.to_be() as u32,
+    };
+
+    let result = unsafe { SetTcpEntry(&row) };
+    if result != 0 {
+        return Err(ApertureError::win32("SetTcpEntry", result));
     }
+    Ok(())
+}
+
+/// Per-socket details from the owner-module tables, which the regular poll doesn't collect.
+#[derive(Debug, Clone, Default)]
+pub struct SocketOwnerInfo {
+    /// When the socket was created, in Unix seconds.
+    pub created: Option<u64>,
+    /// svchost service tag identifying the hosted service that owns the socket, 0 if none.
+    pub service_tag: u32,
+    pub process_path: Option<String>,
+}
+
+// Seconds between 1601-01-01 (FILETIME epoch) and 1970-01-01
+const FILETIME_UNIX_OFFSET: u64 = 11_644_473_600;
+
+pub(crate) fn filetime_to_unix(filetime: i64) -> Option<u64> {
+    if filetime <= 0 {
+        return None;
+    }
+    (filetime as u64 / 10_000_000).checked_sub(FILETIME_UNIX_OFFSET)
+}
+
+/// Reads a TCP or UDP owner-module table for one address family, retrying if it grows
+/// between the size query and the read. The buffer is u64-backed because the rows hold
+/// 64-bit timestamps and need 8-byte alignment.
+fn owner_module_table(tcp: bool, family: u32) -> Result<Vec<u64>> {
+    let api = if tcp { "GetExtendedTcpTable" } else { "GetExtendedUdpTable" };
+    let mut size = 0u32;
+    for _ in 0..3 {
+        let mut buffer = vec![0u64; (size as usize).div_ceil(8)];
+        let table = if buffer.is_empty() {
+            None
+        } else {
+            Some(buffer.as_mut_ptr() as *mut _)
+        };
+        let result = unsafe {
+            if tcp {
+                GetExtendedTcpTable(table, &mut size, false, family, TCP_TABLE_OWNER_MODULE_ALL, 0)
+            } else {
+                GetExtendedUdpTable(table, &mut size, false, family, UDP_TABLE_OWNER_MODULE, 0)
+            }
+        };
+        match result {
+            0 if !buffer.is_empty() => return Ok(buffer),
+            // ERROR_INSUFFICIENT_BUFFER: size now holds what is needed
+            0 | 122 => continue,
+            code => return Err(ApertureError::win32(api, code)),
+        }
+    }
+    Err(ApertureError::win32(api, 122))
+}
+
+/// Rows of an owner-module table buffer, which starts with a u32 entry count.
+///
+/// # Safety
+/// `buffer` must hold a table of `T` rows as returned by `owner_module_table`.
+unsafe fn table_rows<T>(buffer: &[u64]) -> &[T] {
+    let count = unsafe { *(buffer.as_ptr() as *const u32) } as usize;
+    // Rows start after the count, aligned for T
+    let offset = std::mem::size_of::<u32>().next_multiple_of(std::mem::align_of::<T>());
+    let rows = unsafe { (buffer.as_ptr() as *const u8).add(offset) };
+    unsafe { std::slice::from_raw_parts(rows as *const T, count) }
+}
+
+/// Looks up the creation time, service tag and image path of the socket behind `conn`.
+/// Fails if the socket has closed since the last refresh.
+pub fn socket_owner_info(conn: &ConnectionInfo) -> Result<SocketOwnerInfo> {
+    let found = match conn.protocol.as_str() {
+        "TCP" => {
+            let buffer = owner_module_table(true, 2)?;
+            let rows = unsafe { table_rows::<MIB_TCPROW_OWNER_MODULE>(&buffer) };
+            rows.iter()
+                .find(|row| {
+                    row.dwOwningPid == conn.pid
+                        && ip_to_string(unsafe { ntohl(row.dwLocalAddr) }) == conn.local_addr
+                        && unsafe { ntohs(row.dwLocalPort as u16) } == conn.local_port
+                        && ip_to_string(unsafe { ntohl(row.dwRemoteAddr) }) == conn.remote_addr
+                        && unsafe { ntohs(row.dwRemotePort as u16) } == conn.remote_port
+                })
+                .map(|row| (row.liCreateTimestamp, row.OwningModuleInfo[0]))
+        }
+        "TCP6" => {
+            let buffer = owner_module_table(true, 23)?;
+            let rows = unsafe { table_rows::<MIB_TCP6ROW_OWNER_MODULE>(&buffer) };
+            rows.iter()
+                .find(|row| {
+                    row.dwOwningPid == conn.pid
+                        && ipv6_to_string(&row.ucLocalAddr) == conn.local_addr
+                        && unsafe { ntohs(row.dwLocalPort as u16) } == conn.local_port
+                        && ipv6_to_string(&row.ucRemoteAddr) == conn.remote_addr
+                        && unsafe { ntohs(row.dwRemotePort as u16) } == conn.remote_port
+                })
+                .map(|row| (row.liCreateTimestamp, row.OwningModuleInfo[0]))
+        }
+        "UDP" => {
+            let buffer = owner_module_table(false, 2)?;
+            let rows = unsafe { table_rows::<MIB_UDPROW_OWNER_MODULE>(&buffer) };
+            rows.iter()
+                .find(|row| {
+                    row.dwOwningPid == conn.pid
+                        && ip_to_string(unsafe { ntohl(row.dwLocalAddr) }) == conn.local_addr
+                        && unsafe { ntohs(row.dwLocalPort as u16) } == conn.local_port
+                })
+                .map(|row| (row.liCreateTimestamp, row.OwningModuleInfo[0]))
+        }
+        "UDP6" => {
+            let buffer = owner_module_table(false, 23)?;
+            let rows = unsafe { table_rows::<MIB_UDP6ROW_OWNER_MODULE>(&buffer) };
+            rows.iter()
+                .find(|row| {
+                    row.dwOwningPid == conn.pid
+                        && ipv6_to_string(&row.ucLocalAddr) == conn.local_addr
+                        && unsafe { ntohs(row.dwLocalPort as u16) } == conn.local_port
+                })
+                .map(|row| (row.liCreateTimestamp, row.OwningModuleInfo[0]))
+        }
+        other => return Err(format!("Unknown protocol {}", other).into()),
+    };
+
+    let (created, module_info) =
+        found.ok_or_else(|| ApertureError::from("The socket is no longer open"))?;
+    Ok(SocketOwnerInfo {
+        created: filetime_to_unix(created),
+        // The service tag is the low DWORD of the first owning-module slot
+        service_tag: module_info as u32,
+        process_path: get_process_path(conn.pid),
+    })
 }
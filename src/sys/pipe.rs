@@ -0,0 +1,50 @@
+use windows::core::w;
+use windows::Win32::Storage::FileSystem::{FindClose, FindFirstFileW, FindNextFileW, WIN32_FIND_DATAW};
+
+use crate::error::{Context, Result};
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct PipeInfo {
+    pub name: String,
+    pub instances: u32,
+}
+
+/// Lists the named pipes currently open under \\.\pipe\.
+/// FindFirstFileW on the pipe namespace goes through NtQueryDirectoryFile on the
+/// NPFS root; for pipes the reported file size is the number of active instances.
+/// Pipes are listed without opening them, since connecting would consume an instance.
+///
+/// AF_UNIX sockets have no system-wide table on Windows (they are reparse points
+/// on disk owned by AFD), so they are not part of this enumeration.
+pub fn enumerate_named_pipes() -> Result<Vec<PipeInfo>> {
+    let mut pipes = Vec::new();
+
+    unsafe {
+        let mut data = WIN32_FIND_DATAW::default();
+        let find_handle = FindFirstFileW(w!("\\\\.\\pipe\\*"), &mut data)
+            .context("FindFirstFileW(\\\\.\\pipe\\*)")?;
+
+        loop {
+            let len = data
+                .cFileName
+                .iter()
+                .position(|&c| c == 0)
+                .unwrap_or(data.cFileName.len());
+            if len > 0 {
+                pipes.push(PipeInfo {
+                    name: String::from_utf16_lossy(&data.cFileName[..len]),
+                    instances: data.nFileSizeLow,
+                });
+            }
+
+            if FindNextFileW(find_handle, &mut data).is_err() {
+                break;
+            }
+        }
+
+        let _ = FindClose(find_handle);
+    }
+
+    pipes.sort_by_key(|p| p.name.to_lowercase());
+    Ok(pipes)
+}
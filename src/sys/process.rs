@@ -2,39 +2,86 @@ use std::collections::HashMap;
 use std::mem;
 use std::sync::{Mutex, OnceLock};
 use std::time::Instant;
-use windows::core::PWSTR;
-use windows::Win32::Foundation::{CloseHandle, FILETIME};
-use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::core::{PCWSTR, PWSTR};
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessBasicInformation};
+use windows::Win32::Foundation::{CloseHandle, GetLastError, FILETIME, HANDLE};
+use windows::Win32::Security::{
+    AdjustTokenPrivileges, GetTokenInformation, LookupPrivilegeValueW, TokenElevation,
+    LUID_AND_ATTRIBUTES, SE_DEBUG_NAME, SE_PRIVILEGE_ENABLED, SE_SHUTDOWN_NAME,
+    TOKEN_ADJUST_PRIVILEGES,
+    TOKEN_ELEVATION, TOKEN_PRIVILEGES, TOKEN_QUERY,
+};
 use windows::Win32::System::Diagnostics::ToolHelp::{
     CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W, TH32CS_SNAPPROCESS,
 };
 use windows::Win32::System::ProcessStatus::{
     EnumProcessModules, EnumProcesses, GetModuleBaseNameW, GetModuleFileNameExW,
-    GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS,
+    GetProcessMemoryInfo, PROCESS_MEMORY_COUNTERS_EX,
 };
+use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
 use windows::Win32::System::SystemInformation::{GetSystemInfo, SYSTEM_INFO};
 use windows::Win32::System::Threading::{
-    GetCurrentProcess, GetProcessTimes, OpenProcess, OpenProcessToken, QueryFullProcessImageNameW,
-    PROCESS_NAME_FORMAT, PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE,
+    GetCurrentProcess, GetGuiResources, GetProcessHandleCount, GetProcessIoCounters, GetProcessTimes,
+    OpenProcess, OpenProcessToken, QueryFullProcessImageNameW, SetPriorityClass,
+    BELOW_NORMAL_PRIORITY_CLASS, GR_GDIOBJECTS, GR_USEROBJECTS,
+    IO_COUNTERS, PEB, PROCESS_BASIC_INFORMATION, PROCESS_NAME_FORMAT,
+    PROCESS_QUERY_LIMITED_INFORMATION, PROCESS_TERMINATE, PROCESS_VM_READ,
+    RTL_USER_PROCESS_PARAMETERS,
 };
 
-#[derive(Debug, Clone, serde::Serialize)]
+use crate::error::{Context, Result};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub parent_pid: u32,
     pub name: String,
     pub path: Option<String>,
     pub cpu_usage: f32,
+    /// Working set.
     pub memory_mb: f64,
+    /// Private bytes: committed memory no other process can share.
+    #[serde(default)]
+    pub private_mb: f64,
+    pub gpu_usage: f32,
+    pub gpu_memory_mb: f64,
+    pub io_read_bps: f64,
+    pub io_write_bps: f64,
+    /// When the process started, in Unix seconds. Filled in by `update_process_metrics`.
+    #[serde(default)]
+    pub start_time: Option<u64>,
+    /// Open kernel handles, and GDI/USER objects (0 for processes without a GUI).
+    #[serde(default)]
+    pub handle_count: u32,
+    #[serde(default)]
+    pub gdi_objects: u32,
+    #[serde(default)]
+    pub user_objects: u32,
+    /// Full command line, read from the PEB by a background task while the Locker shows it.
+    #[serde(default)]
+    pub command_line: Option<String>,
+    /// "wsl", "docker", "container" or "vm" for processes that belong to WSL, Docker or
+    /// Windows containers. Set by the Locker from names and ancestry.
+    #[serde(default)]
+    pub container: Option<String>,
+    /// Number of the innermost job object the process is in, counted up per session as
+    /// jobs are first seen. Set by the Locker's job scan while jobs are shown.
+    #[serde(default)]
+    pub job_id: Option<u32>,
     // Cache for displaying stable values when metrics temporarily unavailable
     pub last_cpu_usage: f32,
     pub last_memory_mb: f64,
 }
 
 static PREV_CPU_TIMES: OnceLock<Mutex<HashMap<u32, (u64, Instant)>>> = OnceLock::new();
+// (read bytes, write bytes, creation time, sampled at) per PID. The creation time tells a
+// reused PID from the process the counters were read from.
+type IoSample = (u64, u64, Option<u64>, Instant);
+
+static PREV_IO_COUNTERS: OnceLock<Mutex<HashMap<u32, IoSample>>> = OnceLock::new();
 static NUM_CPUS: OnceLock<u32> = OnceLock::new();
 
-fn get_num_cpus() -> u32 {
+pub(crate) fn get_num_cpus() -> u32 {
     *NUM_CPUS.get_or_init(|| unsafe {
         let mut sys_info: SYSTEM_INFO = SYSTEM_INFO::default();
         GetSystemInfo(&mut sys_info);
@@ -67,31 +114,177 @@ pub fn is_elevated() -> bool {
     }
 }
 
-pub fn kill_process(pid: u32) -> Result<(), Box<dyn std::error::Error>> {
+/// Enables SeDebugPrivilege on the current process token.
+/// Elevated tokens hold the privilege but it is disabled by default; without it,
+/// OpenProcess fails for protected and SYSTEM-owned processes.
+pub fn enable_debug_privilege() -> Result<()> {
+    enable_privilege(SE_DEBUG_NAME, "SeDebugPrivilege")
+}
+
+/// Enables SeShutdownPrivilege on the current process token. The SCM refuses to set a
+/// "restart the computer" recovery action for a caller that hasn't enabled it.
+pub fn enable_shutdown_privilege() -> Result<()> {
+    enable_privilege(SE_SHUTDOWN_NAME, "SeShutdownPrivilege")
+}
+
+fn enable_privilege(name: PCWSTR, label: &str) -> Result<()> {
+    unsafe {
+        let mut token = Default::default();
+        OpenProcessToken(
+            GetCurrentProcess(),
+            TOKEN_ADJUST_PRIVILEGES | TOKEN_QUERY,
+            &mut token,
+        )
+        .context("OpenProcessToken")?;
+
+        let mut luid = Default::default();
+        if let Err(e) = LookupPrivilegeValueW(PCWSTR::null(), name, &mut luid) {
+            let _ = CloseHandle(token);
+            return Err(e).context(format!("LookupPrivilegeValueW({})", label));
+        }
+
+        let privileges = TOKEN_PRIVILEGES {
+            PrivilegeCount: 1,
+            Privileges: [LUID_AND_ATTRIBUTES {
+                Luid: luid,
+                Attributes: SE_PRIVILEGE_ENABLED,
+            }],
+        };
+
+        let result = AdjustTokenPrivileges(token, false, Some(&privileges), 0, None, None);
+
+        // AdjustTokenPrivileges succeeds even when the privilege isn't held,
+        // so check the last error for ERROR_NOT_ALL_ASSIGNED (1300)
+        let last_error = GetLastError();
+        let _ = CloseHandle(token);
+
+        result.context("AdjustTokenPrivileges")?;
+        if last_error.0 == 1300 {
+            return Err(format!("{} is not held by this token", label).into());
+        }
+    }
+    Ok(())
+}
+
+/// Drops Aperture's own priority class to BELOW_NORMAL, so its polling yields to the
+/// processes it is watching. Background threads follow, as they share the class.
+pub fn lower_own_priority() -> Result<()> {
+    unsafe {
+        SetPriorityClass(GetCurrentProcess(), BELOW_NORMAL_PRIORITY_CLASS)
+            .context("SetPriorityClass(BELOW_NORMAL_PRIORITY_CLASS)")
+    }
+}
+
+/// How `kill_options` terminates a process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KillOptions {
+    /// Enable SeDebugPrivilege first so protected and SYSTEM processes can be opened.
+    pub force: bool,
+    /// Also kill the descendants passed to `kill_options`, deepest first.
+    pub tree: bool,
+    /// Kill the other processes of its job, as passed to `kill_options`, instead. Takes
+    /// precedence over `tree`.
+    pub job: bool,
+    /// Exit code the killed processes report.
+    pub exit_code: u32,
+}
+
+impl Default for KillOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            tree: false,
+            job: false,
+            exit_code: 1,
+        }
+    }
+}
+
+/// Kills `pid` with the default options if it is still the process created at `created`,
+/// as read by `identify`. A PID that has moved on to a newer process is left alone.
+pub fn kill_process(pid: u32, created: u64) -> Result<()> {
+    terminate(pid, KillOptions::default().exit_code, Some(created))
+}
+
+/// Terminates `pid` as described by `options`, unless `created` is set and the PID now
+/// belongs to a process created at another time. In tree and job mode `members`, as listed
+/// by `kill_members` when the kill was confirmed, go first, children before their parent so
+/// none of them gets re-parented mid-way. Only those are killed: processes started or added
+/// to the job since are left, and members that exited on their own or whose PID now
+/// belongs to a newer process are skipped.
+pub fn kill_options(
+    pid: u32,
+    created: Option<u64>,
+    options: &KillOptions,
+    members: &[KillMember],
+) -> Result<()> {
+    if options.force {
+        enable_debug_privilege()?;
+    }
+
+    if options.tree || options.job {
+        for member in members.iter().rev() {
+            if let Err(e) = terminate(member.pid, options.exit_code, Some(member.created)) {
+                // ERROR_INVALID_PARAMETER - the PID no longer exists
+                if e.code() != Some(87) {
+                    return Err(e);
+                }
+            }
+        }
+    }
+
+    terminate(pid, options.exit_code, created)
+}
+
+/// Terminates `pid`. With `created` set, a process on the PID that was created at another
+/// time is a newer one that reused it, and is left alone.
+fn terminate(pid: u32, exit_code: u32, created: Option<u64>) -> Result<()> {
+    let access = match created {
+        Some(_) => PROCESS_TERMINATE | PROCESS_QUERY_LIMITED_INFORMATION,
+        None => PROCESS_TERMINATE,
+    };
     unsafe {
-        let handle = OpenProcess(PROCESS_TERMINATE, false, pid)?;
-        windows::Win32::System::Threading::TerminateProcess(handle, 1)?;
+        let handle =
+            OpenProcess(access, false, pid).context(format!("OpenProcess(PID {})", pid))?;
+        if created.is_some() && handle_creation_time(handle) != created {
+            let _ = CloseHandle(handle);
+            return Ok(());
+        }
+        let result = windows::Win32::System::Threading::TerminateProcess(handle, exit_code);
         let _ = CloseHandle(handle);
+        result.context(format!("TerminateProcess(PID {})", pid))?;
     }
     Ok(())
 }
 
-pub fn enumerate_processes() -> Result<Vec<ProcessInfo>, Box<dyn std::error::Error>> {
-    let mut processes = Vec::new();
-    let mut parent_map: HashMap<u32, u32> = HashMap::new();
+/// PID -> parent PID for every running process, from a ToolHelp snapshot.
+fn parent_pids() -> Result<HashMap<u32, u32>> {
+    Ok(snapshot_processes()?
+        .into_iter()
+        .map(|(pid, (parent, _))| (pid, parent))
+        .collect())
+}
+
+/// PID -> (parent PID, image name) for every running process, from a ToolHelp snapshot.
+fn snapshot_processes() -> Result<HashMap<u32, (u32, String)>> {
+    let mut parent_map = HashMap::new();
 
     unsafe {
-        // First, get parent PIDs using ToolHelp API
-        let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
+        let snapshot =
+            CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0).context("CreateToolhelp32Snapshot")?;
 
         let mut entry: PROCESSENTRY32W = std::mem::zeroed();
         entry.dwSize = std::mem::size_of::<PROCESSENTRY32W>() as u32;
 
         if Process32FirstW(snapshot, &mut entry).is_ok() {
             loop {
-                let pid = entry.th32ProcessID;
-                let parent_pid = entry.th32ParentProcessID;
-                parent_map.insert(pid, parent_pid);
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+                parent_map.insert(entry.th32ProcessID, (entry.th32ParentProcessID, name));
 
                 if Process32NextW(snapshot, &mut entry).is_err() {
                     break;
@@ -100,7 +293,159 @@ pub fn enumerate_processes() -> Result<Vec<ProcessInfo>, Box<dyn std::error::Err
         }
 
         let _ = CloseHandle(snapshot);
+    }
+
+    Ok(parent_map)
+}
+
+/// Descendants of `pid` in breadth-first order, so parents always come before their
+/// children, each with its creation time. A parent PID is only a number: once the parent
+/// exits, a new process can get the same PID and would look like the parent of processes
+/// it never started, say a user app on the PID of the smss.exe that started csrss.exe. So
+/// a child only counts if it was created after its parent. Processes whose creation time
+/// can't be read are left out, as is everything under them.
+fn descendants(pid: u32, parent_map: &HashMap<u32, u32>) -> Vec<(u32, u64)> {
+    let Some(root_created) = creation_time(pid) else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    let mut seen = std::collections::HashSet::from([pid]);
+    let mut next = 0;
+    let (mut current, mut current_created) = (pid, root_created);
+    loop {
+        for (&child, &parent) in parent_map {
+            if parent != current || child == 0 || seen.contains(&child) {
+                continue;
+            }
+            if let Some(created) = creation_time(child)
+                && created >= current_created
+            {
+                seen.insert(child);
+                found.push((child, created));
+            }
+        }
+        let Some(&(child, created)) = found.get(next) else {
+            break;
+        };
+        (current, current_created) = (child, created);
+        next += 1;
+    }
+    found
+}
 
+/// The image name and creation time of the running process `pid`, read from the process
+/// itself rather than a snapshot. None if it can't be opened or its image read.
+pub fn identify(pid: u32) -> Option<(String, u64)> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buffer = [0u16; 260];
+        let mut len = buffer.len() as u32;
+        let path = QueryFullProcessImageNameW(
+            handle,
+            PROCESS_NAME_FORMAT(0),
+            PWSTR(buffer.as_mut_ptr()),
+            &mut len,
+        )
+        .ok()
+        .map(|()| String::from_utf16_lossy(&buffer[..len as usize]));
+        let created = handle_creation_time(handle);
+        let _ = CloseHandle(handle);
+        let path = path?;
+        Some((path.rsplit('\\').next().unwrap_or(&path).to_string(), created?))
+    }
+}
+
+/// When `pid` was created, in FILETIME ticks, or None if it can't be opened.
+pub fn creation_time(pid: u32) -> Option<u64> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let created = handle_creation_time(handle);
+        let _ = CloseHandle(handle);
+        created
+    }
+}
+
+/// When the process behind `handle` was created, in FILETIME ticks.
+fn handle_creation_time(handle: HANDLE) -> Option<u64> {
+    let mut creation = FILETIME::default();
+    let mut exit = FILETIME::default();
+    let mut kernel = FILETIME::default();
+    let mut user = FILETIME::default();
+    unsafe { GetProcessTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user) }
+        .ok()
+        .map(|()| filetime_to_u64(creation))
+}
+
+/// A process a tree or job kill takes down along with the one picked.
+#[derive(Debug, Clone)]
+pub struct KillMember {
+    pub pid: u32,
+    pub name: String,
+    /// 1 for a child of the root, 2 for a grandchild, and so on. Job members are all 1.
+    pub depth: usize,
+    /// Creation time in FILETIME ticks, to tell the process from a later one on its PID.
+    pub created: u64,
+}
+
+/// The descendants `kill_options` would terminate for a tree kill of `pid`, from a fresh
+/// snapshot. Ordered depth-first, so each process follows its parent and can be drawn
+/// indented under it.
+pub fn process_tree(pid: u32) -> Result<Vec<KillMember>> {
+    let processes = snapshot_processes()?;
+    let parent_map = processes
+        .iter()
+        .map(|(&pid, (parent, _))| (pid, *parent))
+        .collect();
+    let members: HashMap<u32, u64> = descendants(pid, &parent_map).into_iter().collect();
+
+    let mut tree = Vec::with_capacity(members.len());
+    let mut stack = vec![(pid, 0)];
+    while let Some((current, depth)) = stack.pop() {
+        if current != pid {
+            let name = processes.get(&current).map(|(_, name)| name.clone()).unwrap_or_default();
+            let created = members[&current];
+            tree.push(KillMember { pid: current, name, depth, created });
+        }
+        // Pushed in reverse so the lowest PID is popped, and listed, first
+        let mut children: Vec<u32> = members
+            .keys()
+            .copied()
+            .filter(|child| parent_map.get(child) == Some(&current))
+            .collect();
+        children.sort_unstable_by(|a, b| b.cmp(a));
+        stack.extend(children.into_iter().map(|child| (child, depth + 1)));
+    }
+    Ok(tree)
+}
+
+/// The other processes a kill of `pid` with `options` takes down: its descendants for a
+/// tree kill, the rest of its job for a job kill. A job member that can't be identified
+/// fails the whole list, since neither the guardrail nor the policy could check it.
+pub fn kill_members(pid: u32, options: &KillOptions) -> Result<Vec<KillMember>> {
+    if options.job {
+        return super::job::job_members(pid)?
+            .into_iter()
+            .filter(|&member| member != pid)
+            .map(|member| {
+                let (name, created) = identify(member).ok_or_else(|| {
+                    format!("Cannot read the name of process {} in the job", member)
+                })?;
+                Ok(KillMember { pid: member, name, depth: 1, created })
+            })
+            .collect();
+    }
+    if options.tree {
+        return process_tree(pid);
+    }
+    Ok(Vec::new())
+}
+
+pub fn enumerate_processes() -> Result<Vec<ProcessInfo>> {
+    let mut processes = Vec::new();
+    // First, get parent PIDs using ToolHelp API
+    let parent_map = parent_pids()?;
+
+    unsafe {
         // Now enumerate processes to get full details
         let mut pids = vec![0u32; 1024];
         let mut bytes_returned = 0u32;
@@ -109,7 +454,8 @@ pub fn enumerate_processes() -> Result<Vec<ProcessInfo>, Box<dyn std::error::Err
             pids.as_mut_ptr(),
             (pids.len() * 4) as u32,
             &mut bytes_returned,
-        )?;
+        )
+        .context("EnumProcesses")?;
 
         let count = bytes_returned as usize / 4;
         pids.truncate(count);
@@ -176,6 +522,18 @@ pub fn enumerate_processes() -> Result<Vec<ProcessInfo>, Box<dyn std::error::Err
                         path,
                         cpu_usage: 0.0,
                         memory_mb: 0.0,
+                        private_mb: 0.0,
+                        gpu_usage: 0.0,
+                        gpu_memory_mb: 0.0,
+                        io_read_bps: 0.0,
+                        io_write_bps: 0.0,
+                        start_time: None,
+                        handle_count: 0,
+                        gdi_objects: 0,
+                        user_objects: 0,
+                        command_line: None,
+                        container: None,
+                        job_id: None,
                         last_cpu_usage: 0.0,
                         last_memory_mb: 0.0,
                     });
@@ -194,14 +552,24 @@ fn filetime_to_u64(ft: FILETIME) -> u64 {
 
 pub fn update_process_metrics(
     processes: &mut [ProcessInfo],
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<()> {
+    let gpu_usage = super::gpu::sample_gpu_usage();
+
     unsafe {
         let now = Instant::now();
         let prev_times = PREV_CPU_TIMES.get_or_init(|| Mutex::new(HashMap::new()));
         let mut prev_times_guard = prev_times.lock().unwrap();
         let mut new_times: HashMap<u32, (u64, Instant)> = HashMap::new();
+        let prev_io = PREV_IO_COUNTERS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut prev_io_guard = prev_io.lock().unwrap();
+        // Rebuilt every poll, so exited processes drop out
+        let mut new_io: HashMap<u32, IoSample> = HashMap::new();
 
         for process in processes.iter_mut() {
+            let gpu = gpu_usage.get(&process.pid).copied().unwrap_or_default();
+            process.gpu_usage = gpu.utilization;
+            process.gpu_memory_mb = gpu.dedicated_mb;
+
             let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, process.pid);
 
             if let Ok(handle) = handle {
@@ -219,17 +587,29 @@ pub fn update_process_metrics(
                 )
                 .is_ok();
 
-                let mut mem_counters = PROCESS_MEMORY_COUNTERS::default();
+                let mut mem_counters = PROCESS_MEMORY_COUNTERS_EX::default();
                 let mem_ok = GetProcessMemoryInfo(
                     handle,
                     &mut mem_counters as *mut _ as *mut _,
-                    mem::size_of::<PROCESS_MEMORY_COUNTERS>() as u32,
+                    mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
                 )
                 .is_ok();
 
+                let mut io_counters = IO_COUNTERS::default();
+                let io_ok = GetProcessIoCounters(handle, &mut io_counters).is_ok();
+
+                let mut handle_count = 0u32;
+                if GetProcessHandleCount(handle, &mut handle_count).is_ok() {
+                    process.handle_count = handle_count;
+                }
+                process.gdi_objects = GetGuiResources(handle, GR_GDIOBJECTS);
+                process.user_objects = GetGuiResources(handle, GR_USEROBJECTS);
+
                 let _ = CloseHandle(handle);
 
                 if times_ok {
+                    process.start_time =
+                        super::network::filetime_to_unix(filetime_to_u64(creation_time) as i64);
                     let total_time = filetime_to_u64(kernel_time) + filetime_to_u64(user_time);
                     new_times.insert(process.pid, (total_time, now));
 
@@ -251,9 +631,39 @@ pub fn update_process_metrics(
 
                 if mem_ok {
                     process.memory_mb = mem_counters.WorkingSetSize as f64 / (1024.0 * 1024.0);
+                    process.private_mb = mem_counters.PrivateUsage as f64 / (1024.0 * 1024.0);
                     // Cache the value for stable display
                     process.last_memory_mb = process.memory_mb;
                 }
+
+                let created = times_ok.then(|| filetime_to_u64(creation_time));
+                if io_ok {
+                    let read = io_counters.ReadTransferCount;
+                    let write = io_counters.WriteTransferCount;
+                    match prev_io_guard.get(&process.pid) {
+                        Some(&(prev_read, prev_write, prev_created, prev_instant))
+                            if prev_created == created =>
+                        {
+                            let elapsed = now.duration_since(prev_instant).as_secs_f64();
+                            if elapsed > 0.0 {
+                                process.io_read_bps =
+                                    read.saturating_sub(prev_read) as f64 / elapsed;
+                                process.io_write_bps =
+                                    write.saturating_sub(prev_write) as f64 / elapsed;
+                            }
+                        }
+                        // First sample of this process; its totals since start aren't a rate
+                        _ => {
+                            process.io_read_bps = 0.0;
+                            process.io_write_bps = 0.0;
+                        }
+                    }
+                    new_io.insert(process.pid, (read, write, created, now));
+                } else if let Some(&sample) = prev_io_guard.get(&process.pid)
+                    && sample.2 == created
+                {
+                    new_io.insert(process.pid, sample);
+                }
             }
         }
 
@@ -262,11 +672,112 @@ pub fn update_process_metrics(
         for (pid, time_data) in new_times {
             prev_times_guard.insert(pid, time_data);
         }
+        *prev_io_guard = new_io;
     }
 
     Ok(())
 }
 
+/// Memory counters for the details pane, in bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryCounters {
+    pub working_set: u64,
+    pub peak_working_set: u64,
+    pub private_bytes: u64,
+    /// Commit charge (PagefileUsage).
+    pub commit: u64,
+    pub peak_commit: u64,
+    pub page_faults: u32,
+}
+
+pub fn memory_counters(pid: u32) -> Result<MemoryCounters> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid)
+            .context(format!("OpenProcess(PID {})", pid))?;
+        let mut counters = PROCESS_MEMORY_COUNTERS_EX::default();
+        let result = GetProcessMemoryInfo(
+            handle,
+            &mut counters as *mut _ as *mut _,
+            mem::size_of::<PROCESS_MEMORY_COUNTERS_EX>() as u32,
+        );
+        let _ = CloseHandle(handle);
+        result.context(format!("GetProcessMemoryInfo(PID {})", pid))?;
+
+        Ok(MemoryCounters {
+            working_set: counters.WorkingSetSize as u64,
+            peak_working_set: counters.PeakWorkingSetSize as u64,
+            private_bytes: counters.PrivateUsage as u64,
+            commit: counters.PagefileUsage as u64,
+            peak_commit: counters.PeakPagefileUsage as u64,
+            page_faults: counters.PageFaultCount,
+        })
+    }
+}
+
+/// Reads a process's full command line out of its memory: NtQueryInformationProcess gives
+/// the PEB address, the PEB points at ProcessParameters, and those hold the CommandLine
+/// string. Needs PROCESS_VM_READ, so protected processes and, without admin, other users'
+/// processes fail.
+pub fn query_command_line(pid: u32) -> Result<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, false, pid)
+            .context(format!("OpenProcess(PID {})", pid))?;
+        let result = read_command_line(handle);
+        let _ = CloseHandle(handle);
+        result
+    }
+}
+
+unsafe fn read_command_line(handle: HANDLE) -> Result<String> {
+    unsafe {
+        let mut info = PROCESS_BASIC_INFORMATION::default();
+        NtQueryInformationProcess(
+            handle,
+            ProcessBasicInformation,
+            &mut info as *mut _ as *mut _,
+            mem::size_of::<PROCESS_BASIC_INFORMATION>() as u32,
+            std::ptr::null_mut(),
+        )
+        .ok()
+        .context("NtQueryInformationProcess")?;
+
+        let mut peb = PEB::default();
+        ReadProcessMemory(
+            handle,
+            info.PebBaseAddress as *const _,
+            &mut peb as *mut _ as *mut _,
+            mem::size_of::<PEB>(),
+            None,
+        )
+        .context("ReadProcessMemory(PEB)")?;
+
+        let mut params = RTL_USER_PROCESS_PARAMETERS::default();
+        ReadProcessMemory(
+            handle,
+            peb.ProcessParameters as *const _,
+            &mut params as *mut _ as *mut _,
+            mem::size_of::<RTL_USER_PROCESS_PARAMETERS>(),
+            None,
+        )
+        .context("ReadProcessMemory(ProcessParameters)")?;
+
+        // Length is in bytes
+        let mut buffer = vec![0u16; params.CommandLine.Length as usize / 2];
+        if buffer.is_empty() {
+            return Ok(String::new());
+        }
+        ReadProcessMemory(
+            handle,
+            params.CommandLine.Buffer.0 as *const _,
+            buffer.as_mut_ptr() as *mut _,
+            buffer.len() * 2,
+            None,
+        )
+        .context("ReadProcessMemory(CommandLine)")?;
+        Ok(String::from_utf16_lossy(&buffer))
+    }
+}
+
 pub fn get_process_details(
     pid: u32,
 ) -> (
@@ -275,18 +786,14 @@ pub fn get_process_details(
     Vec<String>,
     Option<String>,
 ) {
-    let mut command_line = None;
+    let command_line = query_command_line(pid).ok();
     let environment = Vec::new();
     let mut modules = Vec::new();
     let mut error = None;
 
     unsafe {
         // Try to open process with VM read and query access
-        let handle = OpenProcess(
-            PROCESS_QUERY_LIMITED_INFORMATION | windows::Win32::System::Threading::PROCESS_VM_READ,
-            false,
-            pid,
-        );
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION | PROCESS_VM_READ, false, pid);
 
         if let Ok(handle) = handle {
             // Get loaded modules
@@ -1,12 +1,24 @@
 use windows::core::PCWSTR;
 use windows::Win32::System::Services::{
-    CloseServiceHandle, ControlService, EnumServicesStatusExW, OpenSCManagerW, OpenServiceW,
-    QueryServiceConfigW, StartServiceW, ENUM_SERVICE_STATUS_PROCESSW, QUERY_SERVICE_CONFIGW,
-    SC_ENUM_PROCESS_INFO, SERVICE_CONTROL_STOP, SERVICE_QUERY_CONFIG, SERVICE_STATE_ALL,
-    SERVICE_STATUS, SERVICE_STATUS_CURRENT_STATE, SERVICE_WIN32,
+    ChangeServiceConfig2W, ChangeServiceConfigW, CloseServiceHandle, ControlService, CreateServiceW, DeleteService,
+    EnumDependentServicesW, EnumServicesStatusExW, OpenSCManagerW, OpenServiceW,
+    QueryServiceConfig2W, QueryServiceConfigW, QueryServiceStatusEx, StartServiceW,
+    ENUM_SERVICE_STATUSW, ENUM_SERVICE_STATUS_PROCESSW, QUERY_SERVICE_CONFIGW, SC_ACTION,
+    SC_ACTION_NONE, SC_ACTION_REBOOT, SC_ACTION_RESTART, SC_ACTION_RUN_COMMAND,
+    SC_ENUM_PROCESS_INFO, SC_MANAGER_CREATE_SERVICE, SC_STATUS_PROCESS_INFO, SERVICE_ACTIVE,
+    SERVICE_AUTO_START, SERVICE_CHANGE_CONFIG, SERVICE_CONFIG_DELAYED_AUTO_START_INFO,
+    SERVICE_CONFIG_DESCRIPTION, SERVICE_CONFIG_FAILURE_ACTIONS, SERVICE_CONTROL_STOP,
+    SERVICE_DELAYED_AUTO_START_INFO, SERVICE_DEMAND_START, SERVICE_DESCRIPTIONW,
+    SERVICE_DISABLED, SERVICE_ERROR, SERVICE_ERROR_NORMAL, SERVICE_FAILURE_ACTIONSW,
+    SERVICE_NO_CHANGE, SERVICE_START_TYPE,
+    SERVICE_QUERY_CONFIG, SERVICE_QUERY_STATUS, SERVICE_RUNNING, SERVICE_STATE_ALL,
+    SERVICE_STATUS, SERVICE_STATUS_CURRENT_STATE, SERVICE_STATUS_PROCESS, SERVICE_STOPPED,
+    SERVICE_WIN32, SERVICE_WIN32_OWN_PROCESS,
 };
 
-#[derive(Debug, Clone, serde::Serialize)]
+use crate::error::{Context, Result};
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[allow(dead_code)]
 pub struct ServiceInfo {
     pub service_name: String,
@@ -17,6 +29,13 @@ pub struct ServiceInfo {
     pub pid: u32,
 }
 
+#[derive(Debug, Clone)]
+pub struct DependentService {
+    pub service_name: String,
+    pub display_name: String,
+    pub status: String,
+}
+
 fn status_to_string(current_state: SERVICE_STATUS_CURRENT_STATE) -> String {
     match current_state.0 {
         0x00000001 => "Stopped".to_string(),
@@ -66,9 +85,10 @@ unsafe fn pwstr_to_string(ptr: windows::core::PWSTR) -> String {
     }
 }
 
-pub fn enumerate_services() -> Result<Vec<ServiceInfo>, Box<dyn std::error::Error>> {
+pub fn enumerate_services() -> Result<Vec<ServiceInfo>> {
     unsafe {
-        let sc_manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0004)?;
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0004).context("OpenSCManagerW")?;
 
         let mut bytes_needed = 0u32;
         let mut services_returned = 0u32;
@@ -103,7 +123,8 @@ pub fn enumerate_services() -> Result<Vec<ServiceInfo>, Box<dyn std::error::Erro
             &mut services_returned,
             None,
             PCWSTR::null(),
-        )?;
+        )
+        .context("EnumServicesStatusExW")?;
 
         let _ = CloseServiceHandle(sc_manager);
 
@@ -176,34 +197,692 @@ pub fn enumerate_services() -> Result<Vec<ServiceInfo>, Box<dyn std::error::Erro
     }
 }
 
-pub fn toggle_service(
-    service_name: &str,
-    current_status: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+/// Running services that depend on `service_name`, directly or indirectly.
+/// The SCM returns them in reverse start order, which is the order they must be stopped in.
+pub fn active_dependents(service_name: &str) -> Result<Vec<DependentService>> {
     unsafe {
-        let sc_manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001)?;
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
 
         let wide_name: Vec<u16> = service_name
             .encode_utf16()
             .chain(std::iter::once(0))
             .collect();
-        let service = OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0001 | 0x0020)?;
+        // SERVICE_ENUMERATE_DEPENDENTS
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0008) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let mut bytes_needed = 0u32;
+        let mut services_returned = 0u32;
+
+        // A zero-sized query succeeds when there are no dependents, otherwise reports the size
+        let _ = EnumDependentServicesW(
+            service,
+            SERVICE_ACTIVE,
+            None,
+            0,
+            &mut bytes_needed,
+            &mut services_returned,
+        );
+
+        let mut dependents = Vec::new();
+        if bytes_needed > 0 {
+            let mut buffer: Vec<u8> = vec![0; bytes_needed as usize];
+            let result = EnumDependentServicesW(
+                service,
+                SERVICE_ACTIVE,
+                Some(buffer.as_mut_ptr() as *mut ENUM_SERVICE_STATUSW),
+                bytes_needed,
+                &mut bytes_needed,
+                &mut services_returned,
+            );
+            if let Err(e) = result {
+                let _ = CloseServiceHandle(service);
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("EnumDependentServicesW({})", service_name));
+            }
+
+            let ptr = buffer.as_ptr() as *const ENUM_SERVICE_STATUSW;
+            for i in 0..services_returned as usize {
+                let entry = &*ptr.add(i);
+                dependents.push(DependentService {
+                    service_name: pwstr_to_string(entry.lpServiceName),
+                    display_name: pwstr_to_string(entry.lpDisplayName),
+                    status: status_to_string(entry.ServiceStatus.dwCurrentState),
+                });
+            }
+        }
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        Ok(dependents)
+    }
+}
+
+pub fn start_service(service_name: &str) -> Result<()> {
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        // SERVICE_START
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0010) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let result = StartServiceW(service, None);
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("StartServiceW({})", service_name))?;
+    }
+
+    Ok(())
+}
+
+pub fn stop_service(service_name: &str) -> Result<()> {
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        // SERVICE_STOP
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0020) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
 
         let mut status = SERVICE_STATUS::default();
+        let result = ControlService(service, SERVICE_CONTROL_STOP, &mut status);
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("ControlService({}, STOP)", service_name))?;
+    }
+
+    Ok(())
+}
 
-        match current_status {
-            "Running" => {
-                ControlService(service, SERVICE_CONTROL_STOP, &mut status)?;
+/// Start types offered when creating a service, as shown in the Controller.
+pub const START_TYPES: [&str; 3] = ["Manual", "Auto", "Disabled"];
+
+/// Registers a new own-process service running as LocalSystem, like `sc create`.
+pub fn create_service(
+    service_name: &str,
+    display_name: &str,
+    binary_path: &str,
+    start_type: &str,
+) -> Result<()> {
+    let start_type = match start_type {
+        "Auto" => SERVICE_AUTO_START,
+        "Disabled" => SERVICE_DISABLED,
+        _ => SERVICE_DEMAND_START,
+    };
+    let wide_name: Vec<u16> = service_name
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let wide_display: Vec<u16> = display_name
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let wide_path: Vec<u16> = quote_image_path(binary_path)
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    unsafe {
+        let sc_manager = OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), SC_MANAGER_CREATE_SERVICE)
+            .context("OpenSCManagerW")?;
+
+        let result = CreateServiceW(
+            sc_manager,
+            PCWSTR(wide_name.as_ptr()),
+            PCWSTR(wide_display.as_ptr()),
+            SERVICE_QUERY_STATUS,
+            SERVICE_WIN32_OWN_PROCESS,
+            start_type,
+            SERVICE_ERROR_NORMAL,
+            PCWSTR(wide_path.as_ptr()),
+            PCWSTR::null(),
+            None,
+            PCWSTR::null(),
+            PCWSTR::null(),
+            PCWSTR::null(),
+        );
+
+        if let Ok(service) = &result {
+            let _ = CloseServiceHandle(*service);
+        }
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("CreateServiceW({})", service_name))?;
+    }
+
+    Ok(())
+}
+
+/// Quotes the executable of a service command line that has spaces in it. Unquoted,
+/// the SCM tries `C:\Program.exe` before `C:\Program Files\...`, so anyone who can
+/// write there runs as LocalSystem. Arguments after the `.exe` stay outside the quotes.
+fn quote_image_path(binary_path: &str) -> String {
+    let binary_path = binary_path.trim();
+    if binary_path.starts_with('"') || !binary_path.contains(' ') {
+        return binary_path.to_string();
+    }
+    let lower = binary_path.to_ascii_lowercase();
+    let image_end = lower
+        .match_indices(".exe")
+        .map(|(i, _)| i + ".exe".len())
+        .find(|&end| lower[end..].is_empty() || lower[end..].starts_with(' '))
+        .unwrap_or(binary_path.len());
+    let (image, args) = binary_path.split_at(image_end);
+    if !image.contains(' ') {
+        return binary_path.to_string();
+    }
+    format!("\"{}\"{}", image, args)
+}
+
+/// Marks a service for deletion. A running service is removed once it stops
+/// and every open handle to it is closed.
+pub fn delete_service(service_name: &str) -> Result<()> {
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        // DELETE
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0001_0000) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
             }
-            "Stopped" => {
-                StartServiceW(service, None)?;
+        };
+
+        let result = DeleteService(service);
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("DeleteService({})", service_name))?;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum RecoveryAction {
+    None,
+    Restart,
+    Reboot,
+    RunCommand,
+}
+
+impl RecoveryAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecoveryAction::None => "Take no action",
+            RecoveryAction::Restart => "Restart the service",
+            RecoveryAction::Reboot => "Restart the computer",
+            RecoveryAction::RunCommand => "Run a program",
+        }
+    }
+
+    /// Editing only toggles between no action and restarting the service.
+    /// Reboot and run-command actions are kept as-is until changed.
+    pub fn toggle(&self) -> Self {
+        match self {
+            RecoveryAction::Restart => RecoveryAction::None,
+            _ => RecoveryAction::Restart,
+        }
+    }
+}
+
+/// A service's recovery options, normalized to the first / second / subsequent
+/// failure slots that services.msc shows.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct FailureActions {
+    /// Seconds without failures before the failure count resets. `u32::MAX` means never.
+    pub reset_period_secs: u32,
+    /// Action and delay in milliseconds for each slot.
+    pub actions: [(RecoveryAction, u32); 3],
+    pub command: String,
+}
+
+pub fn query_failure_actions(service_name: &str) -> Result<FailureActions> {
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), SERVICE_QUERY_CONFIG)
+        {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let mut bytes_needed = 0u32;
+        let _ = QueryServiceConfig2W(service, SERVICE_CONFIG_FAILURE_ACTIONS, None, &mut bytes_needed);
+        // u64 backing keeps the embedded pointers aligned
+        let mut buffer: Vec<u64> = vec![0; (bytes_needed as usize).div_ceil(8).max(1)];
+        let result = QueryServiceConfig2W(
+            service,
+            SERVICE_CONFIG_FAILURE_ACTIONS,
+            Some(std::slice::from_raw_parts_mut(
+                buffer.as_mut_ptr() as *mut u8,
+                buffer.len() * 8,
+            )),
+            &mut bytes_needed,
+        );
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("QueryServiceConfig2W({})", service_name))?;
+
+        let info = &*(buffer.as_ptr() as *const SERVICE_FAILURE_ACTIONSW);
+        let raw: &[SC_ACTION] = if info.lpsaActions.is_null() {
+            &[]
+        } else {
+            std::slice::from_raw_parts(info.lpsaActions, info.cActions as usize)
+        };
+
+        // The last configured action repeats for every later failure
+        let mut actions = [(RecoveryAction::None, 0u32); 3];
+        for (i, slot) in actions.iter_mut().enumerate() {
+            if let Some(action) = raw.get(i).or(raw.last()) {
+                let kind = match action.Type {
+                    SC_ACTION_RESTART => RecoveryAction::Restart,
+                    SC_ACTION_REBOOT => RecoveryAction::Reboot,
+                    SC_ACTION_RUN_COMMAND => RecoveryAction::RunCommand,
+                    _ => RecoveryAction::None,
+                };
+                *slot = (kind, action.Delay);
             }
-            _ => {}
         }
 
+        Ok(FailureActions {
+            reset_period_secs: info.dwResetPeriod,
+            actions,
+            command: pwstr_to_string(info.lpCommand),
+        })
+    }
+}
+
+/// The service's description as shown in services.msc, empty if it has none.
+pub fn query_description(service_name: &str) -> Result<String> {
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), SERVICE_QUERY_CONFIG)
+        {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let mut bytes_needed = 0u32;
+        let _ = QueryServiceConfig2W(service, SERVICE_CONFIG_DESCRIPTION, None, &mut bytes_needed);
+        let mut buffer: Vec<u64> = vec![0; (bytes_needed as usize).div_ceil(8).max(1)];
+        let result = QueryServiceConfig2W(
+            service,
+            SERVICE_CONFIG_DESCRIPTION,
+            Some(std::slice::from_raw_parts_mut(
+                buffer.as_mut_ptr() as *mut u8,
+                buffer.len() * 8,
+            )),
+            &mut bytes_needed,
+        );
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("QueryServiceConfig2W({})", service_name))?;
+
+        let info = &*(buffer.as_ptr() as *const SERVICE_DESCRIPTIONW);
+        Ok(pwstr_to_string(info.lpDescription))
+    }
+}
+
+/// Writes the recovery actions back. The reboot message is left unchanged, and so is the
+/// command line when `command` is empty.
+pub fn set_failure_actions(service_name: &str, failure_actions: &FailureActions) -> Result<()> {
+    let mut actions: Vec<SC_ACTION> = failure_actions
+        .actions
+        .iter()
+        .map(|(kind, delay)| SC_ACTION {
+            Type: match kind {
+                RecoveryAction::None => SC_ACTION_NONE,
+                RecoveryAction::Restart => SC_ACTION_RESTART,
+                RecoveryAction::Reboot => SC_ACTION_REBOOT,
+                RecoveryAction::RunCommand => SC_ACTION_RUN_COMMAND,
+            },
+            Delay: *delay,
+        })
+        .collect();
+    // Without SeShutdownPrivilege enabled, a reboot action fails with access denied
+    if failure_actions.actions.iter().any(|(kind, _)| *kind == RecoveryAction::Reboot) {
+        crate::sys::process::enable_shutdown_privilege()?;
+    }
+    let mut wide_command: Vec<u16> = failure_actions
+        .command
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+
+    let info = SERVICE_FAILURE_ACTIONSW {
+        dwResetPeriod: failure_actions.reset_period_secs,
+        lpRebootMsg: windows::core::PWSTR::null(),
+        lpCommand: if failure_actions.command.is_empty() {
+            windows::core::PWSTR::null()
+        } else {
+            windows::core::PWSTR(wide_command.as_mut_ptr())
+        },
+        cActions: actions.len() as u32,
+        lpsaActions: actions.as_mut_ptr(),
+    };
+
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        // SERVICE_CHANGE_CONFIG | SERVICE_START - restart actions need start access
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), 0x0002 | 0x0010) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let result = ChangeServiceConfig2W(
+            service,
+            SERVICE_CONFIG_FAILURE_ACTIONS,
+            Some(&info as *const _ as *const core::ffi::c_void),
+        );
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("ChangeServiceConfig2W({}, FAILURE_ACTIONS)", service_name))?;
+    }
+
+    Ok(())
+}
+
+/// The settings of a service that are exported to a file and re-applied with `apply_config`,
+/// e.g. on another machine.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ServiceConfig {
+    pub service_name: String,
+    pub display_name: String,
+    /// "Auto", "Manual" or "Disabled", as in `START_TYPES`.
+    pub start_type: String,
+    #[serde(default)]
+    pub delayed_auto_start: bool,
+    /// Account the service logs on as, e.g. "LocalSystem" or "NT AUTHORITY\LocalService".
+    pub account: String,
+    pub recovery: FailureActions,
+}
+
+/// Accounts a service can be switched to without a password: the built-in service
+/// accounts, virtual `NT SERVICE\` accounts and group managed service accounts.
+fn is_passwordless_account(account: &str) -> bool {
+    let lower = account.to_lowercase();
+    matches!(
+        lower.as_str(),
+        "localsystem"
+            | ".\\localsystem"
+            | "nt authority\\system"
+            | "nt authority\\localservice"
+            | "nt authority\\networkservice"
+    ) || lower.starts_with("nt service\\")
+        || lower.ends_with('$')
+}
+
+/// Reads the exportable settings of a service.
+pub fn query_config(service_name: &str) -> Result<ServiceConfig> {
+    let recovery = query_failure_actions(service_name)?;
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), SERVICE_QUERY_CONFIG)
+        {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let mut bytes_needed = 0u32;
+        let _ = QueryServiceConfigW(service, None, 0, &mut bytes_needed);
+        let mut buffer: Vec<u64> = vec![0; (bytes_needed as usize).div_ceil(8).max(1)];
+        let result = QueryServiceConfigW(
+            service,
+            Some(buffer.as_mut_ptr() as *mut _),
+            (buffer.len() * 8) as u32,
+            &mut bytes_needed,
+        );
+
+        let mut delayed = SERVICE_DELAYED_AUTO_START_INFO::default();
+        let mut delayed_needed = 0u32;
+        // Fails on old systems without delayed start, which reads as not delayed
+        let _ = QueryServiceConfig2W(
+            service,
+            SERVICE_CONFIG_DELAYED_AUTO_START_INFO,
+            Some(std::slice::from_raw_parts_mut(
+                &mut delayed as *mut _ as *mut u8,
+                std::mem::size_of::<SERVICE_DELAYED_AUTO_START_INFO>(),
+            )),
+            &mut delayed_needed,
+        );
+
         let _ = CloseServiceHandle(service);
         let _ = CloseServiceHandle(sc_manager);
+        result.context(format!("QueryServiceConfigW({})", service_name))?;
+
+        let config = &*(buffer.as_ptr() as *const QUERY_SERVICE_CONFIGW);
+        Ok(ServiceConfig {
+            service_name: service_name.to_string(),
+            display_name: pwstr_to_string(config.lpDisplayName),
+            start_type: start_type_to_string(config.dwStartType.0),
+            delayed_auto_start: delayed.fDelayedAutostart.as_bool(),
+            account: pwstr_to_string(config.lpServiceStartName),
+            recovery,
+        })
     }
+}
+
+/// Applies exported settings to the service of the same name. The account is only changed
+/// to one that needs no password; any other account is reported as an error after the
+/// start type and recovery options have been applied.
+pub fn apply_config(config: &ServiceConfig) -> Result<()> {
+    let name = &config.service_name;
+    let current = query_config(name)?;
+    let account_change = !config.account.is_empty()
+        && !config.account.eq_ignore_ascii_case(&current.account);
+    let set_account = account_change && is_passwordless_account(&config.account);
+
+    let start_type = match config.start_type.as_str() {
+        "Auto" => SERVICE_AUTO_START,
+        "Manual" => SERVICE_DEMAND_START,
+        "Disabled" => SERVICE_DISABLED,
+        _ => SERVICE_START_TYPE(SERVICE_NO_CHANGE),
+    };
+    let wide_account: Vec<u16> = config
+        .account
+        .encode_utf16()
+        .chain(std::iter::once(0))
+        .collect();
+    let empty_password = [0u16];
 
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+        let service = match OpenServiceW(
+            sc_manager,
+            PCWSTR(wide_name.as_ptr()),
+            SERVICE_CHANGE_CONFIG | SERVICE_QUERY_CONFIG,
+        ) {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", name));
+            }
+        };
+
+        let (account, password) = if set_account {
+            (PCWSTR(wide_account.as_ptr()), PCWSTR(empty_password.as_ptr()))
+        } else {
+            (PCWSTR::null(), PCWSTR::null())
+        };
+        let result = ChangeServiceConfigW(
+            service,
+            windows::Win32::System::Services::ENUM_SERVICE_TYPE(SERVICE_NO_CHANGE),
+            start_type,
+            SERVICE_ERROR(SERVICE_NO_CHANGE),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            None,
+            PCWSTR::null(),
+            account,
+            password,
+            PCWSTR::null(),
+        )
+        .context(format!("ChangeServiceConfigW({})", name));
+
+        // Delayed start only means something for automatic services
+        let delayed_result = if result.is_ok() && start_type == SERVICE_AUTO_START {
+            let delayed = SERVICE_DELAYED_AUTO_START_INFO {
+                fDelayedAutostart: config.delayed_auto_start.into(),
+            };
+            ChangeServiceConfig2W(
+                service,
+                SERVICE_CONFIG_DELAYED_AUTO_START_INFO,
+                Some(&delayed as *const _ as *const core::ffi::c_void),
+            )
+            .context(format!("ChangeServiceConfig2W({}, DELAYED_AUTO_START_INFO)", name))
+        } else {
+            Ok(())
+        };
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result?;
+        delayed_result?;
+    }
+
+    set_failure_actions(name, &config.recovery)?;
+
+    if account_change && !set_account {
+        return Err(format!(
+            "{} logs on as {} here; {} needs a password, so set it in services.msc",
+            name, current.account, config.account
+        )
+        .into());
+    }
     Ok(())
 }
+
+/// Blocks until the service reports Running or `timeout` passes, returning the last seen status.
+pub fn wait_until_running(service_name: &str, timeout: std::time::Duration) -> Result<String> {
+    wait_for_state(service_name, SERVICE_RUNNING, timeout)
+}
+
+/// Blocks until the service reports Stopped or `timeout` passes, returning the last seen status.
+pub fn wait_until_stopped(service_name: &str, timeout: std::time::Duration) -> Result<String> {
+    wait_for_state(service_name, SERVICE_STOPPED, timeout)
+}
+
+/// Polls a service until it reaches `target` or `timeout` passes.
+/// Returns the state it ended in, so callers can tell a timeout from success.
+fn wait_for_state(
+    service_name: &str,
+    target: SERVICE_STATUS_CURRENT_STATE,
+    timeout: std::time::Duration,
+) -> Result<String> {
+    let started = std::time::Instant::now();
+
+    unsafe {
+        let sc_manager =
+            OpenSCManagerW(PCWSTR::null(), PCWSTR::null(), 0x0001).context("OpenSCManagerW")?;
+
+        let wide_name: Vec<u16> = service_name
+            .encode_utf16()
+            .chain(std::iter::once(0))
+            .collect();
+        let service = match OpenServiceW(sc_manager, PCWSTR(wide_name.as_ptr()), SERVICE_QUERY_STATUS)
+        {
+            Ok(service) => service,
+            Err(e) => {
+                let _ = CloseServiceHandle(sc_manager);
+                return Err(e).context(format!("OpenServiceW({})", service_name));
+            }
+        };
+
+        let result = loop {
+            let mut status = SERVICE_STATUS_PROCESS::default();
+            let mut bytes_needed = 0u32;
+            let buffer = std::slice::from_raw_parts_mut(
+                &mut status as *mut SERVICE_STATUS_PROCESS as *mut u8,
+                std::mem::size_of::<SERVICE_STATUS_PROCESS>(),
+            );
+            if let Err(e) =
+                QueryServiceStatusEx(service, SC_STATUS_PROCESS_INFO, Some(buffer), &mut bytes_needed)
+            {
+                break Err(e).context(format!("QueryServiceStatusEx({})", service_name));
+            }
+
+            if status.dwCurrentState == target || started.elapsed() >= timeout {
+                break Ok(status_to_string(status.dwCurrentState));
+            }
+            std::thread::sleep(std::time::Duration::from_millis(250));
+        };
+
+        let _ = CloseServiceHandle(service);
+        let _ = CloseServiceHandle(sc_manager);
+        result
+    }
+}
@@ -0,0 +1,160 @@
+use std::os::windows::process::CommandExt;
+use std::process::{Command, Stdio};
+
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::Threading::CREATE_NEW_CONSOLE;
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+use crate::error::{ApertureError, Context, Result};
+
+fn to_wide(s: &str) -> Vec<u16> {
+    s.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// The command interpreter, %COMSPEC%.
+pub fn comspec() -> String {
+    std::env::var("COMSPEC").unwrap_or_else(|_| "cmd.exe".to_string())
+}
+
+/// A command line run through the command interpreter, for commands the user typed into
+/// the config. Nothing from a row may go into `line`, as cmd expands `%VAR%` and obeys
+/// `&`, `|` and `>` wherever they appear.
+pub fn shell_command(line: &str) -> Command {
+    let mut command = Command::new(comspec());
+    // raw_arg, as cmd parses its /C line itself and would choke on added quotes
+    command.arg("/C").raw_arg(line);
+    command
+}
+
+/// A program and its arguments, started directly so each argument reaches it as is.
+pub fn program_command(argv: &[String]) -> Option<Command> {
+    let (program, args) = argv.split_first()?;
+    let mut command = Command::new(program);
+    command.args(args);
+    Some(command)
+}
+
+/// `argv` as one command line, for showing what a tool will run.
+pub fn command_line(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| quote_arg(arg))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Starts a program in a window of its own without waiting for it. Console programs get
+/// a new console and GUI programs just open.
+pub fn start_detached(argv: &[String]) -> Result<()> {
+    let mut command = program_command(argv).ok_or("Empty command")?;
+    command
+        .creation_flags(CREATE_NEW_CONSOLE.0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .context(format!("Failed to start {}", command_line(argv)))?;
+    Ok(())
+}
+
+/// Opens a URL in the user's default browser.
+pub fn open_url(url: &str) -> Result<()> {
+    let url_w = to_wide(url);
+    let result = unsafe {
+        ShellExecuteW(
+            HWND::default(),
+            w!("open"),
+            PCWSTR(url_w.as_ptr()),
+            PCWSTR::null(),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Values above 32 mean success; anything else is an error code
+    let code = result.0 as usize;
+    if code <= 32 {
+        return Err(ApertureError::win32(format!("ShellExecuteW({})", url), code as u32));
+    }
+    Ok(())
+}
+
+/// Quotes a command line argument the way CommandLineToArgvW splits it back.
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are doubled, plus one to escape the quote
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            quoted.push(c);
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Starts this executable again with the same arguments through the UAC prompt. The
+/// elevated copy opens in a console of its own, as UAC can't attach it to this one.
+pub fn relaunch_elevated() -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the Aperture executable")?;
+    let exe_w = to_wide(&exe.to_string_lossy());
+    let params = std::env::args()
+        .skip(1)
+        .map(|arg| quote_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let params_w = to_wide(&params);
+    let result = unsafe {
+        ShellExecuteW(
+            HWND::default(),
+            w!("runas"),
+            PCWSTR(exe_w.as_ptr()),
+            PCWSTR(params_w.as_ptr()),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Declining the UAC prompt fails with ERROR_CANCELLED or access denied
+    let code = result.0 as usize;
+    if code <= 32 {
+        return Err(ApertureError::win32("ShellExecuteW(runas)", code as u32));
+    }
+    Ok(())
+}
+
+/// Runs a whois command line such as `whois -nobanner {ip}` and returns its output.
+/// Windows has no built-in whois; Sysinternals' whois.exe on the PATH works.
+pub fn run_whois(command: &str, ip: &str) -> Result<String> {
+    let mut parts = command.split_whitespace().map(|part| part.replace("{ip}", ip));
+    let program = parts.next().ok_or("No whois command configured")?;
+    let args: Vec<String> = parts.collect();
+
+    let output = Command::new(&program)
+        .args(&args)
+        .output()
+        .context(format!("Failed to run {}", program))?;
+
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    if !output.status.success() {
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        if text.trim().is_empty() {
+            return Err(format!("{} exited with {}", program, output.status).into());
+        }
+    }
+    Ok(text)
+}
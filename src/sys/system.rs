@@ -0,0 +1,205 @@
+use std::mem;
+use std::time::Duration;
+use windows::core::PWSTR;
+use windows::Wdk::System::SystemServices::RtlGetVersion;
+use windows::Win32::Foundation::{FILETIME, SYSTEMTIME};
+use windows::Win32::NetworkManagement::IpHelper::{
+    FreeMibTable, GetIfTable2, IF_TYPE_SOFTWARE_LOOPBACK, MIB_IF_ROW2, MIB_IF_TABLE2,
+};
+use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+use windows::Win32::Storage::FileSystem::FileTimeToLocalFileTime;
+use windows::Win32::System::SystemInformation::{
+    ComputerNameDnsFullyQualified, GetComputerNameExW, GetLocalTime, GetSystemTimeAsFileTime,
+    GetTickCount64, GlobalMemoryStatusEx, MEMORYSTATUSEX, OSVERSIONINFOW,
+};
+use windows::Win32::System::Threading::GetSystemTimes;
+use windows::Win32::System::Time::FileTimeToSystemTime;
+
+use crate::error::{Context, Result};
+
+/// Raw system-wide counters. CPU times and octet counts are cumulative,
+/// so rates come from the delta between two samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemSample {
+    pub idle_time: u64,
+    pub kernel_time: u64,
+    pub user_time: u64,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    pub net_in_octets: u64,
+    pub net_out_octets: u64,
+}
+
+/// Cumulative counters of one network interface.
+#[derive(Debug, Clone)]
+pub struct InterfaceSample {
+    pub index: u32,
+    /// Name as in Network Connections, e.g. "Ethernet" or "Wi-Fi".
+    pub alias: String,
+    pub in_octets: u64,
+    pub out_octets: u64,
+    /// Negotiated link speeds in bits per second.
+    pub receive_link_bps: u64,
+    pub transmit_link_bps: u64,
+}
+
+/// Count only physical, connected adapters; filter drivers and virtual switches report the
+/// same traffic again.
+fn is_counted(row: &MIB_IF_ROW2) -> bool {
+    let is_hardware = row.InterfaceAndOperStatusFlags._bitfield & 0x1 != 0;
+    is_hardware && row.Type != IF_TYPE_SOFTWARE_LOOPBACK && row.OperStatus == IfOperStatusUp
+}
+
+fn filetime_to_u64(ft: FILETIME) -> u64 {
+    ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
+}
+
+/// The machine Aperture runs on, for the header, so a screenshot says where it was taken.
+#[derive(Debug, Clone, Default)]
+pub struct MachineInfo {
+    /// Fully qualified DNS name, e.g. "web01.corp.example.com".
+    pub hostname: String,
+    /// e.g. "Windows 10.0.22631".
+    pub os_version: String,
+    /// Local time of the last boot, YYYY-MM-DD HH:MM.
+    pub booted: String,
+}
+
+/// Reads the machine's name, Windows version and boot time. Parts that can't be read
+/// are left empty.
+pub fn machine_info() -> MachineInfo {
+    MachineInfo {
+        hostname: hostname().unwrap_or_default(),
+        os_version: os_version().unwrap_or_default(),
+        booted: boot_time().unwrap_or_default(),
+    }
+}
+
+/// Time since the last boot. Sleep and hibernation count.
+pub fn uptime() -> Duration {
+    Duration::from_millis(unsafe { GetTickCount64() })
+}
+
+fn hostname() -> Option<String> {
+    let mut buffer = [0u16; 256];
+    let mut size = buffer.len() as u32;
+    unsafe {
+        GetComputerNameExW(ComputerNameDnsFullyQualified, PWSTR(buffer.as_mut_ptr()), &mut size)
+            .ok()?;
+    }
+    Some(String::from_utf16_lossy(&buffer[..size as usize]))
+}
+
+/// The real version: GetVersionEx reports whatever the manifest claims compatibility with.
+fn os_version() -> Option<String> {
+    let mut info = OSVERSIONINFOW {
+        dwOSVersionInfoSize: mem::size_of::<OSVERSIONINFOW>() as u32,
+        ..Default::default()
+    };
+    unsafe { RtlGetVersion(&mut info).ok().ok()? };
+    Some(format!(
+        "Windows {}.{}.{}",
+        info.dwMajorVersion, info.dwMinorVersion, info.dwBuildNumber
+    ))
+}
+
+/// Now minus the uptime, in local time.
+fn boot_time() -> Option<String> {
+    unsafe {
+        // FILETIME counts 100ns intervals
+        let now = filetime_to_u64(GetSystemTimeAsFileTime());
+        let boot = now.saturating_sub(GetTickCount64() * 10_000);
+        let boot = FILETIME {
+            dwLowDateTime: boot as u32,
+            dwHighDateTime: (boot >> 32) as u32,
+        };
+        let mut local = FILETIME::default();
+        FileTimeToLocalFileTime(&boot, &mut local).ok()?;
+        let mut time = SYSTEMTIME::default();
+        FileTimeToSystemTime(&local, &mut time).ok()?;
+        Some(format!(
+            "{}-{:02}-{:02} {:02}:{:02}",
+            time.wYear, time.wMonth, time.wDay, time.wHour, time.wMinute
+        ))
+    }
+}
+
+/// Local wall-clock time as HH:MM:SS.
+pub fn local_clock() -> String {
+    let now = unsafe { GetLocalTime() };
+    format!("{:02}:{:02}:{:02}", now.wHour, now.wMinute, now.wSecond)
+}
+
+pub fn sample_system() -> Result<SystemSample> {
+    let mut sample = SystemSample::default();
+
+    unsafe {
+        let mut idle = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        GetSystemTimes(Some(&mut idle), Some(&mut kernel), Some(&mut user))
+            .context("GetSystemTimes")?;
+        // Kernel time includes idle time
+        sample.idle_time = filetime_to_u64(idle);
+        sample.kernel_time = filetime_to_u64(kernel);
+        sample.user_time = filetime_to_u64(user);
+
+        let mut mem_status = MEMORYSTATUSEX {
+            dwLength: mem::size_of::<MEMORYSTATUSEX>() as u32,
+            ..Default::default()
+        };
+        GlobalMemoryStatusEx(&mut mem_status).context("GlobalMemoryStatusEx")?;
+        sample.memory_total = mem_status.ullTotalPhys;
+        sample.memory_used = mem_status.ullTotalPhys.saturating_sub(mem_status.ullAvailPhys);
+
+        let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+        if GetIfTable2(&mut table).is_ok() && !table.is_null() {
+            let num_entries = (*table).NumEntries;
+            let rows = (*table).Table.as_ptr();
+
+            for i in 0..num_entries {
+                let row = &*rows.add(i as usize);
+                if !is_counted(row) {
+                    continue;
+                }
+
+                sample.net_in_octets += row.InOctets;
+                sample.net_out_octets += row.OutOctets;
+            }
+
+            FreeMibTable(table as *const _);
+        }
+    }
+
+    Ok(sample)
+}
+
+/// Counters of each physical, connected interface, the ones `sample_system` sums up.
+pub fn sample_interfaces() -> Result<Vec<InterfaceSample>> {
+    let mut interfaces = Vec::new();
+    unsafe {
+        let mut table: *mut MIB_IF_TABLE2 = std::ptr::null_mut();
+        GetIfTable2(&mut table).ok().context("GetIfTable2")?;
+        if table.is_null() {
+            return Ok(interfaces);
+        }
+        let rows = (*table).Table.as_ptr();
+        for i in 0..(*table).NumEntries {
+            let row = &*rows.add(i as usize);
+            if !is_counted(row) {
+                continue;
+            }
+            let len = row.Alias.iter().position(|&c| c == 0).unwrap_or(row.Alias.len());
+            interfaces.push(InterfaceSample {
+                index: row.InterfaceIndex,
+                alias: String::from_utf16_lossy(&row.Alias[..len]),
+                in_octets: row.InOctets,
+                out_octets: row.OutOctets,
+                receive_link_bps: row.ReceiveLinkSpeed,
+                transmit_link_bps: row.TransmitLinkSpeed,
+            });
+        }
+        FreeMibTable(table as *const _);
+    }
+    Ok(interfaces)
+}
@@ -0,0 +1,191 @@
+use std::mem;
+use std::time::Duration;
+
+use windows::Wdk::System::Threading::{NtQueryInformationThread, ThreadSuspendCount};
+use windows::Win32::Foundation::{CloseHandle, GetLastError, FILETIME, HANDLE};
+use windows::Win32::System::Diagnostics::ToolHelp::{
+    CreateToolhelp32Snapshot, Thread32First, Thread32Next, TH32CS_SNAPTHREAD, THREADENTRY32,
+};
+use windows::Win32::System::Threading::{
+    GetCurrentProcessId, GetThreadPriority, GetThreadTimes, OpenThread, ResumeThread,
+    SuspendThread, THREAD_QUERY_LIMITED_INFORMATION, THREAD_SUSPEND_RESUME,
+};
+
+use crate::error::{ApertureError, Context, Result};
+
+#[derive(Debug, Clone)]
+pub struct ThreadInfo {
+    pub tid: u32,
+    /// Kernel base priority (0-31) from the Toolhelp snapshot.
+    pub base_priority: i32,
+    /// Priority relative to the process class (THREAD_PRIORITY_*), if the thread could be opened.
+    pub relative_priority: Option<i32>,
+    /// Times the thread has been suspended and not yet resumed; None if it couldn't be read.
+    pub suspend_count: Option<u32>,
+    /// Kernel plus user time, in 100ns units.
+    pub cpu_time: u64,
+    /// Share of total CPU since the previous sample, 0-100.
+    pub cpu_usage: f32,
+}
+
+impl ThreadInfo {
+    pub fn state(&self) -> &'static str {
+        match self.suspend_count {
+            Some(0) => "Active",
+            Some(_) => "Suspended",
+            None => "No access",
+        }
+    }
+
+    pub fn priority_name(&self) -> &'static str {
+        match self.relative_priority {
+            Some(-15) => "Idle",
+            Some(-2) => "Lowest",
+            Some(-1) => "Below normal",
+            Some(0) => "Normal",
+            Some(1) => "Above normal",
+            Some(2) => "Highest",
+            Some(15) => "Time critical",
+            Some(_) => "Custom",
+            None => "-",
+        }
+    }
+}
+
+/// Threads of `pid` from a Toolhelp snapshot, in TID order. Each thread is opened for its
+/// times, priority and suspend count; threads that can't be opened keep only the
+/// snapshot data.
+pub fn enumerate_threads(pid: u32) -> Result<Vec<ThreadInfo>> {
+    let mut threads = Vec::new();
+
+    unsafe {
+        let snapshot =
+            CreateToolhelp32Snapshot(TH32CS_SNAPTHREAD, 0).context("CreateToolhelp32Snapshot")?;
+
+        let mut entry = THREADENTRY32 {
+            dwSize: mem::size_of::<THREADENTRY32>() as u32,
+            ..Default::default()
+        };
+
+        if Thread32First(snapshot, &mut entry).is_ok() {
+            loop {
+                if entry.th32OwnerProcessID == pid {
+                    threads.push(query_thread(entry.th32ThreadID, entry.tpBasePri));
+                }
+                if Thread32Next(snapshot, &mut entry).is_err() {
+                    break;
+                }
+            }
+        }
+
+        let _ = CloseHandle(snapshot);
+    }
+
+    threads.sort_by_key(|t| t.tid);
+    Ok(threads)
+}
+
+fn query_thread(tid: u32, base_priority: i32) -> ThreadInfo {
+    let mut info = ThreadInfo {
+        tid,
+        base_priority,
+        relative_priority: None,
+        suspend_count: None,
+        cpu_time: 0,
+        cpu_usage: 0.0,
+    };
+
+    unsafe {
+        let Ok(handle) = OpenThread(THREAD_QUERY_LIMITED_INFORMATION, false, tid) else {
+            return info;
+        };
+
+        let mut creation = FILETIME::default();
+        let mut exit = FILETIME::default();
+        let mut kernel = FILETIME::default();
+        let mut user = FILETIME::default();
+        if GetThreadTimes(handle, &mut creation, &mut exit, &mut kernel, &mut user).is_ok() {
+            info.cpu_time = filetime_to_u64(kernel) + filetime_to_u64(user);
+        }
+
+        // THREAD_PRIORITY_ERROR_RETURN
+        let priority = GetThreadPriority(handle);
+        if priority != i32::MAX {
+            info.relative_priority = Some(priority);
+        }
+
+        let mut count = 0u32;
+        let status = NtQueryInformationThread(
+            handle,
+            ThreadSuspendCount,
+            &mut count as *mut u32 as *mut _,
+            mem::size_of::<u32>() as u32,
+            std::ptr::null_mut(),
+        );
+        if status.is_ok() {
+            info.suspend_count = Some(count);
+        }
+
+        let _ = CloseHandle(handle);
+    }
+
+    info
+}
+
+fn filetime_to_u64(ft: FILETIME) -> u64 {
+    ((ft.dwHighDateTime as u64) << 32) | (ft.dwLowDateTime as u64)
+}
+
+/// Fills in `cpu_usage` from the CPU time used since `previous` was sampled, `elapsed` ago.
+/// Like the process CPU column, 100% means every logical CPU is busy.
+pub fn update_cpu_usage(threads: &mut [ThreadInfo], previous: &[ThreadInfo], elapsed: Duration) {
+    let elapsed = elapsed.as_secs_f64();
+    if elapsed <= 0.0 {
+        return;
+    }
+    let num_cpus = super::process::get_num_cpus() as f64;
+    for thread in threads {
+        if let Some(prev) = previous.iter().find(|p| p.tid == thread.tid) {
+            let delta = thread.cpu_time.saturating_sub(prev.cpu_time) as f64 / 10_000_000.0;
+            thread.cpu_usage = (delta / elapsed * 100.0 / num_cpus).clamp(0.0, 100.0) as f32;
+        }
+    }
+}
+
+/// Opens a thread for suspend/resume, refusing Aperture's own threads: suspending one of
+/// them would freeze the UI with no way to resume it.
+fn open_for_suspend(pid: u32, tid: u32) -> Result<HANDLE> {
+    if pid == unsafe { GetCurrentProcessId() } {
+        return Err("Refusing to suspend one of Aperture's own threads".into());
+    }
+    unsafe { OpenThread(THREAD_SUSPEND_RESUME, false, tid) }
+        .context(format!("OpenThread(TID {})", tid))
+}
+
+/// Suspends the thread, returning its new suspend count.
+pub fn suspend_thread(pid: u32, tid: u32) -> Result<u32> {
+    let handle = open_for_suspend(pid, tid)?;
+    unsafe {
+        let previous = SuspendThread(handle);
+        let error = GetLastError();
+        let _ = CloseHandle(handle);
+        if previous == u32::MAX {
+            return Err(ApertureError::win32(format!("SuspendThread(TID {})", tid), error.0));
+        }
+        Ok(previous + 1)
+    }
+}
+
+/// Undoes one suspend, returning the remaining suspend count.
+pub fn resume_thread(pid: u32, tid: u32) -> Result<u32> {
+    let handle = open_for_suspend(pid, tid)?;
+    unsafe {
+        let previous = ResumeThread(handle);
+        let error = GetLastError();
+        let _ = CloseHandle(handle);
+        if previous == u32::MAX {
+            return Err(ApertureError::win32(format!("ResumeThread(TID {})", tid), error.0));
+        }
+        Ok(previous.saturating_sub(1))
+    }
+}
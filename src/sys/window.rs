@@ -0,0 +1,118 @@
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, WPARAM};
+use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
+use windows::Win32::UI::WindowsAndMessaging::{
+    BringWindowToTop, EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindowVisible, PostMessageW,
+    SetForegroundWindow, ShowWindow, SW_MINIMIZE, SW_RESTORE, WM_CLOSE,
+};
+
+use crate::error::{Context, Result};
+
+/// A visible top-level window. `hwnd` is kept as an integer so the list can cross threads.
+#[derive(Debug, Clone)]
+pub struct WindowInfo {
+    pub hwnd: isize,
+    pub title: String,
+    pub class_name: String,
+    pub minimized: bool,
+}
+
+impl WindowInfo {
+    fn handle(&self) -> HWND {
+        HWND(self.hwnd as *mut _)
+    }
+}
+
+struct EnumContext {
+    pid: u32,
+    windows: Vec<WindowInfo>,
+}
+
+unsafe extern "system" fn collect_window(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    unsafe {
+        let context = &mut *(lparam.0 as *mut EnumContext);
+
+        let mut owner = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut owner));
+        if owner != context.pid || !IsWindowVisible(hwnd).as_bool() {
+            return true.into();
+        }
+
+        // Untitled visible windows are tooltips, shadows and the like
+        let length = GetWindowTextLengthW(hwnd);
+        if length <= 0 {
+            return true.into();
+        }
+        let mut title = vec![0u16; length as usize + 1];
+        let copied = GetWindowTextW(hwnd, &mut title);
+        let mut class_name = [0u16; 256];
+        let class_len = GetClassNameW(hwnd, &mut class_name);
+
+        context.windows.push(WindowInfo {
+            hwnd: hwnd.0 as isize,
+            title: String::from_utf16_lossy(&title[..copied.max(0) as usize]),
+            class_name: String::from_utf16_lossy(&class_name[..class_len.max(0) as usize]),
+            minimized: IsIconic(hwnd).as_bool(),
+        });
+        true.into()
+    }
+}
+
+/// Visible, titled top-level windows owned by `pid`, in Z order.
+pub fn windows_for_pid(pid: u32) -> Result<Vec<WindowInfo>> {
+    let mut context = EnumContext {
+        pid,
+        windows: Vec::new(),
+    };
+    unsafe {
+        EnumWindows(
+            Some(collect_window),
+            LPARAM(&mut context as *mut EnumContext as isize),
+        )
+        .context("EnumWindows")?;
+    }
+    Ok(context.windows)
+}
+
+/// Restores the window if minimized and makes it the foreground window. Windows only lets
+/// the process owning the foreground window hand focus over, so this briefly attaches to
+/// the input queue of the terminal Aperture runs in.
+pub fn bring_to_front(window: &WindowInfo) -> Result<()> {
+    let hwnd = window.handle();
+    unsafe {
+        if IsIconic(hwnd).as_bool() {
+            let _ = ShowWindow(hwnd, SW_RESTORE);
+        }
+
+        let foreground_thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        let current_thread = GetCurrentThreadId();
+        let attached = foreground_thread != 0
+            && foreground_thread != current_thread
+            && AttachThreadInput(current_thread, foreground_thread, true).as_bool();
+
+        let _ = BringWindowToTop(hwnd);
+        let focused = SetForegroundWindow(hwnd).as_bool();
+
+        if attached {
+            let _ = AttachThreadInput(current_thread, foreground_thread, false);
+        }
+        if !focused {
+            return Err(format!("Windows refused to focus \"{}\"", window.title).into());
+        }
+    }
+    Ok(())
+}
+
+pub fn minimize(window: &WindowInfo) {
+    unsafe {
+        let _ = ShowWindow(window.handle(), SW_MINIMIZE);
+    }
+}
+
+/// Asks the window to close, as clicking its X would. The app may prompt or refuse.
+pub fn close(window: &WindowInfo) -> Result<()> {
+    unsafe {
+        PostMessageW(window.handle(), WM_CLOSE, WPARAM(0), LPARAM(0))
+            .context(format!("PostMessageW(WM_CLOSE, \"{}\")", window.title))
+    }
+}
@@ -0,0 +1,638 @@
+use crossterm::event::KeyCode;
+use ratatui::layout::Rect;
+use ratatui::Frame;
+
+use crate::config::Config;
+use crate::error::Result;
+use crate::state::controller::{self, ControllerState};
+use crate::state::locker::{self, LockerState};
+use crate::state::nexus::{self, NexusState, ProtocolFilter};
+use crate::state::presets::FilterPreset;
+use crate::state::table::TableRow;
+use crate::sys;
+use crate::ui;
+
+/// App-level operations behind a tab's keys. They open modals, check for admin or read
+/// other tabs, so a tab only names them in `TabView::command` and App runs them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabCommand {
+    ProcessDetails,
+    Kill,
+    Dump,
+    Threads,
+    LogonSessions,
+    ServiceDetails,
+    /// Starts a stopped service or stops a running one.
+    ToggleService,
+    CreateService,
+    DeleteService,
+    ConnectionDetails,
+    NamedPipes,
+    DnsCache,
+    RemoteLookup,
+    ToggleCapture,
+    TogglePortWatch,
+}
+
+/// Everything App, the key handler and the UI need from a tab. Each tab's state implements
+/// it, so adding a tab means a `Tab` variant, an `AppState` field and one impl here rather
+/// than an arm in every dispatch match.
+pub trait TabView {
+    /// One-line summary shown under the tab bar. Tab strings are English and translated
+    /// when drawn, so they double as `i18n` keys.
+    fn description(&self) -> &'static str;
+
+    /// Label for errors from `refresh`, e.g. "Process list".
+    fn source(&self) -> &'static str;
+
+    /// Reloads the tab's data from the system.
+    fn refresh(&mut self) -> Result<()>;
+
+    fn render(&mut self, f: &mut Frame, search_query: &str, area: Rect);
+
+    /// Tab-specific (key, action) pairs for the shortcuts sidebar.
+    fn keybindings(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Handles a key only this tab uses. Returns false if the key isn't one of them.
+    fn handle_key(&mut self, code: KeyCode) -> bool;
+
+    /// The App command this tab binds to a key no global binding took, such as `d` for
+    /// the selected row's details.
+    fn command(&self, code: KeyCode) -> Option<TabCommand>;
+
+    fn select_next(&mut self, search_query: &str);
+    fn select_prev(&mut self, search_query: &str);
+    fn select_page_up(&mut self, search_query: &str);
+    fn select_page_down(&mut self, search_query: &str);
+    fn select_screen_up(&mut self, search_query: &str);
+    fn select_screen_down(&mut self, search_query: &str);
+    /// Moves `rows` rows without wrapping, for count prefixes.
+    fn move_up(&mut self, search_query: &str, rows: usize);
+    fn move_down(&mut self, search_query: &str, rows: usize);
+    fn select_first(&mut self, search_query: &str);
+    fn select_last(&mut self, search_query: &str);
+
+    /// Moves to the next (or previous) shown row matching `pattern` without filtering.
+    /// Returns false if no row matches.
+    fn find(&mut self, search_query: &str, pattern: &str, forward: bool, include_current: bool)
+        -> bool;
+
+    /// Freezes or unfreezes the selected row. Returns a label for it and whether it is
+    /// now frozen, or None if nothing is selected.
+    fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)>;
+
+    fn set_mark(&mut self, mark: char) -> Result<()>;
+    fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()>;
+
+    fn set_filter(&mut self, query: String);
+    fn clear_filter(&mut self);
+    fn has_filter(&self) -> bool;
+
+    /// This tab's presets from the config, in number-key order.
+    fn presets<'a>(&self, config: &'a Config) -> &'a [FilterPreset];
+    fn preset(&self) -> Option<&FilterPreset>;
+    fn set_preset(&mut self, preset: Option<FilterPreset>);
+
+    fn cycle_sort_key(&mut self);
+    fn toggle_sort_order(&mut self);
+    /// Sorts by the Nth header column (1-based). Returns false if it can't be sorted.
+    fn sort_by_column(&mut self, column: usize) -> bool;
+    /// Current sort for the status bar, e.g. "CPU ▼".
+    fn sort_label(&self) -> String;
+
+    /// Detail about the selected row that doesn't fit in the table, for the status bar.
+    fn selection_hint(&self, search_query: &str) -> Option<String>;
+
+    /// The selected row's values for external tool placeholders, e.g. ("pid", "1234").
+    /// Empty when nothing is selected.
+    fn tool_values(&self, search_query: &str) -> Vec<(&'static str, String)>;
+}
+
+impl TabView for LockerState {
+    fn description(&self) -> &'static str {
+        "Find and kill processes holding file locks"
+    }
+
+    fn source(&self) -> &'static str {
+        "Process list"
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.update_processes(sys::process::enumerate_processes()?);
+        Ok(())
+    }
+
+    fn render(&mut self, f: &mut Frame, search_query: &str, area: Rect) {
+        ui::locker::render(f, self, search_query, area);
+    }
+
+    fn keybindings(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("t", "TreeView"),
+            ("SPC", "Expand"),
+            ("d", "Details"),
+            ("K", "Kill"),
+            ("M", "Dump"),
+            ("T", "Threads"),
+            ("H", "Handles/GDI"),
+            ("c", "Cmdline"),
+            ("O", "Jobs"),
+            ("B", "Top"),
+            ("C", "CPU/core"),
+            ("U", "Sessions"),
+            ("h/l", "Scroll path"),
+        ]
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('t') => self.toggle_tree_mode(),
+            KeyCode::Char('H') => self.toggle_object_columns(),
+            KeyCode::Char('c') => self.toggle_command_line(),
+            KeyCode::Char('O') => self.toggle_jobs(),
+            KeyCode::Char('B') => self.toggle_top(),
+            KeyCode::Char('C') => self.toggle_cpu_per_core(),
+            KeyCode::Char('h') | KeyCode::Left => self.scroll_path(false),
+            KeyCode::Char('l') | KeyCode::Right => self.scroll_path(true),
+            KeyCode::Char(' ') if self.tree_mode => self.toggle_expand(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn command(&self, code: KeyCode) -> Option<TabCommand> {
+        Some(match code {
+            KeyCode::Char('d') => TabCommand::ProcessDetails,
+            KeyCode::Char('K') => TabCommand::Kill,
+            KeyCode::Char('M') => TabCommand::Dump,
+            KeyCode::Char('T') => TabCommand::Threads,
+            KeyCode::Char('U') => TabCommand::LogonSessions,
+            _ => return None,
+        })
+    }
+
+    fn select_next(&mut self, search_query: &str) {
+        LockerState::select_next(self, search_query);
+    }
+
+    fn select_prev(&mut self, search_query: &str) {
+        LockerState::select_prev(self, search_query);
+    }
+
+    fn select_page_up(&mut self, search_query: &str) {
+        LockerState::select_page_up(self, search_query);
+    }
+
+    fn select_page_down(&mut self, search_query: &str) {
+        LockerState::select_page_down(self, search_query);
+    }
+
+    fn select_screen_up(&mut self, search_query: &str) {
+        LockerState::select_screen_up(self, search_query);
+    }
+
+    fn select_screen_down(&mut self, search_query: &str) {
+        LockerState::select_screen_down(self, search_query);
+    }
+
+    fn move_up(&mut self, search_query: &str, rows: usize) {
+        LockerState::move_up(self, search_query, rows);
+    }
+
+    fn move_down(&mut self, search_query: &str, rows: usize) {
+        LockerState::move_down(self, search_query, rows);
+    }
+
+    fn select_first(&mut self, search_query: &str) {
+        LockerState::select_first(self, search_query);
+    }
+
+    fn select_last(&mut self, search_query: &str) {
+        LockerState::select_last(self, search_query);
+    }
+
+    fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        LockerState::find(self, search_query, pattern, forward, include_current)
+    }
+
+    fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        LockerState::toggle_freeze(self, search_query)
+    }
+
+    fn set_mark(&mut self, mark: char) -> Result<()> {
+        self.table.set_mark(mark)
+    }
+
+    fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        LockerState::jump_to_mark(self, search_query, mark)
+    }
+
+    fn set_filter(&mut self, query: String) {
+        LockerState::set_filter(self, query);
+    }
+
+    fn clear_filter(&mut self) {
+        LockerState::clear_filter(self);
+    }
+
+    fn has_filter(&self) -> bool {
+        self.table.active_filter.is_some()
+    }
+
+    fn presets<'a>(&self, config: &'a Config) -> &'a [FilterPreset] {
+        &config.presets.locker
+    }
+
+    fn preset(&self) -> Option<&FilterPreset> {
+        self.table.preset.as_ref()
+    }
+
+    fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        LockerState::set_preset(self, preset);
+    }
+
+    fn cycle_sort_key(&mut self) {
+        LockerState::cycle_sort_key(self);
+    }
+
+    fn toggle_sort_order(&mut self) {
+        LockerState::toggle_sort_order(self);
+    }
+
+    fn sort_by_column(&mut self, column: usize) -> bool {
+        locker::SortKey::for_column(column)
+            .map(|key| self.sort_by(key))
+            .is_some()
+    }
+
+    fn sort_label(&self) -> String {
+        format!("{} {}", self.table.sort_key.as_str(), self.table.sort_order.as_str())
+    }
+
+    fn selection_hint(&self, search_query: &str) -> Option<String> {
+        let process = self.get_selected_process(search_query)?;
+        Some(match &process.path {
+            Some(path) => path.clone(),
+            None => format!("{} (path unavailable)", process.name),
+        })
+    }
+
+    fn tool_values(&self, search_query: &str) -> Vec<(&'static str, String)> {
+        self.get_selected_process(search_query)
+            .map(TableRow::values)
+            .unwrap_or_default()
+    }
+}
+
+impl TabView for ControllerState {
+    fn description(&self) -> &'static str {
+        "Start, stop, and manage Windows services"
+    }
+
+    fn source(&self) -> &'static str {
+        "Service list"
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.update_services(sys::service::enumerate_services()?);
+        Ok(())
+    }
+
+    fn render(&mut self, f: &mut Frame, search_query: &str, area: Rect) {
+        ui::controller::render(f, self, search_query, area);
+    }
+
+    fn keybindings(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("Enter", "Toggle"),
+            ("d", "Details"),
+            ("a", "New Service"),
+            ("D", "Delete"),
+            ("t", "By host"),
+        ]
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        // Service actions need admin checks or a modal, so App handles those
+        match code {
+            KeyCode::Char('t') => self.toggle_group_mode(),
+            KeyCode::Char(' ') if self.group_mode => self.toggle_collapse(),
+            _ => return false,
+        }
+        true
+    }
+
+    fn command(&self, code: KeyCode) -> Option<TabCommand> {
+        Some(match code {
+            KeyCode::Enter => TabCommand::ToggleService,
+            KeyCode::Char('d') => TabCommand::ServiceDetails,
+            KeyCode::Char('a') => TabCommand::CreateService,
+            KeyCode::Char('D') => TabCommand::DeleteService,
+            _ => return None,
+        })
+    }
+
+    fn select_next(&mut self, search_query: &str) {
+        ControllerState::select_next(self, search_query);
+    }
+
+    fn select_prev(&mut self, search_query: &str) {
+        ControllerState::select_prev(self, search_query);
+    }
+
+    fn select_page_up(&mut self, search_query: &str) {
+        ControllerState::select_page_up(self, search_query);
+    }
+
+    fn select_page_down(&mut self, search_query: &str) {
+        ControllerState::select_page_down(self, search_query);
+    }
+
+    fn select_screen_up(&mut self, search_query: &str) {
+        ControllerState::select_screen_up(self, search_query);
+    }
+
+    fn select_screen_down(&mut self, search_query: &str) {
+        ControllerState::select_screen_down(self, search_query);
+    }
+
+    fn move_up(&mut self, search_query: &str, rows: usize) {
+        ControllerState::move_up(self, search_query, rows);
+    }
+
+    fn move_down(&mut self, search_query: &str, rows: usize) {
+        ControllerState::move_down(self, search_query, rows);
+    }
+
+    fn select_first(&mut self, search_query: &str) {
+        ControllerState::select_first(self, search_query);
+    }
+
+    fn select_last(&mut self, search_query: &str) {
+        ControllerState::select_last(self, search_query);
+    }
+
+    fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        ControllerState::find(self, search_query, pattern, forward, include_current)
+    }
+
+    fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        ControllerState::toggle_freeze(self, search_query)
+    }
+
+    fn set_mark(&mut self, mark: char) -> Result<()> {
+        self.table.set_mark(mark)
+    }
+
+    fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        ControllerState::jump_to_mark(self, search_query, mark)
+    }
+
+    fn set_filter(&mut self, query: String) {
+        ControllerState::set_filter(self, query);
+    }
+
+    fn clear_filter(&mut self) {
+        ControllerState::clear_filter(self);
+    }
+
+    fn has_filter(&self) -> bool {
+        self.table.active_filter.is_some()
+    }
+
+    fn presets<'a>(&self, config: &'a Config) -> &'a [FilterPreset] {
+        &config.presets.controller
+    }
+
+    fn preset(&self) -> Option<&FilterPreset> {
+        self.table.preset.as_ref()
+    }
+
+    fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        ControllerState::set_preset(self, preset);
+    }
+
+    fn cycle_sort_key(&mut self) {
+        ControllerState::cycle_sort_key(self);
+    }
+
+    fn toggle_sort_order(&mut self) {
+        ControllerState::toggle_sort_order(self);
+    }
+
+    fn sort_by_column(&mut self, column: usize) -> bool {
+        controller::SortKey::for_column(column)
+            .map(|key| self.sort_by(key))
+            .is_some()
+    }
+
+    fn sort_label(&self) -> String {
+        format!("{} {}", self.table.sort_key.as_str(), self.table.sort_order.as_str())
+    }
+
+    fn selection_hint(&self, search_query: &str) -> Option<String> {
+        if let Some(pid) = self.selected_host() {
+            return Some(format!("PID {} hosts {}", pid, self.services_for_pid(pid).join(", ")));
+        }
+        let service = self.selected_service(search_query)?;
+        match self.descriptions.get(&service.service_name) {
+            Some(description) if !description.is_empty() => Some(description.clone()),
+            _ => Some(service.display_name.clone()),
+        }
+    }
+
+    fn tool_values(&self, search_query: &str) -> Vec<(&'static str, String)> {
+        self.selected_service(search_query)
+            .map(TableRow::values)
+            .unwrap_or_default()
+    }
+}
+
+impl TabView for NexusState {
+    fn description(&self) -> &'static str {
+        "Monitor active network connections"
+    }
+
+    fn source(&self) -> &'static str {
+        "Connection list"
+    }
+
+    fn refresh(&mut self) -> Result<()> {
+        self.update_connections(sys::network::enumerate_connections()?);
+        Ok(())
+    }
+
+    fn render(&mut self, f: &mut Frame, search_query: &str, area: Rect) {
+        ui::nexus::render(f, self, search_query, area);
+    }
+
+    fn keybindings(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("d", "Details"),
+            ("z", "Lingering"),
+            ("L", "Hide local"),
+            ("w", "Lookup IP"),
+            ("T/U", "TCP/UDP only"),
+            ("p", "Pipes"),
+            ("n", "DNS/Hosts"),
+            ("C", "Capture"),
+            ("W", "Watch port"),
+        ]
+    }
+
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('z') => self.toggle_lingering_only(),
+            KeyCode::Char('L') => self.toggle_hide_local(),
+            KeyCode::Char('T') => self.toggle_protocol_filter(ProtocolFilter::Tcp),
+            KeyCode::Char('U') => self.toggle_protocol_filter(ProtocolFilter::Udp),
+            _ => return false,
+        }
+        true
+    }
+
+    fn command(&self, code: KeyCode) -> Option<TabCommand> {
+        Some(match code {
+            KeyCode::Char('d') => TabCommand::ConnectionDetails,
+            KeyCode::Char('p') => TabCommand::NamedPipes,
+            KeyCode::Char('n') => TabCommand::DnsCache,
+            KeyCode::Char('w') => TabCommand::RemoteLookup,
+            KeyCode::Char('C') => TabCommand::ToggleCapture,
+            KeyCode::Char('W') => TabCommand::TogglePortWatch,
+            _ => return None,
+        })
+    }
+
+    fn select_next(&mut self, search_query: &str) {
+        NexusState::select_next(self, search_query);
+    }
+
+    fn select_prev(&mut self, search_query: &str) {
+        NexusState::select_prev(self, search_query);
+    }
+
+    fn select_page_up(&mut self, search_query: &str) {
+        NexusState::select_page_up(self, search_query);
+    }
+
+    fn select_page_down(&mut self, search_query: &str) {
+        NexusState::select_page_down(self, search_query);
+    }
+
+    fn select_screen_up(&mut self, search_query: &str) {
+        NexusState::select_screen_up(self, search_query);
+    }
+
+    fn select_screen_down(&mut self, search_query: &str) {
+        NexusState::select_screen_down(self, search_query);
+    }
+
+    fn move_up(&mut self, search_query: &str, rows: usize) {
+        NexusState::move_up(self, search_query, rows);
+    }
+
+    fn move_down(&mut self, search_query: &str, rows: usize) {
+        NexusState::move_down(self, search_query, rows);
+    }
+
+    fn select_first(&mut self, search_query: &str) {
+        NexusState::select_first(self, search_query);
+    }
+
+    fn select_last(&mut self, search_query: &str) {
+        NexusState::select_last(self, search_query);
+    }
+
+    fn find(
+        &mut self,
+        search_query: &str,
+        pattern: &str,
+        forward: bool,
+        include_current: bool,
+    ) -> bool {
+        NexusState::find(self, search_query, pattern, forward, include_current)
+    }
+
+    fn toggle_freeze(&mut self, search_query: &str) -> Option<(String, bool)> {
+        NexusState::toggle_freeze(self, search_query)
+    }
+
+    fn set_mark(&mut self, mark: char) -> Result<()> {
+        self.table.set_mark(mark)
+    }
+
+    fn jump_to_mark(&mut self, search_query: &str, mark: char) -> Result<()> {
+        NexusState::jump_to_mark(self, search_query, mark)
+    }
+
+    fn set_filter(&mut self, query: String) {
+        NexusState::set_filter(self, query);
+    }
+
+    fn clear_filter(&mut self) {
+        NexusState::clear_filter(self);
+    }
+
+    fn has_filter(&self) -> bool {
+        self.table.active_filter.is_some()
+    }
+
+    fn presets<'a>(&self, config: &'a Config) -> &'a [FilterPreset] {
+        &config.presets.nexus
+    }
+
+    fn preset(&self) -> Option<&FilterPreset> {
+        self.table.preset.as_ref()
+    }
+
+    fn set_preset(&mut self, preset: Option<FilterPreset>) {
+        NexusState::set_preset(self, preset);
+    }
+
+    fn cycle_sort_key(&mut self) {
+        NexusState::cycle_sort_key(self);
+    }
+
+    fn toggle_sort_order(&mut self) {
+        NexusState::toggle_sort_order(self);
+    }
+
+    fn sort_by_column(&mut self, column: usize) -> bool {
+        nexus::SortKey::for_column(column)
+            .map(|key| self.sort_by(key))
+            .is_some()
+    }
+
+    fn sort_label(&self) -> String {
+        format!("{} {}", self.table.sort_key.as_str(), self.table.sort_order.as_str())
+    }
+    fn selection_hint(&self, search_query: &str) -> Option<String> {
+        let conn = self.selected_connection(search_query)?;
+        if nexus::remote_scope(&conn.remote_addr) == "none" {
+            return Some(format!("{}:{} (no remote)", conn.local_addr, conn.local_port));
+        }
+        let remote = format!("{}:{}", conn.remote_addr, conn.remote_port);
+        Some(match self.host_names.get(&conn.remote_addr) {
+            Some(Some(host)) => format!("{} -> {}", remote, host),
+            _ => remote,
+        })
+    }
+
+    fn tool_values(&self, search_query: &str) -> Vec<(&'static str, String)> {
+        self.selected_connection(search_query)
+            .map(TableRow::values)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,67 @@
+use std::io;
+use std::process::Command;
+
+use crossterm::cursor::Show;
+use crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use windows::Win32::Foundation::{BOOL, FALSE};
+use windows::Win32::System::Console::SetConsoleCtrlHandler;
+
+/// Switches the console to the full-screen UI: raw input on the alternate screen.
+pub fn enter() -> io::Result<()> {
+    enable_raw_mode()?;
+    crossterm::execute!(io::stdout(), EnterAlternateScreen)
+}
+
+/// Leaves the full-screen UI. Errors are ignored since this also runs while crashing,
+/// when there is nothing better to do than try.
+pub fn restore() {
+    let _ = disable_raw_mode();
+    let _ = crossterm::execute!(io::stdout(), LeaveAlternateScreen, Show);
+}
+
+/// Leaves the UI for a shell, or for `command`, and switches back once that exits. The
+/// caller redraws the whole screen afterwards.
+pub fn suspend(command: Option<Command>) -> io::Result<()> {
+    restore();
+    let status = match command {
+        Some(mut command) => {
+            let status = command.status();
+            println!("\nPress Enter to return to Aperture");
+            let _ = io::stdin().read_line(&mut String::new());
+            status
+        }
+        None => {
+            println!("Aperture is suspended. Type exit to return.");
+            Command::new(crate::sys::shell::comspec()).status()
+        }
+    };
+    enter()?;
+    status.map(|_| ())
+}
+
+/// Restores the console before the process dies on a Ctrl+Break or the console
+/// window closing. Returning FALSE lets the default handler end the process as usual.
+unsafe extern "system" fn on_console_ctrl(_ctrl_type: u32) -> BOOL {
+    restore();
+    FALSE
+}
+
+/// Makes sure the terminal is usable again however Aperture exits: a panic on the UI
+/// thread, Ctrl+Break, or the console being closed. Panics on other threads are caught
+/// by tokio and the UI keeps running, so those leave the screen alone.
+pub fn install_crash_guards() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if std::thread::current().name() == Some("main") {
+            restore();
+        }
+        default_hook(info);
+    }));
+
+    unsafe {
+        // Without the handler only clean exits restore the console, which is no worse
+        let _ = SetConsoleCtrlHandler(Some(on_console_ctrl), true);
+    }
+}
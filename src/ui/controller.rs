@@ -6,34 +6,137 @@ use ratatui::{
     Frame,
 };
 
-use crate::state::controller::ControllerState;
+use crate::state::controller::{ControllerState, GroupRow, SortKey};
+use crate::state::table::TableRow;
+use crate::sys::service::ServiceInfo;
+
+use super::text::{fit, fit_line, pad};
+use super::{frozen_indicator, frozen_style, header_line, highlight_spans, spinner_frame};
+
+/// One service line. Grouped services are indented under their host line, with the name
+/// column narrowed to keep the other columns aligned.
+fn service_item(
+    state: &ControllerState,
+    s: &ServiceInfo,
+    grouped: bool,
+    search_query: &str,
+    width: usize,
+) -> ListItem<'static> {
+    let (indent, name_width) = if grouped { ("  ", 38) } else { ("", 40) };
+    if let Some(pending) = state.pending_services.get(&s.service_name) {
+        let status = format!(
+            "{} {}… {}s",
+            spinner_frame(),
+            pending.label,
+            pending.started.elapsed().as_secs()
+        );
+        let line = format!(
+            "{}{} {:16} {:12} {}",
+            indent,
+            pad(&s.display_name, name_width),
+            status,
+            s.start_type,
+            s.service_type
+        );
+        return ListItem::new(fit(&line, width)).style(Style::default().fg(Color::Magenta));
+    }
+
+    let row_style = state.table.row_style(s).unwrap_or_default();
+    let positions = state.match_positions(&s.display_name, search_query);
+    let mut spans = vec![Span::raw(indent)];
+    spans.extend(highlight_spans(pad(&s.display_name, name_width), &positions));
+    // The badge takes the end of the status column, cutting a long pending status short
+    let badge = state
+        .flapping(&s.service_name)
+        .map(|starts| format!(" FLAP {}x", starts))
+        .unwrap_or_default();
+    spans.push(Span::raw(format!(" {}", pad(&s.status, 16 - badge.len()))));
+    spans.push(Span::styled(
+        badge,
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+    ));
+    spans.push(Span::raw(format!(
+        " {:12}{} {}",
+        s.start_type,
+        state.table.script_cells(&s.id()),
+        s.service_type
+    )));
+    let style = frozen_style(
+        row_style,
+        state.table.is_frozen(&s.id()),
+        state.table.is_vanished(&s.id()),
+    );
+    ListItem::new(fit_line(Line::from(spans), width)).style(style)
+}
 
 pub fn render(f: &mut Frame, state: &mut ControllerState, search_query: &str, area: Rect) {
+    // Rebuild the groups to apply any filter changes
+    if state.group_mode {
+        state.build_groups(search_query);
+    }
+
     let filtered = state.filtered_services(search_query);
+    // Rows, header and title are cut to the space inside the border
+    let width = area.width.saturating_sub(2) as usize;
 
-    let items: Vec<ListItem> = filtered
-        .iter()
-        .map(|(_, s)| {
-            let status_color = match s.status.as_str() {
-                "Running" => Color::Green,
-                "Stopped" => Color::Red,
-                _ => Color::Yellow,
-            };
-            ListItem::new(format!(
-                "{:40} {:10} {:12} {}",
-                s.display_name, s.status, s.start_type, s.service_type
-            ))
-            .style(Style::default().fg(status_color))
-        })
-        .collect();
+    let items: Vec<ListItem> = if state.group_mode {
+        state
+            .group_rows
+            .iter()
+            .map(|row| match row {
+                GroupRow::Host {
+                    pid,
+                    services,
+                    collapsed,
+                } => {
+                    let icon = if *collapsed { ">" } else { "v" };
+                    let line = format!("{} PID {} - {} services", icon, pid, services);
+                    ListItem::new(fit(&line, width)).style(
+                        Style::default()
+                            .fg(Color::Cyan)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                }
+                GroupRow::Service { service, grouped } => {
+                    service_item(state, service, *grouped, search_query, width)
+                }
+            })
+            .collect()
+    } else {
+        filtered
+            .iter()
+            .map(|(_, s)| service_item(state, s, false, search_query, width))
+            .collect()
+    };
 
     // Build title with filter and sort info
-    let total = state.services.len();
+    let total = state.table.rows.len();
     let showing = filtered.len();
-    let sort_info = format!("{} {}", state.sort_key.as_str(), state.sort_order.as_str());
+    let sort_info = format!(
+        "{} {}",
+        state.table.sort_key.as_str(),
+        state.table.sort_order.as_str()
+    );
+    let preset_indicator = state
+        .table
+        .preset
+        .as_ref()
+        .map(|p| format!(" [{}]", p.name))
+        .unwrap_or_default();
+    let mode_indicator = if state.group_mode { " [BY HOST]" } else { "" };
+    let flapping_indicator = match state.flapping_count() {
+        0 => String::new(),
+        n => format!(" [{} FLAPPING]", n),
+    };
     let title = format!(
-        " Services (Controller) [{}/{} | {}] ",
-        showing, total, sort_info
+        " Services (Controller){}{}{}{} [{}/{} | {}] ",
+        mode_indicator,
+        flapping_indicator,
+        preset_indicator,
+        frozen_indicator(state.table.frozen.len()),
+        showing,
+        total,
+        sort_info
     );
 
     // Create inner area inside the border for the header
@@ -46,22 +149,20 @@ pub fn render(f: &mut Frame, state: &mut ControllerState, search_query: &str, ar
         .split(inner_area);
 
     // Render header as non-selectable text in the first line of inner area
-    let header_text = format!(
-        "{:40} {:10} {:12} {}",
-        "Name", "Status", "Start Type", "Type"
-    );
-    let header = Paragraph::new(Line::from(vec![Span::styled(
-        header_text,
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )]));
+    let mut columns = vec![
+        (format!("{:40}", "Name"), Some(SortKey::Name)),
+        (format!("{:16}", "Status"), Some(SortKey::Status)),
+        (format!("{:12}", "Start Type"), Some(SortKey::StartType)),
+    ];
+    columns.extend(state.table.script_headers().into_iter().map(|header| (header, None)));
+    columns.push(("Type".to_string(), Some(SortKey::Type)));
+    let header = Paragraph::new(fit_line(header_line(columns, state.table.sort_key), width));
     f.render_widget(header, chunks[0]);
 
     // Render list block with border (full area)
     let list_block = Block::default()
         .borders(Borders::ALL)
-        .title(title)
+        .title(fit(&title, width))
         .title_style(Style::default().fg(Color::Cyan));
     f.render_widget(list_block.clone(), area);
 
@@ -69,5 +170,6 @@ pub fn render(f: &mut Frame, state: &mut ControllerState, search_query: &str, ar
     let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
 
     // Pass mutable reference directly (not cloned) so selection is preserved
-    f.render_stateful_widget(list, chunks[1], &mut state.list_state);
+    state.table.viewport_rows = chunks[1].height as usize;
+    f.render_stateful_widget(list, chunks[1], &mut state.table.list_state);
 }
@@ -1,12 +1,133 @@
 use ratatui::{
     layout::{Constraint, Direction, Layout, Margin, Rect},
-    style::{Color, Modifier, Style},
+    style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::state::locker::LockerState;
+use crate::state::locker::{LockerState, ObjectThresholds, SortKey};
+use crate::sys::process::ProcessInfo;
+
+use super::text::{fit, fit_line, pad, skip, truncate};
+use super::{
+    format_bytes, format_rate, format_uptime, frozen_indicator, frozen_style, header_line,
+    highlight_spans, spinner_frame,
+};
+
+// Processes per resource in the top offenders strip
+const TOP_COUNT: usize = 3;
+
+/// The top CPU and memory consumers, one line each, drawn above the list.
+fn render_top_offenders(f: &mut Frame, state: &LockerState, area: Rect) {
+    let (cpu, memory) = state.top_offenders(TOP_COUNT);
+    let entry = |name: &str, value: String, color: Color| {
+        vec![
+            Span::raw(format!("{} ", pad(name, 20))),
+            Span::styled(format!("{:>8}", value), Style::default().fg(color)),
+            Span::raw("   "),
+        ]
+    };
+    let mut cpu_line = vec![Span::styled("CPU  ", Style::default().fg(Color::Yellow))];
+    for p in cpu {
+        let cpu = state.cpu_display(p.cpu_usage);
+        cpu_line.extend(entry(&p.name, format!("{:.1}%", cpu), Color::Cyan));
+    }
+    let mut memory_line = vec![Span::styled("Mem  ", Style::default().fg(Color::Yellow))];
+    for p in memory {
+        let bytes = (p.memory_mb * 1024.0 * 1024.0) as u64;
+        memory_line.extend(entry(&p.name, format_bytes(bytes), Color::Cyan));
+    }
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Top Offenders ")
+        .title_style(Style::default().fg(Color::Cyan));
+    let lines = vec![Line::from(cpu_line), Line::from(memory_line)];
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+/// CPU column text, dropping the decimal once a per-core reading passes 999.9% so it keeps
+/// the column's width.
+fn cpu_cell(percent: f32) -> String {
+    if percent >= 999.95 {
+        format!("{:5.0}%", percent)
+    } else {
+        format!("{:5.1}%", percent)
+    }
+}
+
+/// Name column text and row style, with a spinner in front while a kill is in flight.
+/// Other rows take the style of the first matching color rule, white without one.
+fn name_cell(state: &LockerState, p: &ProcessInfo) -> (String, Style) {
+    if state.pending_kills.contains(&p.pid) {
+        return (
+            format!("{} {}", spinner_frame(), truncate(&p.name, 18)),
+            Style::default().fg(Color::Magenta),
+        );
+    }
+    let style = state
+        .table
+        .row_style(p)
+        .unwrap_or_else(|| Style::default().fg(Color::White));
+    (truncate(&p.name, 20).to_string(), style)
+}
+
+/// Path column text, led by the exit time for processes that are only tombstones now and
+/// by the WSL/container tag.
+fn path_cell(state: &LockerState, p: &ProcessInfo) -> String {
+    let path = state.path_text(p);
+    // Scrolled with h/l; the leading `…` says the start is cut off
+    let path = match state.path_scroll {
+        0 => path.to_string(),
+        scroll => format!("…{}", skip(path, scroll)),
+    };
+    let mut tag = p
+        .container
+        .as_ref()
+        .map(|kind| format!("[{}] ", kind))
+        .unwrap_or_default();
+    if state.show_jobs
+        && let Some(job) = p.job_id {
+            tag.insert_str(0, &format!("[job {}] ", job));
+        }
+    match state.tombstones.get(&p.pid) {
+        Some(tombstone) => format!(" [exited {}] {}{}", tombstone.clock, tag, path),
+        None => format!(" {}{}", tag, path),
+    }
+}
+
+/// Handle, GDI and USER counts, yellow above their threshold and red above twice that.
+fn object_spans(p: &ProcessInfo, thresholds: &ObjectThresholds) -> Vec<Span<'static>> {
+    let cell = |count: u32, threshold: u32, width: usize| {
+        let text = if count > 0 {
+            format!(" {:>width$}", count)
+        } else {
+            format!(" {:>width$}", "-")
+        };
+        let style = if count > threshold.saturating_mul(2) {
+            Style::default().fg(Color::Red)
+        } else if count > threshold {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default()
+        };
+        Span::styled(text, style)
+    };
+    vec![
+        cell(p.handle_count, thresholds.handles, 7),
+        cell(p.gdi_objects, thresholds.gdi, 5),
+        cell(p.user_objects, thresholds.user, 5),
+    ]
+}
+
+/// Search highlight positions in the name column; none while the spinner shifts the name.
+fn name_matches(state: &LockerState, p: &ProcessInfo, search_query: &str) -> Vec<usize> {
+    if state.pending_kills.contains(&p.pid) {
+        Vec::new()
+    } else {
+        state.match_positions(&p.name, search_query)
+    }
+}
 
 pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area: Rect) {
     // Rebuild tree if in tree mode to apply any filter changes
@@ -14,6 +135,12 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
         state.build_tree(search_query);
     }
 
+    // Rows, header and title are cut to the space inside the border
+    let width = area.width.saturating_sub(2) as usize;
+
+    // The Mem column shows private bytes instead of the working set while sorted by them
+    let show_private = state.table.sort_key == SortKey::PrivateBytes;
+
     // Determine what to render based on tree mode
     let items: Vec<ListItem> = if state.tree_mode {
         // In tree mode, render from tree_nodes
@@ -28,14 +155,16 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
                 } else {
                     p.last_cpu_usage
                 };
-                let mem_val = if p.memory_mb > 0.0 {
+                let mem_val = if show_private {
+                    p.private_mb
+                } else if p.memory_mb > 0.0 {
                     p.memory_mb
                 } else {
                     p.last_memory_mb
                 };
 
                 let cpu_str = if cpu_val > 0.0 {
-                    format!("{:5.1}%", cpu_val)
+                    cpu_cell(state.cpu_display(cpu_val))
                 } else {
                     "     -".to_string()
                 };
@@ -44,6 +173,20 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
                 } else {
                     "     -".to_string()
                 };
+                let gpu_str = if p.gpu_usage > 0.0 {
+                    format!("{:5.1}%", p.gpu_usage)
+                } else {
+                    "     -".to_string()
+                };
+                let gpu_mem_str = if p.gpu_memory_mb > 0.0 {
+                    format!("{:6.1}MB", p.gpu_memory_mb)
+                } else {
+                    "       -".to_string()
+                };
+                let read_str = format_rate(p.io_read_bps);
+                let write_str = format_rate(p.io_write_bps);
+                let uptime_str = format_uptime(p.uptime_secs());
+                let (name, row_style) = name_cell(state, p);
 
                 // Build tree prefix
                 let indent = "  ".repeat(node.depth);
@@ -58,20 +201,26 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
                 };
                 let prefix = format!("{}{}", indent, expand_icon);
 
-                ListItem::new(format!(
-                    "{}{:6} {:20} {} {} {}",
-                    prefix,
-                    p.pid,
-                    if p.name.len() > 20 {
-                        &p.name[..20]
-                    } else {
-                        &p.name
-                    },
-                    cpu_str,
-                    mem_str,
-                    p.path.as_deref().unwrap_or("-")
-                ))
-                .style(Style::default().fg(Color::White))
+                let mut spans = vec![Span::raw(format!("{}{:6} ", prefix, p.pid))];
+                spans.extend(highlight_spans(
+                    pad(&name, 20),
+                    &name_matches(state, p, search_query),
+                ));
+                spans.push(Span::raw(format!(
+                    " {} {} {} {} {} {} {}",
+                    cpu_str, mem_str, gpu_str, gpu_mem_str, read_str, write_str, uptime_str
+                )));
+                if state.show_object_columns {
+                    spans.extend(object_spans(p, &state.object_thresholds));
+                }
+                spans.push(Span::raw(state.table.script_cells(&p.pid)));
+                spans.push(Span::raw(path_cell(state, p)));
+                let style = frozen_style(
+                    row_style,
+                    state.table.is_frozen(&p.pid),
+                    state.table.is_vanished(&p.pid) || state.tombstones.contains_key(&p.pid),
+                );
+                ListItem::new(fit_line(Line::from(spans), width)).style(style)
             })
             .collect()
     } else {
@@ -86,14 +235,16 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
                 } else {
                     p.last_cpu_usage
                 };
-                let mem_val = if p.memory_mb > 0.0 {
+                let mem_val = if show_private {
+                    p.private_mb
+                } else if p.memory_mb > 0.0 {
                     p.memory_mb
                 } else {
                     p.last_memory_mb
                 };
 
                 let cpu_str = if cpu_val > 0.0 {
-                    format!("{:5.1}%", cpu_val)
+                    cpu_cell(state.cpu_display(cpu_val))
                 } else {
                     "     -".to_string()
                 };
@@ -102,37 +253,92 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
                 } else {
                     "     -".to_string()
                 };
-                ListItem::new(format!(
-                    "{:6} {:20} {} {} {}",
-                    p.pid,
-                    if p.name.len() > 20 {
-                        &p.name[..20]
-                    } else {
-                        &p.name
-                    },
-                    cpu_str,
-                    mem_str,
-                    p.path.as_deref().unwrap_or("-")
-                ))
-                .style(Style::default().fg(Color::White))
+                let gpu_str = if p.gpu_usage > 0.0 {
+                    format!("{:5.1}%", p.gpu_usage)
+                } else {
+                    "     -".to_string()
+                };
+                let gpu_mem_str = if p.gpu_memory_mb > 0.0 {
+                    format!("{:6.1}MB", p.gpu_memory_mb)
+                } else {
+                    "       -".to_string()
+                };
+                let read_str = format_rate(p.io_read_bps);
+                let write_str = format_rate(p.io_write_bps);
+                let uptime_str = format_uptime(p.uptime_secs());
+                let (name, row_style) = name_cell(state, p);
+                let mut spans = vec![Span::raw(format!("{:6} ", p.pid))];
+                spans.extend(highlight_spans(
+                    pad(&name, 20),
+                    &name_matches(state, p, search_query),
+                ));
+                spans.push(Span::raw(format!(
+                    " {} {} {} {} {} {} {}",
+                    cpu_str, mem_str, gpu_str, gpu_mem_str, read_str, write_str, uptime_str
+                )));
+                if state.show_object_columns {
+                    spans.extend(object_spans(p, &state.object_thresholds));
+                }
+                spans.push(Span::raw(state.table.script_cells(&p.pid)));
+                spans.push(Span::raw(path_cell(state, p)));
+                let style = frozen_style(
+                    row_style,
+                    state.table.is_frozen(&p.pid),
+                    state.table.is_vanished(&p.pid) || state.tombstones.contains_key(&p.pid),
+                );
+                ListItem::new(fit_line(Line::from(spans), width)).style(style)
             })
             .collect()
     };
 
     // Build title with filter, sort info, and tree mode indicator
-    let total = state.processes.len();
+    let total = state.table.rows.len();
     let showing = if state.tree_mode {
         state.tree_nodes.len()
     } else {
         state.filtered_processes(search_query).len()
     };
-    let sort_info = format!("{} {}", state.sort_key.as_str(), state.sort_order.as_str());
-    let mode_indicator = if state.tree_mode { " [TREE]" } else { "" };
+    let sort_info = format!(
+        "{} {}",
+        state.table.sort_key.as_str(),
+        state.table.sort_order.as_str()
+    );
+    let mode_indicator = match (state.tree_mode, state.show_jobs) {
+        (true, true) => " [TREE] [JOBS]",
+        (true, false) => " [TREE]",
+        (false, true) => " [JOBS]",
+        (false, false) => "",
+    };
+    let cpu_indicator = if state.cpu_per_core { " [CPU/CORE]" } else { "" };
+    let preset_indicator = state
+        .table
+        .preset
+        .as_ref()
+        .map(|p| format!(" [{}]", p.name))
+        .unwrap_or_default();
     let title = format!(
-        " Processes (Locker){} [{}/{} | {}] ",
-        mode_indicator, showing, total, sort_info
+        " Processes (Locker){}{}{}{} [{}/{} | {}] ",
+        mode_indicator,
+        cpu_indicator,
+        preset_indicator,
+        frozen_indicator(state.table.frozen.len()),
+        showing,
+        total,
+        sort_info
     );
 
+    // Top offenders strip above the process list
+    let area = if state.show_top {
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(4), Constraint::Min(0)])
+            .split(area);
+        render_top_offenders(f, state, sections[0]);
+        sections[1]
+    } else {
+        area
+    };
+
     // Create inner area inside the border for the header
     let inner_area = area.inner(Margin::new(1, 1));
 
@@ -143,22 +349,44 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
         .split(inner_area);
 
     // Render header as non-selectable text in the first line of inner area
-    let header_text = format!(
-        "{:6} {:20} {:>6} {:>6} {}",
-        "PID", "Name", "CPU%", "Mem", "Path"
-    );
-    let header = Paragraph::new(Line::from(vec![Span::styled(
-        header_text,
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )]));
+    let mut columns = vec![
+        (format!("{:6}", "PID"), Some(SortKey::Pid)),
+        (format!("{:20}", "Name"), Some(SortKey::Name)),
+        // Core% reads against one core, CPU% against the whole machine
+        (
+            format!("{:>6}", if state.cpu_per_core { "Core%" } else { "CPU%" }),
+            Some(SortKey::Cpu),
+        ),
+        if show_private {
+            (format!("{:>6}", "Priv"), Some(SortKey::PrivateBytes))
+        } else {
+            (format!("{:>6}", "Mem"), Some(SortKey::Memory))
+        },
+        (format!("{:>6}", "GPU%"), Some(SortKey::Gpu)),
+        (format!("{:>8}", "GPU Mem"), Some(SortKey::GpuMemory)),
+        (format!("{:>7}", "Read/s"), Some(SortKey::DiskIo)),
+        (format!("{:>7}", "Write/s"), Some(SortKey::DiskIo)),
+        (format!("{:>7}", "Uptime"), Some(SortKey::Started)),
+    ];
+    if state.show_object_columns {
+        columns.push((format!("{:>7}", "Handles"), Some(SortKey::Handles)));
+        columns.push((format!("{:>5}", "GDI"), Some(SortKey::GdiObjects)));
+        columns.push((format!("{:>5}", "USER"), Some(SortKey::UserObjects)));
+    }
+    columns.extend(state.table.script_headers().into_iter().map(|header| (header, None)));
+    let path_header = if state.show_command_line { "Command Line" } else { "Path" };
+    let path_header = match state.path_scroll {
+        0 => path_header.to_string(),
+        scroll => format!("{} [+{} h/l]", path_header, scroll),
+    };
+    columns.push((path_header, None));
+    let header = Paragraph::new(fit_line(header_line(columns, state.table.sort_key), width));
     f.render_widget(header, chunks[0]);
 
     // Render list block with border (full area)
     let list_block = Block::default()
         .borders(Borders::ALL)
-        .title(title)
+        .title(fit(&title, width))
         .title_style(Style::default().fg(Color::Cyan));
     f.render_widget(list_block.clone(), area);
 
@@ -166,5 +394,6 @@ pub fn render(f: &mut Frame, state: &mut LockerState, search_query: &str, area:
     let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
 
     // Pass mutable reference directly (not cloned) so selection is preserved
-    f.render_stateful_widget(list, chunks[1], &mut state.list_state);
+    state.table.viewport_rows = chunks[1].height as usize;
+    f.render_stateful_widget(list, chunks[1], &mut state.table.list_state);
 }
@@ -1,62 +1,193 @@
-mod controller;
-mod locker;
-mod nexus;
+pub mod controller;
+pub mod locker;
+pub mod nexus;
+mod perf;
+pub mod text;
 
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Paragraph, Tabs},
+    widgets::{Block, Borders, Clear, Paragraph, Tabs, Wrap},
     Frame,
 };
 
-use crate::app::{App, Modal, Tab};
+use crate::app::{
+    AdminTask, App, KillConfirmation, MarkPrefix, Modal, PendingAction, SessionCommand, Tab,
+    ToolRun,
+};
+use crate::i18n::{tr, tr_fmt};
+use crate::state::notifications::{Notifications, Severity};
+
+/// Narrower terminals drop the keybindings sidebar to leave the lists room.
+const SIDEBAR_MIN_TERMINAL_WIDTH: u16 = 100;
 
 pub fn render(f: &mut Frame, app: &mut App) {
+    if app.zoomed {
+        render_zoomed(f, app);
+        return;
+    }
+
+    let banner = port_watch_banner(app);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .margin(1)
         .constraints([
-            Constraint::Length(3), // Tabs
-            Constraint::Length(1), // Tab description
+            Constraint::Length(3),                       // Tabs
+            Constraint::Length(3),                       // System performance strip
+            Constraint::Length(banner.is_some() as u16), // Port watch banner
+            Constraint::Length(1),                       // Tab description
             Constraint::Min(0),    // Content (will be split horizontally)
             Constraint::Length(1), // Status bar
         ])
         .split(f.area());
 
     render_header(f, app, chunks[0]);
-    render_tab_description(f, app, chunks[1]);
+    perf::render(f, &app.state.perf, chunks[1]);
+    if let Some(banner) = banner {
+        f.render_widget(banner, chunks[2]);
+    }
+    render_tab_description(f, app, chunks[3]);
 
-    // Split content area into main panel + sidebar
+    // Split content area into main panel + sidebar, leaving the sidebar out when the lists
+    // would get too narrow for it
+    let sidebar_width = if f.area().width < SIDEBAR_MIN_TERMINAL_WIDTH { 0 } else { 22 };
     let content_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
-            Constraint::Min(0),     // Main content (flexible)
-            Constraint::Length(22), // Sidebar (22 columns for keybindings)
+            Constraint::Min(0),                // Main content (flexible)
+            Constraint::Length(sidebar_width), // Sidebar (22 columns for keybindings)
         ])
-        .split(chunks[2]);
+        .split(chunks[4]);
+
+    // The focused pane, which the search box belongs to
+    let focused_area = match app.split {
+        Some(split) => {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(content_chunks[0]);
+            let (focused, other) = if split.focus_left { (0, 1) } else { (1, 0) };
+            render_pane_label(f, split.other, false, panes[other]);
+            render_tab_content(f, app, split.other, "", pane_body(panes[other]));
+            render_pane_label(f, app.current_tab, true, panes[focused]);
+            pane_body(panes[focused])
+        }
+        None => content_chunks[0],
+    };
 
-    if app.search_mode {
+    if app.search_mode || app.find_mode {
         let inner_area = Rect::new(
-            content_chunks[0].x,
-            content_chunks[0].y,
-            content_chunks[0].width,
-            content_chunks[0].height.saturating_sub(3),
+            focused_area.x,
+            focused_area.y,
+            focused_area.width,
+            focused_area.height.saturating_sub(3),
         );
-        render_tab_content(f, app, inner_area);
-        render_search_box(f, app, content_chunks[0]);
+        let query = app.search_query.clone();
+        render_tab_content(f, app, app.current_tab, &query, inner_area);
+        render_search_box(f, app, focused_area);
     } else {
-        render_tab_content(f, app, content_chunks[0]);
+        let query = app.search_query.clone();
+        render_tab_content(f, app, app.current_tab, &query, focused_area);
     }
 
     // Render sidebar with keybindings
-    render_keybindings_sidebar(f, app, content_chunks[1]);
+    if sidebar_width > 0 {
+        render_keybindings_sidebar(f, app, content_chunks[1]);
+    }
+
+    render_status_bar(f, app, chunks[5]);
+
+    if app.modal.is_some() {
+        render_modal(f, app);
+    }
+
+    // Toasts go on top of everything, including modals
+    render_toasts(f, &app.state.notifications, content_chunks[0]);
+}
 
-    render_status_bar(f, app, chunks[3]);
+/// The focused list over the whole screen, with only the status bar kept, for small
+/// terminals.
+fn render_zoomed(f: &mut Frame, app: &mut App) {
+    // A port going down matters even with everything else hidden
+    let banner = port_watch_banner(app);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(banner.is_some() as u16),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .split(f.area());
+    if let Some(banner) = banner {
+        f.render_widget(banner, chunks[0]);
+    }
+    let chunks = [chunks[1], chunks[2]];
+
+    let query = app.search_query.clone();
+    if app.search_mode || app.find_mode {
+        let inner_area = Rect::new(
+            chunks[0].x,
+            chunks[0].y,
+            chunks[0].width,
+            chunks[0].height.saturating_sub(3),
+        );
+        render_tab_content(f, app, app.current_tab, &query, inner_area);
+        render_search_box(f, app, chunks[0]);
+    } else {
+        render_tab_content(f, app, app.current_tab, &query, chunks[0]);
+    }
+    render_status_bar(f, app, chunks[1]);
 
     if app.modal.is_some() {
         render_modal(f, app);
     }
+    render_toasts(f, &app.state.notifications, chunks[0]);
+}
+
+fn severity_color(severity: Severity) -> Color {
+    match severity {
+        Severity::Info => Color::Cyan,
+        Severity::Success => Color::Green,
+        Severity::Warning => Color::Yellow,
+        Severity::Error => Color::Red,
+    }
+}
+
+/// Stacks active notifications in the bottom-right corner of the content area, newest at the bottom.
+fn render_toasts(f: &mut Frame, notifications: &Notifications, area: Rect) {
+    let width = area.width.min(60);
+    if width < 20 {
+        return;
+    }
+
+    let mut bottom = area.bottom();
+    for toast in &notifications.active {
+        // Border plus as many lines as the message needs
+        let text_width = width.saturating_sub(2).max(1) as usize;
+        let text_lines = toast.message.chars().count().div_ceil(text_width).max(1) as u16;
+        let height = text_lines.min(3) + 2;
+        if bottom < area.y + height {
+            break;
+        }
+        bottom -= height;
+
+        let toast_area = Rect::new(area.right() - width, bottom, width, height);
+        let color = severity_color(toast.severity);
+        let paragraph = Paragraph::new(toast.message.as_str())
+            .wrap(Wrap { trim: true })
+            .style(Style::default().fg(Color::White))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(color))
+                    .title(format!(" {} ", toast.severity.as_str()))
+                    .title_style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+            );
+
+        f.render_widget(Clear, toast_area);
+        f.render_widget(paragraph, toast_area);
+    }
 }
 
 fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
@@ -64,7 +195,7 @@ fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
         .iter()
         .map(|t| {
             let (first, rest) = t.as_str().split_at(1);
-            Line::from(vec![
+            let mut spans = vec![
                 Span::styled(
                     first,
                     Style::default()
@@ -72,21 +203,38 @@ fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
                         .add_modifier(Modifier::UNDERLINED),
                 ),
                 Span::styled(rest, Style::default().fg(Color::White)),
-            ])
+            ];
+            // What happened there while another tab was shown
+            if let Some(badge) = app.tab_badges.get(t)
+                && let Some(label) = badge.label()
+            {
+                let color = if badge.urgent { Color::Red } else { Color::Yellow };
+                spans.push(Span::styled(
+                    format!(" {}", label),
+                    Style::default().fg(color).add_modifier(Modifier::BOLD),
+                ));
+            }
+            Line::from(spans)
         })
         .collect();
 
+    // Where and when this runs, so a screenshot speaks for itself; left out when it
+    // would run into the app title
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Aperture ")
+        .title_style(
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        );
+    let machine = machine_line(app);
+    if machine.width() + 16 <= area.width as usize {
+        block = block.title(machine.right_aligned());
+    }
+
     let tabs = Tabs::new(titles)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(" Aperture ")
-                .title_style(
-                    Style::default()
-                        .fg(Color::Cyan)
-                        .add_modifier(Modifier::BOLD),
-                ),
-        )
+        .block(block)
         .select(
             Tab::all()
                 .iter()
@@ -102,12 +250,34 @@ fn render_header(f: &mut Frame, app: &mut App, area: Rect) {
     f.render_widget(tabs, area);
 }
 
-fn render_tab_description(f: &mut Frame, app: &mut App, area: Rect) {
-    let description = match app.current_tab {
-        Tab::Locker => "Find and kill processes holding file locks",
-        Tab::Controller => "Start, stop, and manage Windows services",
-        Tab::Nexus => "Monitor active network connections",
+/// Host name, Windows version, uptime and boot time, e.g.
+/// ` web01 · Windows 10.0.22631 · up 3d 04h12m (booted 2024-05-02 08:15) `.
+fn machine_line(app: &App) -> Line<'static> {
+    let machine = &app.machine;
+    let dim = Style::default().fg(Color::Gray);
+    let mut spans = Vec::new();
+    for part in [&machine.hostname, &machine.os_version] {
+        if !part.is_empty() {
+            spans.push(Span::styled(format!(" {} ·", part), dim));
+        }
+    }
+    let secs = crate::sys::system::uptime().as_secs();
+    let uptime = if secs < 86400 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d {:02}h{:02}m", secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60)
     };
+    let up = tr_fmt(" up {}", &[&uptime]);
+    spans.push(Span::styled(up, Style::default().fg(Color::White)));
+    if !machine.booted.is_empty() {
+        spans.push(Span::styled(tr_fmt(" (booted {})", &[&machine.booted]), dim));
+    }
+    spans.push(Span::raw(" "));
+    Line::from(spans)
+}
+
+fn render_tab_description(f: &mut Frame, app: &mut App, area: Rect) {
+    let description = tr(app.state.tab(app.current_tab).description());
 
     let desc_line = Line::from(vec![
         Span::styled("  → ", Style::default().fg(Color::DarkGray)),
@@ -129,101 +299,64 @@ fn render_keybindings_sidebar(f: &mut Frame, app: &App, area: Rect) {
         .add_modifier(Modifier::BOLD);
     let key_style = Style::default().fg(Color::Cyan);
     let action_style = Style::default().fg(Color::White);
-    let _muted_style = Style::default().fg(Color::Gray);
+    let header = |text: &'static str| Line::from(Span::styled(tr(text), header_style));
+    // Keys are padded to line the (translated) actions up
+    let key_line = |key: &'static str, action: &'static str| {
+        let padding = " ".repeat(6usize.saturating_sub(key.chars().count()).max(1));
+        Line::from(vec![
+            Span::styled(key, key_style),
+            Span::styled(format!("{}{}", padding, tr(action)), action_style),
+        ])
+    };
 
     let mut lines = vec![
-        Line::from(Span::styled("Keys", header_style)),
+        header("Keys"),
         Line::from(""),
-        Line::from(Span::styled("Navigation", header_style)),
-        Line::from(vec![
-            Span::styled("j/k", key_style),
-            Span::styled("  Move", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("↑/↓", key_style),
-            Span::styled("  Move", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("C-d/u", key_style),
-            Span::styled(" Page", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("gg/G", key_style),
-            Span::styled(" First/Last", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("Tab", key_style),
-            Span::styled("  Switch", action_style),
-        ]),
+        header("Navigation"),
+        key_line("j/k", "Move"),
+        key_line("↑/↓", "Move"),
+        key_line("C-d/u", "Page"),
+        key_line("gg/G", "First/Last"),
+        key_line("Tab", "Switch"),
         Line::from(""),
-        Line::from(Span::styled("Actions", header_style)),
-        Line::from(vec![
-            Span::styled("/", key_style),
-            Span::styled("     Search", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("s/S", key_style),
-            Span::styled("   Sort", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("f", key_style),
-            Span::styled("     FindLocks", action_style),
-        ]),
+        header("Actions"),
+        key_line("/", "Search"),
+        key_line("s/S", "Sort"),
+        key_line("A-1-9", "Sort column"),
+        key_line("f", "FindLocks"),
+        key_line("o", "PortOwner"),
+        key_line("J", "Go To"),
+        key_line("x", "Tools"),
+        key_line("P", "Profiles"),
+        key_line("b", "Snapshots"),
+        key_line("!", "Errors"),
+        key_line("I", "Timings"),
+        key_line("E", "Messages"),
+        key_line("1-9", "Presets"),
+        key_line("C-z", "Shell"),
+        key_line("V", "Split"),
+        key_line("Z", "Zoom"),
+        key_line("C-w", "Other pane"),
     ];
 
     // Tab-specific keybindings
-    match app.current_tab {
-        Tab::Locker => {
-            lines.push(Line::from(vec![
-                Span::styled("t", key_style),
-                Span::styled("     TreeView", action_style),
-            ]));
-            lines.push(Line::from(vec![
-                Span::styled("SPC", key_style),
-                Span::styled("   Expand", action_style),
-            ]));
-            lines.push(Line::from(vec![
-                Span::styled("d", key_style),
-                Span::styled("     Details", action_style),
-            ]));
-            lines.push(Line::from(vec![
-                Span::styled("K", key_style),
-                Span::styled("     Kill", action_style),
-            ]));
-        }
-        Tab::Controller => {
-            lines.push(Line::from(vec![
-                Span::styled("Enter", key_style),
-                Span::styled(" Toggle", action_style),
-            ]));
-        }
-        Tab::Nexus => {
-            // Nexus has fewer specific actions
-        }
+    for (key, action) in app.state.tab(app.current_tab).keybindings() {
+        lines.push(key_line(key, action));
     }
 
     // Common keybindings
     lines.extend(vec![
-        Line::from(vec![
-            Span::styled("r", key_style),
-            Span::styled("     Refresh", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("Esc", key_style),
-            Span::styled("   ClearFilt", action_style),
-        ]),
-        Line::from(vec![
-            Span::styled("e", key_style),
-            Span::styled("     Export", action_style),
-        ]),
+        key_line("r", "Refresh"),
+        key_line("Esc", "ClearFilt"),
+        key_line("e", "Export"),
         Line::from(""),
-        Line::from(Span::styled("System", header_style)),
+        header("System"),
     ]);
 
     // Show filter status
     if app.has_active_filter() {
         lines.push(Line::from(vec![Span::styled(
-            "FILTER",
+            tr("FILTER"),
             Style::default().fg(Color::Yellow),
         )]));
     }
@@ -231,96 +364,263 @@ fn render_keybindings_sidebar(f: &mut Frame, app: &App, area: Rect) {
     // Show elevation status
     if !app.is_elevated {
         lines.push(Line::from(vec![Span::styled(
-            "[!] Admin",
+            tr("[!] Admin"),
             Style::default().fg(Color::Red),
         )]));
     }
 
-    lines.extend(vec![
-        Line::from(""),
-        Line::from(Span::styled("Quit", header_style)),
-        Line::from(vec![
-            Span::styled("q", key_style),
-            Span::styled("     Exit", action_style),
-        ]),
-    ]);
+    lines.extend(vec![Line::from(""), header("Quit"), key_line("q", "Exit")]);
 
     let paragraph = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Shortcuts ")
+            .title(format!(" {} ", tr("Shortcuts")))
             .title_style(Style::default().fg(Color::Cyan)),
     );
 
     f.render_widget(paragraph, area);
 }
 
-fn render_tab_content(f: &mut Frame, app: &mut App, area: Rect) {
-    match app.current_tab {
-        Tab::Locker => locker::render(f, &mut app.state.locker, &app.search_query, area),
-        Tab::Controller => {
-            controller::render(f, &mut app.state.controller, &app.search_query, area)
-        }
-        Tab::Nexus => nexus::render(f, &mut app.state.nexus, &app.search_query, area),
+/// A split pane below its one-line label.
+fn pane_body(area: Rect) -> Rect {
+    Rect::new(area.x, area.y + 1, area.width, area.height.saturating_sub(1))
+}
+
+/// One red line naming the watched ports that are down, or None while all are up.
+fn port_watch_banner(app: &App) -> Option<Paragraph<'static>> {
+    let failing: Vec<String> = app
+        .port_watches
+        .failing()
+        .filter_map(|status| {
+            let (problem, since) = status.down.as_ref()?;
+            Some(tr_fmt("{}: {} since {}", &[&status.watch.label(), problem, since]))
+        })
+        .collect();
+    if failing.is_empty() {
+        return None;
     }
+    Some(
+        Paragraph::new(format!(" {} {}", tr("PORT DOWN"), failing.join("  |  "))).style(
+            Style::default()
+                .fg(Color::White)
+                .bg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        ),
+    )
+}
+
+/// The tab name above a split pane, highlighted on the focused one.
+fn render_pane_label(f: &mut Frame, tab: Tab, focused: bool, area: Rect) {
+    let style = if focused {
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let label = if focused {
+        format!(" {} ", tab.as_str())
+    } else {
+        format!(" {} {}", tab.as_str(), tr("(C-w to focus)"))
+    };
+    let area = Rect::new(area.x, area.y, area.width, area.height.min(1));
+    f.render_widget(Paragraph::new(Span::styled(label, style)), area);
+}
+
+fn render_tab_content(f: &mut Frame, app: &mut App, tab: Tab, search_query: &str, area: Rect) {
+    // A failed refresh leaves the last good rows in place, under a banner saying so
+    let area = match app.refresh_status.get(&tab) {
+        Some(status) if status.failed() => {
+            let source = tr(app.state.tab(tab).source()).to_lowercase();
+            let shown = match &status.succeeded_clock {
+                Some(clock) => tr_fmt("showing data from {}", &[clock]),
+                None => tr("no data yet").to_string(),
+            };
+            let failure = tr_fmt(
+                "Failed to refresh the {}: {}",
+                &[&source, &status.error.as_deref().unwrap_or_default()],
+            );
+            let banner = format!(" {} — {}", failure, shown);
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(1), Constraint::Min(0)])
+                .split(area);
+            f.render_widget(
+                Paragraph::new(banner)
+                    .style(Style::default().fg(Color::White).bg(Color::Red)),
+                chunks[0],
+            );
+            chunks[1]
+        }
+        _ => area,
+    };
+    app.state.tab_mut(tab).render(f, search_query, area);
 }
 
 fn render_status_bar(f: &mut Frame, app: &mut App, area: Rect) {
     let mut spans = vec![];
 
     // Show sort indicator
-    let sort_info = match app.current_tab {
-        Tab::Locker => format!(
-            "Sort: {} {}",
-            app.state.locker.sort_key.as_str(),
-            app.state.locker.sort_order.as_str()
-        ),
-        Tab::Controller => format!(
-            "Sort: {} {}",
-            app.state.controller.sort_key.as_str(),
-            app.state.controller.sort_order.as_str()
-        ),
-        Tab::Nexus => format!(
-            "Sort: {} {}",
-            app.state.nexus.sort_key.as_str(),
-            app.state.nexus.sort_order.as_str()
-        ),
-    };
+    let sort_info = tr_fmt("Sort: {}", &[&app.state.tab(app.current_tab).sort_label()]);
     spans.push(Span::styled(sort_info, Style::default().fg(Color::Cyan)));
 
+    if app.read_only {
+        spans.push(Span::styled(
+            format!("  {}", tr("[READ-ONLY]")),
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // The sidebar is hidden while zoomed, so say how to get it back
+    if app.zoomed {
+        spans.push(Span::styled(
+            format!("  {}", tr("[ZOOM - Z to restore]")),
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
     // Show filter status if active
     if app.has_active_filter() {
         spans.push(Span::styled(
-            "  [FILTER ACTIVE]",
+            format!("  {}", tr("[FILTER ACTIVE]")),
             Style::default().fg(Color::Yellow),
         ));
     }
 
-    // Show status message if present
-    if let Some(msg) = &app.status_message {
-        spans.push(Span::styled("  ", Style::default()));
-        spans.push(Span::styled(msg, Style::default().fg(Color::Yellow)));
+    // Point at the error panel when something failed since it was last opened
+    if app.state.errors.unseen > 0 {
+        spans.push(Span::styled(
+            format!("  {}", tr_fmt("[{} new errors - press !]", &[&app.state.errors.unseen])),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        ));
     }
 
     // Show elevation warning
     if !app.is_elevated {
         spans.push(Span::styled(
-            "  [!] No admin",
+            format!("  {}", tr("[!] No admin")),
             Style::default().fg(Color::Red),
         ));
     }
 
+    if !app.find_mode && !app.find_query.is_empty() {
+        spans.push(Span::styled(
+            format!("  [{}]", tr_fmt("FIND: {} - n/N", &[&app.find_query])),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+
+    if let Some(watch) = &app.lock_watch {
+        let (text, style) = match (watch.freed_at, watch.lockers) {
+            (Some(_), _) => (
+                tr_fmt("[{} free]", &[&watch.label()]),
+                Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+            ),
+            (None, Some(lockers)) => (
+                tr_fmt("[watching {}: {} locks]", &[&watch.label(), &lockers]),
+                Style::default().fg(Color::Yellow),
+            ),
+            (None, None) => (
+                tr_fmt("[watching {}]", &[&watch.label()]),
+                Style::default().fg(Color::Yellow),
+            ),
+        };
+        spans.push(Span::styled(format!("  {}", text), style));
+    }
+
+    if app.is_idle() {
+        spans.push(Span::styled(
+            format!(
+                "  {}",
+                tr_fmt("[idle - polling every {}s]", &[&app.config.idle_polling.interval_secs])
+            ),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+
+    // Keys typed so far of a count or mark, like vim's showcmd
+    let pending = match (app.pending_count, app.pending_mark) {
+        (_, Some(MarkPrefix::Set)) => Some("m".to_string()),
+        (_, Some(MarkPrefix::Jump)) => Some("'".to_string()),
+        (Some(count), None) => Some(count.to_string()),
+        (None, None) => None,
+    };
+    if let Some(pending) = pending {
+        spans.push(Span::styled(
+            format!("  {}", pending),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // Detail about the selected row that the table has no room for
+    if let Some(hint) = app.state.tab(app.current_tab).selection_hint(&app.search_query) {
+        spans.push(Span::styled(
+            format!("  │ {}", hint),
+            Style::default().fg(Color::Gray),
+        ));
+    }
+
+    // Data age and wall clock on the right, red once the tab's data stops being refreshed
+    let refresh = app
+        .refresh_status
+        .get(&app.current_tab)
+        .cloned()
+        .unwrap_or_default();
+    let age = match refresh.succeeded_at {
+        None => tr("not refreshed yet").to_string(),
+        Some(at) => tr_fmt(
+            if refresh.failed() { "refresh failed, refreshed {}" } else { "refreshed {}" },
+            &[&format_secs_ago(at.elapsed().as_secs())],
+        ),
+    };
+    let clock = format!(" {}  {} ", age, crate::sys::system::local_clock());
+    let clock_style = if refresh.is_stale(app.stale_after()) {
+        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+    // Aperture's own footprint, so it's plain when the monitor is the problem
+    let own = app
+        .state
+        .locker
+        .own_process()
+        .map(|p| {
+            let cpu = format!("{:.1}", app.state.locker.cpu_display(p.cpu_usage));
+            let memory = format!("{:.0}", p.private_mb);
+            format!(" {} ", tr_fmt("self {}% {}MB", &[&cpu, &memory]))
+        })
+        .unwrap_or_default();
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Min(0),
+            Constraint::Length(own.chars().count() as u16),
+            Constraint::Length(clock.chars().count() as u16),
+        ])
+        .split(area);
+
     let status = Paragraph::new(Line::from(spans));
-    f.render_widget(status, area);
+    f.render_widget(status, chunks[0]);
+    let own_style = Style::default().fg(Color::DarkGray);
+    f.render_widget(Paragraph::new(Span::styled(own, own_style)), chunks[1]);
+    f.render_widget(Paragraph::new(Span::styled(clock, clock_style)), chunks[2]);
 }
 
 fn render_search_box(f: &mut Frame, app: &mut App, area: Rect) {
     let search_area = Rect::new(area.x, area.bottom().saturating_sub(3), area.width, 3);
-    let search = Paragraph::new(format!("Search: {}", app.search_query))
+    let (title, text) = if app.find_mode {
+        (tr(" ? find [Enter] "), tr_fmt("Find: {}", &[&app.find_query]))
+    } else if app.config.fuzzy_search {
+        (tr(" / fuzzy [Tab] "), tr_fmt("Search: {}", &[&app.search_query]))
+    } else {
+        (tr(" / exact [Tab] "), tr_fmt("Search: {}", &[&app.search_query]))
+    };
+    let search = Paragraph::new(text)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" / ")
+                .title(title)
                 .title_style(Style::default().fg(Color::Cyan)),
         )
         .style(Style::default().fg(Color::White));
@@ -329,169 +629,2941 @@ fn render_search_box(f: &mut Frame, app: &mut App, area: Rect) {
 
 fn render_modal(f: &mut Frame, app: &mut App) {
     match &app.modal {
-        Some(Modal::KillConfirmation { pid, name }) => {
-            render_kill_confirmation(f, *pid, name);
+        Some(Modal::KillConfirmation(confirmation)) => {
+            let services = app.state.controller.services_for_pid(confirmation.pid);
+            render_kill_confirmation(
+                f,
+                confirmation,
+                &services,
+                app.config.dump.dump_type.as_str(),
+            );
+        }
+        Some(Modal::KillTreePreview {
+            confirmation,
+            tree,
+            scroll,
+        }) => {
+            render_kill_tree_preview(f, app, confirmation, tree, *scroll);
+        }
+        Some(Modal::CreateService {
+            name,
+            display_name,
+            binary_path,
+            start_type,
+            field,
+            error,
+        }) => {
+            render_create_service_modal(
+                f,
+                [name, display_name, binary_path],
+                *start_type,
+                *field,
+                error,
+            );
+        }
+        Some(Modal::DeleteService {
+            name,
+            display_name,
+            confirm,
+            error,
+        }) => {
+            render_delete_service_modal(f, name, display_name, confirm, error);
+        }
+        Some(Modal::ImportServices {
+            path,
+            loaded,
+            error,
+        }) => {
+            render_import_services_modal(f, path, loaded.as_ref(), error);
         }
-        Some(Modal::HandleSearch {
+        Some(Modal::StopDependents {
+            name,
+            display_name,
+            dependents,
+        }) => {
+            render_stop_dependents(f, name, display_name, dependents);
+        }
+        Some(Modal::Guardrail {
+            name,
+            typed,
+            action,
+        }) => {
+            render_guardrail(f, name, typed, action);
+        }
+        Some(Modal::Unlock {
+            paths,
+            lockers,
+            rename,
+        }) => {
+            render_unlock(f, paths, lockers, rename);
+        }
+        Some(Modal::ElevationRequired { task, error, .. }) => {
+            render_elevation_required(f, *task, error);
+        }
+        Some(Modal::HandleSearch { .. }) => {
+            render_handle_search_modal(f, app);
+        }
+        Some(Modal::ProcessDetails(details)) => {
+            let footprint = app.state.nexus.footprint(details.pid);
+            let services = app.state.controller.services_for_pid(details.pid);
+            render_process_details_modal(
+                f,
+                details,
+                &app.state.locker,
+                &footprint,
+                &services,
+                app.is_elevated,
+            );
+        }
+        Some(Modal::Threads(view)) => {
+            render_threads_modal(f, view, &app.state.locker);
+        }
+        Some(Modal::RemoteLookup(lookup)) => {
+            render_remote_lookup_modal(f, &app.config.lookup, lookup);
+        }
+        Some(Modal::GoTo { targets, selected }) => {
+            render_go_to_modal(f, targets, *selected);
+        }
+        Some(Modal::Tools { tools, selected, .. }) => {
+            render_tools_modal(f, tools, *selected);
+        }
+        Some(Modal::ConnectionDetails { conn, owner, error }) => {
+            let firewall = app.state.nexus.firewall_check(conn);
+            render_connection_details_modal(f, conn, owner, firewall, error);
+        }
+        Some(Modal::ServiceDetails {
+            service,
+            recovery,
+            field,
+            dirty,
+            error,
+        }) => {
+            render_service_details_modal(f, app, service, recovery, *field, *dirty, error);
+        }
+        Some(Modal::ExportFormat) => {
+            render_export_format_modal(f);
+        }
+        Some(Modal::PortLookup {
             input,
             results,
             selected,
-            loading,
             error,
-            is_directory,
-            files_scanned,
+            searched,
         }) => {
-            render_handle_search_modal(
+            render_port_lookup_modal(
                 f,
                 input,
                 results,
                 *selected,
-                *loading,
                 error,
+                *searched,
                 app.is_elevated,
-                app.handle_search_input_mode,
-                *is_directory,
-                *files_scanned,
             );
         }
-        Some(Modal::ProcessDetails(details)) => {
-            render_process_details_modal(f, details, app.is_elevated);
+        Some(Modal::NamedPipes {
+            pipes,
+            selected,
+            error,
+        }) => {
+            render_named_pipes_modal(f, pipes, *selected, error);
         }
-        Some(Modal::ExportFormat) => {
-            render_export_format_modal(f);
+        Some(Modal::LogonSessions {
+            sessions,
+            selected,
+            error,
+            confirm,
+        }) => {
+            render_logon_sessions_modal(f, sessions, *selected, error, *confirm);
+        }
+        Some(Modal::DnsCache {
+            entries,
+            hosts,
+            selected,
+            show_hosts,
+            error,
+        }) => {
+            render_dns_cache_modal(f, entries, hosts, *selected, *show_hosts, error);
+        }
+        Some(Modal::Profiles {
+            selected,
+            naming,
+            error,
+        }) => {
+            render_profiles_modal(f, &app.config.profiles, *selected, naming, error);
+        }
+        Some(Modal::Snapshots {
+            snapshots,
+            selected,
+            base,
+            error,
+        }) => {
+            render_snapshots_modal(f, snapshots, *selected, *base, app.recorder_status(), error);
+        }
+        Some(Modal::RecorderHistory {
+            events,
+            selected,
+            error,
+        }) => {
+            render_recorder_history_modal(f, events, *selected, error);
+        }
+        Some(Modal::ErrorLog { selected }) => {
+            render_error_log_modal(f, &app.state.errors, *selected);
+        }
+        Some(Modal::Timings) => {
+            render_timings_modal(f, &app.state.timings);
+        }
+        Some(Modal::Notifications { selected }) => {
+            render_notifications_modal(f, &app.state.notifications, *selected);
+        }
+        Some(Modal::SnapshotDiff {
+            title,
+            entries,
+            selected,
+        }) => {
+            render_snapshot_diff_modal(f, title, entries, *selected);
         }
         _ => {}
     }
 }
 
-fn render_kill_confirmation(f: &mut Frame, pid: u32, name: &str) {
-    let area = centered_rect(50, 9, f.area());
+fn render_kill_confirmation(
+    f: &mut Frame,
+    confirmation: &KillConfirmation,
+    services: &[&str],
+    dump_type: &str,
+) {
+    let height = if services.is_empty() { 15 } else { 16 };
+    let area = centered_rect(50, height, f.area());
+
+    // Centered, so labels are padded to one width to keep the checkboxes in a column
+    let width = [
+        "[f] Force (SeDebugPrivilege)",
+        "[t] Kill process tree",
+        "[j] Kill the whole job",
+    ]
+    .iter()
+    .map(|label| tr(label).chars().count())
+    .max()
+    .unwrap_or_default();
+    let option = |on: bool, label: String| {
+        let style = if on {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let checkbox = if on { "[x]" } else { "[ ]" };
+        Line::from(Span::styled(format!("{} {:width$}", checkbox, label), style))
+    };
+    let exit_code = if confirmation.exit_code.is_empty() {
+        "1".to_string()
+    } else {
+        confirmation.exit_code.clone()
+    };
+    let target = tr(if confirmation.job {
+        "Every process in its job is killed too."
+    } else if confirmation.tree {
+        "Its child processes are killed too."
+    } else {
+        "This action cannot be undone."
+    });
 
-    let text = vec![
+    let mut text = vec![
         Line::from(""),
         Line::from(Span::styled(
-            "Confirm Kill Process",
+            tr("Confirm Kill Process"),
             Style::default()
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(format!("  Kill \"{}\" (PID: {})?", name, pid)),
-        Line::from("  This action cannot be undone."),
+        Line::from(format!(
+            "  {}",
+            tr_fmt("Kill \"{}\" (PID: {})?", &[&confirmation.name, &confirmation.pid])
+        )),
+        Line::from(format!("  {}", target)),
+    ];
+    if !services.is_empty() {
+        // Killing a shared host takes every service in it down
+        text.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Also stops services: {}", &[&services.join(", ")])),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    text.extend([
+        Line::from(""),
+        option(confirmation.force, tr("[f] Force (SeDebugPrivilege)").to_string()),
+        option(confirmation.tree, tr("[t] Kill process tree").to_string()),
+        option(confirmation.job, tr("[j] Kill the whole job").to_string()),
+        option(confirmation.dump, tr_fmt("[d] Write a {} dump first", &[&dump_type])),
+        Line::from(vec![
+            Span::raw(tr("Exit code (0-9, Backspace): ")),
+            Span::styled(exit_code, Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("       {}  ", tr("[Y] Yes")),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(tr("[N] No"), Style::default().fg(Color::Red)),
+        ]),
+        Line::from(""),
+    ]);
+
+    let paragraph = Paragraph::new(text)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", tr("Confirmation")))
+                .title_style(Style::default().fg(Color::Red)),
+        )
+        .alignment(Alignment::Center);
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_kill_tree_preview(
+    f: &mut Frame,
+    app: &App,
+    confirmation: &KillConfirmation,
+    tree: &[crate::sys::process::KillMember],
+    scroll: usize,
+) {
+    let height = (tree.len().max(1) as u16 + 9).min(f.area().height.saturating_sub(2));
+    let area = centered_rect(60, height, f.area());
+    // Leave room for the header, footer and borders
+    let visible = (height as usize).saturating_sub(9).max(1);
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Confirm Kill Process Tree"),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "  {}",
+            tr_fmt(
+                "Kill \"{}\" (PID: {}) and {} descendants:",
+                &[&confirmation.name, &confirmation.pid, &tree.len()],
+            )
+        )),
+        Line::from(""),
+    ];
+    if tree.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No child processes, only this one is killed.")),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    for member in tree.iter().skip(scroll).take(visible) {
+        let indent = "  ".repeat(member.depth);
+        let mut spans = vec![Span::styled(
+            format!("  {}{} ({})", indent, member.name, member.pid),
+            Style::default().fg(Color::White),
+        )];
+        // Protected members ask for their name to be typed before the kill goes ahead
+        if app.config.guardrails.is_protected_process(&member.name) {
+            spans.push(Span::styled(
+                format!("  {}", tr("[protected]")),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        let services = app.state.controller.services_for_pid(member.pid);
+        if !services.is_empty() {
+            spans.push(Span::styled(
+                format!("  [{}]", services.join(", ")),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    let hidden = tree.len().saturating_sub(scroll + visible);
+    if hidden > 0 {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("... and {} more", &[&hidden])),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{}  ", tr("[Y] Kill all")),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(format!("{}  ", tr("[j/k] Scroll")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Back"), Style::default().fg(Color::Red)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Confirmation")))
+            .title_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_create_service_modal(
+    f: &mut Frame,
+    values: [&String; 3],
+    start_type: usize,
+    field: usize,
+    error: &Option<String>,
+) {
+    let area = centered_rect(80, 16, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Create Service"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!("  {}", tr("Own-process service running as LocalSystem")),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+    ];
+
+    let labels = ["Service name", "Display name", "Binary path"];
+    for (i, (label, value)) in labels.iter().zip(values).enumerate() {
+        let focused = i == field;
+        let label_style = if focused {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        let cursor = if focused { "_" } else { "" };
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:14}", tr(label)), label_style),
+            Span::styled(format!("{}{}", value, cursor), Style::default().fg(Color::White)),
+        ]));
+    }
+
+    let start_style = if field == 3 {
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Gray)
+    };
+    let mut start_spans = vec![Span::styled(format!("  {:14}", tr("Start type")), start_style)];
+    for (i, name) in crate::sys::service::START_TYPES.iter().enumerate() {
+        let style = if i == start_type {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::Gray)
+        };
+        start_spans.push(Span::styled(format!(" {} ", name), style));
+        start_spans.push(Span::raw(" "));
+    }
+    lines.push(Line::from(start_spans));
+
+    if let Some(err) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[Tab] Next Field")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[Space] Start Type")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[Enter] Create")), Style::default().fg(Color::Green)),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("New Service")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_delete_service_modal(
+    f: &mut Frame,
+    name: &str,
+    display_name: &str,
+    confirm: &str,
+    error: &Option<String>,
+) {
+    let area = centered_rect(70, 13, f.area());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Delete Service"),
+            Style::default()
+                .fg(Color::Red)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!("  {}", tr_fmt("Delete \"{}\" ({})?", &[&display_name, &name]))),
+        Line::from(format!(
+            "  {}",
+            tr("The service is removed from the registry. This cannot be undone.")
+        )),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(
+                format!("  {} ", tr_fmt("Type \"{}\" to confirm:", &[&name])),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled(format!("{}_", confirm), Style::default().fg(Color::White)),
+        ]),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {}  ", tr("[Enter] Delete")), Style::default().fg(Color::Red)),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Confirmation")))
+            .title_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_import_services_modal(
+    f: &mut Frame,
+    path: &str,
+    loaded: Option<&crate::export::ServiceConfigFile>,
+    error: &Option<String>,
+) {
+    // Cap the list so the modal still fits; the rest is summarized
+    const MAX_LISTED: usize = 12;
+    let listed = loaded.map_or(0, |data| data.services.len().min(MAX_LISTED + 1));
+    let area = centered_rect(80, listed as u16 + 11, f.area());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Import Service Configuration"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    match loaded {
+        None => {
+            lines.push(Line::from(format!(
+                "  {}",
+                tr("Settings exported with [s] in the export menu (e).")
+            )));
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} ", tr("File:")), Style::default().fg(Color::Yellow)),
+                Span::styled(format!("{}_", path), Style::default().fg(Color::White)),
+            ]));
+        }
+        Some(data) => {
+            let question = if data.computer.is_empty() {
+                tr_fmt("Apply the settings of {} service(s)?", &[&data.services.len()])
+            } else {
+                tr_fmt(
+                    "Apply the settings of {} service(s) from {}?",
+                    &[&data.services.len(), &data.computer],
+                )
+            };
+            lines.push(Line::from(format!("  {}", question)));
+            lines.push(Line::from(""));
+            for config in data.services.iter().take(MAX_LISTED) {
+                let start = if config.delayed_auto_start && config.start_type == "Auto" {
+                    tr("Auto (Delayed)").to_string()
+                } else {
+                    config.start_type.clone()
+                };
+                let recovery = config
+                    .recovery
+                    .actions
+                    .iter()
+                    .map(|(action, _)| match action {
+                        crate::sys::service::RecoveryAction::None => "-",
+                        crate::sys::service::RecoveryAction::Restart => tr("restart"),
+                        crate::sys::service::RecoveryAction::Reboot => tr("reboot"),
+                        crate::sys::service::RecoveryAction::RunCommand => tr("run"),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {:28} ", config.service_name),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(format!("{:15} ", start), Style::default().fg(Color::Green)),
+                    Span::styled(
+                        format!("{:28} ", config.account),
+                        Style::default().fg(Color::Gray),
+                    ),
+                    Span::styled(recovery, Style::default().fg(Color::Gray)),
+                ]));
+            }
+            if data.services.len() > MAX_LISTED {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "  {}",
+                        tr_fmt("... and {} more", &[&(data.services.len() - MAX_LISTED)])
+                    ),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+        }
+    }
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(if loaded.is_some() {
+        vec![
+            Span::styled(format!("  {}  ", tr("[Y] Apply")), Style::default().fg(Color::Green)),
+            Span::styled(tr("[N] Cancel"), Style::default().fg(Color::Red)),
+        ]
+    } else {
+        vec![
+            Span::styled(format!("  {}  ", tr("[Enter] Load")), Style::default().fg(Color::Green)),
+            Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+        ]
+    }));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Import")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_stop_dependents(
+    f: &mut Frame,
+    name: &str,
+    display_name: &str,
+    dependents: &[crate::sys::service::DependentService],
+) {
+    // Cap the list so the modal still fits; the rest is summarized
+    const MAX_LISTED: usize = 12;
+    let listed = dependents.len().min(MAX_LISTED);
+    let area = centered_rect(70, listed as u16 + 12, f.area());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Stop Service With Dependents"),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "  {}",
+            tr_fmt(
+                "{} running service(s) depend on \"{}\". They will be stopped in this order:",
+                &[&dependents.len(), &display_name],
+            )
+        )),
+        Line::from(""),
+    ];
+
+    for (i, dependent) in dependents.iter().take(MAX_LISTED).enumerate() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:>2}. ", i + 1), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{:30} ", dependent.service_name),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!("{:14} ", dependent.status),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(dependent.display_name.clone(), Style::default().fg(Color::Gray)),
+        ]));
+    }
+    if dependents.len() > MAX_LISTED {
+        lines.push(Line::from(Span::styled(
+            format!("      {}", tr_fmt("... and {} more", &[&(dependents.len() - MAX_LISTED)])),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("  {:>2}. ", dependents.len() + 1),
+            Style::default().fg(Color::Gray),
+        ),
+        Span::styled(
+            format!("{:30} ", name),
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(format!("{:14} ", tr("Running")), Style::default().fg(Color::Green)),
+        Span::styled(display_name.to_string(), Style::default().fg(Color::Gray)),
+    ]));
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {}  ", tr("[Y] Stop all")), Style::default().fg(Color::Green)),
+        Span::styled(tr("[N] Cancel"), Style::default().fg(Color::Red)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Confirmation")))
+            .title_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_guardrail(f: &mut Frame, name: &str, typed: &str, action: &PendingAction) {
+    let area = centered_rect(60, 13, f.area());
+    let warning = match action {
+        PendingAction::Kill { .. } | PendingAction::Unlock { .. } => tr_fmt(
+            "{} is critical to Windows. Killing it can crash or reboot the machine.",
+            &[&name],
+        ),
+        PendingAction::Dump { .. } => tr_fmt(
+            "{} is critical to Windows. Its memory can hold passwords and keys, which a dump \
+             writes to disk.",
+            &[&name],
+        ),
+        PendingAction::ApplyServiceConfigs(_) => tr_fmt(
+            "{} is critical to Windows. The imported settings can disable it and break logon, \
+             networking or other services.",
+            &[&name],
+        ),
+        _ => tr_fmt(
+            "{} is critical to Windows. Stopping it can break logon, networking or other services.",
+            &[&name],
+        ),
+    };
+    let lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Protected"),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!("  {}", warning)),
+        Line::from(""),
+        Line::from(format!("  {}", tr_fmt("Type {} to confirm:", &[&name]))),
+        Line::from(vec![
+            Span::styled("  > ", Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}_", typed),
+                Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+            ),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(format!("  {}  ", tr("[Enter] Confirm")), Style::default().fg(Color::Red)),
+            Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Green)),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Confirmation")))
+            .title_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_unlock(
+    f: &mut Frame,
+    paths: &[String],
+    lockers: &[crate::app::LockingProcess],
+    rename: &Option<String>,
+) {
+    let height = (paths.len() + lockers.len()) as u16 + 12;
+    let area = centered_rect(70, height.min(f.area().height), f.area());
+    let mut lines = vec![Line::from("")];
+    if lockers.is_empty() {
+        lines.push(Line::from(format!("  {}", tr("No process locks it at the last search."))));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Kill {} locking processes:", &[&lockers.len()])),
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )));
+        for locker in lockers {
+            lines.push(Line::from(format!("    {} ({})", locker.name, locker.pid)));
+        }
+    }
+    lines.push(Line::from(""));
+    match rename {
+        None => {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", tr("Then delete for good, without the Recycle Bin:")),
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            for path in paths {
+                lines.push(Line::from(format!("    {}", path)));
+            }
+        }
+        Some(name) => {
+            lines.push(Line::from(format!("  {}", tr("Then rename:"))));
+            for path in paths {
+                lines.push(Line::from(format!("    {}", path)));
+            }
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {} > ", tr("to")), Style::default().fg(Color::Gray)),
+                Span::styled(
+                    format!("{}_", name),
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                ),
+            ]));
+        }
+    }
+    lines.push(Line::from(""));
+    let confirm = if rename.is_some() { "[Enter] Confirm" } else { "[y] Confirm" };
+    lines.push(Line::from(vec![
+        Span::styled(format!("  {}  ", tr(confirm)), Style::default().fg(Color::Red)),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Green)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Confirmation")))
+            .title_style(Style::default().fg(Color::Red)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_elevation_required(f: &mut Frame, task: AdminTask, error: &Option<String>) {
+    let area = centered_rect(60, 15, f.area());
+
+    let mut lines = vec![
+        Line::from(""),
+        Line::from(Span::styled(
+            tr("Administrator Rights Required"),
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(format!(
+            "  {}",
+            tr_fmt("{} needs Aperture to run as administrator.", &[&tr(task.action())])
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("  {}", tr(task.reason())),
+            Style::default().fg(Color::Gray),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {}",
+                tr("Restarting elevated opens Aperture in a new window after the UAC prompt \
+                    and closes this one.")
+            ),
+            Style::default().fg(Color::Gray),
+        )),
+    ];
+    if let Some(error) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Restart failed: {}", &[error])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("  {}  ", tr("[Enter] Restart as administrator")),
+            Style::default().fg(Color::Green),
+        ),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Red)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Not Elevated")))
+            .title_style(Style::default().fg(Color::Yellow)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Whether a typed path is a file, a folder or missing.
+fn path_status(path: &str) -> Span<'static> {
+    let path = path.trim();
+    if crate::sys::handle::is_registry_path(path) {
+        return if crate::sys::handle::path_exists(path) {
+            Span::styled(format!("  {}", tr("[registry key]")), Style::default().fg(Color::Green))
+        } else {
+            Span::styled(format!("  {}", tr("[not found]")), Style::default().fg(Color::Red))
+        };
+    }
+    if crate::sys::handle::volume_letter(path).is_some() && std::path::Path::new(path).exists() {
+        return Span::styled(format!("  {}", tr("[volume]")), Style::default().fg(Color::Green));
+    }
+    // Only this machine's processes show up for a network path, so it's marked as one
+    let network = crate::sys::handle::is_network_path(path);
+    let path = std::path::Path::new(path);
+    let label = |local, remote| if network { tr(remote) } else { tr(local) };
+    if path.as_os_str().is_empty() {
+        Span::raw("")
+    } else if path.is_dir() {
+        let label = label("[folder]", "[network folder]");
+        Span::styled(format!("  {}", label), Style::default().fg(Color::Green))
+    } else if path.exists() {
+        let label = label("[file]", "[network file]");
+        Span::styled(format!("  {}", label), Style::default().fg(Color::Green))
+    } else {
+        Span::styled(format!("  {}", tr("[not found]")), Style::default().fg(Color::Red))
+    }
+}
+
+fn render_handle_search_modal(f: &mut Frame, app: &App) {
+    let Some(Modal::HandleSearch {
+        input,
+        results,
+        selected,
+        loading,
+        error,
+        is_directory,
+        files_scanned,
+        scan_total,
+        ..
+    }) = &app.modal
+    else {
+        return;
+    };
+    let (selected, loading, is_directory) = (*selected, *loading, *is_directory);
+    let (files_scanned, scan_total) = (*files_scanned, *scan_total);
+    let is_elevated = app.is_elevated;
+    let input_mode = app.handle_search_input_mode;
+    let favorites = &app.config.handle_search.favorites;
+    let completion = app.handle_search_completion();
+    let restartable = app.locker_restart.as_ref().map(|session| session.names.len());
+    let max_files = app.config.handle_search.max_files;
+    let area = centered_rect(70, 20, f.area());
+
+    let input_display = if input.is_empty() && !input_mode {
+        tr("(enter path)").to_string()
+    } else if input_mode {
+        format!("{}_", input)
+    } else {
+        input.to_string()
+    };
+    let path_style = Style::default().fg(if input_mode { Color::White } else { Color::Gray });
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Find Locking Processes"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+    // One line per path, numbered once there are several, and marked with whether it
+    // exists so typos show before searching
+    let paths: Vec<&str> = input_display.split('\n').collect();
+    let typed: Vec<&str> = input.split('\n').collect();
+    for (i, path) in paths.iter().enumerate() {
+        let label = match (paths.len(), i) {
+            (1, _) => format!("{} ", tr("Path:")),
+            (_, i) => format!("{:>4}: ", i + 1),
+        };
+        let mut spans = vec![Span::styled(format!("{}{}", label, path), path_style)];
+        if !input.is_empty()
+            && let Some(typed) = typed.get(i)
+        {
+            spans.push(path_status(typed));
+        }
+        if i + 1 == paths.len()
+            && let Some((shown, count)) = completion
+        {
+            spans.push(Span::styled(
+                format!("  ({})", tr_fmt("{}/{} matches", &[&shown, &count])),
+                Style::default().fg(Color::Gray),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    lines.push(Line::from(""));
+
+    // A scan that hit the limit missed the rest of the folder, so say so
+    let scanned = |count: usize| {
+        if count >= max_files {
+            tr_fmt("{} files (limit reached)", &[&count])
+        } else {
+            tr_fmt("{} files", &[&count])
+        }
+    };
+    // A directory scan streams in, so what it found so far shows while it runs
+    let show_results = if loading {
+        let scan_msg = if is_directory {
+            match (files_scanned, scan_total) {
+                (Some(checked), Some(total)) if total > 0 => format!(
+                    "  {}",
+                    tr_fmt(
+                        "Checked {} of {} files ({}%)...",
+                        &[&checked, &total, &(checked * 100 / total)]
+                    )
+                ),
+                (Some(count), _) => format!("  {}", tr_fmt("Scanning {} files...", &[&count])),
+                _ => format!("  {}", tr("Scanning directory...")),
+            }
+        } else {
+            format!("  {}", tr("Searching..."))
+        };
+        lines.push(Line::from(Span::styled(
+            scan_msg,
+            Style::default().fg(Color::Yellow),
+        )));
+        if !results.is_empty() {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", tr_fmt("Found {} locks so far:", &[&results.len()])),
+                Style::default().fg(Color::Yellow),
+            )));
+            lines.push(Line::from(""));
+        }
+        true
+    } else if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+        false
+    } else if results.is_empty() {
+        let empty_msg = if is_directory {
+            if let Some(count) = files_scanned {
+                format!("  {}", tr_fmt("Scanned {} - no locks found.", &[&scanned(count)]))
+            } else {
+                format!("  {}", tr("No locking processes found."))
+            }
+        } else {
+            format!("  {}", tr("No locking processes found."))
+        };
+        lines.push(Line::from(Span::styled(
+            empty_msg,
+            Style::default().fg(Color::Green),
+        )));
+        false
+    } else {
+        let results_msg = if is_directory {
+            if let Some(count) = files_scanned {
+                let found = tr_fmt(
+                    "Scanned {} - Found {} locks:",
+                    &[&scanned(count), &results.len()],
+                );
+                format!("  {}", found)
+            } else {
+                format!("  {}", tr_fmt("Found {} locks:", &[&results.len()]))
+            }
+        } else {
+            format!("  {}", tr_fmt("Locking processes ({}):", &[&results.len()]))
+        };
+        lines.push(Line::from(Span::styled(
+            results_msg,
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(""));
+        true
+    };
+    if show_results {
+        for (i, proc) in results.iter().enumerate() {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(Span::styled(
+                format!("    PID: {:6}  {}", proc.pid, proc.name),
+                style,
+            )));
+            // What it holds; a folder scan can have it lock many files
+            const MAX_FILES: usize = 3;
+            for file in proc.files.iter().take(MAX_FILES) {
+                lines.push(Line::from(Span::styled(
+                    format!("          {}", file),
+                    Style::default().fg(Color::Gray),
+                )));
+            }
+            if proc.files.len() > MAX_FILES {
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "          {}",
+                        tr_fmt("... and {} more", &[&(proc.files.len() - MAX_FILES)])
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                )));
+            }
+        }
+    }
+
+    // Favorites stand in for the results until there are some
+    if results.is_empty() && !loading && !favorites.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("Favorites:")),
+            Style::default().fg(Color::Cyan),
+        )));
+        let keys = crate::config::HandleSearchConfig::FAVORITE_KEYS;
+        for (i, path) in favorites.iter().take(keys).enumerate() {
+            lines.push(Line::from(vec![
+                Span::styled(format!("    {}  ", i + 1), Style::default().fg(Color::Yellow)),
+                Span::styled(path.replace('\n', "; "), Style::default().fg(Color::White)),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+
+    let hints = if input_mode {
+        vec![
+            Span::styled(format!("{}  ", tr("[Enter] Search")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[Up/Down] Recent")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[Tab] Complete")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[C-n] Add path")), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}  ", tr("[C-u] Remove path")),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(format!("{}  ", tr("[Esc] Cancel")), Style::default().fg(Color::Gray)),
+        ]
+    } else {
+        vec![
+            Span::styled(format!("{}  ", tr("[/] Edit Path")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[Enter] Search")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[s] Favorite")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[e] Last export")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[J] Go to")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[w] Watch")), Style::default().fg(Color::Gray)),
+            Span::styled(
+                format!("{}  ", tr("[D] Unlock+delete")),
+                Style::default().fg(Color::Gray),
+            ),
+            Span::styled(
+                format!("{}  ", tr("[N] Unlock+rename")),
+                Style::default().fg(Color::Gray),
+            ),
+            if is_elevated {
+                Span::styled(format!("{}  ", tr("[K] Kill")), Style::default().fg(Color::Red))
+            } else {
+                Span::styled(
+                    format!("{}  ", tr("[K] Kill (admin)")),
+                    Style::default().fg(Color::DarkGray),
+                )
+            },
+            if is_elevated {
+                Span::styled(
+                    format!("{}  ", tr("[C] Close handles")),
+                    Style::default().fg(Color::Yellow),
+                )
+            } else {
+                Span::styled(
+                    format!("{}  ", tr("[C] Close handles (admin)")),
+                    Style::default().fg(Color::DarkGray),
+                )
+            },
+            if is_elevated {
+                Span::styled(
+                    format!("{}  ", tr("[x] Shut down")),
+                    Style::default().fg(Color::Yellow),
+                )
+            } else {
+                Span::styled(
+                    format!("{}  ", tr("[x] Shut down (admin)")),
+                    Style::default().fg(Color::DarkGray),
+                )
+            },
+            match restartable {
+                Some(count) => Span::styled(
+                    format!("{}  ", tr_fmt("[R] Restart {}", &[&count])),
+                    Style::default().fg(Color::Green),
+                ),
+                None => Span::raw(""),
+            },
+            Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+        ]
+    };
+    lines.push(Line::from(hints));
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Handle Search")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_process_details_modal(
+    f: &mut Frame,
+    details: &crate::app::ProcessDetails,
+    locker: &crate::state::locker::LockerState,
+    footprint: &crate::state::nexus::PortFootprint,
+    services: &[&str],
+    is_elevated: bool,
+) {
+    let area = centered_rect(80, 38, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Process Details"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    // Basic info
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("Name:")), Style::default().fg(Color::Yellow)),
+        Span::styled(&details.name, Style::default().fg(Color::White)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("PID:")), Style::default().fg(Color::Yellow)),
+        Span::styled(details.pid.to_string(), Style::default().fg(Color::White)),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("Parent:")), Style::default().fg(Color::Yellow)),
+        Span::styled(
+            details.parent_pid.to_string(),
+            Style::default().fg(Color::White),
+        ),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("CPU:")), Style::default().fg(Color::Yellow)),
+        Span::styled(
+            format!("{:.1}%", locker.cpu_display(details.cpu_usage)),
+            Style::default().fg(Color::White),
+        ),
+        Span::styled(
+            format!(
+                " {}",
+                if locker.cpu_per_core { tr("of one core") } else { tr("of all cores") }
+            ),
+            Style::default().fg(Color::Gray),
+        ),
+    ]));
+    match &details.memory {
+        Some(memory) => {
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:10}", tr("Memory:")), Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    tr_fmt(
+                        "{} working set (peak {}), {} private",
+                        &[
+                            &format_bytes(memory.working_set),
+                            &format_bytes(memory.peak_working_set),
+                            &format_bytes(memory.private_bytes),
+                        ],
+                    ),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+            lines.push(Line::from(vec![
+                Span::styled(format!("{:10}", tr("Commit:")), Style::default().fg(Color::Yellow)),
+                Span::styled(
+                    tr_fmt(
+                        "{} (peak {}), {} page faults",
+                        &[
+                            &format_bytes(memory.commit),
+                            &format_bytes(memory.peak_commit),
+                            &memory.page_faults,
+                        ],
+                    ),
+                    Style::default().fg(Color::White),
+                ),
+            ]));
+        }
+        None => lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Memory:")), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                format!("{:.1} MB", details.memory_mb),
+                Style::default().fg(Color::White),
+            ),
+        ])),
+    }
+    // Kept from Aperture's start, so spikes missed while looking elsewhere still show
+    if let Some(usage) = locker.usage.get(details.pid) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Peak:")), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                tr_fmt(
+                    "CPU {}% at {}, memory {} MB at {}",
+                    &[
+                        &format!("{:.1}", locker.cpu_display(usage.peak_cpu)),
+                        &usage.peak_cpu_at,
+                        &format!("{:.1}", usage.peak_memory_mb),
+                        &usage.peak_memory_at,
+                    ],
+                ),
+                Style::default().fg(Color::White),
+            ),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Average:")), Style::default().fg(Color::Yellow)),
+            Span::styled(
+                tr_fmt(
+                    "CPU {}%, memory {} MB",
+                    &[
+                        &format!("{:.1}", locker.cpu_display(usage.average_cpu())),
+                        &format!("{:.1}", usage.average_memory_mb()),
+                    ],
+                ),
+                Style::default().fg(Color::White),
+            ),
+            Span::styled(
+                format!(
+                    " {}",
+                    tr_fmt("over {} samples since {}", &[&usage.samples, &usage.since])
+                ),
+                Style::default().fg(Color::Gray),
+            ),
+        ]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("Started:")), Style::default().fg(Color::Yellow)),
+        Span::styled(
+            details.start_time.map(format_age).unwrap_or_else(|| "-".to_string()),
+            Style::default().fg(Color::White),
+        ),
+    ]));
+
+    if let Some(path) = &details.path {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Path:")), Style::default().fg(Color::Yellow)),
+            Span::styled(path, Style::default().fg(Color::White)),
+        ]));
+    }
+    if let Some(command_line) = &details.command_line {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Command:")), Style::default().fg(Color::Yellow)),
+            Span::styled(command_line, Style::default().fg(Color::White)),
+        ]));
+    }
+    if !services.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Services:")), Style::default().fg(Color::Yellow)),
+            Span::styled(services.join(", "), Style::default().fg(Color::White)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+
+    // Network footprint from the Nexus data, so ports can be checked without switching tabs
+    let mut ports: Vec<String> = footprint
+        .listening
+        .iter()
+        .take(8)
+        .map(|(proto, addr, port)| format!("{} {}:{}", proto, addr, port))
+        .collect();
+    if footprint.listening.len() > 8 {
+        ports.push(tr_fmt("+{} more", &[&(footprint.listening.len() - 8)]));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("Ports:")), Style::default().fg(Color::Yellow)),
+        if ports.is_empty() {
+            Span::styled(tr("none"), Style::default().fg(Color::DarkGray))
+        } else {
+            Span::styled(ports.join(", "), Style::default().fg(Color::White))
+        },
+    ]));
+    let mut connections = tr_fmt("{} established", &[&footprint.established]);
+    if footprint.lingering > 0 {
+        connections.push_str(&format!(", {} TIME_WAIT/CLOSE_WAIT", footprint.lingering));
+    }
+    lines.push(Line::from(vec![
+        Span::styled(format!("{:10}", tr("Network:")), Style::default().fg(Color::Yellow)),
+        Span::styled(connections, Style::default().fg(Color::White)),
+    ]));
+
+    lines.push(Line::from(""));
+
+    // Top-level windows, so a PID can be matched to what is on screen
+    if details.windows.is_empty() {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:10}", tr("Windows:")), Style::default().fg(Color::Yellow)),
+            Span::styled(tr("none"), Style::default().fg(Color::DarkGray)),
+        ]));
+    } else {
+        lines.push(Line::from(Span::styled(
+            tr_fmt("Windows ({}):", &[&details.windows.len()]),
+            Style::default().fg(Color::Yellow),
+        )));
+        // Keep the selected window in a 5-row slice
+        let start = details.selected_window.saturating_sub(4);
+        for (i, window) in details.windows.iter().enumerate().skip(start).take(5) {
+            let state = if window.minimized {
+                format!(" ({})", tr("minimized"))
+            } else {
+                String::new()
+            };
+            let text = format!("  {} [{}]{}", window.title, window.class_name, state);
+            let style = if i == details.selected_window {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(Span::styled(text, style)));
+        }
+    }
+
+    lines.push(Line::from(""));
+
+    // Show modules section
+    if !details.modules.is_empty() {
+        lines.push(Line::from(Span::styled(
+            tr("Loaded Modules (first 10):"),
+            Style::default().fg(Color::Yellow),
+        )));
+        for module in details.modules.iter().take(10) {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(module, Style::default().fg(Color::White)),
+            ]));
+        }
+        if details.modules.len() > 10 {
+            lines.push(Line::from(vec![Span::styled(
+                format!("  {}", tr_fmt("... and {} more", &[&(details.modules.len() - 10)])),
+                Style::default().fg(Color::DarkGray),
+            )]));
+        }
+    } else if details.error.is_some() {
+        lines.push(Line::from(Span::styled(
+            tr("Modules: (access denied)"),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    lines.push(Line::from(""));
+
+    // Error message if any
+    if let Some(err) = &details.error {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", tr("Error:")), Style::default().fg(Color::Red)),
+            Span::styled(err, Style::default().fg(Color::Red)),
+        ]));
+        lines.push(Line::from(""));
+    }
+
+    // Help text
+    lines.push(Line::from(vec![
+        Span::styled(
+            format!("{}  ", tr("[K] Kill")),
+            if is_elevated {
+                Style::default().fg(Color::Red)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        ),
+        Span::styled(
+            format!("{}  ", tr("[j/k] Window  [Enter] Front  [m] Minimize  [c] Close window")),
+            if details.windows.is_empty() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::Gray)
+            },
+        ),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} (PID: {}) ", details.name, details.pid))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn format_duration_secs(secs: u32) -> String {
+    if secs == u32::MAX {
+        tr("never").to_string()
+    } else if secs >= 86_400 && secs.is_multiple_of(86_400) {
+        tr_fmt("{} day(s)", &[&(secs / 86_400)])
+    } else if secs >= 3600 {
+        format!("{}h {}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn render_remote_lookup_modal(
+    f: &mut Frame,
+    config: &crate::config::LookupConfig,
+    lookup: &crate::app::RemoteLookup,
+) {
+    let height = f.area().height.saturating_sub(4).min(30);
+    let area = centered_rect(80, height, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr_fmt("Look up {}", &[&lookup.ip]),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let whois_label = if lookup.running {
+        format!("{} {}", spinner_frame(), tr("Running whois..."))
+    } else {
+        tr_fmt("Run whois ({})", &[&config.whois_command])
+    };
+    let targets = config
+        .urls
+        .iter()
+        .map(|u| tr_fmt("Open {} in browser", &[&u.name]))
+        .chain(std::iter::once(whois_label));
+    for (i, label) in targets.enumerate() {
+        let style = if i == lookup.selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(format!("  {}", label), style)));
+    }
+
+    if let Some(err) = &lookup.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", tr("Error:")), Style::default().fg(Color::Red)),
+            Span::styled(err.as_str(), Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    if let Some(output) = &lookup.whois {
+        lines.push(Line::from(""));
+        // Leave room for the footer and borders
+        let room = (height as usize).saturating_sub(lines.len() + 4);
+        for line in output.iter().skip(lookup.scroll).take(room) {
+            lines.push(Line::from(Span::styled(
+                line.clone(),
+                Style::default().fg(Color::Gray),
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[Enter] Open/Run")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[PgUp/PgDn] Scroll")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Remote IP Lookup")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_go_to_modal(f: &mut Frame, targets: &[crate::app::GoToTarget], selected: usize) {
+    let area = centered_rect(50, targets.len() as u16 + 6, f.area());
+
+    let mut lines = vec![Line::from("")];
+    for (i, target) in targets.iter().enumerate() {
+        let style = if i == selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(format!("  {}", target.label()), style)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[Enter] Go")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Go To")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_tools_modal(f: &mut Frame, tools: &[crate::app::ToolCommand], selected: usize) {
+    let area = centered_rect(70, tools.len() as u16 * 2 + 6, f.area());
+
+    let mut lines = vec![Line::from("")];
+    for (i, tool) in tools.iter().enumerate() {
+        let style = if i == selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let (mode, command) = match &tool.run {
+            ToolRun::Command { argv, detach } => (
+                if *detach { tr("(new window)") } else { "" },
+                crate::sys::shell::command_line(argv),
+            ),
+            ToolRun::Script(_) => (tr("(script)"), tr("Runs with the selected row").to_string()),
+        };
+        let name = if mode.is_empty() {
+            tool.name.clone()
+        } else {
+            format!("{} {}", tool.name, mode)
+        };
+        lines.push(Line::from(Span::styled(format!("  {}", name), style)));
+        lines.push(Line::from(Span::styled(
+            format!("    {}", command),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[Enter] Run")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("External Tools")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_threads_modal(
+    f: &mut Frame,
+    view: &crate::app::ThreadView,
+    locker: &crate::state::locker::LockerState,
+) {
+    let area = centered_rect(70, f.area().height.saturating_sub(4), f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr_fmt("{} threads", &[&view.threads.len()]),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:>7}  {:10} {:>4}  {:14} {:>6}  {:>13}",
+                "TID",
+                tr("State"),
+                tr("Base"),
+                tr("Priority"),
+                if locker.cpu_per_core { tr("Core%") } else { "CPU%" },
+                tr("CPU time")
+            ),
+            Style::default().fg(Color::Yellow),
+        )),
+    ];
+
+    if let Some(error) = &view.error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", error),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    // Keep the selected row inside the visible window
+    let visible = area.height.saturating_sub(7) as usize;
+    let start = if view.selected >= visible {
+        view.selected + 1 - visible
+    } else {
+        0
+    };
+
+    for (i, thread) in view.threads.iter().enumerate().skip(start).take(visible) {
+        let color = if thread.suspend_count.unwrap_or(0) > 0 {
+            Color::Magenta
+        } else if thread.suspend_count.is_none() {
+            Color::DarkGray
+        } else {
+            Color::White
+        };
+        let style = if i == view.selected {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(color)
+        };
+        let cpu = if thread.cpu_usage > 0.0 {
+            format!("{:5.1}%", locker.cpu_display(thread.cpu_usage))
+        } else {
+            "     -".to_string()
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:>7}  {:10} {:>4}  {:14} {}  {:>13}",
+                thread.tid,
+                thread.state(),
+                thread.base_priority,
+                thread.priority_name(),
+                cpu,
+                format_cpu_time(thread.cpu_time)
+            ),
+            style,
+        )));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[s] Suspend")), Style::default().fg(Color::Yellow)),
+        Span::styled(format!("{}  ", tr("[r] Resume")), Style::default().fg(Color::Green)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr_fmt("Threads: {} (PID: {})", &[&view.name, &view.pid])))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+/// Formats a CPU time in 100ns units as h:mm:ss.mmm.
+fn format_cpu_time(time: u64) -> String {
+    let millis = time / 10_000;
+    let secs = millis / 1000;
+    format!(
+        "{}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60,
+        millis % 1000
+    )
+}
+
+fn render_connection_details_modal(
+    f: &mut Frame,
+    conn: &crate::sys::network::ConnectionInfo,
+    owner: &Option<crate::sys::network::SocketOwnerInfo>,
+    firewall: Option<&crate::sys::firewall::FirewallCheck>,
+    error: &Option<String>,
+) {
+    let area = centered_rect(80, 19, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Connection Details"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let mut info = vec![
+        ("Protocol:", conn.protocol.clone()),
+        ("Local:", format!("{}:{}", conn.local_addr, conn.local_port)),
+        (
+            "Remote:",
+            if crate::state::nexus::has_remote(conn) {
+                format!("{}:{}", conn.remote_addr, conn.remote_port)
+            } else {
+                format!("- ({})", tr("connectionless"))
+            },
+        ),
+        ("State:", conn.state.clone()),
+        (
+            "Process:",
+            format!("{} (PID {})", conn.process_name.as_deref().unwrap_or("-"), conn.pid),
+        ),
+    ];
+    if let Some(owner) = owner {
+        info.push((
+            "Path:",
+            owner.process_path.clone().unwrap_or_else(|| "-".to_string()),
+        ));
+        info.push((
+            "Created:",
+            owner.created.map(format_age).unwrap_or_else(|| "-".to_string()),
+        ));
+        info.push((
+            "Service tag:",
+            match (owner.service_tag, &conn.service_name) {
+                (0, _) => "-".to_string(),
+                (tag, Some(service)) => format!("{} ({})", tag, service),
+                (tag, None) => tag.to_string(),
+            },
+        ));
+    }
+    if let Some(check) = firewall {
+        let verdict = if check.allowed { tr("Allowed") } else { tr("Blocked") };
+        info.push(("Firewall:", format!("{} ({})", verdict, check.reason)));
+    }
+    for (label, value) in info {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:13}", tr(label)), Style::default().fg(Color::Yellow)),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ]));
+    }
+
+    if let Some(err) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", tr("Error:")), Style::default().fg(Color::Red)),
+            Span::styled(err.as_str(), Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        tr("[Esc] Close"),
+        Style::default().fg(Color::Gray),
+    )));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", conn.process_name.as_deref().unwrap_or(tr("Connection"))))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_service_details_modal(
+    f: &mut Frame,
+    app: &App,
+    service: &crate::sys::service::ServiceInfo,
+    recovery: &Option<crate::sys::service::FailureActions>,
+    field: usize,
+    dirty: bool,
+    error: &Option<String>,
+) {
+    use crate::sys::service::RecoveryAction;
+
+    let history = app.state.controller.status_history(&service.service_name);
+
+    // Newest changes that fit; the rest stay in the history for when they scroll in
+    const HISTORY_ROWS: usize = 6;
+    let history_rows = history.map_or(0, |h| h.len().min(HISTORY_ROWS));
+    let area = centered_rect(80, 24 + history_rows as u16, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Service Details"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    let info = [
+        ("Name:", service.service_name.clone()),
+        ("Display:", service.display_name.clone()),
+        ("Status:", service.status.clone()),
+        ("Start type:", service.start_type.clone()),
+        ("Type:", service.service_type.clone()),
+        ("PID:", service.pid.to_string()),
+    ];
+    for (label, value) in info {
+        lines.push(Line::from(vec![
+            Span::styled(format!("{:12}", tr(label)), Style::default().fg(Color::Yellow)),
+            Span::styled(value, Style::default().fg(Color::White)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        if dirty { tr("Recovery (unsaved)") } else { tr("Recovery") },
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )));
+
+    if let Some(recovery) = recovery {
+        let slots = ["First failure:", "Second failure:", "Subsequent failures:"];
+        for (i, (slot, (action, delay))) in slots.iter().zip(&recovery.actions).enumerate() {
+            let style = if i == field {
+                Style::default().bg(Color::DarkGray).fg(Color::White)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let detail = match action {
+                RecoveryAction::None => String::new(),
+                RecoveryAction::RunCommand => format!(
+                    "  {}  ({})",
+                    tr_fmt("after {}", &[&format_duration_secs(delay / 1000)]),
+                    recovery.command
+                ),
+                _ => format!("  {}", tr_fmt("after {}", &[&format_duration_secs(delay / 1000)])),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {:20} {}{}", tr(slot), tr(action.as_str()), detail),
+                style,
+            )));
+        }
+
+        let style = if field == 3 {
+            Style::default().bg(Color::DarkGray).fg(Color::White)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:20} {}",
+                tr("Reset fail count:"),
+                format_duration_secs(recovery.reset_period_secs)
+            ),
+            style,
+        )));
+    }
+
+    lines.push(Line::from(""));
+    let changes = app.state.controller.change_count(&service.service_name);
+    let flapping = app
+        .state
+        .controller
+        .flapping(&service.service_name)
+        .map(|starts| {
+            let window = crate::state::controller::ControllerState::FLAP_WINDOW.as_secs() / 60;
+            format!(", {}", tr_fmt("flapping: {} starts in {} min", &[&starts, &window]))
+        })
+        .unwrap_or_default();
+    lines.push(Line::from(Span::styled(
+        match changes {
+            0 => tr("Status history").to_string(),
+            n => tr_fmt("Status history ({} changes seen{})", &[&n, &flapping]),
+        },
+        Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    )));
+    match history {
+        Some(history) if !history.is_empty() => {
+            for change in history.iter().rev().take(HISTORY_ROWS) {
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("  {} ", change.clock),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::styled(
+                        format!("{} → {}", change.from, change.to),
+                        Style::default().fg(Color::White),
+                    ),
+                    Span::styled(
+                        format!("  ({})", format_secs_ago(change.at.elapsed().as_secs())),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+            }
+        }
+        _ => lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No changes since Aperture started")),
+            Style::default().fg(Color::DarkGray),
+        ))),
+    }
+
+    if let Some(err) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{} ", tr("Error:")), Style::default().fg(Color::Red)),
+            Span::styled(err.as_str(), Style::default().fg(Color::Red)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Select")), Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("{}  ", tr("[Space] Toggle Restart")),
+            Style::default().fg(Color::Gray),
+        ),
+        Span::styled(format!("{}  ", tr("[+/-] Adjust Time")), Style::default().fg(Color::Gray)),
+        Span::styled(
+            format!("{}  ", tr("[Enter] Save")),
+            if app.is_elevated {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        ),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", service.display_name))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_port_lookup_modal(
+    f: &mut Frame,
+    input: &str,
+    results: &[crate::sys::network::ConnectionInfo],
+    selected: usize,
+    error: &Option<String>,
+    searched: bool,
+    is_elevated: bool,
+) {
+    let area = centered_rect(70, 20, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Who Owns This Port?"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{} {}_", tr("Port:"), input),
+            Style::default().fg(Color::White),
+        )),
+        Line::from(""),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    } else if !searched {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("Type a port number and press Enter")),
+            Style::default().fg(Color::Gray),
+        )));
+    } else if results.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Nothing is bound to port {}.", &[&input])),
+            Style::default().fg(Color::Green),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Sockets on port {} ({}):", &[&input, &results.len()])),
+            Style::default().fg(Color::Yellow),
+        )));
+        lines.push(Line::from(""));
+        for (i, conn) in results.iter().enumerate() {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "    PID: {:6}  {:5} {:12} {:22} {}",
+                    conn.pid,
+                    conn.protocol,
+                    conn.state,
+                    format!("{}:{}", conn.local_addr, conn.local_port),
+                    conn.process_name.as_deref().unwrap_or("-")
+                ),
+                style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+
+    let action_style = if is_elevated {
+        Style::default().fg(Color::Red)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[Enter] Search")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[K] Kill")), action_style),
+        Span::styled(format!("{}  ", tr("[c] Close conn")), action_style),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Port Lookup")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_named_pipes_modal(
+    f: &mut Frame,
+    pipes: &[crate::sys::pipe::PipeInfo],
+    selected: usize,
+    error: &Option<String>,
+) {
+    let area = centered_rect(70, 24, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} (\\\\.\\pipe\\)", tr("Named Pipes")),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}  {:>9}  {}",
+                tr_fmt("{} pipes", &[&pipes.len()]),
+                tr("Instances"),
+                tr("Name")
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+
+        // Keep the selected row inside the visible window
+        let visible = area.height.saturating_sub(7) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, pipe) in pipes.iter().enumerate().skip(start).take(visible) {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {:>17}  {}", pipe.instances, pipe.name),
+                style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[r] Refresh")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("IPC Endpoints")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_logon_sessions_modal(
+    f: &mut Frame,
+    sessions: &[crate::sys::logon::LogonSession],
+    selected: usize,
+    error: &Option<String>,
+    confirm: Option<SessionCommand>,
+) {
+    let area = centered_rect(80, 26, f.area());
+    let width = area.width.saturating_sub(2) as usize;
+    let own = crate::sys::logon::current_session();
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Logon Sessions"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    } else {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {:>4}  {:<12} {:<12} {:<24} {:<14} {:<10} {:>7} {:>5}",
+                "ID",
+                tr("Station"),
+                tr("State"),
+                tr("User"),
+                tr("Client"),
+                tr("Logon"),
+                tr("Idle"),
+                tr("Procs")
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+        for (i, session) in sessions.iter().enumerate() {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else if session.state == "Active" {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            let logon = session.logon_time.map(format_age).unwrap_or_else(|| "-".to_string());
+            let row = format!(
+                "  {:>4}{} {} {} {} {} {} {} {:>5}",
+                session.id,
+                if own == Some(session.id) { "*" } else { " " },
+                text::pad(&session.station, 12),
+                text::pad(session.state, 12),
+                text::pad(&session.user, 24),
+                text::pad(&session.client, 14),
+                text::pad(&logon, 10),
+                format_uptime(session.idle_secs),
+                session.processes.len()
+            );
+            lines.push(Line::from(Span::styled(text::fit(&row, width), style)));
+        }
+
+        // What runs in the selected session, for telling whose session it is to log off
+        if let Some(session) = sessions.get(selected) {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("  {}", tr_fmt("Processes in session {}:", &[&session.id])),
+                Style::default().fg(Color::Yellow),
+            )));
+            let names: Vec<String> = session
+                .processes
+                .iter()
+                .map(|(pid, name)| format!("{} ({})", name, pid))
+                .collect();
+            let mut line = String::from("   ");
+            for name in names {
+                if line.len() + name.len() + 2 > width && !line.trim().is_empty() {
+                    lines.push(Line::from(line));
+                    line = String::from("   ");
+                }
+                line.push(' ');
+                line.push_str(&name);
+            }
+            if line.trim().is_empty() {
+                line.push_str(&format!(" {}", tr("none")));
+            }
+            lines.push(Line::from(line));
+        }
+    }
+
+    lines.push(Line::from(""));
+    match (confirm, sessions.get(selected)) {
+        (Some(command), Some(session)) => {
+            let (prompt, consequence) = match command {
+                SessionCommand::Disconnect => (
+                    "Disconnect session {} ({})?",
+                    "The user stays logged on and their programs keep running.",
+                ),
+                SessionCommand::LogOff => (
+                    "Log off session {} ({})?",
+                    "Its programs close and unsaved work is lost.",
+                ),
+            };
+            let user = if session.user.is_empty() { tr("nobody") } else { session.user.as_str() };
+            let mut question = format!(
+                "  {} {}",
+                tr_fmt(prompt, &[&session.id, &user]),
+                tr(consequence)
+            );
+            if own == Some(session.id) {
+                question.push_str(&format!(" {}", tr("This is the session Aperture runs in.")));
+            }
+            lines.push(Line::from(Span::styled(
+                question,
+                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+            )));
+            lines.push(Line::from(vec![
+                Span::styled(format!("  {}  ", tr("[y] Confirm")), Style::default().fg(Color::Red)),
+                Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Green)),
+            ]));
+        }
+        _ => lines.push(Line::from(vec![
+            Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[D] Disconnect")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[L] Log off")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[r] Refresh")), Style::default().fg(Color::Gray)),
+            Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+        ])),
+    }
+
+    let paragraph = Paragraph::new(lines).wrap(Wrap { trim: false }).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Users")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn format_age(timestamp: u64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    format_secs_ago(now.saturating_sub(timestamp))
+}
+
+fn format_secs_ago(secs: u64) -> String {
+    if secs < 60 {
+        tr_fmt("{}s ago", &[&secs])
+    } else if secs < 3600 {
+        tr_fmt("{}m ago", &[&(secs / 60)])
+    } else if secs < 86400 {
+        tr_fmt("{}h ago", &[&(secs / 3600)])
+    } else {
+        tr_fmt("{}d ago", &[&(secs / 86400)])
+    }
+}
+
+fn render_snapshots_modal(
+    f: &mut Frame,
+    snapshots: &[crate::snapshot::SnapshotFile],
+    selected: usize,
+    base: Option<usize>,
+    recorder_status: Option<&str>,
+    error: &Option<String>,
+) {
+    let area = centered_rect(70, 20, f.area());
+
+    let recorder = match recorder_status {
+        Some(status) => Span::styled(
+            format!("  {}", tr_fmt("Recorder service: {}", &[&status])),
+            Style::default().fg(if status == "Running" {
+                Color::Green
+            } else {
+                Color::Yellow
+            }),
+        ),
+        None => Span::styled(
+            format!(
+                "  {}",
+                tr("Recorder service: not installed (aperture --install-service)")
+            ),
+            Style::default().fg(Color::Gray),
+        ),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Snapshots"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(recorder),
+        Line::from(""),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    if snapshots.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No snapshots yet - press [c] to capture the current state")),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        let visible = area.height.saturating_sub(9) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, snapshot) in snapshots.iter().enumerate().skip(start).take(visible) {
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::White)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::White)
+            };
+            let marker = if base == Some(i) { tr("[base]") } else { "" };
+            let source = if snapshot.recorded { tr("[service]") } else { "" };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  snapshot_{}  {:>8}  {:9} {}",
+                    snapshot.timestamp,
+                    format_age(snapshot.timestamp),
+                    source,
+                    marker
+                ),
+                style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    let enter_action = if base.is_some() {
+        tr("[Enter] Diff vs Base")
+    } else {
+        tr("[Enter] Diff vs Live")
+    };
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[c] Capture")), Style::default().fg(Color::Green)),
+        Span::styled(format!("{}  ", enter_action), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[m] Mark Base")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[h] History")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[D] Delete")), Style::default().fg(Color::Red)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Snapshot & Diff")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_recorder_history_modal(
+    f: &mut Frame,
+    events: &[crate::snapshot::HistoryEvent],
+    selected: usize,
+    error: &Option<String>,
+) {
+    let area = centered_rect(85, 28, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr_fmt("Recorded Changes ({} most recent)", &[&events.len()]),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if let Some(err) = error {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    if events.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!(
+                "  {}",
+                tr("Nothing recorded yet - install the recorder with aperture --install-service")
+            ),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        let visible = area.height.saturating_sub(7) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, event) in events.iter().enumerate().skip(start).take(visible) {
+            let color = match event.kind.as_str() {
+                "+" => Color::Green,
+                "-" => Color::Red,
+                _ => Color::Yellow,
+            };
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(color)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {:>8} {} {:10} {}",
+                    format_age(event.timestamp),
+                    event.kind,
+                    event.category,
+                    event.description
+                ),
+                style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[r] Refresh")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Back"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Recorder History")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn format_duration_ms(duration: std::time::Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// One block character per run, scaled to the slowest run shown.
+fn duration_bars(history: &std::collections::VecDeque<std::time::Duration>) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = history.iter().max().copied().unwrap_or_default();
+    if max.is_zero() {
+        return String::new();
+    }
+    history
+        .iter()
+        .map(|run| BARS[((run.as_secs_f64() / max.as_secs_f64()) * 7.0).round() as usize])
+        .collect()
+}
+
+fn render_timings_modal(f: &mut Frame, timings: &crate::state::timings::Timings) {
+    let history = crate::state::timings::Timings::HISTORY;
+    let area = centered_rect(90, timings.collectors.len() as u16 + 8, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr("Collector timings"),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!(
+                "  {:18} {:>9} {:>9} {:>9} {:>6} {:>8}  {}",
+                tr("Collector"),
+                tr("Last"),
+                tr("Avg"),
+                tr("Max"),
+                tr("Runs"),
+                tr("Ran"),
+                tr_fmt("Last {} runs", &[&history])
+            ),
+            Style::default().fg(Color::Yellow),
+        )),
+    ];
+
+    if timings.collectors.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("Nothing has run yet")),
+            Style::default().fg(Color::Gray),
+        )));
+    }
+    for collector in &timings.collectors {
+        // Slow enough to be felt as lag in the UI, which waits for the main-thread ones
+        let color = match collector.last.as_millis() {
+            ms if ms >= 500 => Color::Red,
+            ms if ms >= 100 => Color::Yellow,
+            _ => Color::Green,
+        };
+        lines.push(Line::from(vec![
+            Span::raw(format!("  {:18.18} ", collector.name)),
+            Span::styled(
+                format!("{:>9}", format_duration_ms(collector.last)),
+                Style::default().fg(color),
+            ),
+            Span::raw(format!(
+                " {:>9} {:>9} {:>6} {:>8}  ",
+                format_duration_ms(collector.average()),
+                format_duration_ms(collector.max),
+                collector.runs,
+                format_secs_ago(collector.last_run.elapsed().as_secs())
+            )),
+            Span::styled(duration_bars(&collector.history), Style::default().fg(Color::Cyan)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[c] Reset")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Timings")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_error_log_modal(f: &mut Frame, log: &crate::state::errors::ErrorLog, selected: usize) {
+    let area = centered_rect(85, 24, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr_fmt("Errors ({})", &[&log.entries.len()]),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+    ];
+
+    if log.entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No errors recorded")),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        // Each entry takes two lines: source/age header and the message
+        let visible = (area.height.saturating_sub(6) / 2) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, entry) in log.entries.iter().enumerate().skip(start).take(visible) {
+            let age = format_secs_ago(entry.last_seen.elapsed().as_secs());
+            let repeats = if entry.count > 1 {
+                format!(" x{}", entry.count)
+            } else {
+                String::new()
+            };
+            let code = entry
+                .code
+                .map(|c| format!(" [{}]", tr_fmt("code {}", &[&c])))
+                .unwrap_or_default();
+
+            let header_style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Yellow)
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  {:>8}  {}{}{}", age, entry.source, code, repeats),
+                header_style,
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("            {}", entry.message),
+                Style::default().fg(Color::Red),
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[c] Clear")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Error Log")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_notifications_modal(f: &mut Frame, notifications: &Notifications, selected: usize) {
+    let area = centered_rect(85, 24, f.area());
+
+    let mut lines = vec![
+        Line::from(Span::styled(
+            tr_fmt("Notifications ({})", &[&notifications.history.len()]),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
         Line::from(""),
+    ];
+
+    if notifications.history.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No notifications yet")),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        let visible = area.height.saturating_sub(6) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, notification) in notifications
+            .history
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(visible)
+        {
+            let color = severity_color(notification.severity);
+            let row_style = if i == selected {
+                Style::default().bg(Color::DarkGray)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(vec![
+                Span::styled(
+                    format!(
+                        "  {:>8}  ",
+                        format_secs_ago(notification.created.elapsed().as_secs())
+                    ),
+                    row_style.fg(Color::Gray),
+                ),
+                Span::styled(
+                    format!("{:<6}", notification.severity.as_str()),
+                    row_style.fg(color).add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(notification.message.clone(), row_style.fg(Color::White)),
+            ]));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[c] Clear")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Notification History")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
+
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+fn render_snapshot_diff_modal(
+    f: &mut Frame,
+    title: &str,
+    entries: &[crate::snapshot::DiffEntry],
+    selected: usize,
+) {
+    use crate::snapshot::ChangeKind;
+
+    let area = centered_rect(85, 28, f.area());
+
+    let count = |kind: ChangeKind| entries.iter().filter(|e| e.kind == kind).count();
+    let mut lines = vec![
+        Line::from(Span::styled(
+            title.to_string(),
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::BOLD),
+        )),
         Line::from(vec![
-            Span::styled("       [Y] Yes  ", Style::default().fg(Color::Green)),
-            Span::styled("[N] No", Style::default().fg(Color::Red)),
+            Span::styled(
+                format!("  +{}  ", tr_fmt("{} added", &[&count(ChangeKind::Added)])),
+                Style::default().fg(Color::Green),
+            ),
+            Span::styled(
+                format!("-{}  ", tr_fmt("{} removed", &[&count(ChangeKind::Removed)])),
+                Style::default().fg(Color::Red),
+            ),
+            Span::styled(
+                format!("~{}", tr_fmt("{} changed", &[&count(ChangeKind::Changed)])),
+                Style::default().fg(Color::Yellow),
+            ),
         ]),
         Line::from(""),
     ];
 
-    let paragraph = Paragraph::new(text)
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .title(" Confirmation ")
-                .title_style(Style::default().fg(Color::Red)),
-        )
-        .alignment(Alignment::Center);
+    if entries.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr("No differences")),
+            Style::default().fg(Color::Gray),
+        )));
+    } else {
+        let visible = area.height.saturating_sub(8) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, entry) in entries.iter().enumerate().skip(start).take(visible) {
+            let color = match entry.kind {
+                ChangeKind::Added => Color::Green,
+                ChangeKind::Removed => Color::Red,
+                ChangeKind::Changed => Color::Yellow,
+            };
+            let style = if i == selected {
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .fg(color)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(color)
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "  {} {:10} {}",
+                    entry.kind.symbol(),
+                    entry.category,
+                    entry.description
+                ),
+                style,
+            )));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Back"), Style::default().fg(Color::Gray)),
+    ]));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {} ", tr("Diff")))
+            .title_style(Style::default().fg(Color::Cyan)),
+    );
 
     f.render_widget(Clear, area);
     f.render_widget(paragraph, area);
 }
 
-fn render_handle_search_modal(
+fn render_profiles_modal(
     f: &mut Frame,
-    input: &str,
-    results: &[crate::app::LockingProcess],
+    profiles: &[crate::config::Profile],
     selected: usize,
-    loading: bool,
+    naming: &Option<String>,
     error: &Option<String>,
-    is_elevated: bool,
-    input_mode: bool,
-    is_directory: bool,
-    files_scanned: Option<usize>,
 ) {
     let area = centered_rect(70, 20, f.area());
 
-    let input_display = if input.is_empty() {
-        if input_mode {
-            "_".to_string()
-        } else {
-            "(enter path)".to_string()
-        }
-    } else {
-        input.to_string()
-    };
-
     let mut lines = vec![
         Line::from(Span::styled(
-            "Find Locking Processes",
+            tr("Saved Profiles"),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
         )),
         Line::from(""),
-        Line::from(Span::styled(
-            format!("Path: {}", input_display.replace('\n', "; ")),
-            Style::default().fg(if input_mode {
-                Color::White
-            } else {
-                Color::Gray
-            }),
-        )),
-        Line::from(""),
     ];
 
-    if loading {
-        let scan_msg = if is_directory {
-            if let Some(count) = files_scanned {
-                format!("  Scanning {} files...", count)
-            } else {
-                "  Scanning directory...".to_string()
-            }
-        } else {
-            "  Searching...".to_string()
-        };
-        lines.push(Line::from(Span::styled(
-            scan_msg,
-            Style::default().fg(Color::Yellow),
-        )));
-    } else if let Some(err) = error {
-        lines.push(Line::from(Span::styled(
-            format!("  Error: {}", err),
-            Style::default().fg(Color::Red),
-        )));
-    } else if results.is_empty() {
-        let empty_msg = if is_directory {
-            if let Some(count) = files_scanned {
-                format!("  Scanned {} files - no locks found.", count)
-            } else {
-                "  No locking processes found.".to_string()
-            }
-        } else {
-            "  No locking processes found.".to_string()
-        };
+    if profiles.is_empty() {
         lines.push(Line::from(Span::styled(
-            empty_msg,
-            Style::default().fg(Color::Green),
+            format!("  {}", tr("No profiles saved yet - press [a] to save the current view")),
+            Style::default().fg(Color::Gray),
         )));
     } else {
-        let results_msg = if is_directory {
-            if let Some(count) = files_scanned {
-                format!("  Scanned {} files - Found {} locks:", count, results.len())
-            } else {
-                format!("  Found {} locks:", results.len())
-            }
-        } else {
-            format!("  Locking processes ({}):", results.len())
-        };
         lines.push(Line::from(Span::styled(
-            results_msg,
+            format!("  {:24} {:11} {}", tr("Name"), tr("Tab"), tr("Filter / Sort")),
             Style::default().fg(Color::Yellow),
         )));
-        lines.push(Line::from(""));
-        for (i, proc) in results.iter().enumerate() {
-            let style = if i == selected {
+
+        let visible = area.height.saturating_sub(9) as usize;
+        let start = if selected >= visible {
+            selected + 1 - visible
+        } else {
+            0
+        };
+
+        for (i, profile) in profiles.iter().enumerate().skip(start).take(visible) {
+            let (filter, sort_key, sort_order) = profile.summary();
+            let style = if i == selected && naming.is_none() {
                 Style::default()
                     .bg(Color::DarkGray)
                     .fg(Color::White)
@@ -500,38 +3572,51 @@ fn render_handle_search_modal(
                 Style::default().fg(Color::White)
             };
             lines.push(Line::from(Span::styled(
-                format!("    PID: {:6}  {}", proc.pid, proc.name),
+                format!(
+                    "  {:24} {:11} '{}' {}{}",
+                    profile.name,
+                    profile.tab.as_str(),
+                    filter.unwrap_or(""),
+                    sort_key,
+                    sort_order
+                ),
                 style,
             )));
         }
     }
 
-    lines.push(Line::from(""));
+    if let Some(err) = error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
+        )));
+    }
 
-    let hints = if input_mode {
-        vec![
-            Span::styled("[Enter] Search  ", Style::default().fg(Color::Gray)),
-            Span::styled("[Esc] Cancel  ", Style::default().fg(Color::Gray)),
-        ]
+    lines.push(Line::from(""));
+    if let Some(name) = naming {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {} ", tr("Save as:")), Style::default().fg(Color::Yellow)),
+            Span::styled(format!("{}_", name), Style::default().fg(Color::White)),
+        ]));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}  ", tr("[Enter] Save")), Style::default().fg(Color::Green)),
+            Span::styled(tr("[Esc] Cancel"), Style::default().fg(Color::Gray)),
+        ]));
     } else {
-        vec![
-            Span::styled("[/] Edit Path  ", Style::default().fg(Color::Gray)),
-            Span::styled("[Enter] Search  ", Style::default().fg(Color::Gray)),
-            Span::styled("[j/k] Navigate  ", Style::default().fg(Color::Gray)),
-            if is_elevated {
-                Span::styled("[K] Kill  ", Style::default().fg(Color::Red))
-            } else {
-                Span::styled("[K] Kill (admin)  ", Style::default().fg(Color::DarkGray))
-            },
-            Span::styled("[Esc] Close", Style::default().fg(Color::Gray)),
-        ]
-    };
-    lines.push(Line::from(hints));
+        lines.push(Line::from(vec![
+            Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[Enter] Load")), Style::default().fg(Color::Green)),
+            Span::styled(format!("{}  ", tr("[a] Save Current")), Style::default().fg(Color::Gray)),
+            Span::styled(format!("{}  ", tr("[D] Delete")), Style::default().fg(Color::Red)),
+            Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
+        ]));
+    }
 
     let paragraph = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(" Handle Search ")
+            .title(format!(" {} ", tr("Profiles")))
             .title_style(Style::default().fg(Color::Cyan)),
     );
 
@@ -539,16 +3624,24 @@ fn render_handle_search_modal(
     f.render_widget(paragraph, area);
 }
 
-fn render_process_details_modal(
+fn render_dns_cache_modal(
     f: &mut Frame,
-    details: &crate::app::ProcessDetails,
-    is_elevated: bool,
+    entries: &[crate::sys::dns::DnsCacheEntry],
+    hosts: &[crate::sys::dns::HostsEntry],
+    selected: usize,
+    show_hosts: bool,
+    error: &Option<String>,
 ) {
-    let area = centered_rect(80, 25, f.area());
+    let area = centered_rect(80, 26, f.area());
 
+    let heading = if show_hosts {
+        tr_fmt("Hosts File Overrides ({})", &[&hosts.len()])
+    } else {
+        tr_fmt("DNS Resolver Cache ({} records)", &[&entries.len()])
+    };
     let mut lines = vec![
         Line::from(Span::styled(
-            "Process Details",
+            heading,
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -556,99 +3649,92 @@ fn render_process_details_modal(
         Line::from(""),
     ];
 
-    // Basic info
-    lines.push(Line::from(vec![
-        Span::styled("Name:     ", Style::default().fg(Color::Yellow)),
-        Span::styled(&details.name, Style::default().fg(Color::White)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("PID:      ", Style::default().fg(Color::Yellow)),
-        Span::styled(details.pid.to_string(), Style::default().fg(Color::White)),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("Parent:   ", Style::default().fg(Color::Yellow)),
-        Span::styled(
-            details.parent_pid.to_string(),
-            Style::default().fg(Color::White),
-        ),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("CPU:      ", Style::default().fg(Color::Yellow)),
-        Span::styled(
-            format!("{:.1}%", details.cpu_usage),
-            Style::default().fg(Color::White),
-        ),
-    ]));
-    lines.push(Line::from(vec![
-        Span::styled("Memory:   ", Style::default().fg(Color::Yellow)),
-        Span::styled(
-            format!("{:.1} MB", details.memory_mb),
-            Style::default().fg(Color::White),
-        ),
-    ]));
-
-    if let Some(path) = &details.path {
-        lines.push(Line::from(vec![
-            Span::styled("Path:     ", Style::default().fg(Color::Yellow)),
-            Span::styled(path, Style::default().fg(Color::White)),
-        ]));
-    }
-
-    lines.push(Line::from(""));
-
-    // Show modules section
-    if !details.modules.is_empty() {
-        lines.push(Line::from(Span::styled(
-            "Loaded Modules (first 10):",
-            Style::default().fg(Color::Yellow),
-        )));
-        for module in details.modules.iter().take(10) {
-            lines.push(Line::from(vec![
-                Span::raw("  "),
-                Span::styled(module, Style::default().fg(Color::White)),
-            ]));
-        }
-        if details.modules.len() > 10 {
-            lines.push(Line::from(vec![Span::styled(
-                format!("  ... and {} more", details.modules.len() - 10),
-                Style::default().fg(Color::DarkGray),
-            )]));
-        }
-    } else if details.error.is_some() {
+    if let Some(err) = error {
         lines.push(Line::from(Span::styled(
-            "Modules: (access denied)",
-            Style::default().fg(Color::DarkGray),
+            format!("  {}", tr_fmt("Error: {}", &[err])),
+            Style::default().fg(Color::Red),
         )));
     }
 
-    lines.push(Line::from(""));
+    let header = if show_hosts {
+        format!("  {:40} {}", tr("Address"), tr("Hostname"))
+    } else {
+        format!("  {:40} {:6} {:>7} {}", tr("Name"), tr("Type"), "TTL", tr("Data"))
+    };
+    lines.push(Line::from(Span::styled(
+        header,
+        Style::default().fg(Color::Yellow),
+    )));
 
-    // Error message if any
-    if let Some(err) = &details.error {
-        lines.push(Line::from(vec![
-            Span::styled("Error: ", Style::default().fg(Color::Red)),
-            Span::styled(err, Style::default().fg(Color::Red)),
-        ]));
-        lines.push(Line::from(""));
+    // Names that resolve through the hosts file rather than DNS
+    let overridden: std::collections::HashSet<&str> =
+        hosts.iter().map(|h| h.hostname.as_str()).collect();
+
+    let rows: Vec<(String, Color)> = if show_hosts {
+        hosts
+            .iter()
+            .map(|h| (format!("  {:40} {}", h.address, h.hostname), Color::White))
+            .collect()
+    } else {
+        entries
+            .iter()
+            .map(|e| {
+                let is_override = overridden.contains(e.name.to_lowercase().as_str());
+                let data = if e.data.is_empty() {
+                    "-".to_string()
+                } else {
+                    e.data.join(", ")
+                };
+                let ttl = e.ttl.map(|t| t.to_string()).unwrap_or_else(|| "-".to_string());
+                let row = format!(
+                    "  {:40} {:6} {:>7} {}{}",
+                    e.name,
+                    e.record_type,
+                    ttl,
+                    data,
+                    if is_override { format!("  [{}]", tr("hosts")) } else { String::new() }
+                );
+                (row, if is_override { Color::Magenta } else { Color::White })
+            })
+            .collect()
+    };
+
+    // Keep the selected row inside the visible window
+    let visible = area.height.saturating_sub(8) as usize;
+    let start = if selected >= visible {
+        selected + 1 - visible
+    } else {
+        0
+    };
+
+    for (i, (row, color)) in rows.into_iter().enumerate().skip(start).take(visible) {
+        let style = if i == selected {
+            Style::default()
+                .bg(Color::DarkGray)
+                .fg(Color::White)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(color)
+        };
+        lines.push(Line::from(Span::styled(row, style)));
     }
 
-    // Help text
+    lines.push(Line::from(""));
     lines.push(Line::from(vec![
+        Span::styled(format!("{}  ", tr("[j/k] Navigate")), Style::default().fg(Color::Gray)),
         Span::styled(
-            "[K] Kill  ",
-            if is_elevated {
-                Style::default().fg(Color::Red)
-            } else {
-                Style::default().fg(Color::DarkGray)
-            },
+            format!("{}  ", if show_hosts { tr("[h] DNS Cache") } else { tr("[h] Hosts File") }),
+            Style::default().fg(Color::Gray),
         ),
-        Span::styled("[Esc] Close", Style::default().fg(Color::Gray)),
+        Span::styled(format!("{}  ", tr("[F] Flush DNS")), Style::default().fg(Color::Red)),
+        Span::styled(format!("{}  ", tr("[r] Refresh")), Style::default().fg(Color::Gray)),
+        Span::styled(tr("[Esc] Close"), Style::default().fg(Color::Gray)),
     ]));
 
     let paragraph = Paragraph::new(lines).block(
         Block::default()
             .borders(Borders::ALL)
-            .title(format!(" {} (PID: {}) ", details.name, details.pid))
+            .title(format!(" {} ", tr("Name Resolution")))
             .title_style(Style::default().fg(Color::Cyan)),
     );
 
@@ -657,12 +3743,12 @@ fn render_process_details_modal(
 }
 
 fn render_export_format_modal(f: &mut Frame) {
-    let area = centered_rect(50, 12, f.area());
+    let area = centered_rect(50, 15, f.area());
 
     let lines = vec![
         Line::from(""),
         Line::from(Span::styled(
-            "Export Data",
+            tr("Export Data"),
             Style::default()
                 .fg(Color::Cyan)
                 .add_modifier(Modifier::BOLD),
@@ -670,16 +3756,31 @@ fn render_export_format_modal(f: &mut Frame) {
         Line::from(""),
         Line::from(vec![
             Span::styled("[j]", Style::default().fg(Color::Green)),
-            Span::styled(" Export to JSON", Style::default().fg(Color::White)),
+            Span::styled(format!(" {}", tr("Export to JSON")), Style::default().fg(Color::White)),
         ]),
         Line::from(vec![
             Span::styled("[c]", Style::default().fg(Color::Green)),
-            Span::styled(" Export to CSV", Style::default().fg(Color::White)),
+            Span::styled(format!(" {}", tr("Export to CSV")), Style::default().fg(Color::White)),
+        ]),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled("[s]", Style::default().fg(Color::Green)),
+            Span::styled(
+                format!(" {}", tr("Export shown services' settings")),
+                Style::default().fg(Color::White),
+            ),
+        ]),
+        Line::from(vec![
+            Span::styled("[i]", Style::default().fg(Color::Green)),
+            Span::styled(
+                format!(" {}", tr("Import service settings")),
+                Style::default().fg(Color::White),
+            ),
         ]),
         Line::from(""),
         Line::from(vec![
             Span::styled("[Esc]", Style::default().fg(Color::Gray)),
-            Span::styled(" Cancel", Style::default().fg(Color::White)),
+            Span::styled(format!(" {}", tr("Cancel")), Style::default().fg(Color::White)),
         ]),
         Line::from(""),
     ];
@@ -688,7 +3789,7 @@ fn render_export_format_modal(f: &mut Frame) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(" Export ")
+                .title(format!(" {} ", tr("Export")))
                 .title_style(Style::default().fg(Color::Cyan)),
         )
         .alignment(Alignment::Center);
@@ -697,6 +3798,134 @@ fn render_export_format_modal(f: &mut Frame) {
     f.render_widget(paragraph, area);
 }
 
+const SPINNER_FRAMES: [&str; 10] = ["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Current spinner glyph for rows with an operation in flight, driven by wall-clock time.
+fn spinner_frame() -> &'static str {
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    SPINNER_FRAMES[(millis / 100) as usize % SPINNER_FRAMES.len()]
+}
+
+/// Builds a list header from pre-padded column labels, underlining the columns
+/// that the list is currently sorted by.
+fn header_line<K: PartialEq>(columns: Vec<(String, Option<K>)>, sort_key: K) -> Line<'static> {
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut spans = Vec::new();
+    for (i, (label, key)) in columns.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::styled(" ", style));
+        }
+        let column_style = if key.as_ref() == Some(&sort_key) {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        };
+        spans.push(Span::styled(label, column_style));
+    }
+    Line::from(spans)
+}
+
+/// Row style for frozen rows: italic while frozen, and grey once the row has vanished and
+/// is only listed from its snapshot.
+fn frozen_style(style: Style, frozen: bool, vanished: bool) -> Style {
+    match (frozen, vanished) {
+        (_, true) => style.fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        (true, false) => style.add_modifier(Modifier::ITALIC),
+        (false, false) => style,
+    }
+}
+
+/// " [N frozen]" for list titles, empty when nothing is frozen.
+fn frozen_indicator(frozen: usize) -> String {
+    if frozen == 0 {
+        String::new()
+    } else {
+        format!(" [{}]", tr_fmt("{} frozen", &[&frozen]))
+    }
+}
+
+/// Splits a cell into spans, highlighting the chars at `positions` where the search matched.
+fn highlight_spans(text: String, positions: &[usize]) -> Vec<Span<'static>> {
+    if positions.is_empty() {
+        return vec![Span::raw(text)];
+    }
+
+    let highlight = Style::default().fg(Color::Black).bg(Color::Yellow);
+    let mut spans = Vec::new();
+    let mut run = String::new();
+    let mut run_matched = false;
+    for (i, c) in text.chars().enumerate() {
+        let matched = positions.contains(&i);
+        if matched != run_matched && !run.is_empty() {
+            let run = std::mem::take(&mut run);
+            spans.push(if run_matched {
+                Span::styled(run, highlight)
+            } else {
+                Span::raw(run)
+            });
+        }
+        run_matched = matched;
+        run.push(c);
+    }
+    if !run.is_empty() {
+        spans.push(if run_matched {
+            Span::styled(run, highlight)
+        } else {
+            Span::raw(run)
+        });
+    }
+    spans
+}
+
+/// Formats a bytes-per-second rate into a fixed 7-column cell (e.g. "  1.2MB").
+fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec < 1.0 {
+        return "      -".to_string();
+    }
+    let units = ["B", "KB", "MB", "GB"];
+    let mut value = bytes_per_sec;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:5.1}{:2}", value, units[unit])
+}
+
+/// Formats a process uptime in at most two units, right-aligned to 7 columns ("3h05m").
+fn format_uptime(secs: Option<u64>) -> String {
+    let Some(secs) = secs else {
+        return "      -".to_string();
+    };
+    let text = if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 3600 {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    } else if secs < 86400 {
+        format!("{}h{:02}m", secs / 3600, (secs % 3600) / 60)
+    } else {
+        format!("{}d{:02}h", secs / 86400, (secs % 86400) / 3600)
+    };
+    format!("{:>7}", text)
+}
+
+/// Formats a byte count with a binary unit suffix (e.g. "12.3GB").
+fn format_bytes(bytes: u64) -> String {
+    let units = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1}{}", value, units[unit])
+}
+
 fn centered_rect(percent_x: u16, height: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -6,37 +6,182 @@ use ratatui::{
     Frame,
 };
 
-use crate::state::nexus::NexusState;
+use crate::state::nexus::{has_remote, NexusState, ProtocolFilter, SortKey};
+use crate::state::table::TableRow;
+
+use super::text::{fit, fit_line, pad};
+use super::{format_rate, frozen_indicator, frozen_style, header_line, highlight_spans};
+
+// Interfaces listed in the throughput panel before it scrolls off
+const MAX_INTERFACE_ROWS: usize = 4;
+
+/// Link speed in the units adapters are sold in, e.g. "1 Gbps".
+fn format_link_speed(bits_per_sec: u64) -> String {
+    match bits_per_sec {
+        0 => "?".to_string(),
+        b if b >= 1_000_000_000 => format!("{} Gbps", b as f64 / 1e9),
+        b if b >= 1_000_000 => format!("{} Mbps", b / 1_000_000),
+        b => format!("{} Kbps", b / 1_000),
+    }
+}
+
+/// One line per physical interface: rates in both directions and how full the link is.
+fn render_interfaces(f: &mut Frame, state: &NexusState, area: Rect) {
+    let lines: Vec<Line> = state
+        .interfaces
+        .iter()
+        .take(MAX_INTERFACE_ROWS)
+        .map(|iface| {
+            let utilization = iface.utilization();
+            let color = match utilization {
+                Some(p) if p >= 80.0 => Color::Red,
+                Some(p) if p >= 50.0 => Color::Yellow,
+                _ => Color::Green,
+            };
+            let load = match utilization {
+                Some(p) => format!("{:5.1}%", p),
+                None => "     -".to_string(),
+            };
+            let link = format_link_speed(iface.receive_link_bps.max(iface.transmit_link_bps));
+            Line::from(vec![
+                Span::raw(format!("{} ", pad(&iface.alias, 24))),
+                Span::styled(
+                    format!(
+                        "Down {}  Up {}  ",
+                        format_rate(iface.rx_bps),
+                        format_rate(iface.tx_bps)
+                    ),
+                    Style::default().fg(Color::Cyan),
+                ),
+                Span::styled(load, Style::default().fg(color)),
+                Span::styled(format!(" of {}", link), Style::default().fg(Color::DarkGray)),
+            ])
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(" Interfaces ")
+        .title_style(Style::default().fg(Color::Cyan));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
 
 pub fn render(f: &mut Frame, state: &mut NexusState, search_query: &str, area: Rect) {
     let filtered = state.filtered_connections(search_query);
+    let lingering = state.lingering();
+    // Rows, header and title are cut to the space inside the border
+    let width = area.width.saturating_sub(2) as usize;
 
     let items: Vec<ListItem> = filtered
         .iter()
         .map(|(_, c)| {
-            let proto_color = match c.protocol.as_str() {
-                "TCP" => Color::Green,
-                "UDP" => Color::Yellow,
-                _ => Color::White,
+            // Flag sockets piling up on one port (TIME_WAIT) or in one process (CLOSE_WAIT)
+            let pileup = lingering.pileup(c);
+            // svchost rows name the hosted service that owns the socket
+            let process_name = match (&c.process_name, &c.service_name) {
+                (Some(name), Some(service)) => format!("{} ({})", name, service),
+                (Some(name), None) => name.clone(),
+                (None, _) => "-".to_string(),
+            };
+            let process_col = match pileup {
+                Some(count) if c.state == "TIME_WAIT" => {
+                    format!("{} [!TW:{} on :{}]", process_name, count, c.local_port)
+                }
+                Some(count) => format!("{} [!CW:{}]", process_name, count),
+                None => process_name.to_string(),
+            };
+
+            let mut style = state
+                .table
+                .row_style(c)
+                .unwrap_or_else(|| Style::default().fg(Color::White));
+            if pileup.is_some() {
+                style = style.add_modifier(Modifier::BOLD);
+            }
+
+            let remote = if has_remote(c) {
+                format!("{}:{}", c.remote_addr, c.remote_port)
+            } else {
+                "-".to_string()
             };
-            ListItem::new(format!(
-                "{:6} {:5} {:22} {:22} {:12} {}",
+            let mut spans = vec![Span::raw(format!(
+                "{:6} {:5} {:22} {:22} {:12}{} ",
                 c.pid,
                 c.protocol,
                 format!("{}:{}", c.local_addr, c.local_port),
-                format!("{}:{}", c.remote_addr, c.remote_port),
+                remote,
                 c.state,
-                c.process_name.as_deref().unwrap_or("-")
-            ))
-            .style(Style::default().fg(proto_color))
+                state.table.script_cells(&c.id())
+            ))];
+            let positions = state.match_positions(&process_name, search_query);
+            spans.extend(highlight_spans(process_col, &positions));
+            if let Some(check) = state.firewall_check(c) {
+                spans.push(if check.allowed {
+                    Span::styled(" [Allowed]", Style::default().fg(Color::Green))
+                } else {
+                    Span::styled(" [Blocked]", Style::default().fg(Color::Red))
+                });
+            }
+            let style = frozen_style(
+                style,
+                state.table.is_frozen(&c.id()),
+                state.table.is_vanished(&c.id()),
+            );
+            ListItem::new(fit_line(Line::from(spans), width)).style(style)
         })
         .collect();
 
     // Build title with filter and sort info
-    let total = state.connections.len();
+    let total = state.table.rows.len();
     let showing = filtered.len();
-    let sort_info = format!("{} {}", state.sort_key.as_str(), state.sort_order.as_str());
-    let title = format!(" Network (Nexus) [{}/{} | {}] ", showing, total, sort_info);
+    let sort_info = format!(
+        "{} {}",
+        state.table.sort_key.as_str(),
+        state.table.sort_order.as_str()
+    );
+    let totals = state.lingering_totals();
+    let mut preset_indicator = if state.lingering_only {
+        " [LINGERING]".to_string()
+    } else {
+        String::new()
+    };
+    if state.hide_local {
+        preset_indicator.push_str(" [NO LOCAL]");
+    }
+    match state.protocol_filter {
+        ProtocolFilter::All => {}
+        ProtocolFilter::Tcp => preset_indicator.push_str(" [TCP]"),
+        ProtocolFilter::Udp => preset_indicator.push_str(" [UDP]"),
+    }
+    if let Some(preset) = &state.table.preset {
+        preset_indicator.push_str(&format!(" [{}]", preset.name));
+    }
+    preset_indicator.push_str(&frozen_indicator(state.table.frozen.len()));
+    if let Some(capture) = &state.capture {
+        let secs = capture.started.elapsed().as_secs();
+        preset_indicator.push_str(&format!(
+            " [CAPTURE {} {}:{:02}]",
+            capture.target.describe(),
+            secs / 60,
+            secs % 60
+        ));
+    }
+    let title = format!(
+        " Network (Nexus){} [{}/{} | {}] [TIME_WAIT: {} CLOSE_WAIT: {}] ",
+        preset_indicator, showing, total, sort_info, totals.time_wait, totals.close_wait
+    );
+
+    // Interface throughput panel above the connection list
+    let area = if state.interfaces.is_empty() {
+        area
+    } else {
+        let rows = state.interfaces.len().min(MAX_INTERFACE_ROWS) as u16;
+        let sections = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(rows + 2), Constraint::Min(0)])
+            .split(area);
+        render_interfaces(f, state, sections[0]);
+        sections[1]
+    };
 
     // Create inner area inside the border for the header
     let inner_area = area.inner(Margin::new(1, 1));
@@ -48,22 +193,22 @@ pub fn render(f: &mut Frame, state: &mut NexusState, search_query: &str, area: R
         .split(inner_area);
 
     // Render header as non-selectable text in the first line of inner area
-    let header_text = format!(
-        "{:6} {:5} {:22} {:22} {:12} {}",
-        "PID", "Proto", "Local", "Remote", "State", "Process"
-    );
-    let header = Paragraph::new(Line::from(vec![Span::styled(
-        header_text,
-        Style::default()
-            .fg(Color::Yellow)
-            .add_modifier(Modifier::BOLD),
-    )]));
+    let mut columns = vec![
+        (format!("{:6}", "PID"), Some(SortKey::Pid)),
+        (format!("{:5}", "Proto"), Some(SortKey::Protocol)),
+        (format!("{:22}", "Local"), Some(SortKey::LocalAddr)),
+        (format!("{:22}", "Remote"), Some(SortKey::RemoteAddr)),
+        (format!("{:12}", "State"), Some(SortKey::State)),
+    ];
+    columns.extend(state.table.script_headers().into_iter().map(|header| (header, None)));
+    columns.push(("Process".to_string(), Some(SortKey::ProcessName)));
+    let header = Paragraph::new(fit_line(header_line(columns, state.table.sort_key), width));
     f.render_widget(header, chunks[0]);
 
     // Render list block with border (full area)
     let list_block = Block::default()
         .borders(Borders::ALL)
-        .title(title)
+        .title(fit(&title, width))
         .title_style(Style::default().fg(Color::Cyan));
     f.render_widget(list_block.clone(), area);
 
@@ -71,5 +216,6 @@ pub fn render(f: &mut Frame, state: &mut NexusState, search_query: &str, area: R
     let list = List::new(items).highlight_style(Style::default().bg(Color::DarkGray));
 
     // Pass mutable reference directly (not cloned) so selection is preserved
-    f.render_stateful_widget(list, chunks[1], &mut state.list_state);
+    state.table.viewport_rows = chunks[1].height as usize;
+    f.render_stateful_widget(list, chunks[1], &mut state.table.list_state);
 }
@@ -0,0 +1,63 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    widgets::{Block, Borders, RenderDirection, Sparkline},
+    Frame,
+};
+
+use crate::state::perf::PerfState;
+
+use super::{format_bytes, format_rate};
+
+pub fn render(f: &mut Frame, state: &PerfState, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+            Constraint::Ratio(1, 4),
+        ])
+        .split(area);
+
+    let cpu_title = format!(" CPU {:.0}% ", state.cpu_percent);
+    render_history(f, chunks[0], cpu_title, &state.cpu_history, Some(100), Color::Green);
+
+    let mem_title = format!(
+        " Mem {}/{} ",
+        format_bytes(state.memory_used),
+        format_bytes(state.memory_total)
+    );
+    render_history(f, chunks[1], mem_title, &state.memory_history, Some(100), Color::Magenta);
+
+    let rx_title = format!(" Down {} ", format_rate(state.net_rx_bps).trim_start());
+    render_history(f, chunks[2], rx_title, &state.net_rx_history, None, Color::Cyan);
+
+    let tx_title = format!(" Up {} ", format_rate(state.net_tx_bps).trim_start());
+    render_history(f, chunks[3], tx_title, &state.net_tx_history, None, Color::Yellow);
+}
+
+fn render_history(
+    f: &mut Frame,
+    area: Rect,
+    title: String,
+    history: &std::collections::VecDeque<u64>,
+    max: Option<u64>,
+    color: Color,
+) {
+    // Newest sample on the right edge, older samples scroll off to the left
+    let mut sparkline = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .title_style(Style::default().fg(Color::Cyan)),
+        )
+        .data(history.iter().rev())
+        .direction(RenderDirection::RightToLeft)
+        .style(Style::default().fg(color));
+    if let Some(max) = max {
+        sparkline = sparkline.max(max);
+    }
+    f.render_widget(sparkline, area);
+}
@@ -0,0 +1,172 @@
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Columns `text` takes on screen: 2 for CJK and most emoji, 0 for combining marks.
+pub fn display_width(text: &str) -> usize {
+    text.width()
+}
+
+/// The longest prefix of `text` that fits in `width` columns, cut between graphemes so
+/// no character or accent is split. Byte slicing can't do this: `&name[..20]` panics in
+/// the middle of a multi-byte character and counts a CJK character as one column.
+pub fn truncate(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (start, grapheme) in text.grapheme_indices(true) {
+        used += grapheme.width();
+        if used > width {
+            return &text[..start];
+        }
+    }
+    text
+}
+
+/// What is left of `text` after its first `width` columns, dropping whole graphemes; a
+/// wide character straddling the cut is dropped too.
+pub fn skip(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (start, grapheme) in text.grapheme_indices(true) {
+        if used >= width {
+            return &text[start..];
+        }
+        used += grapheme.width();
+    }
+    ""
+}
+
+/// `text` cut to `width` columns and padded with spaces to exactly `width`, for a column
+/// that lines up whatever the script. `format!("{:20}")` pads by chars instead.
+pub fn pad(text: &str, width: usize) -> String {
+    let text = truncate(text, width);
+    format!("{}{}", text, " ".repeat(width - display_width(text)))
+}
+
+/// `text` cut to `width` columns, ending in `…` when something was cut.
+pub fn fit(text: &str, width: usize) -> String {
+    if display_width(text) <= width || width == 0 {
+        return truncate(text, width).to_string();
+    }
+    format!("{}…", truncate(text, width - 1))
+}
+
+/// Cuts a row to `width` columns, ending it in `…` when something was cut, so a row that
+/// doesn't fit says so instead of just stopping at the border.
+pub fn fit_line(line: Line<'static>, width: usize) -> Line<'static> {
+    if line.width() <= width {
+        return line;
+    }
+    let mut remaining = width.saturating_sub(1);
+    let mut spans = Vec::new();
+    let mut last_style = Style::default();
+    for span in line.spans {
+        if remaining == 0 {
+            break;
+        }
+        let span_width = display_width(&span.content);
+        last_style = span.style;
+        if span_width <= remaining {
+            remaining -= span_width;
+            spans.push(span);
+        } else {
+            let cut = truncate(&span.content, remaining).to_string();
+            spans.push(Span::styled(cut, span.style));
+            remaining = 0;
+        }
+    }
+    if width > 0 {
+        spans.push(Span::styled("…", last_style));
+    }
+    Line { spans, ..line }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Modifier;
+
+    const CJK: &str = "漢字テスト";
+    // "école" with the accent as a combining mark
+    const COMBINING: &str = "e\u{301}cole";
+    // Man, woman and girl joined into one family emoji
+    const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+
+    #[test]
+    fn truncate_never_splits_a_wide_character() {
+        assert_eq!(truncate(CJK, 3), "漢");
+        assert_eq!(truncate(CJK, 4), "漢字");
+        assert_eq!(truncate(CJK, 1), "");
+        for width in 0..=12 {
+            assert!(display_width(truncate(CJK, width)) <= width);
+        }
+    }
+
+    #[test]
+    fn truncate_keeps_combining_marks_with_their_letter() {
+        assert_eq!(display_width(COMBINING), 5);
+        assert_eq!(truncate(COMBINING, 1), "e\u{301}");
+        assert_eq!(truncate(COMBINING, 0), "");
+    }
+
+    #[test]
+    fn truncate_keeps_zwj_sequences_whole() {
+        let text = format!("{}x", FAMILY);
+        let family_width = display_width(FAMILY);
+        assert_eq!(truncate(&text, family_width), FAMILY);
+        assert_eq!(truncate(&text, family_width - 1), "");
+    }
+
+    #[test]
+    fn skip_drops_a_wide_character_straddling_the_cut() {
+        assert_eq!(skip(CJK, 1), "字テスト");
+        assert_eq!(skip(CJK, 2), "字テスト");
+        assert_eq!(skip("ab漢字", 2), "漢字");
+        assert_eq!(skip(COMBINING, 1), "cole");
+        assert_eq!(skip(CJK, 10), "");
+    }
+
+    #[test]
+    fn pad_fills_exactly_the_width() {
+        assert_eq!(pad("abc", 5), "abc  ");
+        assert_eq!(pad(CJK, 3), "漢 ");
+        for text in [CJK, COMBINING, FAMILY, "abc", ""] {
+            for width in 0..=12 {
+                assert_eq!(display_width(&pad(text, width)), width, "{:?} in {}", text, width);
+            }
+        }
+    }
+
+    #[test]
+    fn fit_ends_in_an_ellipsis_when_cut() {
+        assert_eq!(fit(CJK, 10), CJK);
+        assert_eq!(fit(CJK, 5), "漢字…");
+        assert_eq!(fit(CJK, 4), "漢…");
+        assert_eq!(fit(COMBINING, 3), "e\u{301}c…");
+        for width in 0..=12 {
+            assert!(display_width(&fit(CJK, width)) <= width);
+        }
+    }
+
+    #[test]
+    fn zero_width_gives_nothing() {
+        assert_eq!(truncate(CJK, 0), "");
+        assert_eq!(skip(CJK, 0), CJK);
+        assert_eq!(pad(CJK, 0), "");
+        assert_eq!(fit(CJK, 0), "");
+        assert_eq!(fit_line(Line::from(CJK), 0).width(), 0);
+    }
+
+    #[test]
+    fn fit_line_cuts_across_spans() {
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let line = || Line::from(vec![Span::raw("名前"), Span::styled("テスト", bold)]);
+        assert_eq!(fit_line(line(), 10).width(), 10);
+
+        let cut = fit_line(line(), 7);
+        assert_eq!(cut.to_string(), "名前テ…");
+        assert_eq!(cut.spans.last().map(|span| span.style), Some(bold));
+        for width in 0..=10 {
+            assert!(fit_line(line(), width).width() <= width);
+        }
+    }
+}